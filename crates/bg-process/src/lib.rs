@@ -6,8 +6,8 @@ use eyre::{Context, Error};
 use ledger::Ledger;
 use log::info;
 use process::{
-    ai_messages::MotivationNotifier, birthdays::BirthdaysNotifier, freeze::FreezeBg,
-    notifier::TrainingNotifier, requests::RequestNotifier, rewards::RewardsBg,
+    ai_messages::MotivationNotifier, birthdays::BirthdaysNotifier, freeze::FreezeBg, installments::InstallmentsBg,
+    loyalty::LoyaltyBg, notifier::TrainingNotifier, personal_series::PersonalSeriesBg, prices::PricesBg, requests::RequestNotifier, rewards::RewardsBg,
     subscription::SubscriptionBg, training::TriningBg, user_sync::UserNameSync,
 };
 use teloxide::types::{ChatId, MessageId};
@@ -35,6 +35,18 @@ pub async fn start(ledger: Arc<Ledger>, bot: BotApp) -> Result<(), Error> {
         .add(SubscriptionBg::new(ledger.clone(), bot.clone()).to_job()?)
         .await?;
     sched.add(RewardsBg::new(ledger.clone()).to_job()?).await?;
+    sched
+        .add(PricesBg::new(ledger.clone(), bot.clone()).to_job()?)
+        .await?;
+    sched
+        .add(InstallmentsBg::new(ledger.clone(), bot.clone()).to_job()?)
+        .await?;
+    sched
+        .add(LoyaltyBg::new(ledger.clone(), bot.clone()).to_job()?)
+        .await?;
+    sched
+        .add(PersonalSeriesBg::new(ledger.clone(), bot.clone()).to_job()?)
+        .await?;
     sched
         .add(TrainingNotifier::new(ledger.clone(), bot.clone()).to_job()?)
         .await?;
@@ -42,6 +42,22 @@ impl Task for BirthdaysNotifier {
         for user in users {
             info!("Birthday notification for {}", user.id);
             self.notify(&user, &notification_listener).await?;
+            let points = self
+                .ledger
+                .award_birthday_points(&mut session, user.id, now.year())
+                .await?;
+            if points > 0 {
+                self.bot
+                    .notify(
+                        ChatId(user.tg_id),
+                        &format!(
+                            "🎂 С днем рождения\\! Дарим вам _{}_ баллов лояльности",
+                            points
+                        ),
+                        true,
+                    )
+                    .await;
+            }
         }
 
         Ok(())
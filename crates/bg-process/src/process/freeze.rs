@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use crate::{Ledger, Task};
 use async_trait::async_trait;
-use chrono::Local;
+use chrono::{Local, Utc};
 use eyre::{Error, Result};
 use log::{info, warn};
 
@@ -39,6 +39,28 @@ impl Task for FreezeBg {
             info!("Unfreezing user {}", user.tg_id);
             self.ledger.users.unfreeze(&mut session, user.id).await?;
         }
+
+        let users = self
+            .ledger
+            .users
+            .find_users_with_expired_subscription_freeze(&mut session)
+            .await?;
+        let now = now.with_timezone(&Utc);
+        for user in users {
+            for sub in user.subscriptions() {
+                let Some(freeze) = sub.freeze.as_ref() else {
+                    continue;
+                };
+                if freeze.freeze_end > now {
+                    continue;
+                }
+                info!("Unfreezing subscription {} of user {}", sub.id, user.tg_id);
+                self.ledger
+                    .users
+                    .unfreeze_subscription(&mut session, user.id, sub.id)
+                    .await?;
+            }
+        }
         Ok(())
     }
 }
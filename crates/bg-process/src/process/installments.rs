@@ -0,0 +1,93 @@
+use std::sync::Arc;
+
+use crate::{Ledger, Task};
+use async_trait::async_trait;
+use bot_core::{bot::TgBot, CommonLocation};
+use bot_viewer::{day::fmt_date, fmt_phone};
+use chrono::{Local, Utc};
+use eyre::{Error, Result};
+use log::info;
+use model::{installment::Reminder, rights::Rule};
+use teloxide::{
+    types::{ChatId, InlineKeyboardMarkup},
+    utils::markdown::escape,
+};
+
+/// Reminds clients about upcoming and overdue installments, tells the managers about overdue ones.
+#[derive(Clone)]
+pub struct InstallmentsBg {
+    ledger: Arc<Ledger>,
+    bot: Arc<TgBot>,
+}
+
+#[async_trait]
+impl Task for InstallmentsBg {
+    const NAME: &'static str = "installments";
+    const CRON: &'static str = "every day at 10:00";
+
+    async fn process(&mut self) -> Result<(), Error> {
+        let mut session = self.ledger.db.start_session().await?;
+        let reminders = self
+            .ledger
+            .take_installment_reminders(&mut session, Utc::now())
+            .await?;
+        if reminders.is_empty() {
+            return Ok(());
+        }
+
+        let listeners = self
+            .ledger
+            .users
+            .find_users_with_right(&mut session, Rule::ReceiveNotificationsAboutSubscriptions)
+            .await?;
+        for (user, debt, installment, reminder) in reminders {
+            info!(
+                "Installment reminder {:?} for user {}: {}",
+                reminder, user.id, debt.name
+            );
+            let due = fmt_date(&installment.due.with_timezone(&Local)).to_string();
+            let amount = escape(&installment.amount.to_string());
+            let name = escape(&debt.name);
+            let msg = match reminder {
+                Reminder::Upcoming => format!(
+                    "💳 Напоминаем: {} нужно внести платеж _{}_ руб\\. по рассрочке за абонемент *{}*",
+                    due,
+                    amount,
+                    name
+                ),
+                Reminder::Overdue => format!(
+                    "❗️ Платеж _{}_ руб\\. по рассрочке за абонемент *{}* просрочен \\(срок {}\\)\\. Пожалуйста, внесите его",
+                    amount,
+                    name,
+                    due
+                ),
+            };
+            self.bot.notify(ChatId(user.tg_id), &msg, true).await;
+
+            if reminder == Reminder::Overdue {
+                let msg = format!(
+                    "❗️ Просрочен платеж _{}_ руб\\. по рассрочке за абонемент *{}*\nКлиент: {} {}\nОстаток долга: _{}_ руб\\.",
+                    amount,
+                    name,
+                    escape(&user.name.to_string()),
+                    fmt_phone(user.phone.as_deref()),
+                    escape(&debt.remaining().to_string())
+                );
+                let keymap = InlineKeyboardMarkup::default()
+                    .append_row(vec![CommonLocation::Profile(user.id).button()]);
+                for listener in &listeners {
+                    self.bot
+                        .notify_with_markup(ChatId(listener.tg_id), &msg, keymap.clone())
+                        .await;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl InstallmentsBg {
+    pub fn new(ledger: Arc<Ledger>, bot: Arc<TgBot>) -> InstallmentsBg {
+        InstallmentsBg { ledger, bot }
+    }
+}
@@ -0,0 +1,52 @@
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{Ledger, Task};
+use async_trait::async_trait;
+use bot_core::bot::TgBot;
+use chrono::Utc;
+use eyre::{Error, Result};
+use log::info;
+use teloxide::types::ChatId;
+
+/// Writes off loyalty points past their lifetime and tells the clients about it.
+#[derive(Clone)]
+pub struct LoyaltyBg {
+    ledger: Arc<Ledger>,
+    bot: Arc<TgBot>,
+}
+
+#[async_trait]
+impl Task for LoyaltyBg {
+    const NAME: &'static str = "loyalty";
+    const CRON: &'static str = "every day at 6:00";
+
+    async fn process(&mut self) -> Result<(), Error> {
+        let mut session = self.ledger.db.start_session().await?;
+        let expired = self.ledger.expire_points(&mut session, Utc::now()).await?;
+
+        let mut by_user = HashMap::new();
+        for entry in expired {
+            *by_user.entry(entry.user_id).or_insert(0) += entry.points.unsigned_abs();
+        }
+        for (user_id, points) in by_user {
+            info!("Expired {} loyalty points of user {}", points, user_id);
+            let Some(user) = self.ledger.users.get(&mut session, user_id).await? else {
+                continue;
+            };
+            self.bot
+                .notify(
+                    ChatId(user.tg_id),
+                    &format!("⌛ Сгорело _{}_ баллов лояльности", points),
+                    true,
+                )
+                .await;
+        }
+        Ok(())
+    }
+}
+
+impl LoyaltyBg {
+    pub fn new(ledger: Arc<Ledger>, bot: Arc<TgBot>) -> LoyaltyBg {
+        LoyaltyBg { ledger, bot }
+    }
+}
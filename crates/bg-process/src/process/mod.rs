@@ -1,7 +1,11 @@
 pub mod birthdays;
 pub mod dumps;
 pub mod freeze;
+pub mod installments;
+pub mod loyalty;
 pub mod notifier;
+pub mod personal_series;
+pub mod prices;
 pub mod requests;
 pub mod rewards;
 pub mod subscription;
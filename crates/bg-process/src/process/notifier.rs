@@ -1,11 +1,15 @@
 use crate::Task;
 use async_trait::async_trait;
 use bot_core::bot::TgBot;
-use bot_viewer::day::fmt_time;
+use bot_viewer::day::{fmt_dt, fmt_time};
 use chrono::{DateTime, Local};
 use eyre::Error;
 use ledger::Ledger;
-use model::{ids::DayId, session::Session, training::Notified};
+use model::{
+    ids::DayId,
+    session::Session,
+    training::{Notified, Training},
+};
 use mongodb::bson::oid::ObjectId;
 use std::sync::Arc;
 use teloxide::{types::ChatId, utils::markdown::escape};
@@ -90,11 +94,15 @@ impl TrainingNotifier {
                 continue;
             }
 
-            let msg = escape(&format!(
-                "Завтра в {} у вас тренировка: {}",
-                training.get_slot().start_at().format("%H:%M"),
-                training.name
-            ));
+            let msg = format!(
+                "{}{}",
+                escape(&format!(
+                    "Завтра в {} у вас тренировка: {}",
+                    training.get_slot().start_at().format("%H:%M"),
+                    training.name
+                )),
+                cancel_hint(&training)
+            );
 
             for client in &training.clients {
                 self.notify_user(session, training.get_slot().start_at(), *client, &msg, true)
@@ -128,6 +136,16 @@ impl TrainingNotifier {
 
             let training_id = training.id();
 
+            let msg = format!(
+                "{}{}",
+                escape(&format!(
+                    "У вас запланирована тренировка: {} в {}",
+                    training.name,
+                    fmt_time(&start_at)
+                )),
+                cancel_hint(&training)
+            );
+
             let mut already_notified = match training.notified {
                 Notified::None {} => {
                     vec![]
@@ -138,12 +156,6 @@ impl TrainingNotifier {
                 Notified::ByHours(ids) => ids,
             };
 
-            let msg = escape(&format!(
-                "У вас запланирована тренировка: {} в {}",
-                training.name,
-                fmt_time(&start_at)
-            ));
-
             let mut has_changes = false;
             for client in &training.clients {
                 if !already_notified.contains(client) && self
@@ -164,3 +176,12 @@ impl TrainingNotifier {
         Ok(())
     }
 }
+
+fn cancel_hint(training: &Training) -> String {
+    let deadline = training.sign_out_deadline();
+    if Local::now() < deadline {
+        format!("\nОтменить запись без списания можно до {}", fmt_dt(&deadline))
+    } else {
+        String::new()
+    }
+}
@@ -0,0 +1,73 @@
+use std::sync::Arc;
+
+use crate::{Ledger, Task};
+use async_trait::async_trait;
+use bot_core::bot::TgBot;
+use bot_viewer::day::fmt_dt;
+use chrono::Local;
+use eyre::{Error, Result};
+use log::warn;
+use teloxide::types::ChatId;
+
+#[derive(Clone)]
+pub struct PersonalSeriesBg {
+    ledger: Arc<Ledger>,
+    bot: Arc<TgBot>,
+}
+
+#[async_trait]
+impl Task for PersonalSeriesBg {
+    const NAME: &'static str = "personal_series";
+    const CRON: &'static str = "every day at 6:00";
+
+    async fn process(&mut self) -> Result<(), Error> {
+        let mut session = self.ledger.db.start_session().await?;
+        let series = self
+            .ledger
+            .personal_series
+            .find_active(&mut session)
+            .await?;
+        let now = Local::now();
+        for mut series in series {
+            loop {
+                series.skip_past(now);
+                let Some(start_at) = series.due(now) else {
+                    break;
+                };
+                match self
+                    .ledger
+                    .extend_personal_series(&mut session, series.id)
+                    .await
+                {
+                    Ok(Some(_)) => series.advance(true),
+                    Ok(None) => break,
+                    Err(err) => {
+                        warn!("Failed to extend personal series {}: {:#}", series.id, err);
+                        self.ledger
+                            .skip_personal_session(&mut session, series.id, start_at)
+                            .await?;
+                        series.advance(false);
+                        let client = self.ledger.get_user(&mut session, series.client).await?;
+                        self.bot
+                            .notify(
+                                ChatId(client.tg_id),
+                                &format!(
+                                    "⚠️ Не удалось записать вас на персональную тренировку *{}*\\. Проверьте абонемент или свяжитесь с администратором",
+                                    fmt_dt(&start_at)
+                                ),
+                                true,
+                            )
+                            .await;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl PersonalSeriesBg {
+    pub fn new(ledger: Arc<Ledger>, bot: Arc<TgBot>) -> PersonalSeriesBg {
+        PersonalSeriesBg { ledger, bot }
+    }
+}
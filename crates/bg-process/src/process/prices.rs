@@ -0,0 +1,62 @@
+use std::sync::Arc;
+
+use crate::{Ledger, Task};
+use async_trait::async_trait;
+use bot_core::bot::TgBot;
+use eyre::{Error, Result};
+use log::info;
+use model::rights::Rule;
+use teloxide::{types::ChatId, utils::markdown::escape};
+
+/// Switches subscriptions to scheduled prices and lets the managers know.
+#[derive(Clone)]
+pub struct PricesBg {
+    ledger: Arc<Ledger>,
+    bot: Arc<TgBot>,
+}
+
+#[async_trait]
+impl Task for PricesBg {
+    const NAME: &'static str = "prices";
+    const CRON: &'static str = "every 1 hour";
+
+    async fn process(&mut self) -> Result<(), Error> {
+        let mut session = self.ledger.db.start_session().await?;
+        let activated = self
+            .ledger
+            .subscriptions
+            .activate_scheduled_prices(&mut session)
+            .await?;
+        if activated.is_empty() {
+            return Ok(());
+        }
+
+        let listeners = self
+            .ledger
+            .users
+            .find_users_with_right(&mut session, Rule::ReceiveNotificationsAboutSubscriptions)
+            .await?;
+        for (subscription, old_price) in activated {
+            info!(
+                "Subscription {} price changed: {} -> {}",
+                subscription.name, old_price, subscription.price
+            );
+            let msg = format!(
+                "💸 Новая цена абонемента *{}*: _{}_ \\(было _{}_\\)",
+                escape(&subscription.name),
+                escape(&subscription.price.to_string()),
+                escape(&old_price.to_string())
+            );
+            for listener in &listeners {
+                self.bot.notify(ChatId(listener.tg_id), &msg, true).await;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl PricesBg {
+    pub fn new(ledger: Arc<Ledger>, bot: Arc<TgBot>) -> PricesBg {
+        PricesBg { ledger, bot }
+    }
+}
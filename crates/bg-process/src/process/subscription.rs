@@ -3,10 +3,16 @@ use std::sync::Arc;
 use crate::{Ledger, Task};
 use async_trait::async_trait;
 use bot_core::{bot::TgBot, CommonLocation};
-use bot_viewer::{fmt_phone, user::tg_link};
-use chrono::Utc;
+use bot_viewer::{day::fmt_date, fmt_phone, user::tg_link};
+use chrono::{Local, Utc};
 use eyre::{Error, Result};
-use model::rights::Rule;
+use model::{
+    notification::{Notification, NotificationId},
+    rights::Rule,
+    session::Session,
+    subscription::{Status, UserSubscription},
+    user::User,
+};
 use teloxide::{
     types::{ChatId, InlineKeyboardMarkup},
     utils::markdown::escape,
@@ -38,6 +44,14 @@ impl Task for SubscriptionBg {
             .find_users_with_right(&mut session, Rule::ReceiveNotificationsAboutSubscriptions)
             .await?;
 
+        let reminder_days = self
+            .ledger
+            .settings
+            .get(&mut session)
+            .await?
+            .expiry_reminder_days;
+        let mut digest = vec![];
+
         while let Some(user) = users.next(&mut session).await {
             let user = user?;
             let extension = self
@@ -87,6 +101,29 @@ impl Task for SubscriptionBg {
                     }
                 }
             }
+
+            // Family subscriptions are reminded about once, to the payer.
+            if user.id != payer.as_ref().id {
+                continue;
+            }
+            for sub in payer.subscriptions() {
+                if let Some(line) = self
+                    .remind_about_expiry(&mut session, &user, sub, &reminder_days)
+                    .await?
+                {
+                    digest.push(line);
+                }
+            }
+        }
+
+        if !digest.is_empty() {
+            let msg = format!(
+                "⏳ Скоро сгорят занятия по абонементам:\n{}",
+                digest.join("\n")
+            );
+            for listener in notification_listener.iter() {
+                self.bot.notify(ChatId(listener.tg_id), &msg, true).await;
+            }
         }
 
         Ok(())
@@ -97,4 +134,62 @@ impl SubscriptionBg {
     pub fn new(ledger: Arc<Ledger>, bot: Arc<TgBot>) -> SubscriptionBg {
         SubscriptionBg { ledger, bot }
     }
+
+    /// Warns the client that the subscription ends soon with unused lessons left.
+    /// Returns the managers' digest line, or `None` if no reminder is due or it was already sent.
+    async fn remind_about_expiry(
+        &self,
+        session: &mut Session,
+        user: &User,
+        sub: &UserSubscription,
+        reminder_days: &[u32],
+    ) -> Result<Option<String>, Error> {
+        let Some(days) = sub.expiry_reminder(Utc::now(), reminder_days) else {
+            return Ok(None);
+        };
+        let Status::Active { end_date, .. } = sub.status else {
+            return Ok(None);
+        };
+        let id = NotificationId::ExpiryReminder {
+            client_id: user.id,
+            subscription_id: sub.id,
+            days,
+        };
+        if self.ledger.notifications.has(session, id.clone()).await? {
+            return Ok(None);
+        }
+
+        let end = fmt_date(&end_date.with_timezone(&Local)).to_string();
+        let msg = format!(
+            "⏳ Абонемент *{}* заканчивается {}\\. На нем осталось занятий: _{}_\\. Успейте записаться, чтобы они не сгорели\\!",
+            escape(sub.name.as_str()),
+            end,
+            sub.balance,
+        );
+        let mut notification = Notification::new(
+            user.id,
+            msg.clone(),
+            Local::now(),
+            end_date.with_timezone(&Local),
+            false,
+            id,
+        );
+        notification.sent = true;
+        self.ledger.notifications.insert(session, notification).await?;
+        self.bot
+            .notify_with_markup(
+                ChatId(user.tg_id),
+                &msg,
+                InlineKeyboardMarkup::default().append_row(vec![CommonLocation::Schedule.button()]),
+            )
+            .await;
+        Ok(Some(format!(
+            "{} {}: *{}*, _{}_ зан\\. до {}",
+            tg_link(user.tg_id, user.name.tg_user_name.as_deref()),
+            fmt_phone(user.phone.as_deref()),
+            escape(sub.name.as_str()),
+            sub.balance,
+            end,
+        )))
+    }
 }
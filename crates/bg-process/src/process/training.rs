@@ -4,14 +4,17 @@ use crate::{Ledger, Task};
 use async_trait::async_trait;
 use bot_core::{bot::TgBot, CommonLocation};
 use bot_viewer::{fmt_phone, user::link_to_user};
+use chrono::Duration;
 use eyre::{bail, eyre, Error, Result};
 use log::{error, info};
 use model::{
+    decimal::Decimal,
     program::TrainingType,
     rights::Rule,
     session::Session,
+    settings::NoShowPolicy,
     subscription::UserSubscription,
-    training::{Statistics, Training, TrainingStatus},
+    training::{Attendance, Statistics, Training, TrainingStatus},
     user::{employee::UserRewardContribution, family::FindFor, User},
 };
 use teloxide::{
@@ -34,6 +37,7 @@ impl Task for TriningBg {
     async fn process(&mut self) -> Result<(), Error> {
         let mut session = self.ledger.db.start_session().await?;
 
+        let settings = self.ledger.settings.get(&mut session).await?;
         let mut cursor = self.ledger.calendar.days_to_process(&mut session).await?;
         let now = chrono::Local::now();
         while let Some(day) = cursor.next(&mut session).await {
@@ -44,13 +48,19 @@ impl Task for TriningBg {
                 }
 
                 let result = match training.status(now) {
-                    TrainingStatus::OpenToSignup { .. }
+                    TrainingStatus::NotOpenToSignup
+                    | TrainingStatus::OpenToSignup { .. }
                     | TrainingStatus::ClosedToSignup
                     | TrainingStatus::InProgress => continue,
                     TrainingStatus::Finished => match training.tp {
                         TrainingType::Group { .. } | TrainingType::Personal { .. } => {
+                            let attendance_deadline = training.get_slot().end_at()
+                                + Duration::minutes(settings.attendance_window_min as i64);
+                            if !training.is_attendance_marked() && now < attendance_deadline {
+                                continue;
+                            }
                             let notifications = self
-                                .process_finished_training(&mut session, training)
+                                .process_finished_training(&mut session, training, settings.no_show)
                                 .await?;
                             for notification in notifications {
                                 self.send_notification(&mut session, notification).await?;
@@ -121,6 +131,7 @@ impl TriningBg {
         &self,
         session: &mut Session,
         training: Training,
+        no_show: NoShowPolicy,
     ) -> Result<Vec<Notification>> {
         info!("Finalize training:{:?}", training);
 
@@ -128,30 +139,65 @@ impl TriningBg {
 
         let mut statistic = Statistics::default();
 
+        for client in &training.clients {
+            match training.attendance(*client) {
+                Some(Attendance::NoShow) => statistic.no_show += 1,
+                Some(Attendance::Excused) => statistic.excused += 1,
+                Some(Attendance::Attended) | None => {}
+            }
+        }
+
         let mut users_info = Vec::with_capacity(training.clients.len());
         if training.tp.is_not_free() {
             for client in &training.clients {
+                let attendance = training.attendance(*client).unwrap_or(Attendance::Attended);
+                let charge = match attendance {
+                    Attendance::Attended => true,
+                    Attendance::NoShow => no_show == NoShowPolicy::Charge,
+                    Attendance::Excused => false,
+                };
+
                 let mut user = self.ledger.get_user(session, *client).await?;
                 let mut payer = user.payer_mut()?;
-                let sub = if let Some(sub) = payer.find_subscription(FindFor::Charge, &training) {
-                    if !sub.change_locked_balance(&training) {
-                        return Err(eyre!("Not enough balance:{}", user.id));
+                if !charge {
+                    let sub = payer
+                        .find_subscription(FindFor::Unlock, &training)
+                        .ok_or_else(|| eyre!("Subscription not found for user:{}", client))?;
+                    if !sub.unlock_balance() {
+                        return Err(eyre!("Not enough reserved balance:{}", client));
                     }
-                    statistic.earned += sub.item_price();
+                    sub.remove_visit(&training.get_slot());
                     users_info.push(UserRewardContribution {
                         user: *client,
-                        lesson_price: sub.item_price(),
+                        lesson_price: Decimal::zero(),
                         subscription_price: sub.subscription_price(),
                         lessons_count: sub.items(),
                     });
-                    if sub.balance == 0 {
-                        Some(sub.clone())
+                    self.ledger.users.update(session, &mut payer).await?;
+                    continue;
+                }
+
+                let (sub_id, sub) =
+                    if let Some(sub) = payer.find_subscription(FindFor::Charge, &training) {
+                        if !sub.change_locked_balance(&training) {
+                            return Err(eyre!("Not enough balance:{}", user.id));
+                        }
+                        statistic.earned += sub.item_price();
+                        users_info.push(UserRewardContribution {
+                            user: *client,
+                            lesson_price: sub.item_price(),
+                            subscription_price: sub.subscription_price(),
+                            lessons_count: sub.items(),
+                        });
+                        if sub.balance == 0 {
+                            (sub.id, Some(sub.clone()))
+                        } else {
+                            (sub.id, None)
+                        }
                     } else {
-                        None
-                    }
-                } else {
-                    return Err(eyre!("Subscription not found for user:{}", user.id));
-                };
+                        return Err(eyre!("Subscription not found for user:{}", user.id));
+                    };
+                payer.sync_bundle(sub_id);
 
                 self.ledger.users.update(session, &mut payer).await?;
                 if let Some(sub) = sub {
@@ -179,6 +225,9 @@ impl TriningBg {
                 bail!("Failed to process training. Failed to find instructor");
             }
         }
+        self.ledger
+            .award_training_points_txless(session, &training)
+            .await?;
         self.ledger
             .calendar
             .finalized(session, training.id(), &statistic)
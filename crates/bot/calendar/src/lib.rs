@@ -6,21 +6,24 @@ use bot_core::widget::{Jmp, View};
 use bot_trainigs::list::TrainingList;
 use bot_trainigs::view::TrainingView;
 use bot_viewer::day::{fmt_dm, fmt_month, fmt_weekday};
-use bot_viewer::rooms::fmt_room_emoji;
+use bot_viewer::rooms::{fmt_room, fmt_room_emoji};
 use bot_viewer::training::{fmt_statistics_summary, fmt_training_status};
 use bot_views::Filter;
 use chrono::{Datelike, Duration, Local, Weekday};
 use eyre::Error;
 use model::ids::{DayId, WeekId};
 use model::rights::Rule;
-use model::rooms::Room;
 use mongodb::bson::oid::ObjectId;
 use serde::{Deserialize, Serialize};
 use std::vec;
 use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup};
 use teloxide::utils::markdown::escape;
 
+mod rooms;
 mod schedule;
+mod templates;
+
+const ROOMS_PER_ROW: usize = 3;
 // mod personal;
 // mod sub_rent;
 // mod place;
@@ -94,6 +97,14 @@ impl View for CalendarView {
                     Ok(TrainingList::users(ctx.me.id).into())
                 }
             }
+            Callback::Templates => {
+                ctx.ensure(Rule::EditScheduleTemplates)?;
+                Ok(templates::ScheduleTemplatesView::new(self.week_id).into())
+            }
+            Callback::Rooms => {
+                ctx.ensure(Rule::EditRooms)?;
+                Ok(rooms::RoomsView.into())
+            }
             Callback::SelectRoom(room) => {
                 let room_id = ObjectId::from_bytes(room);
                 if self.rooms.contains(&room_id) {
@@ -143,22 +154,22 @@ pub async fn render_week(
 
     let now = Local::now();
     let mut buttons = InlineKeyboardMarkup::default();
-    let adult_room_name = if rooms.contains(&Room::Adult.id()) {
-        "✅🧘 Взрослые"
-    } else {
-        "🧘 Взрослые"
-    };
-
-    let child_room_name = if rooms.contains(&Room::Child.id()) {
-        "✅🧒 Дети"
-    } else {
-        "🧒 Дети"
-    };
-
-    buttons = buttons.append_row(vec![
-        Callback::SelectRoom(Room::Adult.id().bytes()).button(adult_room_name),
-        Callback::SelectRoom(Room::Child.id().bytes()).button(child_room_name),
-    ]);
+    let all_rooms = ctx.ledger.rooms.get_all(&mut ctx.session).await?;
+    let room_buttons = all_rooms
+        .iter()
+        .filter(|room| room.is_active)
+        .map(|room| {
+            let name = if rooms.contains(&room.id) {
+                format!("✅{}", fmt_room(room))
+            } else {
+                fmt_room(room)
+            };
+            Callback::SelectRoom(room.id.bytes()).button(name)
+        })
+        .collect::<Vec<_>>();
+    for row in room_buttons.chunks(ROOMS_PER_ROW) {
+        buttons = buttons.append_row(row.to_vec());
+    }
 
     let mut row = vec![];
     for week_day in week() {
@@ -234,7 +245,7 @@ pub async fn render_week(
                     training.clients.contains(&ctx.me.id)
                 ),
                 start_at.format("%H:%M"),
-                fmt_room_emoji(Room::from(training.room())),
+                fmt_room_emoji(all_rooms.iter().find(|r| r.id == training.room())),
                 training.name.as_str(),
             ),
             Callback::SelectTraining(training.id().into()).to_data(),
@@ -251,6 +262,14 @@ pub async fn render_week(
         buttons = buttons.append_row(Callback::Schedule.btn_row("📝  запланировать"));
     }
 
+    if ctx.has_right(Rule::EditScheduleTemplates) {
+        buttons = buttons.append_row(Callback::Templates.btn_row("🗓 Шаблоны расписания"));
+    }
+
+    if ctx.has_right(Rule::EditRooms) {
+        buttons = buttons.append_row(Callback::Rooms.btn_row("🏠 Залы"));
+    }
+
     Ok((msg, buttons))
 }
 
@@ -274,4 +293,6 @@ enum Callback {
     Schedule,
     MyTrainings,
     SelectRoom([u8; 12]),
+    Templates,
+    Rooms,
 }
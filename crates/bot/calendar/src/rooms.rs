@@ -0,0 +1,141 @@
+use async_trait::async_trait;
+use bot_core::{
+    callback_data::Calldata as _,
+    calldata,
+    context::Context,
+    widget::{Jmp, View},
+};
+use bot_viewer::rooms::fmt_room;
+use eyre::Result;
+use model::rights::Rule;
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use teloxide::{
+    types::{InlineKeyboardMarkup, Message},
+    utils::markdown::escape,
+};
+
+pub struct RoomsView;
+
+#[async_trait]
+impl View for RoomsView {
+    fn name(&self) -> &'static str {
+        "RoomsView"
+    }
+
+    async fn show(&mut self, ctx: &mut Context) -> Result<()> {
+        ctx.ensure(Rule::EditRooms)?;
+        let rooms = ctx.ledger.rooms.get_all(&mut ctx.session).await?;
+
+        let mut msg = "🏠 *Залы*\n".to_string();
+        let mut keymap = InlineKeyboardMarkup::default();
+        for room in &rooms {
+            let capacity = if room.capacity == 0 {
+                "без ограничений".to_string()
+            } else {
+                format!("до {} чел\\.", room.capacity)
+            };
+            msg.push_str(&format!(
+                "\n{} {}: {}",
+                if room.is_active { "🟢" } else { "⛔" },
+                escape(&fmt_room(room)),
+                capacity
+            ));
+            keymap = keymap.append_row(vec![
+                Callback::Toggle(room.id.bytes()).button(format!(
+                    "{} {}",
+                    if room.is_active { "⛔" } else { "🟢" },
+                    fmt_room(room)
+                )),
+                Callback::Up(room.id.bytes()).button("⬆️"),
+                Callback::Down(room.id.bytes()).button("⬇️"),
+            ]);
+        }
+        keymap = keymap.append_row(Callback::Create.btn_row("➕ Добавить зал"));
+        ctx.edit_origin(&msg, keymap).await?;
+        Ok(())
+    }
+
+    async fn handle_callback(&mut self, ctx: &mut Context, data: &str) -> Result<Jmp> {
+        ctx.ensure(Rule::EditRooms)?;
+        match calldata!(data) {
+            Callback::Toggle(id) => {
+                let id = ObjectId::from_bytes(id);
+                let room = ctx
+                    .ledger
+                    .rooms
+                    .get_by_id(&mut ctx.session, id)
+                    .await?
+                    .ok_or_else(|| eyre::eyre!("Room not found"))?;
+                ctx.ledger
+                    .rooms
+                    .set_active(&mut ctx.session, id, !room.is_active)
+                    .await?;
+            }
+            Callback::Up(id) => {
+                ctx.ledger
+                    .rooms
+                    .move_room(&mut ctx.session, ObjectId::from_bytes(id), true)
+                    .await?;
+            }
+            Callback::Down(id) => {
+                ctx.ledger
+                    .rooms
+                    .move_room(&mut ctx.session, ObjectId::from_bytes(id), false)
+                    .await?;
+            }
+            Callback::Create => return Ok(CreateRoom.into()),
+        }
+        Ok(Jmp::Stay)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+enum Callback {
+    Toggle([u8; 12]),
+    Up([u8; 12]),
+    Down([u8; 12]),
+    Create,
+}
+
+pub struct CreateRoom;
+
+#[async_trait]
+impl View for CreateRoom {
+    fn name(&self) -> &'static str {
+        "CreateRoom"
+    }
+
+    async fn show(&mut self, ctx: &mut Context) -> Result<()> {
+        ctx.ensure(Rule::EditRooms)?;
+        let msg = "Введите название, эмодзи и вместимость зала через пробел\\.\nНапример: _Малый зал 🌿 8_\nВместимость 0 \\- без ограничений";
+        ctx.edit_origin(msg, InlineKeyboardMarkup::default()).await?;
+        Ok(())
+    }
+
+    async fn handle_message(&mut self, ctx: &mut Context, msg: &Message) -> Result<Jmp> {
+        ctx.ensure(Rule::EditRooms)?;
+        ctx.delete_msg(msg.id).await?;
+        let Some((name, emoji, capacity)) = parse_room(msg.text().unwrap_or_default()) else {
+            ctx.send_notification("Неверный формат").await;
+            return Ok(Jmp::Stay);
+        };
+        ctx.ledger
+            .rooms
+            .create(&mut ctx.session, name, emoji, capacity)
+            .await?;
+        ctx.send_notification("Зал добавлен").await;
+        Ok(Jmp::Back)
+    }
+}
+
+fn parse_room(text: &str) -> Option<(String, String, u32)> {
+    let mut parts = text.split_whitespace().rev();
+    let capacity = parts.next()?.parse().ok()?;
+    let emoji = parts.next()?.to_string();
+    let name = parts.rev().collect::<Vec<_>>().join(" ");
+    if name.is_empty() {
+        return None;
+    }
+    Some((name, emoji, capacity))
+}
@@ -0,0 +1,238 @@
+use async_trait::async_trait;
+use bot_core::{
+    callback_data::Calldata as _,
+    calldata,
+    context::Context,
+    widget::{Jmp, View},
+};
+use bot_viewer::day::{fmt_date, fmt_dm, fmt_weekday};
+use chrono::{Local, Weekday};
+use eyre::Result;
+use model::{
+    ids::{DayId, WeekId},
+    rights::Rule,
+    schedule::{DayDiff, ScheduleTemplate},
+};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use teloxide::{types::InlineKeyboardMarkup, utils::markdown::escape};
+
+const MAX_DIFF_DAYS: usize = 15;
+
+pub struct ScheduleTemplatesView {
+    week_id: WeekId,
+}
+
+impl ScheduleTemplatesView {
+    pub fn new(week_id: WeekId) -> Self {
+        Self { week_id }
+    }
+}
+
+#[async_trait]
+impl View for ScheduleTemplatesView {
+    fn name(&self) -> &'static str {
+        "ScheduleTemplatesView"
+    }
+
+    async fn show(&mut self, ctx: &mut Context) -> Result<()> {
+        ctx.ensure(Rule::EditScheduleTemplates)?;
+        let templates = ctx.ledger.schedule.get_all(&mut ctx.session).await?;
+
+        let mut msg = "🗓 *Шаблоны расписания*\n".to_string();
+        if templates.is_empty() {
+            msg.push_str("_Шаблонов нет_\n");
+        }
+
+        let mut keymap = InlineKeyboardMarkup::default();
+        for template in &templates {
+            msg.push_str(&format!("\n{}", fmt_template_period(template)));
+            keymap = keymap.append_row(
+                Callback::Select(template.id.bytes())
+                    .btn_row(format!("👁 {}", template.name)),
+            );
+        }
+
+        keymap = keymap.append_row(Callback::CreateFromWeek.btn_row(format!(
+            "➕ Создать из недели {}",
+            self.week_id.local().format("%d.%m")
+        )));
+        ctx.edit_origin(&msg, keymap).await?;
+        Ok(())
+    }
+
+    async fn handle_callback(&mut self, ctx: &mut Context, data: &str) -> Result<Jmp> {
+        ctx.ensure(Rule::EditScheduleTemplates)?;
+        match calldata!(data) {
+            Callback::Select(id) => Ok(TemplateView::new(ObjectId::from_bytes(id)).into()),
+            Callback::CreateFromWeek => {
+                let effective_from = WeekId::default().next().day(Weekday::Mon);
+                let name = format!("Неделя {}", self.week_id.local().format("%d.%m.%Y"));
+                let template = ctx
+                    .ledger
+                    .schedule
+                    .create_from_week(&mut ctx.session, name, self.week_id, effective_from)
+                    .await?;
+                Ok(TemplateView::new(template.id).into())
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+enum Callback {
+    Select([u8; 12]),
+    CreateFromWeek,
+}
+
+pub struct TemplateView {
+    id: ObjectId,
+}
+
+impl TemplateView {
+    pub fn new(id: ObjectId) -> Self {
+        Self { id }
+    }
+}
+
+#[async_trait]
+impl View for TemplateView {
+    fn name(&self) -> &'static str {
+        "TemplateView"
+    }
+
+    async fn show(&mut self, ctx: &mut Context) -> Result<()> {
+        ctx.ensure(Rule::EditScheduleTemplates)?;
+        let template = ctx
+            .ledger
+            .schedule
+            .get_by_id(&mut ctx.session, self.id)
+            .await?
+            .ok_or_else(|| eyre::eyre!("Template not found"))?;
+
+        let mut msg = format!(
+            "🗓 *{}*\n{}\n",
+            escape(&template.name),
+            fmt_template_period(&template)
+        );
+
+        let mut trainings = template.trainings.clone();
+        trainings.sort_by_key(|t| (t.weekday.num_days_from_monday(), t.hour, t.minute));
+        for training in &trainings {
+            let name = ctx
+                .ledger
+                .programs
+                .get_by_id(&mut ctx.session, training.proto_id)
+                .await?
+                .map(|p| p.name)
+                .unwrap_or_default();
+            msg.push_str(&format!(
+                "\n{} {:02}:{:02} {}",
+                fmt_weekday(training.weekday),
+                training.hour,
+                training.minute,
+                escape(&name)
+            ));
+        }
+
+        let diffs = ctx.ledger.schedule.preview(&mut ctx.session, self.id).await?;
+        msg.push_str("\n\n*Изменения в уже созданных днях:*");
+        if diffs.is_empty() {
+            msg.push_str("\n_нет_");
+        }
+        for diff in diffs.iter().take(MAX_DIFF_DAYS) {
+            msg.push_str(&fmt_diff(diff));
+        }
+        if diffs.len() > MAX_DIFF_DAYS {
+            msg.push_str(&format!(
+                "\n_и еще {} дн\\._",
+                diffs.len() - MAX_DIFF_DAYS
+            ));
+        }
+
+        let mut keymap = InlineKeyboardMarkup::default();
+        if diffs.iter().any(|d| !d.add.is_empty() || !d.remove.is_empty()) {
+            keymap = keymap.append_row(TemplateCallback::Apply.btn_row("✅ Применить"));
+        }
+        if template.effective_from > DayId::default().id() {
+            keymap = keymap.append_row(TemplateCallback::Delete.btn_row("🗑 Удалить"));
+        }
+        ctx.edit_origin(&msg, keymap).await?;
+        Ok(())
+    }
+
+    async fn handle_callback(&mut self, ctx: &mut Context, data: &str) -> Result<Jmp> {
+        ctx.ensure(Rule::EditScheduleTemplates)?;
+        match calldata!(data) {
+            TemplateCallback::Apply => {
+                let diffs = ctx.ledger.schedule.apply(&mut ctx.session, self.id).await?;
+                let blocked: usize = diffs.iter().map(|d| d.blocked.len()).sum();
+                let mut msg = "Шаблон применен ✅".to_string();
+                if blocked > 0 {
+                    msg.push_str(&format!(
+                        "\nНе изменено тренировок с записанными клиентами: {}",
+                        blocked
+                    ));
+                }
+                ctx.send_notification(&msg).await;
+                Ok(Jmp::Stay)
+            }
+            TemplateCallback::Delete => {
+                ctx.ledger
+                    .schedule
+                    .delete(&mut ctx.session, self.id)
+                    .await?;
+                Ok(Jmp::Back)
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+enum TemplateCallback {
+    Apply,
+    Delete,
+}
+
+fn fmt_template_period(template: &ScheduleTemplate) -> String {
+    let from = fmt_date(&template.effective_from.with_timezone(&Local)).to_string();
+    match template.effective_to {
+        Some(to) => format!(
+            "_{}_: с {} по {}",
+            escape(&template.name),
+            from,
+            fmt_date(&to.with_timezone(&Local))
+        ),
+        None => format!("_{}_: с {} бессрочно", escape(&template.name), from),
+    }
+}
+
+fn fmt_diff(diff: &DayDiff) -> String {
+    let mut msg = format!(
+        "\n{} {}:",
+        fmt_weekday(diff.day_id.week_day()),
+        fmt_dm(&diff.day_id.local())
+    );
+    for training in &diff.add {
+        msg.push_str(&format!(
+            "\n ➕ {} {}",
+            training.get_slot().start_at().format("%H:%M"),
+            escape(&training.name)
+        ));
+    }
+    for training in &diff.remove {
+        msg.push_str(&format!(
+            "\n ➖ {} {}",
+            training.get_slot().start_at().format("%H:%M"),
+            escape(&training.name)
+        ));
+    }
+    for training in &diff.blocked {
+        msg.push_str(&format!(
+            "\n ⚠️ {} {} \\(есть записи\\)",
+            training.get_slot().start_at().format("%H:%M"),
+            escape(&training.name)
+        ));
+    }
+    msg
+}
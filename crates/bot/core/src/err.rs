@@ -1,7 +1,11 @@
 use crate::{context::Context, widget::Jmp};
 use chrono::Local;
 use eyre::{Error, Result};
-use model::{errors::LedgerError, training::TrainingId, user::rate::Rate};
+use model::{
+    errors::LedgerError,
+    training::{TrainingId, TrainingStatus},
+    user::rate::Rate,
+};
 use mongodb::bson::oid::ObjectId;
 use teloxide::utils::markdown::escape;
 
@@ -89,6 +93,20 @@ pub async fn bassness_error(ctx: &mut Context, err: &LedgerError) -> Result<Opti
         }
         LedgerError::WrongTrainingClients { .. } => return Ok(None),
         LedgerError::RequestNotFound { id } => format!("Ошибка:*Заявка {} не найдена*", id),
+        LedgerError::SubscriptionHasLockedBalance { .. } => {
+            "Ошибка:*В абонементе есть занятия в резерве*".to_string()
+        }
+        LedgerError::SubscriptionHasDebt { .. } => {
+            "Ошибка:*Абонемент не оплачен полностью: сначала погасите рассрочку*".to_string()
+        }
+        LedgerError::SubscriptionNotFound(_) => "Ошибка:*Абонемент не найден*".to_string(),
+        LedgerError::InvalidSaleParams => "Ошибка:*Неверные параметры продажи*".to_string(),
+        LedgerError::TrialAlreadyUsed { phone } => {
+            format!(
+                "Ошибка:*Пробное занятие для номера {} уже использовано*",
+                escape(phone)
+            )
+        }
         LedgerError::ProgramNotFound(object_id) => {
             format!("Ошибка:*Программа {} не найдена*", object_id)
         }
@@ -110,8 +128,11 @@ pub async fn bassness_error(ctx: &mut Context, err: &LedgerError) -> Result<Opti
                 user_name(ctx, *object_id).await?
             )
         }
-        LedgerError::TooCloseToStart { start_at: _ } => {
-            "Ошибка:*Тренировка должна начаться не ранее чем за 3 часа от начала*".to_string()
+        LedgerError::TooCloseToStart { start_at } => {
+            format!(
+                "Ошибка:*Тренировку в {} поздно планировать: запись на нее уже закрыта*",
+                start_at.format("%d\\.%m\\.%Y %H:%M")
+            )
         }
         LedgerError::TimeSlotCollision(training) => {
             format!(
@@ -120,13 +141,39 @@ pub async fn bassness_error(ctx: &mut Context, err: &LedgerError) -> Result<Opti
                 training.get_slot().start_at().format("%d\\.%m\\.%Y %H:%M")
             )
         }
-        LedgerError::TrainingNotOpenToSignUp(training_id, _) => {
+        LedgerError::InstructorIsBusy(training) => {
             format!(
-                "Ошибка:*Тренировка {} в {} закрыта для записи*",
-                training_name(ctx, training_id).await?,
-                training_id.start_at().format("%d\\.%m\\.%Y %H:%M")
+                "Ошибка:*{} в это время ведет тренировку {} в {} \\({}\\)*",
+                user_name(ctx, training.instructor).await?,
+                escape(&training.name),
+                training.get_slot().start_at().format("%d\\.%m\\.%Y %H:%M"),
+                room_name(ctx, training.room()).await?
             )
         }
+        LedgerError::InstructorIsUnavailable(absence) => {
+            format!(
+                "Ошибка:*{} отсутствует с {} по {}: {}*",
+                user_name(ctx, absence.instructor).await?,
+                absence.from().format("%d\\.%m\\.%Y %H:%M"),
+                absence.to().format("%d\\.%m\\.%Y %H:%M"),
+                escape(&absence.reason)
+            )
+        }
+        LedgerError::TrainingNotOpenToSignUp(training_id, status) => {
+            if *status == TrainingStatus::NotOpenToSignup {
+                format!(
+                    "Ошибка:*Запись на тренировку {} в {} еще не открыта*",
+                    training_name(ctx, training_id).await?,
+                    training_id.start_at().format("%d\\.%m\\.%Y %H:%M")
+                )
+            } else {
+                format!(
+                    "Ошибка:*Тренировка {} в {} закрыта для записи*",
+                    training_name(ctx, training_id).await?,
+                    training_id.start_at().format("%d\\.%m\\.%Y %H:%M")
+                )
+            }
+        }
         LedgerError::ClientAlreadySignedUp(object_id, training_id) => {
             format!(
                 "Ошибка:*Клиент {} уже записан на тренировку {}*",
@@ -142,19 +189,53 @@ pub async fn bassness_error(ctx: &mut Context, err: &LedgerError) -> Result<Opti
             )
         }
         LedgerError::NotEnoughBalance(_) => "Ошибка:*Нет подходящего абонемента*".to_string(),
-        LedgerError::TrainingNotFound(training_id) => {
+        LedgerError::TrainingIsNotFull(training_id) => {
             format!(
-                "Ошибка:*Тренировка {} не найдена*",
+                "Ошибка:*На тренировке {} в {} есть свободные места*",
+                training_name(ctx, training_id).await?,
                 training_id.start_at().format("%d\\.%m\\.%Y %H:%M")
             )
         }
-        LedgerError::TrainingNotOpenToSignOut(training_id) => {
+        LedgerError::ClientAlreadyInWaitlist(object_id, training_id) => {
             format!(
-                "Ошибка:*Тренировка {} в {} закрыта для отмены записи*",
-                training_name(ctx, training_id).await?,
+                "Ошибка:*Клиент {} уже в листе ожидания на тренировку {}*",
+                user_name(ctx, *object_id).await?,
+                training_name(ctx, training_id).await?
+            )
+        }
+        LedgerError::ClientNotInWaitlist(object_id, training_id) => {
+            format!(
+                "Ошибка:*Клиента {} нет в листе ожидания на тренировку {}*",
+                user_name(ctx, *object_id).await?,
+                training_name(ctx, training_id).await?
+            )
+        }
+        LedgerError::TrainingNotFound(training_id) => {
+            format!(
+                "Ошибка:*Тренировка {} не найдена*",
                 training_id.start_at().format("%d\\.%m\\.%Y %H:%M")
             )
         }
+        LedgerError::TrainingNotOpenToSignOut(training_id) => {
+            let training = ctx
+                .ledger
+                .calendar
+                .get_training_by_id(&mut ctx.session, *training_id)
+                .await?;
+            match training {
+                Some(training) => format!(
+                    "Ошибка:*Отменить запись на тренировку {} в {} можно было до {}*",
+                    escape(&training.name),
+                    training_id.start_at().format("%d\\.%m\\.%Y %H:%M"),
+                    training.sign_out_deadline().format("%d\\.%m\\.%Y %H:%M")
+                ),
+                None => format!(
+                    "Ошибка:*Тренировка {} в {} закрыта для отмены записи*",
+                    training_name(ctx, training_id).await?,
+                    training_id.start_at().format("%d\\.%m\\.%Y %H:%M")
+                ),
+            }
+        }
         LedgerError::ClientNotSignedUp(object_id, training_id) => {
             format!(
                 "Ошибка:*Клиент {} не записан на тренировку {}*",
@@ -168,6 +249,13 @@ pub async fn bassness_error(ctx: &mut Context, err: &LedgerError) -> Result<Opti
                 user_name(ctx, *object_id).await?
             )
         }
+        LedgerError::AttendanceIsNotOpen(training_id) => {
+            format!(
+                "Ошибка:*Посещаемость тренировки {} в {} можно отметить только после начала и до завершения*",
+                training_name(ctx, training_id).await?,
+                training_id.start_at().format("%d\\.%m\\.%Y %H:%M")
+            )
+        }
         LedgerError::TrainingHasClients(training_id) => {
             format!(
                 "Ошибка:*Тренировка {} в {} имеет записанных клиентов*",
@@ -175,6 +263,72 @@ pub async fn bassness_error(ctx: &mut Context, err: &LedgerError) -> Result<Opti
                 training_id.start_at().format("%d\\.%m\\.%Y %H:%M")
             )
         }
+        LedgerError::PromoCodeNotFound { code } => {
+            format!("Ошибка:*Промокод {} не найден*", escape(code))
+        }
+        LedgerError::PromoCodeAlreadyExists { code } => {
+            format!("Ошибка:*Промокод {} уже существует*", escape(code))
+        }
+        LedgerError::PromoCodeExpired { code } => {
+            format!("Ошибка:*Промокод {} сейчас не действует*", escape(code))
+        }
+        LedgerError::PromoCodeNotApplicable { code } => {
+            format!(
+                "Ошибка:*Промокод {} не действует на этот абонемент*",
+                escape(code)
+            )
+        }
+        LedgerError::PromoCodeExhausted { code } => {
+            format!("Ошибка:*Промокод {} больше не действует*", escape(code))
+        }
+        LedgerError::PromoCodeClientLimit { code } => {
+            format!(
+                "Ошибка:*Клиент уже использовал промокод {}*",
+                escape(code)
+            )
+        }
+        LedgerError::GiftCertificateNotFound { code } => {
+            format!("Ошибка:*Сертификат {} не найден*", escape(code))
+        }
+        LedgerError::GiftCertificateExpired { code } => {
+            format!("Ошибка:*Срок действия сертификата {} истек*", escape(code))
+        }
+        LedgerError::GiftCertificateRedeemed { code } => {
+            format!("Ошибка:*Сертификат {} уже использован*", escape(code))
+        }
+        LedgerError::GiftCertificateNotApplicable { code } => {
+            format!(
+                "Ошибка:*Сертификат {} не покрывает этот абонемент*",
+                escape(code)
+            )
+        }
+        LedgerError::UpgradeCreditExceedsPrice { credit, price } => {
+            format!(
+                "Ошибка:*Остаток абонемента {} больше цены нового абонемента {}*",
+                escape(&credit.to_string()),
+                escape(&price.to_string())
+            )
+        }
+        LedgerError::DebtNotFound(_) => "Ошибка:*Рассрочка не найдена*".to_string(),
+        LedgerError::PaymentOverdue(object_id) => {
+            format!(
+                "Ошибка:*У клиента {} просрочен платеж по рассрочке*",
+                user_name(ctx, *object_id).await?
+            )
+        }
+        LedgerError::LoyaltyDisabled => "Ошибка:*Программа лояльности отключена*".to_string(),
+        LedgerError::NotEnoughPoints { balance, required } => {
+            format!(
+                "Ошибка:*Недостаточно баллов: {} из {}*",
+                balance, required
+            )
+        }
+        LedgerError::SlotNotAvailable { start_at } => {
+            format!(
+                "Ошибка:*Время {} недоступно для записи к инструктору*",
+                start_at.format("%d\\.%m\\.%Y %H:%M")
+            )
+        }
         LedgerError::DayIdMismatch { old, new } => {
             format!(
                 "Ошибка:*День {} не совпадает с днем {}*",
@@ -189,6 +343,12 @@ pub async fn bassness_error(ctx: &mut Context, err: &LedgerError) -> Result<Opti
                 training_id.start_at().format("%d\\.%m\\.%Y %H:%M")
             )
         }
+        LedgerError::RoomNotFound(room_id) => {
+            format!("Ошибка:*Зал {} не найден*", room_id)
+        }
+        LedgerError::RoomIsNotActive(room_id) => {
+            format!("Ошибка:*Зал {} закрыт*", room_name(ctx, *room_id).await?)
+        }
     }))
 }
 
@@ -210,6 +370,13 @@ async fn training_name(ctx: &mut Context, training_id: &TrainingId) -> Result<St
         .unwrap_or_else(|| "Тренировка не найдена".to_string()))
 }
 
+async fn room_name(ctx: &mut Context, room_id: ObjectId) -> Result<String> {
+    let room = ctx.ledger.rooms.get_by_id(&mut ctx.session, room_id).await?;
+    Ok(room
+        .map(|r| escape(&r.name))
+        .unwrap_or_else(|| obj_id(&room_id)))
+}
+
 fn rate_name(rate: &Rate) -> &'static str {
     match rate {
         Rate::Fix { .. } => "Фиксированный",
@@ -35,6 +35,7 @@ pub(crate) fn sys_button(keymap: InlineKeyboardMarkup, can_back: bool) -> Inline
 pub enum CommonLocation {
     Profile(ObjectId),
     Request(ObjectId),
+    Schedule,
 }
 
 impl CommonLocation {
@@ -46,6 +47,7 @@ impl CommonLocation {
         let name = match self {
             CommonLocation::Profile(_) => "👤 Профиль",
             CommonLocation::Request(_) => "📝 Заявка",
+            CommonLocation::Schedule => "📅 Записаться",
         };
         InlineKeyboardButton::callback(name, self.to_data())
     }
@@ -56,6 +58,7 @@ impl Calldata for CommonLocation {
         let (tp, id) = match self {
             CommonLocation::Profile(id) => ("usr", id.to_hex()),
             CommonLocation::Request(id) => ("req", id.to_hex()),
+            CommonLocation::Schedule => ("sch", String::new()),
         };
         format!("/cl/{}/{}", tp, id)
     }
@@ -81,6 +84,7 @@ impl Calldata for CommonLocation {
                 let id = parts.next()?;
                 Some(CommonLocation::Request(ObjectId::from_str(id).ok()?))
             }
+            "sch" => Some(CommonLocation::Schedule),
             _ => None,
         }
     }
@@ -126,6 +130,16 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_schedule_round_trip() {
+        let data = CommonLocation::Schedule.to_data();
+        assert_eq!(data, "/cl/sch/");
+        assert!(matches!(
+            CommonLocation::from_data(&data),
+            Some(CommonLocation::Schedule)
+        ));
+    }
+
     #[test]
     fn test_from_data_invalid() {
         let data = "/cl/invalid/12345";
@@ -0,0 +1,140 @@
+use async_trait::async_trait;
+use bot_core::{
+    callback_data::{Calldata as _, TrainingIdCallback},
+    calldata,
+    context::Context,
+    widget::{Jmp, View},
+};
+use bot_trainigs::view::TrainingView;
+use bot_viewer::day::{fmt_dt, fmt_weekday};
+use chrono::{Datelike as _, Duration, Local, NaiveDate, TimeZone as _};
+use eyre::Result;
+use model::{rights::Rule, training::Training};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use teloxide::{
+    types::{InlineKeyboardMarkup, Message},
+    utils::markdown::escape,
+};
+
+/// Vacations and sick days of the instructor.
+pub struct Absences {
+    id: ObjectId,
+    affected: Vec<Training>,
+}
+
+impl Absences {
+    pub fn new(id: ObjectId) -> Self {
+        Self {
+            id,
+            affected: vec![],
+        }
+    }
+}
+
+#[async_trait]
+impl View for Absences {
+    fn name(&self) -> &'static str {
+        "Absences"
+    }
+
+    async fn show(&mut self, ctx: &mut Context) -> Result<()> {
+        ctx.ensure(Rule::EditCouch)?;
+        let absences = ctx
+            .ledger
+            .calendar
+            .absences(&mut ctx.session, self.id)
+            .await?;
+
+        let mut msg = "🏖 *Отсутствия*\n\n".to_string();
+        let mut keymap = InlineKeyboardMarkup::default();
+        if absences.is_empty() {
+            msg.push_str("Нет запланированных отсутствий\n");
+        }
+        for absence in &absences {
+            msg.push_str(&format!(
+                "• {} \\- {} _{}_\n",
+                fmt_dt(&absence.from()),
+                fmt_dt(&absence.to()),
+                escape(&absence.reason)
+            ));
+            keymap = keymap.append_row(Callback::Delete(absence.id.bytes()).btn_row(format!(
+                "🗑 {} - {}",
+                absence.from().format("%d.%m"),
+                absence.to().format("%d.%m")
+            )));
+        }
+        msg.push_str("\nЧтобы добавить, введите первый и последний день и причину\\.\nНапример: _01\\.08\\.2025 14\\.08\\.2025 отпуск_");
+
+        if !self.affected.is_empty() {
+            msg.push_str(
+                "\n\n⚠️ *На эти дни назначены тренировки, их нужно передать другому инструктору:*",
+            );
+            for training in &self.affected {
+                let start_at = training.get_slot().start_at();
+                keymap = keymap.append_row(Callback::SelectTraining(training.id().into()).btn_row(
+                    format!(
+                        "{} {} {}",
+                        fmt_weekday(start_at.weekday()),
+                        start_at.format("%d.%m %H:%M"),
+                        training.name
+                    ),
+                ));
+            }
+        }
+
+        ctx.edit_origin(&msg, keymap).await?;
+        Ok(())
+    }
+
+    async fn handle_message(&mut self, ctx: &mut Context, msg: &Message) -> Result<Jmp> {
+        ctx.ensure(Rule::EditCouch)?;
+        ctx.delete_msg(msg.id).await?;
+        let Some((from, to, reason)) = parse_absence(msg.text().unwrap_or_default()) else {
+            ctx.send_notification("Неверный формат").await;
+            return Ok(Jmp::Stay);
+        };
+        self.affected = ctx
+            .ledger
+            .calendar
+            .add_absence(&mut ctx.session, self.id, from, to, reason)
+            .await?;
+        Ok(Jmp::Stay)
+    }
+
+    async fn handle_callback(&mut self, ctx: &mut Context, data: &str) -> Result<Jmp> {
+        ctx.ensure(Rule::EditCouch)?;
+        match calldata!(data) {
+            Callback::Delete(id) => {
+                ctx.ledger
+                    .calendar
+                    .remove_absence(&mut ctx.session, ObjectId::from_bytes(id))
+                    .await?;
+                self.affected.clear();
+                Ok(Jmp::Stay)
+            }
+            Callback::SelectTraining(id) => Ok(TrainingView::new(id.into()).into()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+enum Callback {
+    Delete([u8; 12]),
+    SelectTraining(TrainingIdCallback),
+}
+
+/// `DD.MM.YYYY DD.MM.YYYY reason`; both days are included.
+fn parse_absence(text: &str) -> Option<(chrono::DateTime<Local>, chrono::DateTime<Local>, String)> {
+    let mut parts = text.splitn(3, ' ');
+    let from = NaiveDate::parse_from_str(parts.next()?, "%d.%m.%Y").ok()?;
+    let to = NaiveDate::parse_from_str(parts.next()?, "%d.%m.%Y").ok()? + Duration::days(1);
+    let reason = parts.next().unwrap_or_default().trim().to_string();
+    let from = Local
+        .from_local_datetime(&from.and_hms_opt(0, 0, 0)?)
+        .earliest()?;
+    let to = Local
+        .from_local_datetime(&to.and_hms_opt(0, 0, 0)?)
+        .earliest()?;
+    Some((from, to, reason))
+}
@@ -0,0 +1,146 @@
+use async_trait::async_trait;
+use bot_core::{
+    callback_data::{CallbackDateTime, Calldata as _},
+    calldata,
+    context::Context,
+    widget::{Jmp, View},
+};
+use bot_trainigs::view::TrainingView;
+use bot_viewer::day::{fmt_dt, fmt_weekday};
+use chrono::{DateTime, Datelike as _, Local};
+use eyre::Result;
+use model::{subscription::SubscriptionType, user::User};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use teloxide::{
+    types::{ChatId, InlineKeyboardMarkup},
+    utils::markdown::escape,
+};
+
+const BOOKING_DAYS: u32 = 14;
+const MAX_SLOTS: usize = 40;
+
+/// Client picks a free slot in the instructor's availability windows.
+pub struct PersonalBooking {
+    instructor: ObjectId,
+    selected: Option<DateTime<Local>>,
+}
+
+impl PersonalBooking {
+    pub fn new(instructor: ObjectId) -> Self {
+        Self {
+            instructor,
+            selected: None,
+        }
+    }
+}
+
+#[async_trait]
+impl View for PersonalBooking {
+    fn name(&self) -> &'static str {
+        "PersonalBooking"
+    }
+
+    async fn show(&mut self, ctx: &mut Context) -> Result<()> {
+        let instructor = ctx
+            .ledger
+            .get_user(&mut ctx.session, self.instructor)
+            .await?;
+        let mut keymap = InlineKeyboardMarkup::default();
+
+        if let Some(start_at) = self.selected {
+            let msg = format!(
+                "📅 Записаться на персональную тренировку к {} на *{}*?",
+                escape(&instructor.name.to_string()),
+                fmt_dt(&start_at)
+            );
+            keymap = keymap.append_row(vec![
+                Callback::Confirm.button("✅ Записаться"),
+                Callback::Cancel.button("❌ Отмена"),
+            ]);
+            ctx.edit_origin(&msg, keymap).await?;
+            return Ok(());
+        }
+
+        let slots = ctx
+            .ledger
+            .calendar
+            .free_slots(&mut ctx.session, self.instructor, BOOKING_DAYS)
+            .await?;
+        let mut msg = format!(
+            "📅 *Персональная тренировка*\nИнструктор: {}\n\n",
+            escape(&instructor.name.to_string())
+        );
+        if slots.is_empty() {
+            msg.push_str("Свободного времени на ближайшие две недели нет");
+        } else {
+            msg.push_str("Выберите удобное время:");
+        }
+        for slot in slots.iter().take(MAX_SLOTS) {
+            let start_at = slot.start_at();
+            keymap = keymap.append_row(Callback::Select(start_at.into()).btn_row(format!(
+                "{} {}",
+                fmt_weekday(start_at.weekday()),
+                start_at.format("%d.%m %H:%M")
+            )));
+        }
+        ctx.edit_origin(&msg, keymap).await?;
+        Ok(())
+    }
+
+    async fn handle_callback(&mut self, ctx: &mut Context, data: &str) -> Result<Jmp> {
+        match calldata!(data) {
+            Callback::Select(start_at) => {
+                self.selected = Some(start_at.into());
+                Ok(Jmp::Stay)
+            }
+            Callback::Cancel => {
+                self.selected = None;
+                Ok(Jmp::Stay)
+            }
+            Callback::Confirm => {
+                let Some(start_at) = self.selected.take() else {
+                    return Ok(Jmp::Stay);
+                };
+                let client = ctx.me.id;
+                let id = ctx
+                    .ledger
+                    .book_personal_training(&mut ctx.session, client, self.instructor, start_at)
+                    .await?;
+
+                let instructor = ctx
+                    .ledger
+                    .get_user(&mut ctx.session, self.instructor)
+                    .await?;
+                ctx.bot
+                    .notify(
+                        ChatId(instructor.tg_id),
+                        &format!(
+                            "📅 {} записался\\(ась\\) на персональную тренировку *{}*",
+                            escape(&ctx.me.name.to_string()),
+                            fmt_dt(&start_at)
+                        ),
+                        true,
+                    )
+                    .await;
+                ctx.send_notification("Вы записаны на тренировку").await;
+                Ok(TrainingView::new(id).into())
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+enum Callback {
+    Select(CallbackDateTime),
+    Confirm,
+    Cancel,
+}
+
+/// Whether the user (or their family payer) has a personal subscription to the instructor.
+pub fn can_book(user: &User, instructor: ObjectId) -> Result<bool> {
+    Ok(user.payer()?.subscriptions().iter().any(|sub| {
+        matches!(sub.tp, SubscriptionType::Personal { couch_filter } if couch_filter == instructor)
+            && (sub.unlimited || sub.balance > 0)
+    }))
+}
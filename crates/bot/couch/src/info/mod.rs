@@ -15,7 +15,10 @@ use model::{rights::Rule, training::Training};
 use mongodb::bson::oid::ObjectId;
 use teloxide::utils::markdown::escape;
 
+mod absences;
+mod booking;
 mod edit_description;
+mod windows;
 
 pub fn couch_view(id: ObjectId) -> Widget {
     ScriptView::new("couch_info", State { id }, Stage::list(CouchInfo)).into()
@@ -39,6 +42,26 @@ impl CouchInfo {
         )))
     }
 
+    pub async fn absences(&self, ctx: &mut Context, state: &mut State) -> Result<Dispatch<State>> {
+        ctx.ensure(Rule::EditCouch)?;
+        Ok(Dispatch::Widget(absences::Absences::new(state.id).into()))
+    }
+
+    pub async fn windows(&self, ctx: &mut Context, state: &mut State) -> Result<Dispatch<State>> {
+        if ctx.me.id != state.id {
+            ctx.ensure(Rule::EditCouch)?;
+        }
+        Ok(Dispatch::Widget(
+            windows::AvailabilityWindows::new(state.id).into(),
+        ))
+    }
+
+    pub async fn book(&self, _: &mut Context, state: &mut State) -> Result<Dispatch<State>> {
+        Ok(Dispatch::Widget(
+            booking::PersonalBooking::new(state.id).into(),
+        ))
+    }
+
     pub async fn delete_couch(
         &self,
         ctx: &mut Context,
@@ -91,8 +114,16 @@ impl StageList<State> for CouchInfo {
             .map(|training| vec![make_item(training, ctx, now)])
             .collect::<Vec<Vec<ListItem>>>();
 
+        let me = ctx.ledger.get_user(&mut ctx.session, ctx.me.id).await?;
+        if booking::can_book(&me, user.id)? {
+            row.push(vec![Action::Book.button()]);
+        }
+        if ctx.has_right(Rule::EditCouch) || ctx.me.id == user.id {
+            row.push(vec![Action::Windows.button()]);
+        }
         if ctx.has_right(Rule::EditCouch) {
             row.push(vec![Action::ChangeDescription.button()]);
+            row.push(vec![Action::Absences.button()]);
             row.push(vec![Action::DeleteCouch.button()]);
         }
 
@@ -112,6 +143,9 @@ impl StageList<State> for CouchInfo {
                 match action {
                     Action::ChangeDescription => self.change_description(ctx, state).await,
                     Action::DeleteCouch => self.delete_couch(ctx, state).await,
+                    Action::Absences => self.absences(ctx, state).await,
+                    Action::Windows => self.windows(ctx, state).await,
+                    Action::Book => self.book(ctx, state).await,
                 }
             }
             _ => Err(eyre::eyre!("Invalid id")),
@@ -141,6 +175,9 @@ fn make_item(training: Training, ctx: &mut Context, now: DateTime<Local>) -> Lis
 pub enum Action {
     ChangeDescription,
     DeleteCouch,
+    Absences,
+    Windows,
+    Book,
 }
 
 impl Action {
@@ -154,6 +191,18 @@ impl Action {
                 id: ListId::I64(1),
                 name: "🗑 Удалить профиль".to_string(),
             },
+            Self::Absences => ListItem {
+                id: ListId::I64(2),
+                name: "🏖 Отсутствия".to_string(),
+            },
+            Self::Windows => ListItem {
+                id: ListId::I64(3),
+                name: "🕒 Окна для персональных".to_string(),
+            },
+            Self::Book => ListItem {
+                id: ListId::I64(4),
+                name: "📅 Записаться на персональную".to_string(),
+            },
         }
     }
 }
@@ -165,6 +214,9 @@ impl TryFrom<ListId> for Action {
         match value {
             ListId::I64(0) => Ok(Self::ChangeDescription),
             ListId::I64(1) => Ok(Self::DeleteCouch),
+            ListId::I64(2) => Ok(Self::Absences),
+            ListId::I64(3) => Ok(Self::Windows),
+            ListId::I64(4) => Ok(Self::Book),
             _ => Err(eyre::eyre!("Invalid id")),
         }
     }
@@ -0,0 +1,162 @@
+use async_trait::async_trait;
+use bot_core::{
+    callback_data::Calldata as _,
+    calldata,
+    context::Context,
+    widget::{Jmp, View},
+};
+use bot_viewer::{day::fmt_weekday, rooms::fmt_room};
+use chrono::{NaiveTime, Timelike as _, Weekday};
+use eyre::Result;
+use model::rights::Rule;
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use teloxide::{
+    types::{InlineKeyboardMarkup, Message},
+    utils::markdown::escape,
+};
+
+/// Weekly hours when clients can book personal trainings with the instructor.
+pub struct AvailabilityWindows {
+    id: ObjectId,
+    room: Option<ObjectId>,
+}
+
+impl AvailabilityWindows {
+    pub fn new(id: ObjectId) -> Self {
+        Self { id, room: None }
+    }
+
+    fn ensure_access(&self, ctx: &Context) -> Result<()> {
+        if ctx.me.id != self.id {
+            ctx.ensure(Rule::EditCouch)?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl View for AvailabilityWindows {
+    fn name(&self) -> &'static str {
+        "AvailabilityWindows"
+    }
+
+    async fn show(&mut self, ctx: &mut Context) -> Result<()> {
+        self.ensure_access(ctx)?;
+        let windows = ctx
+            .ledger
+            .calendar
+            .availability_windows(&mut ctx.session, self.id)
+            .await?;
+        let rooms = ctx.ledger.rooms.get_active(&mut ctx.session).await?;
+        if self.room.is_none() {
+            self.room = rooms.first().map(|room| room.id);
+        }
+
+        let mut msg = "🕒 *Окна для персональных тренировок*\n\n".to_string();
+        let mut keymap = InlineKeyboardMarkup::default();
+        if windows.is_empty() {
+            msg.push_str("Окна не заданы, клиенты не могут записаться самостоятельно\n");
+        }
+        for window in &windows {
+            let room = rooms
+                .iter()
+                .find(|room| room.id == window.room)
+                .map(fmt_room)
+                .unwrap_or_default();
+            let name = format!(
+                "{} {} - {} {}",
+                fmt_weekday(window.weekday),
+                fmt_minutes(window.from_min),
+                fmt_minutes(window.to_min),
+                room
+            );
+            msg.push_str(&format!("• {}\n", escape(&name)));
+            keymap = keymap
+                .append_row(Callback::Delete(window.id.bytes()).btn_row(format!("🗑 {}", name)));
+        }
+        msg.push_str(
+            "\nЧтобы добавить окно, выберите зал и введите день недели и время\\.\nНапример: _пн 10:00 14:00_",
+        );
+        for room in &rooms {
+            let selected = self.room == Some(room.id);
+            keymap = keymap.append_row(Callback::SelectRoom(room.id.bytes()).btn_row(format!(
+                "{} {}",
+                if selected { "✅" } else { "⬜" },
+                fmt_room(room)
+            )));
+        }
+
+        ctx.edit_origin(&msg, keymap).await?;
+        Ok(())
+    }
+
+    async fn handle_message(&mut self, ctx: &mut Context, msg: &Message) -> Result<Jmp> {
+        self.ensure_access(ctx)?;
+        ctx.delete_msg(msg.id).await?;
+        let Some((weekday, from_min, to_min)) = parse_window(msg.text().unwrap_or_default()) else {
+            ctx.send_notification("Неверный формат").await;
+            return Ok(Jmp::Stay);
+        };
+        let Some(room) = self.room else {
+            ctx.send_notification("Выберите зал").await;
+            return Ok(Jmp::Stay);
+        };
+        ctx.ledger
+            .calendar
+            .add_availability_window(&mut ctx.session, self.id, weekday, from_min, to_min, room)
+            .await?;
+        Ok(Jmp::Stay)
+    }
+
+    async fn handle_callback(&mut self, ctx: &mut Context, data: &str) -> Result<Jmp> {
+        self.ensure_access(ctx)?;
+        match calldata!(data) {
+            Callback::Delete(id) => {
+                ctx.ledger
+                    .calendar
+                    .remove_availability_window(&mut ctx.session, ObjectId::from_bytes(id))
+                    .await?;
+            }
+            Callback::SelectRoom(room) => {
+                self.room = Some(ObjectId::from_bytes(room));
+            }
+        }
+        Ok(Jmp::Stay)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+enum Callback {
+    Delete([u8; 12]),
+    SelectRoom([u8; 12]),
+}
+
+fn fmt_minutes(min: u32) -> String {
+    format!("{:02}:{:02}", min / 60, min % 60)
+}
+
+/// `пн 10:00 14:00`
+fn parse_window(text: &str) -> Option<(Weekday, u32, u32)> {
+    let mut parts = text.split_whitespace();
+    let weekday = match parts.next()?.to_lowercase().as_str() {
+        "пн" => Weekday::Mon,
+        "вт" => Weekday::Tue,
+        "ср" => Weekday::Wed,
+        "чт" => Weekday::Thu,
+        "пт" => Weekday::Fri,
+        "сб" => Weekday::Sat,
+        "вс" => Weekday::Sun,
+        _ => return None,
+    };
+    let mut minutes = || {
+        let time = NaiveTime::parse_from_str(parts.next()?, "%H:%M").ok()?;
+        Some(time.hour() * 60 + time.minute())
+    };
+    let from = minutes()?;
+    let to = match minutes()? {
+        0 => 24 * 60,
+        to => to,
+    };
+    Some((weekday, from, to))
+}
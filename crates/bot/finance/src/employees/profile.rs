@@ -5,7 +5,7 @@ use bot_core::{
     context::Context,
     widget::{Jmp, View},
 };
-use bot_trainigs::list::TrainingList;
+use bot_trainigs::{list::TrainingList, view::notify_promoted};
 use bot_users::{
     history::HistoryList, rewards::RewardsList, rights::UserRightsView, set_ai_prompt::SetAiPrompt, set_birthday::SetBirthday, set_fio::SetFio, set_phone::SetPhone
 };
@@ -35,9 +35,13 @@ impl EmployeeProfile {
             .get(&mut ctx.session, self.id)
             .await?
             .ok_or_else(|| eyre::eyre!("User not found"))?;
-        ctx.ledger
+        let promoted = ctx
+            .ledger
             .block_user(&mut ctx.session, self.id, !user.is_active)
             .await?;
+        for (training, waiter) in promoted {
+            notify_promoted(ctx, &training, Some(waiter)).await?;
+        }
         ctx.reload_user().await?;
         Ok(Jmp::Stay)
     }
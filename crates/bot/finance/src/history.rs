@@ -90,6 +90,21 @@ pub fn make_list_item(idx: usize, event: &TreasuryEvent) -> ListItem {
         model::treasury::Event::Marketing(come_from) => {
             format!("📊{} Маркетинг \\({}\\)", idx, come_from.name())
         }
+        model::treasury::Event::Refund(refund) => {
+            format!("{} 📉 возврат за {}", idx, escape(&refund.name))
+        }
+        model::treasury::Event::SellGiftCertificate(_) => {
+            format!("{} 📈 продажа сертификата", idx)
+        }
+        model::treasury::Event::RedeemGiftCertificate(redeem) => {
+            format!("{} 🎁 сертификат на {}", idx, escape(&redeem.info.name))
+        }
+        model::treasury::Event::UpgradeSubscription(upgrade) => {
+            format!("{} 📈 переход на {}", idx, escape(&upgrade.info.name))
+        }
+        model::treasury::Event::PayInstallment(pay) => {
+            format!("{} 📈 рассрочка за {}", idx, escape(&pay.name))
+        }
     };
 
     ListItem {
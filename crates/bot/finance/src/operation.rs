@@ -79,11 +79,20 @@ async fn render_event(ctx: &mut Context, event: &TreasuryEvent) -> Result<String
                 model::treasury::subs::UserId::None => "-".to_string(),
             };
 
-            format!(
-                "🛒 Продажа абонемента: {}р пользователю {}",
-                event.sum(),
-                user
-            )
+            if sell_subscription.debt.is_zero() {
+                format!(
+                    "🛒 Продажа абонемента: {}р пользователю {}",
+                    event.sum(),
+                    user
+                )
+            } else {
+                format!(
+                    "🛒 Продажа абонемента: {}р пользователю {}\nВ рассрочку: {}р",
+                    event.sum(),
+                    user,
+                    sell_subscription.debt
+                )
+            }
         }
         model::treasury::Event::Reward(user_id) => {
             let user = match user_id {
@@ -122,6 +131,105 @@ async fn render_event(ctx: &mut Context, event: &TreasuryEvent) -> Result<String
         model::treasury::Event::Marketing(come_from) => {
             format!("📊 Маркетинг: {} руб. ({})", event.sum(), come_from.name())
         }
+        model::treasury::Event::Refund(refund) => {
+            let user = match &refund.buyer_id {
+                model::treasury::subs::UserId::Id(object_id) => ctx
+                    .ledger
+                    .get_user(&mut ctx.session, *object_id)
+                    .await
+                    .ok()
+                    .map(|user| user.name.to_string())
+                    .unwrap_or_else(|| "-".to_string()),
+                model::treasury::subs::UserId::Phone(phone) => phone.to_owned(),
+                model::treasury::subs::UserId::None => "-".to_string(),
+            };
+            format!(
+                "↩️ Возврат за абонемент {}: {} руб. пользователю {}\nЗанятий: {}\nУдержано: {} руб.",
+                refund.name,
+                event.sum(),
+                user,
+                refund.items,
+                refund.fee
+            )
+        }
+        model::treasury::Event::SellGiftCertificate(sell) => {
+            let user = match &sell.buyer_id {
+                model::treasury::subs::UserId::Id(object_id) => ctx
+                    .ledger
+                    .get_user(&mut ctx.session, *object_id)
+                    .await
+                    .ok()
+                    .map(|user| user.name.to_string())
+                    .unwrap_or_else(|| "-".to_string()),
+                model::treasury::subs::UserId::Phone(phone) => phone.to_owned(),
+                model::treasury::subs::UserId::None => "-".to_string(),
+            };
+            format!(
+                "🎁 Продажа подарочного сертификата {}: {} руб. пользователю {}",
+                sell.code,
+                event.sum(),
+                user
+            )
+        }
+        model::treasury::Event::RedeemGiftCertificate(redeem) => {
+            let user = match &redeem.user_id {
+                model::treasury::subs::UserId::Id(object_id) => ctx
+                    .ledger
+                    .get_user(&mut ctx.session, *object_id)
+                    .await
+                    .ok()
+                    .map(|user| user.name.to_string())
+                    .unwrap_or_else(|| "-".to_string()),
+                model::treasury::subs::UserId::Phone(phone) => phone.to_owned(),
+                model::treasury::subs::UserId::None => "-".to_string(),
+            };
+            format!(
+                "🎁 Сертификат {} обменян на абонемент {} пользователем {}\nВыручка: {} руб.",
+                redeem.code, redeem.info.name, user, redeem.amount
+            )
+        }
+        model::treasury::Event::UpgradeSubscription(upgrade) => {
+            let user = match &upgrade.buyer_id {
+                model::treasury::subs::UserId::Id(object_id) => ctx
+                    .ledger
+                    .get_user(&mut ctx.session, *object_id)
+                    .await
+                    .ok()
+                    .map(|user| user.name.to_string())
+                    .unwrap_or_else(|| "-".to_string()),
+                model::treasury::subs::UserId::Phone(phone) => phone.to_owned(),
+                model::treasury::subs::UserId::None => "-".to_string(),
+            };
+            format!(
+                "⬆️ Переход с абонемента {} на {}: {} руб. пользователь {}\nЗачтено занятий: {} на {} руб.",
+                upgrade.name,
+                upgrade.info.name,
+                event.sum(),
+                user,
+                upgrade.items,
+                upgrade.credit
+            )
+        }
+        model::treasury::Event::PayInstallment(pay) => {
+            let user = match &pay.buyer_id {
+                model::treasury::subs::UserId::Id(object_id) => ctx
+                    .ledger
+                    .get_user(&mut ctx.session, *object_id)
+                    .await
+                    .ok()
+                    .map(|user| user.name.to_string())
+                    .unwrap_or_else(|| "-".to_string()),
+                model::treasury::subs::UserId::Phone(phone) => phone.to_owned(),
+                model::treasury::subs::UserId::None => "-".to_string(),
+            };
+            format!(
+                "💳 Платеж по рассрочке за абонемент {}: {} руб. пользователь {}\nОстаток долга: {} руб.",
+                pay.name,
+                event.sum(),
+                user,
+                pay.remaining
+            )
+        }
     };
 
     Ok(format!(
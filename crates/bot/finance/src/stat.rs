@@ -74,6 +74,31 @@ impl View for Stat {
             "Другие расходы:_{}_",
             escape(&stat.outcome.other.sum.to_string())
         )?;
+        writeln!(
+            &mut text,
+            "Возвраты за абонементы:_{}_ на сумму _{}_",
+            stat.outcome.refunds.count,
+            escape(&stat.outcome.refunds.sum.to_string())
+        )?;
+
+        writeln!(&mut text, "*Подарочные сертификаты*:")?;
+        writeln!(
+            &mut text,
+            "Продано:_{}_ на сумму _{}_",
+            stat.gifts.sold.count,
+            escape(&stat.gifts.sold.sum.to_string())
+        )?;
+        writeln!(
+            &mut text,
+            "Использовано:_{}_ на сумму _{}_",
+            stat.gifts.redeemed.count,
+            escape(&stat.gifts.redeemed.sum.to_string())
+        )?;
+        writeln!(
+            &mut text,
+            "Не использовано на конец периода:_{}_",
+            escape(&stat.gifts.liability.to_string())
+        )?;
 
         writeln!(&mut text, "*Маркетинг*:")?;
         stat.outcome
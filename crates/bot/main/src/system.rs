@@ -10,6 +10,9 @@ use model::rights::Rule;
 use serde::{Deserialize, Serialize};
 use teloxide::types::{InlineKeyboardMarkup, Message};
 
+mod closure;
+mod loyalty;
+mod settings;
 mod subscription;
 
 #[derive(Default)]
@@ -30,6 +33,8 @@ impl View for SystemView {
         keymap = keymap.append_row((Calldata::ApplyDump).btn_row("🔄 ApplyDump"));
         keymap =
             keymap.append_row((Calldata::ExtendSubscription).btn_row("🔄 Extend subscription"));
+        keymap = keymap.append_row((Calldata::Settings).btn_row("⚙️ Правила студии"));
+        keymap = keymap.append_row((Calldata::Closure).btn_row("🏖 Закрытие студии"));
         ctx.edit_origin("🔧System", keymap).await?;
         Ok(())
     }
@@ -47,6 +52,12 @@ impl View for SystemView {
             Calldata::ApplyDump => {
                 return Ok(ApplyDump.into());
             }
+            Calldata::Settings => {
+                return Ok(settings::SettingsView.into());
+            }
+            Calldata::Closure => {
+                return Ok(closure::StudioClosure::default().into());
+            }
         }
         Ok(Jmp::Stay)
     }
@@ -57,6 +68,8 @@ enum Calldata {
     Dump,
     ApplyDump,
     ExtendSubscription,
+    Settings,
+    Closure,
 }
 
 pub struct ApplyDump;
@@ -0,0 +1,220 @@
+use std::collections::BTreeMap;
+
+use async_trait::async_trait;
+use bot_core::{
+    callback_data::Calldata as _,
+    calldata,
+    context::Context,
+    widget::{Jmp, View},
+};
+use bot_viewer::{
+    day::{fmt_date, fmt_dt},
+    rooms::fmt_room,
+};
+use chrono::{DateTime, Duration, Local, NaiveDate, TimeZone as _};
+use eyre::{eyre, Error};
+use model::{rights::Rule, training::Training};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use teloxide::{
+    types::{ChatId, InlineKeyboardMarkup, Message},
+    utils::markdown::escape,
+};
+
+/// Holidays: cancels all trainings of a period at once.
+#[derive(Default)]
+pub struct StudioClosure {
+    period: Option<(DateTime<Local>, DateTime<Local>)>,
+    room: Option<ObjectId>,
+    extend: bool,
+}
+
+impl StudioClosure {
+    fn days(&self) -> i64 {
+        self.period
+            .map(|(from, to)| (to - from).num_days())
+            .unwrap_or_default()
+    }
+}
+
+#[async_trait]
+impl View for StudioClosure {
+    fn name(&self) -> &'static str {
+        "StudioClosure"
+    }
+
+    async fn show(&mut self, ctx: &mut Context) -> Result<(), Error> {
+        ctx.ensure(Rule::System)?;
+        let Some((from, to)) = self.period else {
+            ctx.edit_origin(
+                "🏖 *Закрытие студии*\n\nВведите первый и последний день закрытия\\.\nНапример: _31\\.12\\.2025 02\\.01\\.2026_",
+                Default::default(),
+            )
+            .await?;
+            return Ok(());
+        };
+
+        let trainings = ctx
+            .ledger
+            .calendar
+            .find_to_close(&mut ctx.session, from, to, self.room)
+            .await?;
+        let rooms = ctx.ledger.rooms.get_all(&mut ctx.session).await?;
+
+        let mut msg = format!(
+            "🏖 *Закрытие студии*\n\nс {} по {}\n",
+            fmt_date(&from),
+            fmt_date(&(to - Duration::days(1)))
+        );
+        if let Some(room) = rooms.iter().find(|r| Some(r.id) == self.room) {
+            msg.push_str(&format!("Только зал {}\n", escape(&fmt_room(room))));
+        }
+        if self.extend {
+            let whose = if self.room.is_some() {
+                "Абонементы записанных клиентов"
+            } else {
+                "Абонементы"
+            };
+            msg.push_str(&format!(
+                "{} будут продлены на {} дн\\.\n",
+                whose,
+                self.days()
+            ));
+        }
+        if trainings.is_empty() {
+            msg.push_str("\nВ этот период нет тренировок\\.");
+        } else {
+            let clients: usize = trainings.iter().map(|t| t.clients.len()).sum();
+            msg.push_str(&format!(
+                "\nБудут отменены тренировки: {}, записей: {}\n",
+                trainings.len(),
+                clients
+            ));
+            for training in &trainings {
+                msg.push_str(&format!(
+                    "• {} {} \\({}\\)\n",
+                    fmt_dt(&training.get_slot().start_at()),
+                    escape(&training.name),
+                    training.clients.len()
+                ));
+            }
+        }
+
+        let mut keymap = InlineKeyboardMarkup::default();
+        keymap = keymap
+            .append_row(Callback::SelectRoom(None).btn_row(mark(self.room.is_none(), "Все залы")));
+        for room in &rooms {
+            keymap = keymap.append_row(
+                Callback::SelectRoom(Some(room.id.bytes()))
+                    .btn_row(mark(self.room == Some(room.id), &fmt_room(room))),
+            );
+        }
+        keymap =
+            keymap.append_row(Callback::Extend.btn_row(mark(self.extend, "Продлить абонементы")));
+        if !trainings.is_empty() || self.extend {
+            keymap = keymap.append_row(Callback::Confirm.btn_row("✅ Закрыть студию"));
+        }
+        ctx.edit_origin(&msg, keymap).await?;
+        Ok(())
+    }
+
+    async fn handle_message(&mut self, ctx: &mut Context, msg: &Message) -> Result<Jmp, Error> {
+        ctx.ensure(Rule::System)?;
+        ctx.delete_msg(msg.id).await?;
+        match parse_period(msg.text().unwrap_or_default()) {
+            Some(period) => self.period = Some(period),
+            None => ctx.send_notification("Неверный формат").await,
+        }
+        Ok(Jmp::Stay)
+    }
+
+    async fn handle_callback(&mut self, ctx: &mut Context, data: &str) -> Result<Jmp, Error> {
+        ctx.ensure(Rule::System)?;
+        match calldata!(data) {
+            Callback::SelectRoom(room) => {
+                self.room = room.map(ObjectId::from_bytes);
+                Ok(Jmp::Stay)
+            }
+            Callback::Extend => {
+                self.extend = !self.extend;
+                Ok(Jmp::Stay)
+            }
+            Callback::Confirm => {
+                let (from, to) = self.period.ok_or_else(|| eyre!("Period is not set"))?;
+                let canceled = ctx
+                    .ledger
+                    .close_studio(&mut ctx.session, from, to, self.room, self.extend)
+                    .await?;
+                notify_clients(ctx, &canceled, self.extend.then(|| self.days())).await?;
+                ctx.send_notification(&format!(
+                    "Студия закрыта\\. Отменено тренировок: {}",
+                    canceled.len()
+                ))
+                .await;
+                Ok(Jmp::Back)
+            }
+        }
+    }
+}
+
+/// One message per client with all of their cancelled trainings.
+async fn notify_clients(
+    ctx: &mut Context,
+    canceled: &[Training],
+    extended: Option<i64>,
+) -> Result<(), Error> {
+    let mut by_client: BTreeMap<ObjectId, Vec<&Training>> = BTreeMap::new();
+    for training in canceled {
+        for client in &training.clients {
+            by_client.entry(*client).or_default().push(training);
+        }
+    }
+
+    for (client, trainings) in by_client {
+        let Ok(user) = ctx.ledger.get_user(&mut ctx.session, client).await else {
+            continue;
+        };
+        let mut msg = "🏖 Студия закрыта, ваши тренировки *отменены*:\n".to_string();
+        for training in trainings {
+            msg.push_str(&format!(
+                "• {} {}\n",
+                fmt_dt(&training.get_slot().start_at()),
+                escape(&training.name)
+            ));
+        }
+        msg.push_str("Занятия вернулись на абонемент\\.");
+        if let Some(days) = extended {
+            msg.push_str(&format!(" Абонемент продлен на {} дн\\.", days));
+        }
+        ctx.bot.notify(ChatId(user.tg_id), &msg, true).await;
+    }
+    Ok(())
+}
+
+fn mark(selected: bool, name: &str) -> String {
+    format!("{} {}", if selected { "✅" } else { "⬜" }, name)
+}
+
+/// `DD.MM.YYYY DD.MM.YYYY`; the last day is closed too.
+fn parse_period(text: &str) -> Option<(DateTime<Local>, DateTime<Local>)> {
+    let mut parts = text.split_whitespace();
+    let from = NaiveDate::parse_from_str(parts.next()?, "%d.%m.%Y").ok()?;
+    let to = NaiveDate::parse_from_str(parts.next()?, "%d.%m.%Y").ok()? + Duration::days(1);
+    if from >= to || parts.next().is_some() {
+        return None;
+    }
+    let from = Local
+        .from_local_datetime(&from.and_hms_opt(0, 0, 0)?)
+        .earliest()?;
+    let to = Local
+        .from_local_datetime(&to.and_hms_opt(0, 0, 0)?)
+        .earliest()?;
+    Some((from, to))
+}
+
+#[derive(Serialize, Deserialize)]
+enum Callback {
+    SelectRoom(Option<[u8; 12]>),
+    Extend,
+    Confirm,
+}
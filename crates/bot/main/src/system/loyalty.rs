@@ -0,0 +1,142 @@
+use async_trait::async_trait;
+use bot_core::{
+    callback_data::Calldata as _,
+    calldata,
+    context::Context,
+    widget::{Jmp, View},
+};
+use eyre::Error;
+use model::rights::Rule;
+use serde::{Deserialize, Serialize};
+use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup};
+
+const TRAINING_POINTS: [u32; 4] = [0, 10, 20, 50];
+const PURCHASE_PERCENTS: [u32; 4] = [0, 3, 5, 10];
+const BIRTHDAY_POINTS: [u32; 4] = [0, 200, 500, 1000];
+const FREE_LESSON_COSTS: [u32; 4] = [500, 1000, 1500, 2000];
+const LIFETIME_DAYS: [u32; 4] = [90, 180, 365, 0];
+
+pub struct LoyaltySettingsView;
+
+#[async_trait]
+impl View for LoyaltySettingsView {
+    fn name(&self) -> &'static str {
+        "LoyaltySettingsView"
+    }
+
+    async fn show(&mut self, ctx: &mut Context) -> Result<(), Error> {
+        ctx.ensure(Rule::System)?;
+        let policy = ctx.ledger.settings.get(&mut ctx.session).await?.loyalty;
+
+        let msg = format!(
+            "🎁 *Программа лояльности*: _{}_\n\n1 балл \\= 1 руб\\. скидки\nЗа тренировку: _{}_ баллов\nЗа покупку абонемента: _{}%_ от цены\nНа день рождения: _{}_ баллов\nБесплатное занятие: _{}_ баллов\nСрок жизни баллов: _{}_",
+            if policy.enabled { "включена" } else { "выключена" },
+            policy.training_points,
+            policy.purchase_percent,
+            policy.birthday_points,
+            policy.free_lesson_cost,
+            fmt_lifetime(policy.lifetime_days),
+        );
+
+        let mut keymap = InlineKeyboardMarkup::default();
+        keymap = keymap.append_row(Calldata::ToggleEnabled.btn_row(if policy.enabled {
+            "⛔ Выключить"
+        } else {
+            "🟢 Включить"
+        }));
+        keymap = keymap.append_row(preset_row(
+            &TRAINING_POINTS,
+            policy.training_points,
+            "🏋",
+            Calldata::TrainingPoints,
+        ));
+        keymap = keymap.append_row(preset_row(
+            &PURCHASE_PERCENTS,
+            policy.purchase_percent,
+            "💳%",
+            Calldata::PurchasePercent,
+        ));
+        keymap = keymap.append_row(preset_row(
+            &BIRTHDAY_POINTS,
+            policy.birthday_points,
+            "🎂",
+            Calldata::BirthdayPoints,
+        ));
+        keymap = keymap.append_row(preset_row(
+            &FREE_LESSON_COSTS,
+            policy.free_lesson_cost,
+            "🎁",
+            Calldata::FreeLessonCost,
+        ));
+        keymap = keymap.append_row(
+            LIFETIME_DAYS
+                .iter()
+                .map(|days| {
+                    let text = if *days == policy.lifetime_days {
+                        format!("✅⌛{}", fmt_lifetime(*days))
+                    } else {
+                        format!("⌛{}", fmt_lifetime(*days))
+                    };
+                    Calldata::LifetimeDays(*days).button(text)
+                })
+                .collect::<Vec<_>>(),
+        );
+        ctx.edit_origin(&msg, keymap).await?;
+        Ok(())
+    }
+
+    async fn handle_callback(&mut self, ctx: &mut Context, data: &str) -> Result<Jmp, Error> {
+        ctx.ensure(Rule::System)?;
+        let mut policy = ctx.ledger.settings.get(&mut ctx.session).await?.loyalty;
+        match calldata!(data) {
+            Calldata::ToggleEnabled => policy.enabled = !policy.enabled,
+            Calldata::TrainingPoints(points) => policy.training_points = points,
+            Calldata::PurchasePercent(percent) => policy.purchase_percent = percent,
+            Calldata::BirthdayPoints(points) => policy.birthday_points = points,
+            Calldata::FreeLessonCost(points) => policy.free_lesson_cost = points,
+            Calldata::LifetimeDays(days) => policy.lifetime_days = days,
+        }
+        ctx.ledger
+            .settings
+            .set_loyalty_policy(&mut ctx.session, policy)
+            .await?;
+        Ok(Jmp::Stay)
+    }
+}
+
+fn preset_row(
+    presets: &[u32],
+    current: u32,
+    icon: &str,
+    calldata: fn(u32) -> Calldata,
+) -> Vec<InlineKeyboardButton> {
+    presets
+        .iter()
+        .map(|value| {
+            let text = if *value == current {
+                format!("✅{}{}", icon, value)
+            } else {
+                format!("{}{}", icon, value)
+            };
+            calldata(*value).button(text)
+        })
+        .collect()
+}
+
+fn fmt_lifetime(days: u32) -> String {
+    if days == 0 {
+        "бессрочно".to_string()
+    } else {
+        format!("{}д", days)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+enum Calldata {
+    ToggleEnabled,
+    TrainingPoints(u32),
+    PurchasePercent(u32),
+    BirthdayPoints(u32),
+    FreeLessonCost(u32),
+    LifetimeDays(u32),
+}
@@ -0,0 +1,248 @@
+use async_trait::async_trait;
+use bot_core::{
+    callback_data::Calldata as _,
+    calldata,
+    context::Context,
+    widget::{Jmp, View},
+};
+use bot_trainigs::windows::{EditWindows, WindowsTarget};
+use bot_viewer::training::fmt_booking_windows;
+use eyre::Error;
+use model::{rights::Rule, settings::NoShowPolicy};
+use serde::{Deserialize, Serialize};
+use teloxide::types::InlineKeyboardMarkup;
+
+use super::loyalty::LoyaltySettingsView;
+
+const ATTENDANCE_WINDOWS_MIN: [u32; 4] = [30, 2 * 60, 6 * 60, 24 * 60];
+const LATE_CANCEL_PENALTIES: [u32; 4] = [25, 50, 75, 100];
+const REFUND_FEES: [u32; 4] = [0, 10, 20, 30];
+const EXPIRY_REMINDERS: [&[u32]; 4] = [&[], &[3], &[1, 3], &[1, 3, 7]];
+
+pub struct SettingsView;
+
+#[async_trait]
+impl View for SettingsView {
+    fn name(&self) -> &'static str {
+        "SettingsView"
+    }
+
+    async fn show(&mut self, ctx: &mut Context) -> Result<(), Error> {
+        ctx.ensure(Rule::System)?;
+        let settings = ctx.ledger.settings.get(&mut ctx.session).await?;
+
+        let no_show = match settings.no_show {
+            NoShowPolicy::Charge => "занятие списывается",
+            NoShowPolicy::Free => "занятие возвращается на абонемент",
+        };
+        let late_cancel = settings.late_cancel;
+        let late_cancel_msg = if late_cancel.allowed {
+            format!(
+                "списывается _{}%_ занятия, инструктору _{}_",
+                late_cancel.penalty_percent,
+                if late_cancel.credit_instructor {
+                    "начисляется вознаграждение"
+                } else {
+                    "ничего не начисляется"
+                }
+            )
+        } else {
+            "_запрещена_".to_string()
+        };
+        let msg = format!(
+            "⚙️ *Правила студии*\n\nНеявка без уважительной причины: _{}_\nОтметить посещаемость можно в течение _{}_ мин\\. после окончания тренировки\nОтмена записи после окончания бесплатной отмены: {}\nУдержание при возврате абонемента: _{}%_\nЗапись при просроченной рассрочке: _{}_\nНапоминание о сгорающих занятиях: _{}_\n\n*Персональные тренировки*\n{}",
+            no_show,
+            settings.attendance_window_min,
+            late_cancel_msg,
+            settings.refund_fee_percent,
+            if settings.block_overdue_sign_up {
+                "запрещена"
+            } else {
+                "разрешена"
+            },
+            fmt_reminder_days(&settings.expiry_reminder_days),
+            fmt_booking_windows(&settings.personal_windows)
+        );
+
+        let mut keymap = InlineKeyboardMarkup::default();
+        keymap = keymap.append_row(Calldata::ToggleNoShow.btn_row("🔄 Правило неявки"));
+        keymap = keymap.append_row(
+            ATTENDANCE_WINDOWS_MIN
+                .iter()
+                .map(|min| {
+                    let text = if *min == settings.attendance_window_min {
+                        format!("✅{}м", min)
+                    } else {
+                        format!("{}м", min)
+                    };
+                    Calldata::AttendanceWindow(*min).button(text)
+                })
+                .collect::<Vec<_>>(),
+        );
+        keymap = keymap.append_row(Calldata::ToggleLateCancel.btn_row(if late_cancel.allowed {
+            "⛔ Запретить позднюю отмену"
+        } else {
+            "🟢 Разрешить позднюю отмену"
+        }));
+        if late_cancel.allowed {
+            keymap = keymap.append_row(
+                LATE_CANCEL_PENALTIES
+                    .iter()
+                    .map(|percent| {
+                        let text = if *percent == late_cancel.penalty_percent {
+                            format!("✅{}%", percent)
+                        } else {
+                            format!("{}%", percent)
+                        };
+                        Calldata::LateCancelPenalty(*percent).button(text)
+                    })
+                    .collect::<Vec<_>>(),
+            );
+            keymap = keymap.append_row(Calldata::ToggleLateCancelCredit.btn_row(
+                if late_cancel.credit_instructor {
+                    "🧘 Не начислять инструктору"
+                } else {
+                    "🧘 Начислять инструктору"
+                },
+            ));
+        }
+        keymap = keymap.append_row(
+            REFUND_FEES
+                .iter()
+                .map(|percent| {
+                    let text = if *percent == settings.refund_fee_percent {
+                        format!("✅↩️{}%", percent)
+                    } else {
+                        format!("↩️{}%", percent)
+                    };
+                    Calldata::RefundFee(*percent).button(text)
+                })
+                .collect::<Vec<_>>(),
+        );
+        keymap = keymap.append_row(Calldata::ToggleBlockOverdue.btn_row(
+            if settings.block_overdue_sign_up {
+                "💳 Разрешить запись должникам"
+            } else {
+                "💳 Запретить запись должникам"
+            },
+        ));
+        keymap = keymap.append_row(
+            EXPIRY_REMINDERS
+                .iter()
+                .map(|days| {
+                    let text = if *days == settings.expiry_reminder_days.as_slice() {
+                        format!("✅⏳{}", fmt_reminder_days(days))
+                    } else {
+                        format!("⏳{}", fmt_reminder_days(days))
+                    };
+                    Calldata::ExpiryReminders(days.to_vec()).button(text)
+                })
+                .collect::<Vec<_>>(),
+        );
+        keymap = keymap.append_row(
+            Calldata::PersonalWindows.btn_row("⏳ Правила записи на персональные"),
+        );
+        keymap = keymap.append_row(Calldata::Loyalty.btn_row("🎁 Программа лояльности"));
+        ctx.edit_origin(&msg, keymap).await?;
+        Ok(())
+    }
+
+    async fn handle_callback(&mut self, ctx: &mut Context, data: &str) -> Result<Jmp, Error> {
+        ctx.ensure(Rule::System)?;
+        match calldata!(data) {
+            Calldata::ToggleNoShow => {
+                let settings = ctx.ledger.settings.get(&mut ctx.session).await?;
+                let policy = match settings.no_show {
+                    NoShowPolicy::Charge => NoShowPolicy::Free,
+                    NoShowPolicy::Free => NoShowPolicy::Charge,
+                };
+                ctx.ledger
+                    .settings
+                    .set_no_show_policy(&mut ctx.session, policy)
+                    .await?;
+            }
+            Calldata::AttendanceWindow(min) => {
+                ctx.ledger
+                    .settings
+                    .set_attendance_window(&mut ctx.session, min)
+                    .await?;
+            }
+            Calldata::ToggleLateCancel => {
+                let mut policy = ctx.ledger.settings.get(&mut ctx.session).await?.late_cancel;
+                policy.allowed = !policy.allowed;
+                ctx.ledger
+                    .settings
+                    .set_late_cancel_policy(&mut ctx.session, policy)
+                    .await?;
+            }
+            Calldata::LateCancelPenalty(percent) => {
+                let mut policy = ctx.ledger.settings.get(&mut ctx.session).await?.late_cancel;
+                policy.penalty_percent = percent;
+                ctx.ledger
+                    .settings
+                    .set_late_cancel_policy(&mut ctx.session, policy)
+                    .await?;
+            }
+            Calldata::ToggleLateCancelCredit => {
+                let mut policy = ctx.ledger.settings.get(&mut ctx.session).await?.late_cancel;
+                policy.credit_instructor = !policy.credit_instructor;
+                ctx.ledger
+                    .settings
+                    .set_late_cancel_policy(&mut ctx.session, policy)
+                    .await?;
+            }
+            Calldata::RefundFee(percent) => {
+                ctx.ledger
+                    .settings
+                    .set_refund_fee(&mut ctx.session, percent)
+                    .await?;
+            }
+            Calldata::ToggleBlockOverdue => {
+                let block = ctx
+                    .ledger
+                    .settings
+                    .get(&mut ctx.session)
+                    .await?
+                    .block_overdue_sign_up;
+                ctx.ledger
+                    .settings
+                    .set_block_overdue_sign_up(&mut ctx.session, !block)
+                    .await?;
+            }
+            Calldata::ExpiryReminders(days) => {
+                ctx.ledger
+                    .settings
+                    .set_expiry_reminder_days(&mut ctx.session, days)
+                    .await?;
+            }
+            Calldata::PersonalWindows => {
+                return Ok(EditWindows::new(WindowsTarget::Personal).into());
+            }
+            Calldata::Loyalty => return Ok(LoyaltySettingsView.into()),
+        }
+        Ok(Jmp::Stay)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+enum Calldata {
+    ToggleNoShow,
+    AttendanceWindow(u32),
+    PersonalWindows,
+    ToggleLateCancel,
+    LateCancelPenalty(u32),
+    ToggleLateCancelCredit,
+    RefundFee(u32),
+    ToggleBlockOverdue,
+    ExpiryReminders(Vec<u32>),
+    Loyalty,
+}
+
+fn fmt_reminder_days(days: &[u32]) -> String {
+    if days.is_empty() {
+        "выкл".to_string()
+    } else {
+        let days = days.iter().map(|d| d.to_string()).collect::<Vec<_>>();
+        format!("{}д", days.join("/"))
+    }
+}
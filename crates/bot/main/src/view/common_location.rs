@@ -1,3 +1,4 @@
+use bot_calendar::CalendarView;
 use bot_core::{context::Context, widget::Jmp, CommonLocation};
 use bot_marketing::requests::Requests;
 use bot_users::profile::UserProfile;
@@ -20,5 +21,6 @@ pub async fn handle_common_location(ctx: &mut Context, location: CommonLocation)
                 Jmp::Stay
             }
         }
+        CommonLocation::Schedule => CalendarView::default().into(),
     })
 }
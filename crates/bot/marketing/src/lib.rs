@@ -8,6 +8,7 @@ use bot_core::{
 use serde::{Deserialize, Serialize};
 use teloxide::types::InlineKeyboardMarkup;
 
+pub mod promo;
 pub mod requests;
 mod statistics;
 
@@ -27,6 +28,7 @@ impl View for Marketing {
 
         if ctx.has_right(model::rights::Rule::ViewMarketingInfo) {
             keymap = keymap.append_row(Calldata::Request.btn_row("Заявки 🈸"));
+            keymap = keymap.append_row(Calldata::Promo.btn_row("Промокоды 🎟"));
         }
         if ctx.has_right(model::rights::Rule::ViewStatistics) {
             keymap = keymap.append_row(Calldata::Statistics.btn_row("Статистика 📊"));
@@ -42,6 +44,10 @@ impl View for Marketing {
                 ctx.ensure(model::rights::Rule::ViewMarketingInfo)?;
                 Ok(requests::Requests::default().into())
             }
+            Calldata::Promo => {
+                ctx.ensure(model::rights::Rule::ViewMarketingInfo)?;
+                Ok(promo::PromoCodes::default().into())
+            }
             Calldata::Statistics => {
                 ctx.ensure(model::rights::Rule::ViewStatistics)?;
                 Ok(statistics::StatisticsView::default().into())
@@ -53,5 +59,6 @@ impl View for Marketing {
 #[derive(Serialize, Deserialize)]
 enum Calldata {
     Request,
+    Promo,
     Statistics,
 }
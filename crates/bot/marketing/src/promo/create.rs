@@ -0,0 +1,223 @@
+use std::str::FromStr as _;
+
+use async_trait::async_trait;
+use bot_core::{
+    callback_data::Calldata as _,
+    calldata,
+    context::Context,
+    widget::{Jmp, View},
+};
+use bot_viewer::promo::fmt_promo;
+use chrono::{Duration, Utc};
+use eyre::Result;
+use model::{
+    decimal::Decimal,
+    promo::{PromoCode, PromoDiscount},
+    rights::Rule,
+    statistics::source::Source,
+};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use teloxide::{
+    types::{InlineKeyboardMarkup, Message},
+    utils::markdown::escape,
+};
+
+/// Step by step promo code creation. `Some(None)` in the limits means no limit.
+#[derive(Default)]
+pub struct CreatePromoCode {
+    code: Option<String>,
+    discount: Option<PromoDiscount>,
+    source: Option<Source>,
+    days: Option<Option<u32>>,
+    max_uses: Option<Option<u32>>,
+    max_uses_per_client: Option<Option<u32>>,
+    subscriptions: Vec<ObjectId>,
+    subscriptions_selected: bool,
+}
+
+impl CreatePromoCode {
+    fn promo(&self) -> Option<PromoCode> {
+        let mut promo = PromoCode::new(self.code.as_ref()?, self.discount?, self.source?);
+        let now = Utc::now();
+        promo.valid_from = Some(now);
+        promo.valid_to = self.days?.map(|days| now + Duration::days(days as i64));
+        promo.max_uses = self.max_uses?;
+        promo.max_uses_per_client = self.max_uses_per_client?;
+        promo.subscriptions = self.subscriptions.clone();
+        Some(promo)
+    }
+}
+
+#[async_trait]
+impl View for CreatePromoCode {
+    fn name(&self) -> &'static str {
+        "CreatePromoCode"
+    }
+
+    async fn show(&mut self, ctx: &mut Context) -> Result<()> {
+        ctx.ensure(Rule::EditPromoCodes)?;
+        let mut keymap = InlineKeyboardMarkup::default();
+        let text = if self.code.is_none() {
+            "Введите промокод".to_string()
+        } else if self.discount.is_none() {
+            "Введите скидку: процент \\(например _10%_\\) или сумму в рублях \\(например _500_\\)"
+                .to_string()
+        } else if self.source.is_none() {
+            for source in Source::iter() {
+                keymap = keymap.append_row(Callback::Source(source).btn_row(source.name()));
+            }
+            "Какой кампании принадлежит промокод?".to_string()
+        } else if self.days.is_none() {
+            keymap = keymap.append_row(Callback::NoLimit.btn_row("Бессрочно"));
+            "Сколько дней действует промокод?".to_string()
+        } else if self.max_uses.is_none() {
+            keymap = keymap.append_row(Callback::NoLimit.btn_row("Без ограничений"));
+            "Сколько раз всего можно использовать промокод?".to_string()
+        } else if self.max_uses_per_client.is_none() {
+            keymap = keymap.append_row(Callback::NoLimit.btn_row("Без ограничений"));
+            "Сколько раз один клиент может использовать промокод?".to_string()
+        } else if !self.subscriptions_selected {
+            let subscriptions = ctx.ledger.subscriptions.get_all(&mut ctx.session).await?;
+            for sub in subscriptions {
+                let mark = if self.subscriptions.contains(&sub.id) {
+                    "✅"
+                } else {
+                    "⬜"
+                };
+                keymap = keymap.append_row(
+                    Callback::Subscription(sub.id.bytes())
+                        .btn_row(format!("{} {}", mark, sub.name)),
+                );
+            }
+            keymap = keymap.append_row(Callback::Done.btn_row("Готово"));
+            "На какие абонементы действует промокод? Если ничего не выбрано \\- на все".to_string()
+        } else {
+            let promo = self
+                .promo()
+                .ok_or_else(|| eyre::eyre!("Promo code is not complete"))?;
+            keymap = keymap.append_row(vec![
+                Callback::Create.button("✅ Создать"),
+                Callback::Reset.button("❌ Заново"),
+            ]);
+            let subscriptions = if promo.subscriptions.is_empty() {
+                "все".to_string()
+            } else {
+                promo.subscriptions.len().to_string()
+            };
+            format!(
+                "{}Абонементы: _{}_\n\nВсе верно?",
+                fmt_promo(&promo),
+                escape(&subscriptions)
+            )
+        };
+        ctx.edit_origin(&text, keymap).await?;
+        Ok(())
+    }
+
+    async fn handle_message(&mut self, ctx: &mut Context, message: &Message) -> Result<Jmp> {
+        ctx.ensure(Rule::EditPromoCodes)?;
+        ctx.delete_msg(message.id).await?;
+        let text = message.text().unwrap_or_default().trim();
+        if self.code.is_none() {
+            let code = PromoCode::normalize(text);
+            if code.is_empty() || code.contains(char::is_whitespace) {
+                ctx.send_notification("Промокод не должен содержать пробелы")
+                    .await;
+            } else if ctx
+                .ledger
+                .promo
+                .get_by_code(&mut ctx.session, &code)
+                .await?
+                .is_some()
+            {
+                ctx.send_notification("Такой промокод уже есть").await;
+            } else {
+                self.code = Some(code);
+            }
+        } else if self.discount.is_none() {
+            self.discount = parse_discount(text);
+            if self.discount.is_none() {
+                ctx.send_notification("Неверный формат скидки").await;
+            }
+        } else if self.source.is_none() {
+            // selected with buttons
+        } else if let Ok(value) = text.parse::<u32>() {
+            if value == 0 {
+                ctx.send_notification("Введите число больше нуля").await;
+            } else if self.days.is_none() {
+                self.days = Some(Some(value));
+            } else if self.max_uses.is_none() {
+                self.max_uses = Some(Some(value));
+            } else if self.max_uses_per_client.is_none() {
+                self.max_uses_per_client = Some(Some(value));
+            }
+        } else {
+            ctx.send_notification("Введите число").await;
+        }
+        Ok(Jmp::Stay)
+    }
+
+    async fn handle_callback(&mut self, ctx: &mut Context, data: &str) -> Result<Jmp> {
+        ctx.ensure(Rule::EditPromoCodes)?;
+        match calldata!(data) {
+            Callback::Source(source) => {
+                self.source = Some(source);
+            }
+            Callback::NoLimit => {
+                if self.days.is_none() {
+                    self.days = Some(None);
+                } else if self.max_uses.is_none() {
+                    self.max_uses = Some(None);
+                } else if self.max_uses_per_client.is_none() {
+                    self.max_uses_per_client = Some(None);
+                }
+            }
+            Callback::Subscription(id) => {
+                let id = ObjectId::from_bytes(id);
+                if let Some(idx) = self.subscriptions.iter().position(|s| *s == id) {
+                    self.subscriptions.remove(idx);
+                } else {
+                    self.subscriptions.push(id);
+                }
+            }
+            Callback::Done => {
+                self.subscriptions_selected = true;
+            }
+            Callback::Create => {
+                let promo = self
+                    .promo()
+                    .ok_or_else(|| eyre::eyre!("Promo code is not complete"))?;
+                ctx.ledger.promo.create(&mut ctx.session, promo).await?;
+                ctx.send_notification("Промокод создан").await;
+                return Ok(Jmp::Back);
+            }
+            Callback::Reset => {
+                *self = CreatePromoCode::default();
+            }
+        }
+        Ok(Jmp::Stay)
+    }
+}
+
+fn parse_discount(text: &str) -> Option<PromoDiscount> {
+    if let Some(percent) = text.strip_suffix('%') {
+        let percent = percent.trim().parse::<u32>().ok()?;
+        (1..=100)
+            .contains(&percent)
+            .then_some(PromoDiscount::Percent(percent))
+    } else {
+        let amount = Decimal::from_str(&text.replace(',', ".")).ok()?;
+        (amount > Decimal::zero()).then_some(PromoDiscount::Fixed(amount))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+enum Callback {
+    Source(Source),
+    NoLimit,
+    Subscription([u8; 12]),
+    Done,
+    Create,
+    Reset,
+}
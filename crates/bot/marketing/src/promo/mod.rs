@@ -0,0 +1,181 @@
+use async_trait::async_trait;
+use bot_core::{
+    callback_data::Calldata as _,
+    calldata,
+    context::Context,
+    widget::{Jmp, View},
+};
+use bot_viewer::promo::{fmt_promo, fmt_promo_discount};
+use create::CreatePromoCode;
+use eyre::Result;
+use model::{decimal::Decimal, rights::Rule};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use teloxide::{types::InlineKeyboardMarkup, utils::markdown::escape};
+
+pub mod create;
+
+#[derive(Default)]
+pub struct PromoCodes {
+    archived: bool,
+}
+
+#[async_trait]
+impl View for PromoCodes {
+    fn name(&self) -> &'static str {
+        "PromoCodes"
+    }
+
+    async fn show(&mut self, ctx: &mut Context) -> Result<()> {
+        ctx.ensure(Rule::ViewMarketingInfo)?;
+        let codes = ctx
+            .ledger
+            .promo
+            .list(&mut ctx.session, self.archived)
+            .await?;
+
+        let mut text = if self.archived {
+            "🎟 *Архив промокодов*\n".to_string()
+        } else {
+            "🎟 *Промокоды*\n".to_string()
+        };
+        if codes.is_empty() {
+            text.push_str("_Нет промокодов_");
+        }
+        let mut keymap = InlineKeyboardMarkup::default();
+        for promo in codes {
+            keymap = keymap.append_row(Callback::Select(promo.id.bytes()).btn_row(format!(
+                "{} {} ({})",
+                promo.code,
+                fmt_promo_discount(&promo.discount).replace('\\', ""),
+                promo.uses
+            )));
+        }
+        if ctx.has_right(Rule::EditPromoCodes) && !self.archived {
+            keymap = keymap.append_row(Callback::Create.btn_row("➕ Создать промокод"));
+        }
+        keymap = keymap.append_row(Callback::Archive.btn_row(if self.archived {
+            "🎟 Действующие"
+        } else {
+            "🗄 Архив"
+        }));
+        ctx.edit_origin(&text, keymap).await?;
+        Ok(())
+    }
+
+    async fn handle_callback(&mut self, ctx: &mut Context, data: &str) -> Result<Jmp> {
+        ctx.ensure(Rule::ViewMarketingInfo)?;
+        match calldata!(data) {
+            Callback::Select(id) => Ok(PromoCodeView::new(ObjectId::from_bytes(id)).into()),
+            Callback::Create => {
+                ctx.ensure(Rule::EditPromoCodes)?;
+                Ok(CreatePromoCode::default().into())
+            }
+            Callback::Archive => {
+                self.archived = !self.archived;
+                Ok(Jmp::Stay)
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+enum Callback {
+    Select([u8; 12]),
+    Create,
+    Archive,
+}
+
+/// Promo code details with the campaign revenue.
+pub struct PromoCodeView {
+    id: ObjectId,
+}
+
+impl PromoCodeView {
+    pub fn new(id: ObjectId) -> Self {
+        Self { id }
+    }
+}
+
+#[async_trait]
+impl View for PromoCodeView {
+    fn name(&self) -> &'static str {
+        "PromoCodeView"
+    }
+
+    async fn show(&mut self, ctx: &mut Context) -> Result<()> {
+        ctx.ensure(Rule::ViewMarketingInfo)?;
+        let promo = ctx
+            .ledger
+            .promo
+            .get(&mut ctx.session, self.id)
+            .await?
+            .ok_or_else(|| eyre::eyre!("Promo code not found"))?;
+        let redemptions = ctx
+            .ledger
+            .promo
+            .redemptions(&mut ctx.session, self.id)
+            .await?;
+
+        let mut text = fmt_promo(&promo);
+        if !promo.subscriptions.is_empty() {
+            text.push_str("Абонементы:\n");
+            for id in &promo.subscriptions {
+                let name = ctx
+                    .ledger
+                    .subscriptions
+                    .get(&mut ctx.session, *id)
+                    .await?
+                    .map(|sub| sub.name)
+                    .unwrap_or_else(|| "\\-".to_string());
+                text.push_str(&format!(" \\- _{}_\n", escape(&name)));
+            }
+        }
+        let earned = redemptions
+            .iter()
+            .fold(Decimal::zero(), |sum, r| sum + r.price);
+        let discount = redemptions
+            .iter()
+            .fold(Decimal::zero(), |sum, r| sum + r.discount);
+        text.push_str(&format!(
+            "\nПродано абонементов: _{}_\nВыручка: _{}_\nСкидок выдано на: _{}_",
+            redemptions.len(),
+            escape(&earned.to_string()),
+            escape(&discount.to_string())
+        ));
+
+        let mut keymap = InlineKeyboardMarkup::default();
+        if ctx.has_right(Rule::EditPromoCodes) {
+            keymap = keymap.append_row(ViewCallback::Archive.btn_row(if promo.is_archived {
+                "♻️ Вернуть из архива"
+            } else {
+                "🗄 В архив"
+            }));
+        }
+        ctx.edit_origin(&text, keymap).await?;
+        Ok(())
+    }
+
+    async fn handle_callback(&mut self, ctx: &mut Context, data: &str) -> Result<Jmp> {
+        match calldata!(data) {
+            ViewCallback::Archive => {
+                ctx.ensure(Rule::EditPromoCodes)?;
+                let mut promo = ctx
+                    .ledger
+                    .promo
+                    .get(&mut ctx.session, self.id)
+                    .await?
+                    .ok_or_else(|| eyre::eyre!("Promo code not found"))?;
+                promo.is_archived = !promo.is_archived;
+                promo.version += 1;
+                ctx.ledger.promo.update(&mut ctx.session, &promo).await?;
+                Ok(Jmp::Stay)
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+enum ViewCallback {
+    Archive,
+}
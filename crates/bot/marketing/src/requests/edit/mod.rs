@@ -2,3 +2,4 @@ pub mod change_source;
 pub mod add_comment;
 pub mod notification;
 
+pub mod trial;
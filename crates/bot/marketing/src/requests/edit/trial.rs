@@ -0,0 +1,185 @@
+use async_trait::async_trait;
+use bot_core::{
+    callback_data::{Calldata as _, TrainingIdCallback},
+    calldata,
+    context::Context,
+    widget::{Jmp, View},
+};
+use bot_viewer::{day::fmt_dt, fmt_phone};
+use chrono::Local;
+use eyre::Result;
+use model::{
+    ids::DayId,
+    rights::Rule,
+    subscription::{Subscription, SubscriptionType},
+    training::{Training, TrainingId},
+};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use teloxide::{types::InlineKeyboardMarkup, utils::markdown::escape};
+
+const TRIAL_DAYS: usize = 7;
+
+/// Books a trial lesson for the lead: trial subscription, then a group training.
+pub struct BookTrial {
+    pub id: ObjectId,
+    subscription: Option<ObjectId>,
+    training: Option<TrainingId>,
+}
+
+impl BookTrial {
+    pub fn new(id: ObjectId) -> Self {
+        Self {
+            id,
+            subscription: None,
+            training: None,
+        }
+    }
+
+    async fn trainings(&self, ctx: &mut Context, sub: &Subscription) -> Result<Vec<Training>> {
+        let SubscriptionType::Group { program_filter } = &sub.subscription_type else {
+            return Ok(vec![]);
+        };
+        let now = Local::now();
+        let mut day_id = DayId::default();
+        let mut trainings = vec![];
+        for _ in 0..TRIAL_DAYS {
+            let day = ctx
+                .ledger
+                .calendar
+                .get_day(&mut ctx.session, day_id)
+                .await?;
+            trainings.extend(day.training.into_iter().filter(|training| {
+                training.is_group()
+                    && program_filter.contains(&training.proto_id)
+                    && training.status(now).can_sign_in()
+                    && (training.clients.len() as u32) < training.capacity
+            }));
+            day_id = day_id.next();
+        }
+        trainings.sort_by_key(|training| training.start_at_utc());
+        Ok(trainings)
+    }
+}
+
+#[async_trait]
+impl View for BookTrial {
+    fn name(&self) -> &'static str {
+        "BookTrial"
+    }
+
+    async fn show(&mut self, ctx: &mut Context) -> Result<()> {
+        ctx.ensure(Rule::SellSubscription)?;
+        let request = ctx
+            .ledger
+            .requests
+            .get(&mut ctx.session, self.id)
+            .await?
+            .ok_or_else(|| eyre::eyre!("Request not found"))?;
+        let mut text = format!(
+            "🎟 Пробное занятие для {}\n",
+            fmt_phone(Some(&request.phone))
+        );
+        let mut keymap = InlineKeyboardMarkup::default();
+
+        let Some(sub_id) = self.subscription else {
+            let subscriptions = ctx.ledger.subscriptions.get_all(&mut ctx.session).await?;
+            let trials = subscriptions
+                .iter()
+                .filter(|sub| sub.trial)
+                .collect::<Vec<_>>();
+            if trials.is_empty() {
+                text.push_str("Нет абонементов с пометкой пробного занятия");
+            } else {
+                text.push_str("Выберите абонемент:");
+            }
+            for sub in trials {
+                keymap =
+                    keymap.append_row(Callback::Subscription(sub.id.bytes()).btn_row(&sub.name));
+            }
+            ctx.bot.edit_origin(&text, keymap).await?;
+            return Ok(());
+        };
+
+        let sub = ctx
+            .ledger
+            .subscriptions
+            .get(&mut ctx.session, sub_id)
+            .await?
+            .ok_or_else(|| eyre::eyre!("Subscription not found"))?;
+        text.push_str(&format!("Абонемент: *{}*\n", escape(&sub.name)));
+
+        if let Some(training) = self.training {
+            let training = ctx
+                .ledger
+                .calendar
+                .get_training_by_id(&mut ctx.session, training)
+                .await?
+                .ok_or_else(|| eyre::eyre!("Training not found"))?;
+            text.push_str(&format!(
+                "Тренировка: *{}* {}\n\nВсе верно?",
+                escape(&training.name),
+                fmt_dt(&training.get_slot().start_at())
+            ));
+            keymap = keymap.append_row(vec![
+                Callback::Confirm.button("✅ Записать"),
+                Callback::Back.button("❌ Нет"),
+            ]);
+        } else {
+            let trainings = self.trainings(ctx, &sub).await?;
+            if trainings.is_empty() {
+                text.push_str("Нет тренировок с записью на ближайшую неделю");
+            } else {
+                text.push_str("Выберите тренировку:");
+            }
+            for training in trainings {
+                let start_at = training.get_slot().start_at();
+                keymap = keymap.append_row(Callback::Training(training.id().into()).btn_row(
+                    format!("{} {}", start_at.format("%d.%m %H:%M"), training.name),
+                ));
+            }
+            keymap = keymap.append_row(Callback::Back.btn_row("⬅️ Другой абонемент"));
+        }
+        ctx.bot.edit_origin(&text, keymap).await?;
+        Ok(())
+    }
+
+    async fn handle_callback(&mut self, ctx: &mut Context, data: &str) -> Result<Jmp> {
+        ctx.ensure(Rule::SellSubscription)?;
+        match calldata!(data) {
+            Callback::Subscription(id) => {
+                self.subscription = Some(ObjectId::from_bytes(id));
+                Ok(Jmp::Stay)
+            }
+            Callback::Training(id) => {
+                self.training = Some(id.into());
+                Ok(Jmp::Stay)
+            }
+            Callback::Back => {
+                if self.training.take().is_none() {
+                    self.subscription = None;
+                }
+                Ok(Jmp::Stay)
+            }
+            Callback::Confirm => {
+                let (Some(sub), Some(training)) = (self.subscription, self.training) else {
+                    return Ok(Jmp::Stay);
+                };
+                ctx.ledger
+                    .book_trial(&mut ctx.session, self.id, sub, training)
+                    .await?;
+                ctx.send_notification("Клиент записан на пробное занятие")
+                    .await;
+                Ok(Jmp::Back)
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+enum Callback {
+    Subscription([u8; 12]),
+    Training(TrainingIdCallback),
+    Confirm,
+    Back,
+}
@@ -7,7 +7,10 @@ use bot_core::{
 };
 use bot_viewer::{fmt_phone, request::fmt_request};
 use create::SetComeFrom;
-use edit::{add_comment::AddComment, change_source::ChangeComeFrom, notification::AddNotification};
+use edit::{
+    add_comment::AddComment, change_source::ChangeComeFrom, notification::AddNotification,
+    trial::BookTrial,
+};
 use history::RequestHistory;
 use model::{rights::Rule, user::sanitize_phone};
 use mongodb::bson::oid::ObjectId;
@@ -61,6 +64,9 @@ impl View for Requests {
                 keymap = keymap.append_row(Calldata::AddComment.btn_row("Добавить комментарий 📝"));
                 keymap = keymap.append_row(Calldata::ChangeSource.btn_row("Изменить источник 🔄"));
                 keymap = keymap.append_row(Calldata::Notification.btn_row("Напомнить 🛎"));
+                if request.trial.is_none() && ctx.has_right(Rule::SellSubscription) {
+                    keymap = keymap.append_row(Calldata::Trial.btn_row("Пробное занятие 🎟"));
+                }
             } else {
                 self.found = false;
                 self.id = None;
@@ -147,6 +153,15 @@ impl View for Requests {
                     Ok(Jmp::Stay)
                 }
             }
+            Calldata::Trial => {
+                ctx.ensure(Rule::SellSubscription)?;
+                if let Some(id) = self.id {
+                    Ok(BookTrial::new(id).into())
+                } else {
+                    ctx.bot.send_notification("Заявка не найдена").await;
+                    Ok(Jmp::Stay)
+                }
+            }
         }
     }
 }
@@ -158,4 +173,5 @@ enum Calldata {
     ChangeSource,
     Notification,
     History,
+    Trial,
 }
@@ -0,0 +1,149 @@
+use std::str::FromStr as _;
+
+use async_trait::async_trait;
+use bot_core::{
+    callback_data::Calldata as _,
+    calldata,
+    context::Context,
+    widget::{Jmp, View},
+};
+use bot_viewer::subscription::fmt_subscription_type;
+use eyre::{Error, Result};
+use model::{
+    decimal::Decimal,
+    rights::Rule,
+    subscription::{BundlePool, SubscriptionType},
+};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use teloxide::types::{InlineKeyboardMarkup, Message};
+
+/// Personal lessons sold together with a group subscription.
+pub struct EditBundle {
+    id: ObjectId,
+    couch: Option<ObjectId>,
+}
+
+impl EditBundle {
+    pub fn new(id: ObjectId) -> EditBundle {
+        EditBundle { id, couch: None }
+    }
+}
+
+#[async_trait]
+impl View for EditBundle {
+    fn name(&self) -> &'static str {
+        "EditBundle"
+    }
+
+    async fn show(&mut self, ctx: &mut Context) -> Result<(), Error> {
+        ctx.ensure(Rule::EditSubscription)?;
+        let sub = ctx
+            .ledger
+            .subscriptions
+            .get(&mut ctx.session, self.id)
+            .await?
+            .ok_or_else(|| eyre::eyre!("Subscription not found"))?;
+
+        let mut msg = "📦 *Пакет занятий*\n\n".to_string();
+        msg.push_str(&format!(
+            "Основные занятия: _{}_ за _{}_\n",
+            sub.items,
+            sub.main_price().to_string().replace(".", ",")
+        ));
+        let mut keymap = InlineKeyboardMarkup::default();
+        for (idx, pool) in sub.pools.iter().enumerate() {
+            let tp = fmt_subscription_type(ctx, &pool.tp, false).await?;
+            msg.push_str(&format!(
+                "{}: _{}_ за _{}_\n",
+                tp,
+                pool.items,
+                pool.price.to_string().replace(".", ",")
+            ));
+            keymap = keymap.append_row(
+                Callback::Delete(idx as u8)
+                    .btn_row(format!("🗑 {} занятий за {}", pool.items, pool.price)),
+            );
+        }
+        msg.push_str(
+            "\nЧтобы добавить персональные занятия, выберите инструктора и введите количество занятий и их цену\\. Например: _2 5000_",
+        );
+
+        let couch_list = ctx.ledger.users.instructors(&mut ctx.session).await?;
+        for couch in couch_list {
+            let selected = self.couch == Some(couch.id);
+            keymap = keymap.append_row(Callback::Couch(couch.id.bytes()).btn_row(format!(
+                "{} {}",
+                if selected { "✅" } else { "⬜" },
+                couch.name.first_name
+            )));
+        }
+        ctx.edit_origin(&msg, keymap).await?;
+        Ok(())
+    }
+
+    async fn handle_message(&mut self, ctx: &mut Context, message: &Message) -> Result<Jmp> {
+        ctx.ensure(Rule::EditSubscription)?;
+        ctx.delete_msg(message.id).await?;
+        let Some(couch) = self.couch else {
+            ctx.send_notification("Выберите инструктора").await;
+            return Ok(Jmp::Stay);
+        };
+        let mut parts = message.text().unwrap_or_default().split_whitespace();
+        let items = parts.next().and_then(|items| items.parse::<u32>().ok());
+        let price = parts
+            .next()
+            .and_then(|price| Decimal::from_str(&price.replace(',', ".")).ok());
+        let (Some(items), Some(price)) = (items, price) else {
+            ctx.send_notification("Неверный формат").await;
+            return Ok(Jmp::Stay);
+        };
+
+        let sub = ctx
+            .ledger
+            .subscriptions
+            .get(&mut ctx.session, self.id)
+            .await?
+            .ok_or_else(|| eyre::eyre!("Subscription not found"))?;
+        if items == 0 || price < Decimal::zero() || price > sub.main_price() {
+            ctx.send_notification("Цена не должна превышать цену основных занятий")
+                .await;
+            return Ok(Jmp::Stay);
+        }
+
+        let pool = BundlePool {
+            tp: SubscriptionType::Personal {
+                couch_filter: couch,
+            },
+            items,
+            price,
+        };
+        ctx.ledger
+            .subscriptions
+            .add_bundle_pool(&mut ctx.session, self.id, pool)
+            .await?;
+        Ok(Jmp::Stay)
+    }
+
+    async fn handle_callback(&mut self, ctx: &mut Context, data: &str) -> Result<Jmp> {
+        ctx.ensure(Rule::EditSubscription)?;
+        match calldata!(data) {
+            Callback::Couch(id) => {
+                self.couch = Some(ObjectId::from_bytes(id));
+            }
+            Callback::Delete(idx) => {
+                ctx.ledger
+                    .subscriptions
+                    .remove_bundle_pool(&mut ctx.session, self.id, idx as usize)
+                    .await?;
+            }
+        }
+        Ok(Jmp::Stay)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+enum Callback {
+    Couch([u8; 12]),
+    Delete(u8),
+}
@@ -6,16 +6,28 @@ use super::View;
 use async_trait::async_trait;
 use bot_core::{callback_data::Calldata as _, calldata, context::Context, widget::Jmp};
 use bot_viewer::fmt_phone;
-use eyre::{eyre, Error, Result};
-use model::{decimal::Decimal, rights::Rule};
+use chrono::Utc;
+use eyre::{Error, Result};
+use model::{
+    decimal::Decimal, errors::LedgerError, installment::InstallmentPlan,
+    loyalty::points_discount, promo::PromoCode, rights::Rule,
+};
 use mongodb::bson::oid::ObjectId;
 use serde::{Deserialize, Serialize};
-use teloxide::{types::InlineKeyboardMarkup, utils::markdown::escape};
+use teloxide::{
+    types::{InlineKeyboardMarkup, Message},
+    utils::markdown::escape,
+};
 
 pub struct ConfirmSell {
     user_id: ObjectId,
     sub: ObjectId,
     discount: Option<Decimal>,
+    promo: Option<PromoCode>,
+    wait_promo: bool,
+    installments: Option<InstallmentPlan>,
+    wait_installments: bool,
+    use_points: bool,
 }
 
 impl ConfirmSell {
@@ -24,6 +36,11 @@ impl ConfirmSell {
             user_id,
             sub: sell,
             discount: None,
+            promo: None,
+            wait_promo: false,
+            installments: None,
+            wait_installments: false,
+            use_points: false,
         }
     }
 }
@@ -35,27 +52,121 @@ impl View for ConfirmSell {
     }
 
     async fn show(&mut self, ctx: &mut Context) -> Result<()> {
-        let (text, keymap) = render(ctx, self.user_id, self.sub, self.discount).await?;
+        if self.wait_promo {
+            let keymap = InlineKeyboardMarkup::default()
+                .append_row(Callback::RemovePromo.btn_row("❌ Отмена"));
+            ctx.edit_origin("Введите промокод", keymap).await?;
+            return Ok(());
+        }
+        if self.wait_installments {
+            let keymap = InlineKeyboardMarkup::default()
+                .append_row(Callback::RemoveInstallments.btn_row("❌ Отмена"));
+            ctx.edit_origin(
+                "Введите первый платеж и количество оставшихся платежей\\. Например: _5000 2_\nПлатежи вносятся раз в 30 дней",
+                keymap,
+            )
+            .await?;
+            return Ok(());
+        }
+        let (text, keymap) = render(
+            ctx,
+            self.user_id,
+            self.sub,
+            self.discount,
+            self.promo.as_ref(),
+            self.installments.as_ref(),
+            self.use_points,
+        )
+        .await?;
         ctx.edit_origin(&text, keymap).await?;
         Ok(())
     }
 
+    async fn handle_message(&mut self, ctx: &mut Context, message: &Message) -> Result<Jmp> {
+        ctx.delete_msg(message.id).await?;
+        if self.wait_installments {
+            ctx.ensure(Rule::SellSubscription)?;
+            let mut parts = message.text().unwrap_or_default().split_whitespace();
+            let first_payment = parts
+                .next()
+                .and_then(|amount| Decimal::from_str(&amount.replace(',', ".")).ok());
+            let count = parts.next().and_then(|count| count.parse::<u32>().ok());
+            let (Some(first_payment), Some(parts)) = (first_payment, count) else {
+                ctx.send_notification("Неверный формат").await;
+                return Ok(Jmp::Stay);
+            };
+            if parts == 0 || first_payment.is_negative() {
+                ctx.send_notification("Неверный формат").await;
+                return Ok(Jmp::Stay);
+            }
+            self.wait_installments = false;
+            self.installments = Some(InstallmentPlan {
+                first_payment,
+                parts,
+            });
+            return Ok(Jmp::Stay);
+        }
+        if !self.wait_promo {
+            return Ok(Jmp::Stay);
+        }
+        ctx.ensure(Rule::SellSubscription)?;
+        let sub = ctx
+            .ledger
+            .subscriptions
+            .get_for_sale(&mut ctx.session, self.sub)
+            .await?
+            .ok_or_else(|| eyre::eyre!("Subscription {} not found", self.sub))?;
+        self.wait_promo = false;
+        let promo = ctx
+            .ledger
+            .promo
+            .check(
+                &mut ctx.session,
+                message.text().unwrap_or_default(),
+                &sub,
+                self.user_id,
+            )
+            .await?;
+        self.discount = None;
+        self.promo = Some(promo);
+        Ok(Jmp::Stay)
+    }
+
     async fn handle_callback(&mut self, ctx: &mut Context, data: &str) -> Result<Jmp> {
         match calldata!(data) {
             Callback::Sell => {
                 ctx.ensure(Rule::SellSubscription)?;
-                let result = ctx
-                    .ledger
-                    .sell_subscription(
-                        &mut ctx.session,
-                        self.sub,
-                        self.user_id,
-                        self.discount.map(|d| d / Decimal::int(100)),
-                    )
-                    .await;
+                let result = if self.use_points {
+                    ctx.ledger
+                        .sell_subscription_with_points(&mut ctx.session, self.sub, self.user_id)
+                        .await
+                        .map(|_| ())
+                        .map_err(Error::from)
+                } else if let Some(promo) = &self.promo {
+                    ctx.ledger
+                        .sell_subscription_with_promo(
+                            &mut ctx.session,
+                            self.sub,
+                            self.user_id,
+                            promo.code.clone(),
+                        )
+                        .await
+                        .map_err(Error::from)
+                } else {
+                    ctx.ledger
+                        .sell_subscription(
+                            &mut ctx.session,
+                            self.sub,
+                            self.user_id,
+                            self.discount.map(|d| d / Decimal::int(100)),
+                            self.installments,
+                        )
+                        .await
+                        .map_err(|err| Error::from(LedgerError::from(err)))
+                };
 
                 if let Err(err) = result {
-                    Err(err.into())
+                    Err(err)
                 } else {
                     ctx.send_msg("🤑 Продано").await?;
                     ctx.reset_origin().await?;
@@ -70,6 +181,32 @@ impl View for ConfirmSell {
                 self.discount = None;
                 Ok(Jmp::Stay)
             }
+            Callback::AddPromo => {
+                self.wait_promo = true;
+                Ok(Jmp::Stay)
+            }
+            Callback::RemovePromo => {
+                self.wait_promo = false;
+                self.promo = None;
+                Ok(Jmp::Stay)
+            }
+            Callback::AddInstallments => {
+                self.wait_installments = true;
+                Ok(Jmp::Stay)
+            }
+            Callback::RemoveInstallments => {
+                self.wait_installments = false;
+                self.installments = None;
+                Ok(Jmp::Stay)
+            }
+            Callback::UsePoints => {
+                self.use_points = true;
+                Ok(Jmp::Stay)
+            }
+            Callback::RemovePoints => {
+                self.use_points = false;
+                Ok(Jmp::Stay)
+            }
             Callback::Cancel => Ok(Jmp::Back),
         }
     }
@@ -80,22 +217,50 @@ async fn render(
     user_id: ObjectId,
     sub: ObjectId,
     discount: Option<Decimal>,
+    promo: Option<&PromoCode>,
+    installments: Option<&InstallmentPlan>,
+    use_points: bool,
 ) -> Result<(String, InlineKeyboardMarkup), Error> {
     let sub = ctx
         .ledger
         .subscriptions
-        .get(&mut ctx.session, sub)
+        .get_for_sale(&mut ctx.session, sub)
         .await?
         .ok_or_else(|| eyre::eyre!("Subscription {} not found", sub))?;
 
-    let user = ctx
-        .ledger
-        .users
-        .get(&mut ctx.session, user_id)
-        .await?
-        .ok_or_else(|| eyre!("User not found:{}", user_id))?;
+    let user = ctx.ledger.get_user(&mut ctx.session, user_id).await?;
+    let payer = user.payer()?.as_ref().id;
 
-    let price_with_discount = if let Some(discount) = discount {
+    let loyalty = ctx.ledger.settings.get(&mut ctx.session).await?.loyalty;
+    let points = if loyalty.enabled {
+        Some(
+            ctx.ledger
+                .loyalty
+                .balance(&mut ctx.session, payer, Utc::now())
+                .await?,
+        )
+    } else {
+        None
+    };
+    let points_discount = points
+        .filter(|_| use_points)
+        .map(|points| points_discount(points, sub.price));
+
+    let price_with_discount = if let Some((discount, spent)) = points_discount {
+        let full_price = sub.price * (Decimal::int(1) - discount);
+        format!(
+            "Списывается баллов: *{}*\nЦена со скидкой: *{}*",
+            spent,
+            full_price.to_string().replace(".", ",")
+        )
+    } else if let Some(promo) = promo {
+        let full_price = sub.price - promo.discount_amount(sub.price);
+        format!(
+            "Промокод: *{}*\nЦена со скидкой: *{}*",
+            escape(&promo.code),
+            full_price.to_string().replace(".", ",")
+        )
+    } else if let Some(discount) = discount {
         let full_price = sub.price * (Decimal::int(1) - discount / Decimal::int(100));
         format!(
             "Цена со скидкой: *{}*",
@@ -104,6 +269,20 @@ async fn render(
     } else {
         "".to_string()
     };
+    let installments_text = if let Some(plan) = installments {
+        let mut price = sub.price;
+        if let Some(discount) = discount {
+            price -= sub.price * discount / Decimal::int(100);
+        }
+        format!(
+            "Рассрочка: первый платеж *{}*, затем _{}_ платежей на _{}_",
+            plan.first_payment.to_string().replace(".", ","),
+            plan.parts,
+            (price - plan.first_payment).to_string().replace(".", ",")
+        )
+    } else {
+        "".to_string()
+    };
     let text = format!(
         "
  📌  Продажа
@@ -114,7 +293,9 @@ async fn render(
     Номер:_{}_\n
     Скидка: _{}%_
     {}
+    {}
     \n
+    {}
     Все верно? 
     ",
         escape(&sub.name),
@@ -123,8 +304,20 @@ async fn render(
         escape(&user.name.first_name),
         escape(&user.name.last_name.unwrap_or_else(|| "-".to_string())),
         fmt_phone(user.phone.as_deref()),
-        discount.unwrap_or_default().to_string().replace(".", ","),
-        price_with_discount
+        points_discount
+            .map(|(discount, _)| discount * Decimal::int(100))
+            .or(promo
+                .filter(|_| !sub.price.is_zero())
+                .map(|promo| promo.discount_amount(sub.price) / sub.price * Decimal::int(100)))
+            .or(discount)
+            .unwrap_or_default()
+            .to_string()
+            .replace(".", ","),
+        price_with_discount,
+        installments_text,
+        points
+            .map(|points| format!("Баллы клиента: _{}_\n", points))
+            .unwrap_or_default()
     );
 
     let mut keymap = InlineKeyboardMarkup::default();
@@ -132,7 +325,13 @@ async fn render(
         Callback::Sell.button("✅ Да"),
         Callback::Cancel.button("❌ Отмена"),
     ]);
-    if discount.is_none() {
+    if use_points {
+        keymap = keymap.append_row(vec![Callback::RemovePoints.button("Не списывать баллы")]);
+        return Ok((text, keymap));
+    }
+    if promo.is_some() {
+        keymap = keymap.append_row(vec![Callback::RemovePromo.button("Убрать промокод")]);
+    } else if discount.is_none() {
         keymap = keymap.append_row(vec![
             Callback::AddDiscount(Decimal::int(10)).button("Cкидка 10%"),
             Callback::AddDiscount(Decimal::from_str("13.043478").unwrap()).button("Cкидка 13.043478%"),
@@ -141,6 +340,23 @@ async fn render(
     } else {
         keymap = keymap.append_row(vec![Callback::RemoveDiscount.button("Убрать скидку")]);
     }
+    if promo.is_none() && installments.is_none() {
+        keymap = keymap.append_row(Callback::AddPromo.btn_row("🎟 Промокод"));
+    }
+    if promo.is_none()
+        && discount.is_none()
+        && installments.is_none()
+        && points.is_some_and(|points| points > 0)
+    {
+        keymap = keymap.append_row(Callback::UsePoints.btn_row("🎁 Списать баллы"));
+    }
+    if promo.is_none() {
+        if installments.is_none() {
+            keymap = keymap.append_row(Callback::AddInstallments.btn_row("💳 Рассрочка"));
+        } else {
+            keymap = keymap.append_row(Callback::RemoveInstallments.btn_row("Убрать рассрочку"));
+        }
+    }
     Ok((text, keymap))
 }
 
@@ -149,5 +365,11 @@ enum Callback {
     Sell,
     AddDiscount(Decimal),
     RemoveDiscount,
+    AddPromo,
+    RemovePromo,
+    AddInstallments,
+    RemoveInstallments,
+    UsePoints,
+    RemovePoints,
     Cancel,
 }
@@ -1,6 +1,7 @@
 use super::View;
 use async_trait::async_trait;
 use bot_core::{callback_data::Calldata as _, calldata, context::Context, widget::Jmp};
+use chrono::Utc;
 use eyre::Result;
 use model::{decimal::Decimal, rights::Rule};
 use mongodb::bson::oid::ObjectId;
@@ -30,7 +31,7 @@ impl EditSubscription {
         ctx.ensure(Rule::EditSubscription)?;
         ctx.ledger
             .subscriptions
-            .edit_price(&mut ctx.session, self.id, value)
+            .change_price(&mut ctx.session, self.id, value, Utc::now())
             .await?;
         Ok(Jmp::Stay)
     }
@@ -71,6 +72,15 @@ impl EditSubscription {
         Ok(Jmp::Stay)
     }
 
+    pub async fn edit_trial(&self, ctx: &mut Context, value: bool) -> Result<Jmp> {
+        ctx.ensure(Rule::EditSubscription)?;
+        ctx.ledger
+            .subscriptions
+            .edit_trial(&mut ctx.session, self.id, value)
+            .await?;
+        Ok(Jmp::Stay)
+    }
+
     pub async fn edit_name(&self, ctx: &mut Context, value: String) -> Result<Jmp> {
         ctx.ensure(Rule::EditSubscription)?;
         ctx.ledger
@@ -120,6 +130,14 @@ impl View for EditSubscription {
                     ctx.send_msg_with_markup("Выберите, можно ли покупать подписку", keymap)
                         .await?;
                 }
+                EditType::Trial => {
+                    keymap = keymap.append_row(vec![
+                        Callback::Yes.button("✅ Да"),
+                        Callback::No.button("❌ Нет"),
+                    ]);
+                    ctx.send_msg_with_markup("Это пробное занятие?", keymap)
+                        .await?;
+                }
             }
         }
         Ok(())
@@ -159,7 +177,7 @@ impl View for EditSubscription {
                         }
                         format!("количество дней действия на {}", text)
                     }
-                    EditType::CanBuyByUser => {
+                    EditType::CanBuyByUser | EditType::Trial => {
                         ctx.delete_msg(message.id).await?;
                         return Ok(Jmp::Stay);
                     }
@@ -187,10 +205,15 @@ impl View for EditSubscription {
     async fn handle_callback(&mut self, ctx: &mut Context, data: &str) -> Result<Jmp> {
         match calldata!(data) {
             Callback::Yes => {
-                let value = if let State::Confirm(value) = self.state.clone() {
-                    value
-                } else {
-                    return Ok(Jmp::Stay);
+                let value = match self.state.clone() {
+                    State::Confirm(value) => value,
+                    // Yes/no options are answered right away, without a typed value.
+                    State::Init
+                        if matches!(self.edit_type, EditType::CanBuyByUser | EditType::Trial) =>
+                    {
+                        String::new()
+                    }
+                    State::Init => return Ok(Jmp::Stay),
                 };
                 match self.edit_type {
                     EditType::Price => self.edit_price(ctx, value.parse()?).await?,
@@ -201,15 +224,18 @@ impl View for EditSubscription {
                         self.edit_expiration_days(ctx, value.parse()?).await?
                     }
                     EditType::CanBuyByUser => self.edit_can_buy_by_user(ctx, true).await?,
+                    EditType::Trial => self.edit_trial(ctx, true).await?,
                 };
                 ctx.send_msg("Изменения сохранены ✅").await?;
                 ctx.reset_origin().await?;
                 Ok(Jmp::Back)
             }
             Callback::No => {
-                if self.edit_type == EditType::CanBuyByUser {
-                    self.edit_can_buy_by_user(ctx, false).await?;
-                }
+                match self.edit_type {
+                    EditType::CanBuyByUser => self.edit_can_buy_by_user(ctx, false).await?,
+                    EditType::Trial => self.edit_trial(ctx, false).await?,
+                    _ => Jmp::Stay,
+                };
 
                 ctx.reset_origin().await?;
                 Ok(Jmp::Back)
@@ -232,6 +258,7 @@ pub enum EditType {
     FreezeDays,
     ExpirationDays,
     CanBuyByUser,
+    Trial,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -0,0 +1,163 @@
+use std::str::FromStr as _;
+
+use crate::SubscriptionView;
+
+use super::View;
+use async_trait::async_trait;
+use bot_core::{callback_data::Calldata as _, calldata, context::Context, widget::Jmp};
+use bot_viewer::{
+    fmt_phone,
+    gift::{fmt_gift, fmt_gift_value},
+};
+use eyre::Result;
+use model::{
+    decimal::Decimal,
+    gift::GiftValue,
+    rights::Rule,
+    user::{sanitize_phone, User},
+};
+use serde::{Deserialize, Serialize};
+use teloxide::{
+    types::{InlineKeyboardMarkup, Message},
+    utils::markdown::escape,
+};
+
+const GIFT_DAYS: [u32; 3] = [90, 180, 365];
+
+/// Sells a gift certificate for a subscription or for an amount of money.
+pub struct SellGiftCertificate {
+    value: Option<GiftValue>,
+    buyer: Option<User>,
+    days: Option<u32>,
+}
+
+impl SellGiftCertificate {
+    pub fn amount() -> SellGiftCertificate {
+        SellGiftCertificate {
+            value: None,
+            buyer: None,
+            days: None,
+        }
+    }
+
+    pub fn subscription(value: GiftValue) -> SellGiftCertificate {
+        SellGiftCertificate {
+            value: Some(value),
+            buyer: None,
+            days: None,
+        }
+    }
+}
+
+#[async_trait]
+impl View for SellGiftCertificate {
+    fn name(&self) -> &'static str {
+        "SellGiftCertificate"
+    }
+
+    async fn show(&mut self, ctx: &mut Context) -> Result<()> {
+        ctx.ensure(Rule::SellGiftCertificate)?;
+        let mut keymap = InlineKeyboardMarkup::default();
+        let text = match (&self.value, &self.buyer, self.days) {
+            (None, _, _) => "🎁 Введите сумму сертификата".to_string(),
+            (Some(_), None, _) => "🎁 Введите номер телефона покупателя".to_string(),
+            (Some(_), Some(_), None) => {
+                keymap = keymap.append_row(
+                    GIFT_DAYS
+                        .iter()
+                        .map(|days| Callback::Days(*days).button(format!("{} дней", days)))
+                        .collect::<Vec<_>>(),
+                );
+                "🎁 Сколько дней действует сертификат?".to_string()
+            }
+            (Some(value), Some(buyer), Some(days)) => {
+                keymap = keymap.append_row(vec![
+                    Callback::Sell.button("✅ Да"),
+                    Callback::Cancel.button("❌ Отмена"),
+                ]);
+                format!(
+                    "🎁 Продажа подарочного сертификата\n_{}_\nПокупатель: _{}_ {}\nДействует дней: _{}_\n\nВсе верно?",
+                    fmt_gift_value(value),
+                    escape(&buyer.name.to_string()),
+                    fmt_phone(buyer.phone.as_deref()),
+                    days
+                )
+            }
+        };
+        ctx.edit_origin(&text, keymap).await?;
+        Ok(())
+    }
+
+    async fn handle_message(&mut self, ctx: &mut Context, msg: &Message) -> Result<Jmp> {
+        ctx.ensure(Rule::SellGiftCertificate)?;
+        ctx.delete_msg(msg.id).await?;
+        let text = msg.text().unwrap_or_default().trim();
+
+        if self.value.is_none() {
+            match Decimal::from_str(&text.replace(',', ".")) {
+                Ok(amount) if amount > Decimal::zero() => {
+                    self.value = Some(GiftValue::Amount(amount));
+                }
+                _ => {
+                    ctx.send_notification("Введите сумму больше нуля").await;
+                }
+            }
+        } else if self.buyer.is_none() {
+            let phone = if let Some(rest) = text.strip_prefix('8') {
+                sanitize_phone(&format!("7{}", rest))
+            } else if text.starts_with("+7") {
+                sanitize_phone(text)
+            } else {
+                ctx.send_notification("Номер телефона должен начинаться с 8 или \\+7")
+                    .await;
+                return Ok(Jmp::Stay);
+            };
+            self.buyer = ctx
+                .ledger
+                .users
+                .get_by_phone(&mut ctx.session, &phone)
+                .await?;
+            if self.buyer.is_none() {
+                ctx.send_notification(&format!(
+                    "Пользователь с номером *{}* не найден",
+                    fmt_phone(Some(&phone))
+                ))
+                .await;
+            }
+        }
+        Ok(Jmp::Stay)
+    }
+
+    async fn handle_callback(&mut self, ctx: &mut Context, data: &str) -> Result<Jmp> {
+        ctx.ensure(Rule::SellGiftCertificate)?;
+        match calldata!(data) {
+            Callback::Days(days) => {
+                self.days = Some(days);
+                Ok(Jmp::Stay)
+            }
+            Callback::Sell => {
+                let (Some(value), Some(buyer), Some(days)) =
+                    (self.value.clone(), self.buyer.as_ref(), self.days)
+                else {
+                    return Ok(Jmp::Stay);
+                };
+                let certificate = ctx
+                    .ledger
+                    .sell_gift_certificate(&mut ctx.session, buyer.id, value, days)
+                    .await?;
+                ctx.send_msg(&format!("🤑 Продано\n{}", fmt_gift(&certificate)))
+                    .await?;
+                ctx.reset_origin().await?;
+                Ok(Jmp::Goto(SubscriptionView.into()))
+            }
+            Callback::Cancel => Ok(Jmp::Back),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+enum Callback {
+    Days(u32),
+    Sell,
+    Cancel,
+}
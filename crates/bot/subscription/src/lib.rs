@@ -1,7 +1,11 @@
+pub mod bundle;
 pub mod confirm;
 pub mod create;
 pub mod edit;
 pub mod edit_programs;
+pub mod gift;
+pub mod price;
+pub mod restriction;
 pub mod sell;
 pub mod view;
 
@@ -14,6 +18,7 @@ use bot_core::{
 };
 use create::CreateSubscription;
 use eyre::Result;
+use gift::SellGiftCertificate;
 use model::rights::Rule;
 use mongodb::bson::oid::ObjectId;
 use serde::{Deserialize, Serialize};
@@ -45,6 +50,10 @@ impl View for SubscriptionView {
                 ctx.ensure(Rule::CreateSubscription)?;
                 Ok(CreateSubscription::new().into())
             }
+            Callback::SellGiftCertificate => {
+                ctx.ensure(Rule::SellGiftCertificate)?;
+                Ok(SellGiftCertificate::amount().into())
+            }
         }
     }
 }
@@ -113,6 +122,9 @@ async fn render(ctx: &mut Context) -> Result<(String, InlineKeyboardMarkup)> {
     if ctx.has_right(Rule::CreateSubscription) {
         keymap = keymap.append_row(Callback::CreateSubscription.btn_row("🗒 Создать тариф"));
     }
+    if ctx.has_right(Rule::SellGiftCertificate) {
+        keymap = keymap.append_row(Callback::SellGiftCertificate.btn_row("🎁 Сертификат на сумму"));
+    }
 
     Ok((msg.to_string(), keymap))
 }
@@ -121,4 +133,5 @@ async fn render(ctx: &mut Context) -> Result<(String, InlineKeyboardMarkup)> {
 enum Callback {
     Select([u8; 12]),
     CreateSubscription,
+    SellGiftCertificate,
 }
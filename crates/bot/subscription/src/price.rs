@@ -0,0 +1,114 @@
+use std::str::FromStr as _;
+
+use super::View;
+use async_trait::async_trait;
+use bot_core::{callback_data::Calldata as _, calldata, context::Context, widget::Jmp};
+use chrono::{Local, NaiveDate, TimeZone as _, Utc};
+use eyre::Result;
+use model::{decimal::Decimal, rights::Rule};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use teloxide::{
+    types::{InlineKeyboardMarkup, Message},
+    utils::markdown::escape,
+};
+
+/// Schedules a new subscription price from a future date.
+pub struct SchedulePrice {
+    id: ObjectId,
+    price: Option<Decimal>,
+    date: Option<NaiveDate>,
+}
+
+impl SchedulePrice {
+    pub fn new(id: ObjectId) -> Self {
+        Self {
+            id,
+            price: None,
+            date: None,
+        }
+    }
+}
+
+#[async_trait]
+impl View for SchedulePrice {
+    fn name(&self) -> &'static str {
+        "SchedulePrice"
+    }
+
+    async fn show(&mut self, ctx: &mut Context) -> Result<()> {
+        ctx.ensure(Rule::EditSubscription)?;
+        let mut keymap = InlineKeyboardMarkup::default();
+        let text = match (self.price, self.date) {
+            (None, _) => "Введите новую цену".to_string(),
+            (Some(_), None) => "С какой даты действует цена? \\(ДД\\.ММ\\.ГГГГ\\)".to_string(),
+            (Some(price), Some(date)) => {
+                keymap = keymap.append_row(vec![
+                    Callback::Yes.button("✅ Да"),
+                    Callback::No.button("❌ Нет"),
+                ]);
+                escape(&format!(
+                    "Цена {} с {}. Все верно?",
+                    price,
+                    date.format("%d.%m.%Y")
+                ))
+            }
+        };
+        ctx.edit_origin(&text, keymap).await?;
+        Ok(())
+    }
+
+    async fn handle_message(&mut self, ctx: &mut Context, message: &Message) -> Result<Jmp> {
+        ctx.ensure(Rule::EditSubscription)?;
+        ctx.delete_msg(message.id).await?;
+        let text = message.text().unwrap_or_default().trim();
+        if self.price.is_none() {
+            match Decimal::from_str(&text.replace(',', ".")) {
+                Ok(price) if price >= Decimal::zero() => self.price = Some(price),
+                _ => ctx.send_notification("Неверный формат цены").await,
+            }
+        } else if self.date.is_none() {
+            match NaiveDate::parse_from_str(text, "%d.%m.%Y") {
+                Ok(date) if date > Local::now().date_naive() => self.date = Some(date),
+                Ok(_) => {
+                    ctx.send_notification("Дата должна быть в будущем").await;
+                }
+                Err(_) => ctx.send_notification("Неверный формат даты").await,
+            }
+        }
+        Ok(Jmp::Stay)
+    }
+
+    async fn handle_callback(&mut self, ctx: &mut Context, data: &str) -> Result<Jmp> {
+        ctx.ensure(Rule::EditSubscription)?;
+        match calldata!(data) {
+            Callback::Yes => {
+                let (Some(price), Some(date)) = (self.price, self.date) else {
+                    return Ok(Jmp::Stay);
+                };
+                let effective_from = date
+                    .and_hms_opt(0, 0, 0)
+                    .and_then(|dt| Local.from_local_datetime(&dt).earliest())
+                    .ok_or_else(|| eyre::eyre!("Invalid date"))?;
+                ctx.ledger
+                    .subscriptions
+                    .change_price(
+                        &mut ctx.session,
+                        self.id,
+                        price,
+                        effective_from.with_timezone(&Utc),
+                    )
+                    .await?;
+                ctx.send_notification("Цена запланирована ✅").await;
+                Ok(Jmp::Back)
+            }
+            Callback::No => Ok(Jmp::Back),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+enum Callback {
+    Yes,
+    No,
+}
@@ -0,0 +1,170 @@
+use async_trait::async_trait;
+use bot_core::{
+    callback_data::Calldata as _,
+    calldata,
+    context::Context,
+    widget::{Jmp, View},
+};
+use bot_viewer::{
+    day::fmt_weekday,
+    subscription::{fmt_minutes, fmt_restriction},
+};
+use chrono::{NaiveTime, Timelike as _, Weekday};
+use eyre::{Error, Result};
+use model::{
+    restriction::{TimeRestriction, TimeWindow},
+    rights::Rule,
+};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use teloxide::types::{InlineKeyboardMarkup, Message};
+
+const WEEKDAYS: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+/// Days, hours and weekly limit of an off-peak subscription.
+pub struct EditRestriction {
+    id: ObjectId,
+}
+
+impl EditRestriction {
+    pub fn new(id: ObjectId) -> EditRestriction {
+        EditRestriction { id }
+    }
+
+    async fn restriction(&self, ctx: &mut Context) -> Result<TimeRestriction> {
+        let sub = ctx
+            .ledger
+            .subscriptions
+            .get(&mut ctx.session, self.id)
+            .await?
+            .ok_or_else(|| eyre::eyre!("Subscription not found"))?;
+        Ok(sub.restriction.unwrap_or_default())
+    }
+
+    async fn save(&self, ctx: &mut Context, restriction: TimeRestriction) -> Result<()> {
+        let restriction = (!restriction.is_empty()).then_some(restriction);
+        ctx.ledger
+            .subscriptions
+            .edit_restriction(&mut ctx.session, self.id, restriction.as_ref())
+            .await
+    }
+}
+
+#[async_trait]
+impl View for EditRestriction {
+    fn name(&self) -> &'static str {
+        "EditRestriction"
+    }
+
+    async fn show(&mut self, ctx: &mut Context) -> Result<(), Error> {
+        ctx.ensure(Rule::EditSubscription)?;
+        let restriction = self.restriction(ctx).await?;
+
+        let mut msg = "🌙 *Ограничения по времени*\n\n".to_string();
+        if restriction.is_empty() {
+            msg.push_str("Абонемент действует в любое время\n");
+        } else {
+            msg.push_str(&fmt_restriction(&restriction));
+        }
+        msg.push_str(
+            "\nЧтобы добавить часы, введите время начала и конца\\. Например: _08:00 12:00_\nЧтобы ограничить число занятий в неделю, введите число \\(0 \\- без ограничения\\)",
+        );
+
+        let mut keymap = InlineKeyboardMarkup::default();
+        keymap = keymap.append_row(
+            WEEKDAYS
+                .iter()
+                .map(|day| {
+                    let selected = restriction.weekdays.contains(day);
+                    Callback::Weekday(day.num_days_from_monday() as u8).button(format!(
+                        "{}{}",
+                        if selected { "✅" } else { "" },
+                        fmt_weekday(*day)
+                    ))
+                })
+                .collect::<Vec<_>>(),
+        );
+        for (idx, window) in restriction.windows.iter().enumerate() {
+            keymap = keymap.append_row(Callback::DeleteWindow(idx as u8).btn_row(format!(
+                "🗑 {} - {}",
+                fmt_minutes(window.from_min),
+                fmt_minutes(window.to_min)
+            )));
+        }
+        if !restriction.is_empty() {
+            keymap = keymap.append_row(Callback::Clear.btn_row("❌ Снять все ограничения"));
+        }
+        ctx.edit_origin(&msg, keymap).await?;
+        Ok(())
+    }
+
+    async fn handle_message(&mut self, ctx: &mut Context, message: &Message) -> Result<Jmp> {
+        ctx.ensure(Rule::EditSubscription)?;
+        ctx.delete_msg(message.id).await?;
+        let text = message.text().unwrap_or_default().trim();
+        let mut restriction = self.restriction(ctx).await?;
+        if let Ok(limit) = text.parse::<u32>() {
+            restriction.weekly_limit = (limit > 0).then_some(limit);
+        } else if let Some(window) = parse_window(text) {
+            restriction.add_window(window);
+        } else {
+            ctx.send_notification("Неверный формат").await;
+            return Ok(Jmp::Stay);
+        }
+        self.save(ctx, restriction).await?;
+        Ok(Jmp::Stay)
+    }
+
+    async fn handle_callback(&mut self, ctx: &mut Context, data: &str) -> Result<Jmp> {
+        ctx.ensure(Rule::EditSubscription)?;
+        let mut restriction = self.restriction(ctx).await?;
+        match calldata!(data) {
+            Callback::Weekday(day) => {
+                let Some(day) = WEEKDAYS.get(day as usize) else {
+                    return Ok(Jmp::Stay);
+                };
+                restriction.toggle_weekday(*day);
+            }
+            Callback::DeleteWindow(idx) => {
+                if (idx as usize) < restriction.windows.len() {
+                    restriction.windows.remove(idx as usize);
+                }
+            }
+            Callback::Clear => {
+                restriction = TimeRestriction::default();
+            }
+        }
+        self.save(ctx, restriction).await?;
+        Ok(Jmp::Stay)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+enum Callback {
+    Weekday(u8),
+    DeleteWindow(u8),
+    Clear,
+}
+
+/// `08:00 12:00`
+fn parse_window(text: &str) -> Option<TimeWindow> {
+    let mut parts = text.split_whitespace();
+    let mut minutes = || {
+        let time = NaiveTime::parse_from_str(parts.next()?, "%H:%M").ok()?;
+        Some(time.hour() * 60 + time.minute())
+    };
+    let from = minutes()?;
+    let to = match minutes()? {
+        0 => 24 * 60,
+        to => to,
+    };
+    TimeWindow::new(from, to)
+}
@@ -292,7 +292,7 @@ impl View for CreateUserAndSell {
         let sub = ctx
             .ledger
             .subscriptions
-            .get(&mut ctx.session, self.sub_id)
+            .get_for_sale(&mut ctx.session, self.sub_id)
             .await?
             .ok_or_else(|| eyre::eyre!("Subscription {} not found", self.sub_id))?;
 
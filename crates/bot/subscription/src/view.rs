@@ -1,15 +1,23 @@
 use crate::edit_programs::EditPrograms;
 
 use super::{
+    bundle::EditBundle,
     edit::{EditSubscription, EditType},
+    gift::SellGiftCertificate,
+    price::SchedulePrice,
+    restriction::EditRestriction,
     sell::SellView,
     View,
 };
 use async_trait::async_trait;
 use bot_core::{callback_data::Calldata as _, calldata, context::Context, widget::Jmp};
-use bot_viewer::subscription::fmt_subscription_type;
+use bot_viewer::{
+    day::fmt_date,
+    subscription::{fmt_restriction, fmt_subscription_type},
+};
+use chrono::{Local, Utc};
 use eyre::{Context as _, Error, Result};
-use model::rights::Rule;
+use model::{gift::GiftValue, rights::Rule};
 use mongodb::bson::oid::ObjectId;
 use serde::{Deserialize, Serialize};
 use teloxide::{types::InlineKeyboardMarkup, utils::markdown::escape};
@@ -78,6 +86,20 @@ impl View for SubscriptionOption {
                 ctx.ensure(Rule::SellSubscription)?;
                 Ok(SellView::new(self.id).into())
             }
+            Callback::SellGiftCertificate => {
+                ctx.ensure(Rule::SellGiftCertificate)?;
+                let sub = ctx
+                    .ledger
+                    .subscriptions
+                    .get(&mut ctx.session, self.id)
+                    .await?
+                    .ok_or_else(|| eyre::eyre!("Subscription not found"))?;
+                Ok(SellGiftCertificate::subscription(GiftValue::Subscription {
+                    id: sub.id,
+                    name: sub.name,
+                })
+                .into())
+            }
             Callback::EditPrice => {
                 ctx.ensure(Rule::EditSubscription)?;
                 self.edit(EditType::Price).await
@@ -106,6 +128,30 @@ impl View for SubscriptionOption {
                 ctx.ensure(Rule::EditSubscription)?;
                 self.edit(EditType::ExpirationDays).await
             }
+            Callback::EditTrial => {
+                ctx.ensure(Rule::EditSubscription)?;
+                self.edit(EditType::Trial).await
+            }
+            Callback::SchedulePrice => {
+                ctx.ensure(Rule::EditSubscription)?;
+                Ok(SchedulePrice::new(self.id).into())
+            }
+            Callback::EditBundle => {
+                ctx.ensure(Rule::EditSubscription)?;
+                Ok(EditBundle::new(self.id).into())
+            }
+            Callback::EditRestriction => {
+                ctx.ensure(Rule::EditSubscription)?;
+                Ok(EditRestriction::new(self.id).into())
+            }
+            Callback::CancelPrice(version) => {
+                ctx.ensure(Rule::EditSubscription)?;
+                ctx.ledger
+                    .subscriptions
+                    .cancel_scheduled_price(&mut ctx.session, self.id, version)
+                    .await?;
+                Ok(Jmp::Stay)
+            }
         }
     }
 }
@@ -117,7 +163,7 @@ async fn render_sub(
     let sub = ctx
         .ledger
         .subscriptions
-        .get(&mut ctx.session, id)
+        .get_for_sale(&mut ctx.session, id)
         .await?
         .ok_or_else(|| eyre::eyre!("Subscription not found"))?;
 
@@ -147,6 +193,41 @@ async fn render_sub(
     )
     };
 
+    let mut msg = if sub.trial {
+        format!("{}🎟 Пробное занятие\n", msg)
+    } else {
+        msg
+    };
+
+    if sub.is_bundle() {
+        msg.push_str("📦 В пакете:\n");
+        for pool in &sub.pools {
+            msg.push_str(&format!(
+                "{}: _{}_ занятий\n",
+                fmt_subscription_type(ctx, &pool.tp, !ctx.has_right(Rule::EditSubscription))
+                    .await?,
+                pool.items
+            ));
+        }
+    }
+
+    if let Some(restriction) = &sub.restriction {
+        msg.push_str("🌙 Ограничения:\n");
+        msg.push_str(&fmt_restriction(restriction));
+    }
+
+    let scheduled = sub.scheduled_prices(Utc::now());
+    if !scheduled.is_empty() {
+        msg.push_str("📅 Запланированные цены:\n");
+        for price in &scheduled {
+            msg.push_str(&format!(
+                "_{}_ с _{}_\n",
+                price.price.to_string().replace(".", ","),
+                fmt_date(&price.effective_from.with_timezone(&Local))
+            ));
+        }
+    }
+
     let mut keymap = InlineKeyboardMarkup::default();
 
     if ctx.has_right(Rule::BuySubscription) {
@@ -155,17 +236,33 @@ async fn render_sub(
     if ctx.has_right(Rule::SellSubscription) {
         keymap = keymap.append_row(Callback::Sell.btn_row("🛒 Продать"));
     }
+    if ctx.has_right(Rule::SellGiftCertificate) && !sub.trial {
+        keymap = keymap.append_row(Callback::SellGiftCertificate.btn_row("🎁 Продать сертификат"));
+    }
     if ctx.has_right(Rule::EditSubscription) {
         keymap = keymap.append_row(Callback::Delete.btn_row("❌ Удалить"));
         keymap = keymap.append_row(Callback::EditPrice.btn_row("Изменить цену 💸"));
+        keymap = keymap.append_row(Callback::SchedulePrice.btn_row("Запланировать цену 📅"));
+        for price in &scheduled {
+            keymap = keymap.append_row(Callback::CancelPrice(price.version).btn_row(format!(
+                "❌ Отменить цену с {}",
+                price.effective_from.with_timezone(&Local).format("%d.%m.%Y")
+            )));
+        }
         keymap = keymap.append_row(Callback::EditItems.btn_row("Изменить количество занятий"));
         keymap = keymap.append_row(Callback::EditName.btn_row("Изменить название"));
         keymap = keymap.append_row(Callback::EditFreezeDays.btn_row("Изменить дни заморозки"));
         keymap = keymap
             .append_row(Callback::EditCanBuyByUser.btn_row("Изменить доступность для покупки"));
         keymap = keymap.append_row(Callback::EditExpirationDays.btn_row("Изменить время действия"));
+        keymap = keymap.append_row(Callback::EditTrial.btn_row("Пробное занятие 🎟"));
+        keymap = keymap.append_row(Callback::EditRestriction.btn_row("Дни и часы действия 🌙"));
         if sub.subscription_type.is_group() {
             keymap = keymap.append_row(Callback::EditPrograms.btn_row("Изменить программы"));
+            if !sub.unlimited && !sub.trial {
+                keymap =
+                    keymap.append_row(Callback::EditBundle.btn_row("Пакет с персональными 📦"));
+            }
         }
     }
 
@@ -184,4 +281,10 @@ enum Callback {
     EditFreezeDays,
     EditCanBuyByUser,
     EditExpirationDays,
+    EditTrial,
+    SellGiftCertificate,
+    SchedulePrice,
+    CancelPrice(u32),
+    EditRestriction,
+    EditBundle,
 }
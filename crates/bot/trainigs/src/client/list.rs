@@ -8,12 +8,18 @@ use bot_core::{
 use bot_viewer::day::fmt_dt;
 use chrono::Local;
 use eyre::{bail, Result};
-use model::{rights::Rule, training::TrainingId};
+use model::{
+    rights::Rule,
+    training::{Attendance, TrainingId},
+};
 use mongodb::bson::oid::ObjectId;
 use serde::{Deserialize, Serialize};
 use teloxide::{types::InlineKeyboardMarkup, utils::markdown::escape};
 
-use crate::client::{ClientView, Reason};
+use crate::{
+    client::{ClientView, Reason},
+    view::notify_promoted,
+};
 
 use super::add::AddClientView;
 
@@ -49,12 +55,36 @@ impl ClientsList {
                 .await;
             return Ok(Jmp::Stay);
         }
-        ctx.ledger
+        let promoted = ctx
+            .ledger
             .sign_out(&mut ctx.session, training.id(), id, true)
             .await?;
+        notify_promoted(ctx, &training, promoted).await?;
         ctx.send_notification("Клиент удален из тренировки").await;
         Ok(Jmp::Stay)
     }
+
+    pub async fn mark_attendance(
+        &mut self,
+        ctx: &mut Context,
+        id: ObjectId,
+        status: Attendance,
+    ) -> Result<Jmp> {
+        let training = ctx
+            .ledger
+            .calendar
+            .get_training_by_id(&mut ctx.session, self.id)
+            .await?
+            .ok_or_else(|| eyre::eyre!("Training not found"))?;
+        if training.instructor != ctx.me.id {
+            ctx.ensure(Rule::EditTrainingClientsList)?;
+        }
+        ctx.ledger
+            .calendar
+            .mark_attendance(&mut ctx.session, self.id, id, status)
+            .await?;
+        Ok(Jmp::Stay)
+    }
 }
 
 #[async_trait]
@@ -82,6 +112,12 @@ impl View for ClientsList {
         if training.is_processed {
             msg.push_str("Тренировка завершена\\. *Редактирование запрещено\\.*");
         }
+        let can_mark = training.can_mark_attendance(Local::now())
+            && (training.instructor == ctx.me.id
+                || ctx.has_right(Rule::EditTrainingClientsList));
+        if can_mark {
+            msg.push_str("Отметьте посещаемость:\n✅ \\- пришел, 🚫 \\- не пришел, 🤒 \\- уважительная причина\n");
+        }
 
         let mut keymap = InlineKeyboardMarkup::default();
         for client in &training.clients {
@@ -102,6 +138,23 @@ impl View for ClientsList {
                 row.push(Callback::DeleteClient(user.id.bytes()).button("❌"));
             }
             keymap = keymap.append_row(row);
+            if can_mark {
+                let current = training.attendance(user.id);
+                let mut row = Vec::with_capacity(3);
+                for (status, emoji) in [
+                    (Attendance::Attended, "✅"),
+                    (Attendance::NoShow, "🚫"),
+                    (Attendance::Excused, "🤒"),
+                ] {
+                    let text = if current == Some(status) {
+                        format!("[{}]", emoji)
+                    } else {
+                        emoji.to_string()
+                    };
+                    row.push(Callback::Mark(user.id.bytes(), status).button(text));
+                }
+                keymap = keymap.append_row(row);
+            }
         }
 
         if training.is_group() && ctx.has_right(Rule::EditTrainingClientsList) && !training.is_processed {
@@ -116,6 +169,10 @@ impl View for ClientsList {
             Callback::SelectClient(id) => self.view_user_profile(ObjectId::from_bytes(id)).await,
             Callback::AddClient => self.add_client(ctx).await,
             Callback::DeleteClient(id) => self.delete_client(ctx, ObjectId::from_bytes(id)).await,
+            Callback::Mark(id, status) => {
+                self.mark_attendance(ctx, ObjectId::from_bytes(id), status)
+                    .await
+            }
         }
     }
 }
@@ -125,4 +182,5 @@ enum Callback {
     SelectClient([u8; 12]),
     AddClient,
     DeleteClient([u8; 12]),
+    Mark([u8; 12], Attendance),
 }
@@ -21,6 +21,8 @@ use teloxide::{
     utils::markdown::escape,
 };
 
+use crate::view::notify_promoted;
+
 pub mod add;
 pub mod list;
 
@@ -110,9 +112,11 @@ impl ClientView {
                 .await;
             return Ok(());
         }
-        ctx.ledger
+        let promoted = ctx
+            .ledger
             .sign_out(&mut ctx.session, training.id(), self.id, true)
             .await?;
+        notify_promoted(ctx, &training, promoted).await?;
 
         Ok(())
     }
@@ -7,7 +7,11 @@ use bot_core::{
 };
 use bot_viewer::day::fmt_dt;
 use eyre::{bail, Result};
-use model::{rights::Rule, training::TrainingId, user::User};
+use model::{
+    rights::Rule,
+    training::{ClientsPolicy, SeriesScope, TrainingId},
+    user::User,
+};
 use mongodb::bson::oid::ObjectId;
 use serde::{Deserialize, Serialize};
 use teloxide::{
@@ -15,14 +19,21 @@ use teloxide::{
     utils::markdown::escape,
 };
 
+use super::{clients_policy_row, has_clients, notify_clients};
+
 pub struct ChangeCouch {
     id: TrainingId,
-    all: bool,
+    scope: SeriesScope,
+    clients: ClientsPolicy,
 }
 
 impl ChangeCouch {
-    pub fn new(id: TrainingId, all: bool) -> ChangeCouch {
-        ChangeCouch { id, all }
+    pub fn new(id: TrainingId, scope: SeriesScope) -> ChangeCouch {
+        ChangeCouch {
+            id,
+            scope,
+            clients: ClientsPolicy::Move,
+        }
     }
 
     async fn change_couch(&self, ctx: &mut Context, id: ObjectId) -> Result<()> {
@@ -45,9 +56,15 @@ impl ChangeCouch {
         }
         let old_couch = training.instructor;
         let new_couch = id;
-        ctx.ledger
-            .calendar
-            .change_couch(&mut ctx.session, training.id(), id, self.all)
+        let (series, released) = ctx
+            .ledger
+            .replace_instructor(
+                &mut ctx.session,
+                training.id(),
+                id,
+                self.scope,
+                self.clients,
+            )
             .await?;
 
         ctx.send_notification("Тренер успешно изменен").await;
@@ -60,12 +77,19 @@ impl ChangeCouch {
             escape(&training.name),
             fmt_dt(&training.get_slot().start_at())
         );
+        let msg = if series.len() > 1 {
+            format!("{} и еще {} тренировках серии", msg, series.len() - 1)
+        } else {
+            msg
+        };
         ctx.notify(ChatId(old_couch.tg_id), &msg, true).await;
         ctx.notify(ChatId(new_couch.tg_id), &msg, true).await;
-        for client in training.clients.iter() {
-            let client = ctx.ledger.get_user(&mut ctx.session, *client).await?;
-            ctx.notify(ChatId(client.tg_id), &msg, true).await;
-        }
+        let change = format!(
+            "Произошла замена инструктора *{}* ➡️ *{}*",
+            escape(&old_couch.name.first_name),
+            escape(&new_couch.name.first_name)
+        );
+        notify_clients(ctx, &series, &released, &change).await;
 
         Ok(())
     }
@@ -85,6 +109,14 @@ impl View for ChangeCouch {
         for instruct in instructs {
             keymap = keymap.append_row(vec![render_button(&instruct)]);
         }
+        let series = ctx
+            .ledger
+            .calendar
+            .series(&mut ctx.session, self.id, self.scope)
+            .await?;
+        if has_clients(&series) {
+            keymap = keymap.append_row(clients_policy_row(self.clients, Callback::Clients));
+        }
 
         ctx.edit_origin(msg, keymap).await?;
         Ok(())
@@ -95,7 +127,11 @@ impl View for ChangeCouch {
             Callback::SelectCouch(id) => {
                 let id: ObjectId = ObjectId::from_bytes(id);
                 self.change_couch(ctx, id).await?;
-                return Ok(Jmp::Back);
+                Ok(Jmp::Back)
+            }
+            Callback::Clients(policy) => {
+                self.clients = policy;
+                Ok(Jmp::Stay)
             }
         }
     }
@@ -104,6 +140,7 @@ impl View for ChangeCouch {
 #[derive(Serialize, Deserialize)]
 enum Callback {
     SelectCouch([u8; 12]),
+    Clients(ClientsPolicy),
 }
 
 fn render_button(user: &User) -> InlineKeyboardButton {
@@ -6,11 +6,21 @@ use bot_core::{
     widget::{Jmp, View},
 };
 use bot_viewer::day::fmt_dt;
+use crate::windows::{EditWindows, WindowsTarget};
 use couch::ChangeCouch;
 use eyre::{bail, Result};
-use model::{rights::Rule, training::TrainingId};
+use log::warn;
+use model::{
+    rights::Rule,
+    training::{ClientsPolicy, SeriesScope, Training, TrainingId},
+};
+use mongodb::bson::oid::ObjectId;
 use serde::{Deserialize, Serialize};
-use teloxide::{types::InlineKeyboardMarkup, utils::markdown::escape};
+use std::collections::BTreeMap;
+use teloxide::{
+    types::{ChatId, InlineKeyboardMarkup},
+    utils::markdown::escape,
+};
 
 pub mod couch;
 pub mod name;
@@ -19,11 +29,15 @@ pub mod program;
 
 pub struct EditTraining {
     id: TrainingId,
+    scope: SeriesScope,
 }
 
 impl EditTraining {
     pub fn new(id: TrainingId) -> Self {
-        Self { id }
+        Self {
+            id,
+            scope: SeriesScope::This,
+        }
     }
 
     pub fn hidden(ctx: &mut Context) -> Result<bool> {
@@ -35,9 +49,9 @@ impl EditTraining {
         Ok(!show)
     }
 
-    async fn change_couch(&mut self, ctx: &mut Context, all: bool) -> Result<Jmp> {
+    async fn change_couch(&mut self, ctx: &mut Context) -> Result<Jmp> {
         ctx.ensure(Rule::EditTrainingCouch)?;
-        Ok(ChangeCouch::new(self.id, all).into())
+        Ok(ChangeCouch::new(self.id, self.scope).into())
     }
 
     async fn delete_training(&mut self, ctx: &mut Context, all: bool) -> Result<Jmp> {
@@ -129,6 +143,22 @@ impl View for EditTraining {
 
         let mut keymap = InlineKeyboardMarkup::default();
 
+        if !training.is_one_time {
+            keymap = keymap.append_row(
+                [
+                    (SeriesScope::This, "эта"),
+                    (SeriesScope::Following, "и следующие"),
+                    (SeriesScope::All, "вся серия"),
+                ]
+                .into_iter()
+                .map(|(scope, name)| {
+                    let mark = if self.scope == scope { "✅" } else { "⬜" };
+                    Callback::Scope(scope).button(format!("{} {}", mark, name))
+                })
+                .collect::<Vec<_>>(),
+            );
+        }
+
         if ctx.has_right(Rule::EditTraining) {
             keymap = keymap.append_row(vec![Callback::ChangeName.button("🔄 Изменить название")]);
             keymap = keymap.append_row(vec![
@@ -137,13 +167,12 @@ impl View for EditTraining {
             ]);
         }
 
+        if ctx.has_right(Rule::EditTraining) {
+            keymap = keymap.append_row(vec![Callback::Windows.button("⏳ Правила записи")]);
+        }
+
         if ctx.has_right(Rule::ChangeTrainingSlot) {
-            keymap = keymap.append_row(vec![
-                Callback::ChangeStartAt(false).button("🕒 Изменить время")
-            ]);
-            keymap = keymap.append_row(vec![
-                Callback::ChangeStartAt(true).button("🕒 Изменить время для всех")
-            ]);
+            keymap = keymap.append_row(vec![Callback::ChangeStartAt.button("🕒 Изменить время")]);
         }
 
         if ctx.has_right(Rule::SetKeepOpen) {
@@ -168,12 +197,8 @@ impl View for EditTraining {
             }
         }
         if ctx.has_right(Rule::EditTrainingCouch) {
-            keymap = keymap.append_row(vec![
-                Callback::ChangeCouch(false).button("🔄 Заменить инструктора")
-            ]);
-            keymap = keymap.append_row(vec![
-                Callback::ChangeCouch(true).button("🔄 Заменить инструктора на все")
-            ]);
+            keymap =
+                keymap.append_row(vec![Callback::ChangeCouch.button("🔄 Заменить инструктора")]);
         }
 
         if ctx.has_right(Rule::SetFree) {
@@ -192,20 +217,24 @@ impl View for EditTraining {
 
     async fn handle_callback(&mut self, ctx: &mut Context, data: &str) -> Result<Jmp> {
         match calldata!(data) {
-            Callback::ChangeCouch(all) => self.change_couch(ctx, all).await,
+            Callback::Scope(scope) => {
+                self.scope = scope;
+                Ok(Jmp::Stay)
+            }
+            Callback::ChangeCouch => self.change_couch(ctx).await,
             Callback::Delete(all) => self.delete_training(ctx, all).await,
             Callback::KeepOpen(keep_open) => self.keep_open(ctx, keep_open).await,
             Callback::SetFree(free) => self.set_free(ctx, free).await,
             Callback::ChangeName => {
                 if ctx.has_right(Rule::EditTraining) {
-                    Ok(name::ChangeName::new(self.id).into())
+                    Ok(name::ChangeName::new(self.id, self.scope).into())
                 } else {
                     Ok(Jmp::Stay)
                 }
             }
-            Callback::ChangeStartAt(all) => {
+            Callback::ChangeStartAt => {
                 if ctx.has_right(Rule::ChangeTrainingSlot) {
-                    Ok(time::ChangeTime::new(self.id, all).into())
+                    Ok(time::ChangeTime::new(self.id, self.scope).into())
                 } else {
                     Ok(Jmp::Stay)
                 }
@@ -217,17 +246,98 @@ impl View for EditTraining {
                     Ok(Jmp::Stay)
                 }
             }
+            Callback::Windows => {
+                if ctx.has_right(Rule::EditTraining) {
+                    Ok(EditWindows::new(WindowsTarget::Training(self.id)).into())
+                } else {
+                    Ok(Jmp::Stay)
+                }
+            }
         }
     }
 }
 
 #[derive(Serialize, Deserialize)]
 enum Callback {
-    ChangeCouch(bool),
+    Scope(SeriesScope),
+    ChangeCouch,
     Delete(bool),
     KeepOpen(bool),
     SetFree(bool),
-    ChangeStartAt(bool),
+    ChangeStartAt,
     ChangeName,
     ChangeProgram(bool),
+    Windows,
+}
+
+/// Row that lets the manager decide what happens to clients of the edited trainings.
+fn clients_policy_row<C: Calldata>(
+    selected: ClientsPolicy,
+    callback: impl Fn(ClientsPolicy) -> C,
+) -> Vec<teloxide::types::InlineKeyboardButton> {
+    [
+        (ClientsPolicy::Move, "Перенести клиентов"),
+        (ClientsPolicy::SignOut, "Выписать с возвратом"),
+    ]
+    .into_iter()
+    .map(|(policy, name)| {
+        let mark = if selected == policy { "✅" } else { "⬜" };
+        callback(policy).button(format!("{} {}", mark, name))
+    })
+    .collect()
+}
+
+/// Sends every client one message about all of their edited trainings.
+/// Only the `released` bookings were signed out, the rest were kept.
+async fn notify_clients(
+    ctx: &mut Context,
+    series: &[Training],
+    released: &[(TrainingId, ObjectId)],
+    change: &str,
+) {
+    let mut by_client: BTreeMap<ObjectId, (Vec<&Training>, Vec<&Training>)> = BTreeMap::new();
+    for training in series {
+        for client in &training.clients {
+            let (kept, signed_out) = by_client.entry(*client).or_default();
+            if released.contains(&(training.id(), *client)) {
+                signed_out.push(training);
+            } else {
+                kept.push(training);
+            }
+        }
+    }
+
+    for (client, (kept, signed_out)) in by_client {
+        let user = match ctx.ledger.get_user(&mut ctx.session, client).await {
+            Ok(user) => user,
+            Err(err) => {
+                warn!("Failed to notify client {} about the change: {:#}", client, err);
+                continue;
+            }
+        };
+        let mut msg = format!("{}\\.", change);
+        if !kept.is_empty() {
+            msg.push_str("\nВаши записи сохранены:\n");
+            push_trainings(&mut msg, &kept);
+        }
+        if !signed_out.is_empty() {
+            msg.push_str("\nВы выписаны с тренировок, занятия вернулись на абонемент:\n");
+            push_trainings(&mut msg, &signed_out);
+        }
+        ctx.notify(ChatId(user.tg_id), &msg, true).await;
+    }
+}
+
+fn push_trainings(msg: &mut String, trainings: &[&Training]) {
+    for training in trainings {
+        msg.push_str(&format!(
+            "• {} {}\n",
+            fmt_dt(&training.get_slot().start_at()),
+            escape(&training.name)
+        ));
+    }
+}
+
+fn has_clients(series: &[Training]) -> bool {
+    series.iter().any(|training| !training.clients.is_empty())
 }
@@ -4,16 +4,20 @@ use bot_core::{
     widget::{Jmp, View},
 };
 use eyre::{Ok, Result};
-use model::{rights::Rule, training::TrainingId};
+use model::{
+    rights::Rule,
+    training::{SeriesScope, TrainingId},
+};
 use teloxide::types::{InlineKeyboardMarkup, Message};
 
 pub struct ChangeName {
     id: TrainingId,
+    scope: SeriesScope,
 }
 
 impl ChangeName {
-    pub fn new(id: TrainingId) -> ChangeName {
-        ChangeName { id }
+    pub fn new(id: TrainingId, scope: SeriesScope) -> ChangeName {
+        ChangeName { id, scope }
     }
 }
 
@@ -42,7 +46,7 @@ impl View for ChangeName {
 
         ctx.ledger
             .calendar
-            .update_training_name(&mut ctx.session, self.id, name)
+            .update_training_name(&mut ctx.session, self.id, name, self.scope)
             .await?;
         ctx.send_notification("Название тренировки изменено").await;
         Ok(Jmp::BackSteps(3))
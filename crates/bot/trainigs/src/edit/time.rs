@@ -9,18 +9,24 @@ use bot_core::{
 use chrono::{Local, Timelike, Utc};
 use eyre::Result;
 use log::warn;
-use model::{rights::Rule, slot::Slot, training::TrainingId};
+use model::{
+    rights::Rule,
+    slot::Slot,
+    training::{ClientsPolicy, SeriesScope, TrainingId},
+};
 use serde::{Deserialize, Serialize};
 use teloxide::types::{InlineKeyboardMarkup, Message};
 
+use super::{clients_policy_row, has_clients, notify_clients};
+
 pub struct ChangeTime {
     id: TrainingId,
-    all: bool,
+    scope: SeriesScope,
 }
 
 impl ChangeTime {
-    pub fn new(id: TrainingId, all: bool) -> ChangeTime {
-        ChangeTime { id, all }
+    pub fn new(id: TrainingId, scope: SeriesScope) -> ChangeTime {
+        ChangeTime { id, scope }
     }
 }
 
@@ -81,7 +87,7 @@ impl View for ChangeTime {
         Ok(ConfirmChangeTime::new(
             self.id,
             Slot::new(start_at, training.duration_min, self.id.room),
-            self.all,
+            self.scope,
         )
         .into())
     }
@@ -90,11 +96,17 @@ impl View for ChangeTime {
 pub struct ConfirmChangeTime {
     id: TrainingId,
     slot: Slot,
-    all: bool,
+    scope: SeriesScope,
+    clients: ClientsPolicy,
 }
 impl ConfirmChangeTime {
-    pub fn new(id: TrainingId, slot: Slot, all: bool) -> ConfirmChangeTime {
-        ConfirmChangeTime { id, slot, all }
+    pub fn new(id: TrainingId, slot: Slot, scope: SeriesScope) -> ConfirmChangeTime {
+        ConfirmChangeTime {
+            id,
+            slot,
+            scope,
+            clients: ClientsPolicy::Move,
+        }
     }
 }
 
@@ -107,7 +119,7 @@ impl View for ConfirmChangeTime {
     async fn show(&mut self, ctx: &mut Context) -> Result<()> {
         ctx.ensure(Rule::ChangeTrainingSlot)?;
 
-        let msg = if self.all {
+        let msg = if self.scope.is_series() {
             format!(
                 "Изменить время тренировок с {} на {}?",
                 self.id.start_at.with_timezone(&Local).format("%H:%M"),
@@ -122,6 +134,14 @@ impl View for ConfirmChangeTime {
         };
 
         let mut keymap = InlineKeyboardMarkup::default();
+        let series = ctx
+            .ledger
+            .calendar
+            .series(&mut ctx.session, self.id, self.scope)
+            .await?;
+        if has_clients(&series) {
+            keymap = keymap.append_row(clients_policy_row(self.clients, ConfirmCallback::Clients));
+        }
         keymap = keymap.append_row(vec![
             ConfirmCallback::Confirm.button("✅ Подтвердить"),
             ConfirmCallback::Cancel.button("❌ Отмена"),
@@ -135,13 +155,29 @@ impl View for ConfirmChangeTime {
             ConfirmCallback::Confirm => {
                 ctx.ensure(Rule::ChangeTrainingSlot)?;
 
-                ctx.ledger
-                    .calendar
-                    .change_slot(&mut ctx.session, self.id, self.slot, self.all)
+                let (series, released) = ctx
+                    .ledger
+                    .reschedule_training(
+                        &mut ctx.session,
+                        self.id,
+                        self.slot,
+                        self.scope,
+                        self.clients,
+                    )
                     .await?;
+                let change = format!(
+                    "Время тренировки изменено с {} на {}",
+                    self.id.start_at.with_timezone(&Local).format("%H:%M"),
+                    self.slot.start_at().format("%H:%M")
+                );
+                notify_clients(ctx, &series, &released, &change).await;
                 ctx.send_notification("Время тренировки изменено").await;
                 Ok(Jmp::BackSteps(4))
             }
+            ConfirmCallback::Clients(policy) => {
+                self.clients = policy;
+                Ok(Jmp::Stay)
+            }
             ConfirmCallback::Cancel => Ok(Jmp::BackSteps(2)),
         }
     }
@@ -151,4 +187,5 @@ impl View for ConfirmChangeTime {
 enum ConfirmCallback {
     Confirm,
     Cancel,
+    Clients(ClientsPolicy),
 }
@@ -19,7 +19,7 @@ use teloxide::{
     utils::markdown::escape,
 };
 
-use crate::view::{sign_out, sign_up};
+use crate::view::{sign_out, sign_up, ConfirmLateSignOut};
 
 pub struct FamilySignIn {
     id: TrainingId,
@@ -46,6 +46,7 @@ impl View for FamilySignIn {
             .ok_or_else(|| eyre::eyre!("Training not found"))?;
 
         let tr_status = training.status(Local::now());
+        let late_cancel = ctx.ledger.settings.get(&mut ctx.session).await?.late_cancel.allowed;
 
         let msg = format!(
             "👨‍👩‍👧‍👦 Запись на тренировку *{}*\nв _{}_",
@@ -55,10 +56,10 @@ impl View for FamilySignIn {
 
         let mut keymap = InlineKeyboardMarkup::default();
 
-        keymap = keymap.append_row(make_row(&training, tr_status, &ctx.me, true));
+        keymap = keymap.append_row(make_row(&training, tr_status, late_cancel, &ctx.me, true));
 
         for child in ctx.me.family.children.iter() {
-            keymap = keymap.append_row(make_row(&training, tr_status, child, false));
+            keymap = keymap.append_row(make_row(&training, tr_status, late_cancel, child, false));
         }
 
         ctx.edit_origin(&msg, keymap).await?;
@@ -75,6 +76,10 @@ impl View for FamilySignIn {
                 let id = ObjectId::from_bytes(id);
                 sign_out(ctx, self.id, id).await?;
             }
+            Callback::SignOutLate(id) => {
+                let id = ObjectId::from_bytes(id);
+                return Ok(Jmp::Next(ConfirmLateSignOut::new(self.id, id).into()));
+            }
             Callback::None => {
                 // do nothing
             }
@@ -86,6 +91,7 @@ impl View for FamilySignIn {
 fn make_row(
     training: &Training,
     tr_status: TrainingStatus,
+    late_cancel: bool,
     user: &User,
     iam: bool,
 ) -> Vec<InlineKeyboardButton> {
@@ -101,6 +107,8 @@ fn make_row(
     let sign_callback = if signed {
         if can_sign_out {
             Callback::SignOut(user.id.bytes()).button("❌ Отменить запись")
+        } else if late_cancel && tr_status.can_sign_out_late() {
+            Callback::SignOutLate(user.id.bytes()).button("❌ Отменить со штрафом")
         } else {
             Callback::None.button("Отмена не возможна")
         }
@@ -123,5 +131,6 @@ fn make_row(
 enum Callback {
     SingIn([u8; 12]),
     SignOut([u8; 12]),
+    SignOutLate([u8; 12]),
     None,
 }
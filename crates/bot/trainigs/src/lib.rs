@@ -2,6 +2,8 @@ mod client;
 pub mod list;
 pub mod program;
 pub mod schedule;
+pub mod series;
 pub mod view;
 pub mod family;
-pub mod edit;
\ No newline at end of file
+pub mod edit;
+pub mod windows;
\ No newline at end of file
@@ -11,7 +11,7 @@ use eyre::Result;
 use model::training::Filter;
 use mongodb::bson::oid::ObjectId;
 use serde::{Deserialize, Serialize};
-use teloxide::types::InlineKeyboardMarkup;
+use teloxide::{types::InlineKeyboardMarkup, utils::markdown::escape};
 
 use crate::view::TrainingView;
 
@@ -57,17 +57,69 @@ impl View for TrainingList {
         Ok(())
     }
 
-    async fn handle_callback(&mut self, _: &mut Context, data: &str) -> Result<Jmp> {
+    async fn handle_callback(&mut self, ctx: &mut Context, data: &str) -> Result<Jmp> {
         match calldata!(data) {
             Callback::SelectTraining(date) => Ok(TrainingView::new(date.into()).into()),
             Callback::Offset(offset) => {
                 self.offset = offset;
                 Ok(Jmp::Stay)
             }
+            Callback::Export => {
+                if let Some(id) = feed_owner(&self.filter) {
+                    export(ctx, id).await?;
+                }
+                Ok(Jmp::Stay)
+            }
         }
     }
 }
 
+fn feed_owner(filter: &Filter) -> Option<ObjectId> {
+    match filter {
+        Filter::Client(id) | Filter::Instructor(id) | Filter::Canceled(id) => Some(*id),
+        Filter::Program(_) => None,
+    }
+}
+
+/// Sends the trainings as an .ics file. The owner also gets a feed link
+/// that keeps the phone calendar in sync.
+async fn export(ctx: &mut Context, id: ObjectId) -> Result<()> {
+    let user = ctx.ledger.get_user(&mut ctx.session, id).await?;
+    let ics = ctx
+        .ledger
+        .calendar
+        .ical_feed(&mut ctx.session, &user)
+        .await?;
+    ctx.send_document(ics.into_bytes(), "trainings.ics").await?;
+
+    if id == ctx.me.id {
+        let token = ctx
+            .ledger
+            .users
+            .calendar_token(&mut ctx.session, id)
+            .await?;
+        let url = feed_url(ctx.bot.env().app_url(), &token);
+        ctx.send_notification(&format!(
+            "📅 Чтобы календарь обновлялся сам, подпишитесь на ссылку:\n`{}`\nНикому ее не показывайте",
+            escape(&url)
+        ))
+        .await;
+    }
+    Ok(())
+}
+
+fn feed_url(app_url: &str, token: &str) -> String {
+    let origin = app_url
+        .find("://")
+        .and_then(|scheme| {
+            app_url[scheme + 3..]
+                .find('/')
+                .map(|path| &app_url[..scheme + 3 + path])
+        })
+        .unwrap_or(app_url);
+    format!("{}/ical/{}.ics", origin.trim_end_matches('/'), token)
+}
+
 async fn render(
     ctx: &mut Context,
     filter: Filter,
@@ -75,6 +127,7 @@ async fn render(
 ) -> Result<(String, InlineKeyboardMarkup)> {
     let mut msg = "🫶🏻 Тренировки:\n".to_owned();
     let mut keymap = InlineKeyboardMarkup::default();
+    let exportable = feed_owner(&filter).is_some();
     let trainings = ctx
         .ledger
         .calendar
@@ -128,6 +181,9 @@ async fn render(
         row.push(Callback::Offset(offset + TRAININGS_PER_PAGE).button("➡️"));
     };
     keymap = keymap.append_row(row);
+    if exportable {
+        keymap = keymap.append_row(Callback::Export.btn_row("📅 В календарь телефона"));
+    }
 
     Ok((msg, keymap))
 }
@@ -136,4 +192,5 @@ async fn render(
 pub enum Callback {
     SelectTraining(TrainingIdCallback),
     Offset(u32),
+    Export,
 }
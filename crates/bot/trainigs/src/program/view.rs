@@ -1,6 +1,7 @@
 use crate::{list::TrainingList, schedule::group::ScheduleTrainingPreset};
 
 use super::edit::EditProgram;
+use crate::windows::{EditWindows, WindowsTarget};
 use async_trait::async_trait;
 use bot_core::{
     callback_data::Calldata as _,
@@ -8,7 +9,7 @@ use bot_core::{
     context::Context,
     widget::{Jmp, View},
 };
-use bot_viewer::training::fmt_training_type;
+use bot_viewer::training::{fmt_booking_windows, fmt_training_type};
 use eyre::Result;
 use model::{program::Program, rights::Rule};
 use mongodb::bson::oid::ObjectId;
@@ -57,6 +58,11 @@ impl ProgramView {
         Ok(EditProgram::new(self.id, super::edit::EditType::Description).into())
     }
 
+    async fn edit_windows(&mut self, ctx: &mut Context) -> Result<Jmp> {
+        ctx.ensure(Rule::EditTraining)?;
+        Ok(EditWindows::new(WindowsTarget::Program(self.id)).into())
+    }
+
     async fn hide(&mut self, ctx: &mut Context, hide: bool) -> Result<Jmp> {
         ctx.ensure(Rule::EditTraining)?;
 
@@ -95,6 +101,7 @@ impl View for ProgramView {
             Callback::EditDuration => self.edit_duration(ctx).await,
             Callback::EditName => self.edit_name(ctx).await,
             Callback::EditDescription => self.edit_description(ctx).await,
+            Callback::EditWindows => self.edit_windows(ctx).await,
             Callback::Hide(visible) => self.hide(ctx, visible).await,
         }
     }
@@ -106,12 +113,14 @@ async fn render(ctx: &Context, training: &Program) -> Result<(String, InlineKeyb
 🧘*Тренировка*: {}
 *Продолжительность*: {}мин
 *Вместимость*: {}
+{}
 [Описание]({})
 {}
 ",
         escape(&training.name),
         training.duration_min,
         training.capacity,
+        fmt_booking_windows(&training.windows),
         escape(&training.description),
         fmt_training_type(training.tp),
     );
@@ -128,6 +137,7 @@ async fn render(ctx: &Context, training: &Program) -> Result<(String, InlineKeyb
         keymap.push(vec![Callback::EditCapacity.button("👥Изменить вместимость")]);
         keymap.push(vec![Callback::EditName.button("📝Изменить название")]);
         keymap.push(vec![Callback::EditDescription.button("📝Изменить описание")]);
+        keymap.push(vec![Callback::EditWindows.button("⏳Правила записи")]);
 
         if training.visible {
             keymap.push(vec![Callback::Hide(true).button("🔒Скрыть")]);
@@ -151,5 +161,6 @@ pub enum Callback {
     EditCapacity,
     EditName,
     EditDescription,
+    EditWindows,
     Hide(bool),
 }
@@ -3,7 +3,7 @@ use bot_viewer::rooms::fmt_room;
 use chrono::{DateTime, Local};
 use eyre::Result;
 use is_one_time::SetOneTime;
-use model::{program::Program, rooms::Room};
+use model::program::Program;
 use mongodb::bson::oid::ObjectId;
 use set_date_time::SetDateTime;
 use set_instructor::SetInstructor;
@@ -92,6 +92,17 @@ pub async fn render_msg(
         "❓".to_string()
     };
 
+    let room = match preset.room {
+        Some(room) => ctx
+            .ledger
+            .rooms
+            .get_by_id(&mut ctx.session, room)
+            .await?
+            .map(|r| escape(&fmt_room(&r)))
+            .unwrap_or_else(|| "❓".to_string()),
+        None => "❓".to_string(),
+    };
+
     Ok(format!(
         "*Тренировка*: _{}_\n*Дата*: _{}_\n*Инструктор*: _{}_\n*Регулярность*: _{}_\n*Зал*: _{}_\n\n*{}*",
         escape(&training.name),
@@ -105,10 +116,7 @@ pub async fn render_msg(
                 "регулярная".to_owned()
             })
             .unwrap_or_else(|| "❓".to_string()),
-        preset
-            .room
-            .map(|r| fmt_room(Room::from(r)))
-            .unwrap_or_else(|| "❓"),
+        room,
         escape(if request.is_empty() {"."} else {request}),
     ))
 }
@@ -8,7 +8,7 @@ use bot_core::{
 };
 use bot_viewer::rooms::fmt_room;
 use eyre::Result;
-use model::rooms::Room;
+use mongodb::bson::oid::ObjectId;
 use serde::{Deserialize, Serialize};
 use teloxide::types::InlineKeyboardMarkup;
 
@@ -44,10 +44,9 @@ impl View for SetRoom {
         )
         .await?;
         let mut keymap = InlineKeyboardMarkup::default();
-        keymap.inline_keyboard.push(vec![
-            Callback::SelectRoom(Room::Adult).button(fmt_room(Room::Adult)),
-            Callback::SelectRoom(Room::Child).button(fmt_room(Room::Child)),
-        ]);
+        for room in ctx.ledger.rooms.get_active(&mut ctx.session).await? {
+            keymap = keymap.append_row(Callback::SelectRoom(room.id.bytes()).btn_row(fmt_room(&room)));
+        }
         ctx.edit_origin(&msg, keymap).await?;
         Ok(())
     }
@@ -55,7 +54,7 @@ impl View for SetRoom {
     async fn handle_callback(&mut self, _: &mut Context, data: &str) -> Result<Jmp> {
         match calldata!(data) {
             Callback::SelectRoom(room) => {
-                self.preset.room = Some(room.id());
+                self.preset.room = Some(ObjectId::from_bytes(room));
             }
         };
         Ok(self.preset.into_next_view().into())
@@ -64,5 +63,5 @@ impl View for SetRoom {
 
 #[derive(Debug, Deserialize, Serialize)]
 pub enum Callback {
-    SelectRoom(Room),
+    SelectRoom([u8; 12]),
 }
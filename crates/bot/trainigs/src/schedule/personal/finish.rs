@@ -7,18 +7,22 @@ use bot_core::{
     widget::{Jmp, View},
 };
 use eyre::Result;
-use model::rights::Rule;
+use model::{personal_series::{PersonalSeries, SeriesEnd}, rights::Rule};
 use serde::{Deserialize, Serialize};
 use teloxide::types::InlineKeyboardMarkup;
 
+/// Options for weekly repeats, in sessions. `0` - a single training.
+const REPEATS: [u32; 4] = [0, 4, 8, 12];
+
 #[derive(Default)]
 pub struct Finish {
     preset: PersonalTrainingPreset,
+    repeat: u32,
 }
 
 impl Finish {
     pub fn new(preset: PersonalTrainingPreset) -> Self {
-        Self { preset }
+        Self { preset, repeat: 0 }
     }
 }
 
@@ -30,10 +34,18 @@ impl View for Finish {
 
     async fn show(&mut self, ctx: &mut Context) -> Result<()> {
         let msg = render_msg(ctx, &self.preset, "Все верно?").await?;
-        let keymap = vec![vec![
-            Callback::Yes.button("✅ Сохранить"),
-            Callback::No.button("❌ Отмена"),
-        ]];
+        let repeat = if self.repeat == 0 {
+            "🔁 Без повтора".to_string()
+        } else {
+            format!("🔁 Каждую неделю: {} тренировок", self.repeat)
+        };
+        let keymap = vec![
+            vec![Callback::Repeat.button(repeat)],
+            vec![
+                Callback::Yes.button("✅ Сохранить"),
+                Callback::No.button("❌ Отмена"),
+            ],
+        ];
         ctx.edit_origin(&msg, InlineKeyboardMarkup::new(keymap))
             .await?;
         Ok(())
@@ -55,17 +67,39 @@ impl View for Finish {
                     .ok_or_else(|| eyre::eyre!("Client is missing"))?;
                 let room = preset.room.ok_or_else(|| eyre::eyre!("Room is missing"))?;
 
-                ctx.ledger
-                    .schedule_personal_training(
-                        &mut ctx.session,
-                        client,
-                        instructor,
-                        date_time,
-                        DURATION,
-                        room,
-                    )
-                    .await?;
-                ctx.send_msg("Тренировка успешно добавлена ✅").await?;
+                if self.repeat == 0 {
+                    ctx.ledger
+                        .schedule_personal_training(
+                            &mut ctx.session,
+                            client,
+                            instructor,
+                            date_time,
+                            DURATION,
+                            room,
+                        )
+                        .await?;
+                    ctx.send_msg("Тренировка успешно добавлена ✅").await?;
+                } else {
+                    ctx.ledger
+                        .schedule_personal_series(
+                            &mut ctx.session,
+                            PersonalSeries::new(
+                                client,
+                                instructor,
+                                room,
+                                date_time,
+                                DURATION,
+                                SeriesEnd::Count(self.repeat),
+                            ),
+                        )
+                        .await?;
+                    ctx.send_msg("Серия тренировок успешно добавлена ✅").await?;
+                }
+            }
+            Callback::Repeat => {
+                let idx = REPEATS.iter().position(|r| *r == self.repeat).unwrap_or(0);
+                self.repeat = REPEATS[(idx + 1) % REPEATS.len()];
+                return Ok(Jmp::Stay);
             }
             Callback::No => {
                 //no-op
@@ -84,4 +118,5 @@ impl View for Finish {
 pub enum Callback {
     Yes,
     No,
+    Repeat,
 }
@@ -8,7 +8,6 @@ use bot_core::{context::Context, widget::Widget};
 use bot_viewer::rooms::fmt_room;
 use chrono::{DateTime, Local};
 use eyre::Result;
-use model::rooms::Room;
 use mongodb::bson::oid::ObjectId;
 use set_date_time::SetDateTime;
 use set_instructor::SetInstructor;
@@ -110,15 +109,23 @@ pub async fn render_msg(
         "❓".to_string()
     };
 
+    let room = match preset.room {
+        Some(room) => ctx
+            .ledger
+            .rooms
+            .get_by_id(&mut ctx.session, room)
+            .await?
+            .map(|r| escape(&fmt_room(&r)))
+            .unwrap_or_else(|| "❓".to_string()),
+        None => "❓".to_string(),
+    };
+
     Ok(format!(
         "*Персональная тренировка*\n*Дата*: _{}_\n*Инструктор*: _{}_\n*Клиент*: _{}_\n*Зал*: _{}_\n\n*{}*",
         date_time,
         escape(&instructor),
         escape(&client),
-        preset
-            .room
-            .map(|r| fmt_room(Room::from(r)))
-            .unwrap_or_else(|| "❓"),
+        room,
         escape(if request.is_empty() { "." } else { request }),
     ))
 }
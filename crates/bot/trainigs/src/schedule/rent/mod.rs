@@ -9,11 +9,12 @@ use bot_core::{context::Context, widget::Widget};
 use bot_viewer::rooms::fmt_room;
 use chrono::{DateTime, Duration, Local};
 use eyre::Result;
-use model::{decimal::Decimal, rooms::Room};
+use model::decimal::Decimal;
 use mongodb::bson::oid::ObjectId;
 use set_date_time::SetDateTime;
 use set_duration::SetDuration;
 use set_room::SetRoom;
+use teloxide::utils::markdown::escape;
 
 #[derive(Default, Clone)]
 pub struct RentPreset {
@@ -58,7 +59,7 @@ impl RentPreset {
     }
 }
 
-pub async fn render_msg(_: &mut Context, preset: &RentPreset, request: &str) -> Result<String> {
+pub async fn render_msg(ctx: &mut Context, preset: &RentPreset, request: &str) -> Result<String> {
     let date_time = if let Some(date_time) = preset.date_time {
         date_time.format("%d\\.%m %H:%M").to_string()
     } else if let Some(date) = preset.day {
@@ -67,10 +68,16 @@ pub async fn render_msg(_: &mut Context, preset: &RentPreset, request: &str) ->
         "❓".to_string()
     };
 
-    let room = preset
-        .room
-        .map(|r| fmt_room(Room::from(r)))
-        .unwrap_or_else(|| "❓");
+    let room = match preset.room {
+        Some(room) => ctx
+            .ledger
+            .rooms
+            .get_by_id(&mut ctx.session, room)
+            .await?
+            .map(|r| escape(&fmt_room(&r)))
+            .unwrap_or_else(|| "❓".to_string()),
+        None => "❓".to_string(),
+    };
 
     let duration = preset
         .duration
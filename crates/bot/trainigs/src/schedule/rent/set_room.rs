@@ -8,7 +8,7 @@ use bot_core::{
 };
 use bot_viewer::rooms::fmt_room;
 use eyre::Result;
-use model::rooms::Room;
+use mongodb::bson::oid::ObjectId;
 use serde::{Deserialize, Serialize};
 use teloxide::types::InlineKeyboardMarkup;
 
@@ -32,10 +32,9 @@ impl View for SetRoom {
     async fn show(&mut self, ctx: &mut Context) -> Result<()> {
         let msg = render_msg(ctx, &self.preset, "В каком зале будет тренировка?").await?;
         let mut keymap = InlineKeyboardMarkup::default();
-        keymap.inline_keyboard.push(vec![
-            Callback::SelectRoom(Room::Adult).button(fmt_room(Room::Adult)),
-            Callback::SelectRoom(Room::Child).button(fmt_room(Room::Child)),
-        ]);
+        for room in ctx.ledger.rooms.get_active(&mut ctx.session).await? {
+            keymap = keymap.append_row(Callback::SelectRoom(room.id.bytes()).btn_row(fmt_room(&room)));
+        }
         ctx.edit_origin(&msg, keymap).await?;
         Ok(())
     }
@@ -43,7 +42,7 @@ impl View for SetRoom {
     async fn handle_callback(&mut self, _: &mut Context, data: &str) -> Result<Jmp> {
         match calldata!(data) {
             Callback::SelectRoom(room) => {
-                self.preset.room = Some(room.id());
+                self.preset.room = Some(ObjectId::from_bytes(room));
             }
         };
         Ok(self.preset.clone().into_next_view().into())
@@ -52,5 +51,5 @@ impl View for SetRoom {
 
 #[derive(Debug, Deserialize, Serialize)]
 pub enum Callback {
-    SelectRoom(Room),
+    SelectRoom([u8; 12]),
 }
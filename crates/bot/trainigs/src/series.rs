@@ -0,0 +1,176 @@
+use crate::view::TrainingView;
+use async_trait::async_trait;
+use bot_core::{
+    callback_data::{Calldata as _, TrainingIdCallback},
+    calldata,
+    context::Context,
+    widget::{Jmp, View},
+};
+use bot_viewer::day::{fmt_dt, fmt_weekday};
+use chrono::{Datelike as _, Local};
+use eyre::{bail, Result};
+use model::{
+    personal_series::{PersonalSeries, SeriesEnd},
+    rights::Rule,
+};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use teloxide::{
+    types::{ChatId, InlineKeyboardMarkup},
+    utils::markdown::escape,
+};
+
+/// Upcoming sessions of a weekly personal series.
+pub struct SeriesView {
+    id: ObjectId,
+    confirm: bool,
+}
+
+impl SeriesView {
+    pub fn new(id: ObjectId) -> Self {
+        Self { id, confirm: false }
+    }
+
+    async fn series(&self, ctx: &mut Context) -> Result<PersonalSeries> {
+        let series = ctx
+            .ledger
+            .personal_series
+            .get(&mut ctx.session, self.id)
+            .await?
+            .ok_or_else(|| eyre::eyre!("Personal series not found"))?;
+        if !can_manage(ctx, &series) {
+            bail!("Access denied");
+        }
+        Ok(series)
+    }
+
+    async fn cancel(&mut self, ctx: &mut Context) -> Result<Jmp> {
+        let series = self.series(ctx).await?;
+        let canceled = ctx
+            .ledger
+            .cancel_personal_series(&mut ctx.session, self.id)
+            .await?;
+        self.confirm = false;
+
+        let mut msg = "⛔ Серия персональных тренировок отменена".to_string();
+        if !canceled.is_empty() {
+            msg.push_str("\nОтменены тренировки:");
+            for training in &canceled {
+                msg.push_str(&format!("\n• {}", fmt_dt(&training.get_slot().start_at())));
+            }
+        }
+        for user in [series.client, series.instructor] {
+            if user == ctx.me.id {
+                continue;
+            }
+            let user = ctx.ledger.get_user(&mut ctx.session, user).await?;
+            ctx.bot.notify(ChatId(user.tg_id), &msg, true).await;
+        }
+        ctx.send_notification("Серия отменена").await;
+        Ok(Jmp::Stay)
+    }
+}
+
+#[async_trait]
+impl View for SeriesView {
+    fn name(&self) -> &'static str {
+        "SeriesView"
+    }
+
+    async fn show(&mut self, ctx: &mut Context) -> Result<()> {
+        let series = self.series(ctx).await?;
+        let sessions = ctx
+            .ledger
+            .calendar
+            .find_series_trainings(&mut ctx.session, self.id)
+            .await?;
+        let client = ctx.ledger.get_user(&mut ctx.session, series.client).await?;
+        let instructor = ctx
+            .ledger
+            .get_user(&mut ctx.session, series.instructor)
+            .await?;
+
+        let next_at = series.next_at();
+        let end = match series.end {
+            SeriesEnd::Count(count) => format!("{} тренировок", count),
+            SeriesEnd::Until(until) => {
+                format!("до {}", until.with_timezone(&Local).format("%d.%m.%Y"))
+            }
+        };
+        let mut msg = format!(
+            "🔁 *Серия персональных тренировок*\n*Клиент*: {}\n*Инструктор*: {}\n*Когда*: каждый {} в {}\n*Длительность серии*: {}\n",
+            escape(&client.name.to_string()),
+            escape(&instructor.name.to_string()),
+            fmt_weekday(next_at.weekday()),
+            next_at.format("%H:%M"),
+            escape(&end),
+        );
+        if series.is_canceled {
+            msg.push_str("\n⛔ Серия отменена\n");
+        } else if series.is_finished() {
+            msg.push_str("\n✅ Все тренировки серии запланированы\n");
+        }
+
+        let mut keymap = InlineKeyboardMarkup::default();
+        if sessions.is_empty() {
+            msg.push_str("\nЗапланированных тренировок нет");
+        } else {
+            msg.push_str("\nЗапланированные тренировки:");
+        }
+        for training in &sessions {
+            let start_at = training.get_slot().start_at();
+            keymap = keymap.append_row(Callback::SelectTraining(training.id().into()).btn_row(
+                format!(
+                    "{} {}",
+                    fmt_weekday(start_at.weekday()),
+                    start_at.format("%d.%m %H:%M")
+                ),
+            ));
+        }
+
+        if !series.is_canceled {
+            if self.confirm {
+                msg.push_str("\n\nОтменить оставшиеся тренировки серии? Тренировки, которые уже нельзя отменить без штрафа, останутся в расписании");
+                keymap = keymap.append_row(vec![
+                    Callback::Cancel.button("⛔ Да, отменить"),
+                    Callback::Back.button("↩️ Нет"),
+                ]);
+            } else {
+                keymap = keymap.append_row(Callback::AskCancel.btn_row("⛔ Отменить оставшиеся"));
+            }
+        }
+
+        ctx.edit_origin(&msg, keymap).await?;
+        Ok(())
+    }
+
+    async fn handle_callback(&mut self, ctx: &mut Context, data: &str) -> Result<Jmp> {
+        match calldata!(data) {
+            Callback::SelectTraining(id) => Ok(TrainingView::new(id.into()).into()),
+            Callback::AskCancel => {
+                self.confirm = true;
+                Ok(Jmp::Stay)
+            }
+            Callback::Back => {
+                self.confirm = false;
+                Ok(Jmp::Stay)
+            }
+            Callback::Cancel => self.cancel(ctx).await,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+enum Callback {
+    SelectTraining(TrainingIdCallback),
+    AskCancel,
+    Back,
+    Cancel,
+}
+
+/// The client, the instructor and staff who schedule personal trainings.
+pub fn can_manage(ctx: &Context, series: &PersonalSeries) -> bool {
+    ctx.me.id == series.client
+        || ctx.me.id == series.instructor
+        || ctx.has_right(Rule::SchedulePersonalTraining)
+}
@@ -1,4 +1,6 @@
-use crate::{client::list::ClientsList, edit::EditTraining, family::FamilySignIn};
+use crate::{
+    client::list::ClientsList, edit::EditTraining, family::FamilySignIn, series::SeriesView,
+};
 use async_trait::async_trait;
 use bot_core::{
     callback_data::Calldata as _,
@@ -6,7 +8,9 @@ use bot_core::{
     context::Context,
     widget::{Jmp, View},
 };
-use bot_viewer::{day::fmt_dt, fmt_phone, training::fmt_training_type};
+use bot_viewer::{
+    day::fmt_dt, fmt_phone, subscription::fmt_uncovered, training::fmt_training_type,
+};
 use chrono::Local;
 use eyre::{bail, Result};
 use model::{
@@ -67,6 +71,19 @@ impl TrainingView {
         Ok(Jmp::Stay)
     }
 
+    async fn series(&mut self, ctx: &mut Context) -> Result<Jmp> {
+        let training = ctx
+            .ledger
+            .calendar
+            .get_training_by_id(&mut ctx.session, self.id)
+            .await?
+            .ok_or_else(|| eyre::eyre!("Training not found"))?;
+        let series = training
+            .personal_series
+            .ok_or_else(|| eyre::eyre!("Training is not a part of a series"))?;
+        Ok(SeriesView::new(series).into())
+    }
+
     async fn client_list(&mut self, ctx: &mut Context) -> Result<Jmp> {
         if !ctx.is_employee() && !ctx.has_right(Rule::EditTrainingClientsList) {
             bail!("Only couch can see client list");
@@ -100,9 +117,15 @@ impl View for TrainingView {
             Callback::UnCancel => self.restore_training(ctx).await,
             Callback::SignUp => sign_up(ctx, self.id, ctx.me.id).await,
             Callback::SignOut => sign_out(ctx, self.id, ctx.me.id).await,
+            Callback::SignOutLate => {
+                Ok(Jmp::Next(ConfirmLateSignOut::new(self.id, ctx.me.id).into()))
+            }
+            Callback::JoinWaitlist => join_waitlist(ctx, self.id, ctx.me.id).await,
+            Callback::LeaveWaitlist => leave_waitlist(ctx, self.id, ctx.me.id).await,
             Callback::ClientList => self.client_list(ctx).await,
             Callback::OpenSignInView => Ok(Jmp::Next(FamilySignIn::new(self.id).into())),
             Callback::Edit => Ok(EditTraining::new(self.id).into()),
+            Callback::Series => self.series(ctx).await,
         }
     }
 }
@@ -128,6 +151,7 @@ async fn render(ctx: &mut Context, training: &Training) -> Result<(String, Inlin
     let tr_status = training.status(now);
     let slot = training.get_slot();
     let signed = training.clients.contains(&ctx.me.id);
+    let waiting = training.waitlist.contains(&ctx.me.id);
 
     let couch = ctx
         .ledger
@@ -154,8 +178,8 @@ async fn render(ctx: &mut Context, training: &Training) -> Result<(String, Inlin
 💪 *{}*: _{}_
 📅 *Дата*: _{}_
 🧘 *Инструктор*: {}
-💁{}
-⏱*Продолжительность*: _{}_мин
+💁{}{}
+⏱*Продолжительность*: _{}_мин{}
 _{}_                            \n
 [Описание]({})
 {}
@@ -166,17 +190,35 @@ _{}_                            \n
         fmt_dt(&slot.start_at()),
         couch,
         cap,
+        if !is_client && !training.waitlist.is_empty() {
+            format!("\n⏳*Лист ожидания*: _{}_", training.waitlist.len())
+        } else {
+            String::new()
+        },
         training.duration_min,
+        deadlines(training, tr_status),
         status(tr_status, training.is_full()),
         training.description,
         fmt_training_type(training.tp),
         if signed {
             "❤️ Вы записаны"
+        } else if waiting {
+            "⏳ Вы в листе ожидания"
         } else {
             ""
         }
     );
 
+    let late_cancel = signed
+        && tr_status.can_sign_out_late()
+        && ctx
+            .ledger
+            .settings
+            .get(&mut ctx.session)
+            .await?
+            .late_cancel
+            .allowed;
+
     let mut keymap = InlineKeyboardMarkup::default();
     if training.is_group() || training.is_personal() {
         keymap = keymap.append_row(vec![Callback::CouchInfo.button("🧘 Об инструкторе")]);
@@ -187,6 +229,14 @@ _{}_                            \n
         keymap = keymap.append_row(vec![Callback::ClientList.button("🗒 Список клиентов")]);
     }
 
+    if training.personal_series.is_some()
+        && (signed
+            || ctx.me.id == training.instructor
+            || ctx.has_right(Rule::SchedulePersonalTraining))
+    {
+        keymap = keymap.append_row(vec![Callback::Series.button("🔁 Серия тренировок")]);
+    }
+
     let mut row = vec![];
     if ctx.has_right(Rule::CancelTraining) || ctx.me.id == training.instructor {
         if tr_status.can_be_canceled() {
@@ -209,9 +259,24 @@ _{}_                            \n
                     if tr_status.can_sign_out() {
                         keymap =
                             keymap.append_row(vec![Callback::SignOut.button("❌ Отменить запись")]);
+                    } else if late_cancel {
+                        keymap = keymap.append_row(vec![
+                            Callback::SignOutLate.button("❌ Отменить запись со штрафом")
+                        ]);
                     }
+                } else if waiting {
+                    keymap = keymap.append_row(vec![
+                        Callback::LeaveWaitlist.button("🚶 Покинуть лист ожидания")
+                    ]);
                 } else if tr_status.can_sign_in() {
-                    keymap = keymap.append_row(vec![Callback::SignUp.button("✔️ Записаться")]);
+                    if training.is_full() {
+                        keymap = keymap.append_row(vec![
+                            Callback::JoinWaitlist.button("⏳ В лист ожидания")
+                        ]);
+                    } else {
+                        keymap =
+                            keymap.append_row(vec![Callback::SignUp.button("✔️ Записаться")]);
+                    }
                 }
             } else {
                 keymap = keymap.append_row(vec![Callback::OpenSignInView.button("👨‍👩‍👧‍👦 Запись")]);
@@ -220,7 +285,13 @@ _{}_                            \n
     }
 
     if training.is_personal() && signed {
-        keymap = keymap.append_row(vec![Callback::SignOut.button("❌ Отменить запись")]);
+        if tr_status.can_sign_out() {
+            keymap = keymap.append_row(vec![Callback::SignOut.button("❌ Отменить запись")]);
+        } else if late_cancel {
+            keymap = keymap.append_row(vec![
+                Callback::SignOutLate.button("❌ Отменить запись со штрафом")
+            ]);
+        }
     }
 
     Ok((msg, keymap))
@@ -234,8 +305,33 @@ enum Callback {
     UnCancel,
     SignUp,
     SignOut,
+    SignOutLate,
     OpenSignInView,
     Edit,
+    JoinWaitlist,
+    LeaveWaitlist,
+    Series,
+}
+
+fn deadlines(training: &Training, status: TrainingStatus) -> String {
+    if training.tp.is_sub_rent() {
+        return String::new();
+    }
+    let mut msg = String::new();
+    if status == TrainingStatus::NotOpenToSignup {
+        if let Some(open_at) = training.sign_up_opens_at() {
+            msg.push_str(&format!("\n🕒*Запись откроется*: _{}_", fmt_dt(&open_at)));
+        }
+    }
+    if status.can_sign_out() {
+        msg.push_str(&format!(
+            "\n↩️*Бесплатная отмена до*: _{}_",
+            fmt_dt(&training.sign_out_deadline())
+        ));
+    } else if status.can_sign_in() {
+        msg.push_str("\n↩️*Бесплатная отмена*: _уже недоступна_");
+    }
+    msg
 }
 
 fn status(status: TrainingStatus, is_full: bool) -> &'static str {
@@ -247,6 +343,7 @@ fn status(status: TrainingStatus, is_full: bool) -> &'static str {
                 "🟢Открыта для записи"
             }
         }
+        TrainingStatus::NotOpenToSignup => "⚪Запись еще не открыта",
         TrainingStatus::ClosedToSignup => "🟠Запись закрыта",
         TrainingStatus::InProgress => "🤸🏼 Идет",
         TrainingStatus::Cancelled => "⛔Отменена",
@@ -341,6 +438,67 @@ enum CancelCallback {
     Stay,
 }
 
+pub struct ConfirmLateSignOut {
+    id: TrainingId,
+    user_id: ObjectId,
+}
+
+impl ConfirmLateSignOut {
+    pub fn new(id: TrainingId, user_id: ObjectId) -> Self {
+        Self { id, user_id }
+    }
+}
+
+#[async_trait]
+impl View for ConfirmLateSignOut {
+    fn name(&self) -> &'static str {
+        "ConfirmLateSignOut"
+    }
+
+    async fn show(&mut self, ctx: &mut Context) -> Result<()> {
+        let training = ctx
+            .ledger
+            .calendar
+            .get_training_by_id(&mut ctx.session, self.id)
+            .await?
+            .ok_or_else(|| eyre::eyre!("Training not found"))?;
+        let policy = ctx.ledger.settings.get(&mut ctx.session).await?.late_cancel;
+
+        let penalty = if training.tp.is_free() {
+            "Тренировка бесплатная, занятие не спишется\\.".to_string()
+        } else {
+            format!(
+                "С абонемента будет списано {}% занятия\\.",
+                policy.penalty_percent.min(100)
+            )
+        };
+        let msg = format!(
+            "Бесплатная отмена записи на тренировку '{}' в {} была доступна до {}\\.\n{}\nОтменить запись?",
+            escape(&training.name),
+            fmt_dt(&training.get_slot().start_at()),
+            fmt_dt(&training.sign_out_deadline()),
+            penalty
+        );
+        let mut keymap = InlineKeyboardMarkup::default();
+        keymap = keymap.append_row(vec![
+            CancelCallback::Cancel.button("✅ Да"),
+            CancelCallback::Stay.button("❌ нет"),
+        ]);
+        ctx.edit_origin(&msg, keymap).await?;
+        Ok(())
+    }
+
+    async fn handle_callback(&mut self, ctx: &mut Context, data: &str) -> Result<Jmp> {
+        Ok(match calldata!(data) {
+            CancelCallback::Cancel => match sign_out(ctx, self.id, self.user_id).await? {
+                Jmp::Back => Jmp::BackSteps(2),
+                _ => Jmp::Back,
+            },
+            CancelCallback::Stay => Jmp::Back,
+        })
+    }
+}
+
 pub async fn sign_up(ctx: &mut Context, id: TrainingId, user_id: ObjectId) -> Result<Jmp> {
     let training = ctx
         .ledger
@@ -370,7 +528,11 @@ pub async fn sign_up(ctx: &mut Context, id: TrainingId, user_id: ObjectId) -> Re
                 return Ok(Jmp::Stay);
             }
         } else {
-            ctx.send_msg("Нет подходящего абонемента🥺").await?;
+            let mut msg = "Нет подходящего абонемента🥺".to_string();
+            for (sub, reason) in user.payer()?.uncovered(&training) {
+                msg.push_str(&format!("\n{}", fmt_uncovered(sub, reason)));
+            }
+            ctx.send_msg(&msg).await?;
             return Ok(Jmp::Stay);
         };
     }
@@ -397,13 +559,16 @@ pub async fn sign_out(ctx: &mut Context, id: TrainingId, user_id: ObjectId) -> R
         .get_training_by_id(&mut ctx.session, id)
         .await?
         .ok_or_else(|| eyre::eyre!("Training not found"))?;
-    if !training.status(Local::now()).can_sign_out() {
+    let status = training.status(Local::now());
+    if !status.can_sign_out() && !status.can_sign_out_late() {
         ctx.send_msg("Запись на тренировку закрыта").await?;
         return Ok(Jmp::Stay);
     }
-    ctx.ledger
+    let promoted = ctx
+        .ledger
         .sign_out(&mut ctx.session, training.id(), user_id, false)
         .await?;
+    notify_promoted(ctx, &training, promoted).await?;
 
     if training.is_group() {
         Ok(Jmp::Stay)
@@ -427,3 +592,41 @@ pub async fn sign_out(ctx: &mut Context, id: TrainingId, user_id: ObjectId) -> R
         Ok(Jmp::Back)
     }
 }
+
+pub async fn join_waitlist(ctx: &mut Context, id: TrainingId, user_id: ObjectId) -> Result<Jmp> {
+    ctx.ledger
+        .join_waitlist(&mut ctx.session, id, user_id)
+        .await?;
+    ctx.send_notification("Вы в листе ожидания\\. Мы запишем вас, как только освободится место")
+        .await;
+    Ok(Jmp::Stay)
+}
+
+pub async fn leave_waitlist(ctx: &mut Context, id: TrainingId, user_id: ObjectId) -> Result<Jmp> {
+    ctx.ledger
+        .leave_waitlist(&mut ctx.session, id, user_id)
+        .await?;
+    Ok(Jmp::Stay)
+}
+
+pub async fn notify_promoted(
+    ctx: &mut Context,
+    training: &Training,
+    promoted: Option<ObjectId>,
+) -> Result<()> {
+    if let Some(promoted) = promoted {
+        let user = ctx.ledger.get_user(&mut ctx.session, promoted).await?;
+        ctx.bot
+            .notify(
+                ChatId(user.tg_id),
+                &format!(
+                    "Освободилось место на тренировке '{}' в {}\\. Вы записаны из листа ожидания ❤️",
+                    escape(&training.name),
+                    fmt_dt(&training.get_slot().start_at())
+                ),
+                true,
+            )
+            .await;
+    }
+    Ok(())
+}
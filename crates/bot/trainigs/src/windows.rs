@@ -0,0 +1,175 @@
+use async_trait::async_trait;
+use bot_core::{
+    callback_data::Calldata as _,
+    calldata,
+    context::Context,
+    widget::{Jmp, View},
+};
+use bot_viewer::training::fmt_booking_windows;
+use eyre::Result;
+use model::{program::BookingWindows, rights::Rule, training::TrainingId};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use teloxide::types::{InlineKeyboardMarkup, Message};
+
+/// Whose sign up and cancellation windows are edited.
+#[derive(Clone, Copy)]
+pub enum WindowsTarget {
+    Program(ObjectId),
+    Training(TrainingId),
+    Personal,
+}
+
+pub struct EditWindows {
+    target: WindowsTarget,
+}
+
+impl EditWindows {
+    pub fn new(target: WindowsTarget) -> Self {
+        Self { target }
+    }
+
+    fn ensure(&self, ctx: &mut Context) -> Result<()> {
+        match self.target {
+            WindowsTarget::Program(_) | WindowsTarget::Training(_) => {
+                ctx.ensure(Rule::EditTraining)
+            }
+            WindowsTarget::Personal => ctx.ensure(Rule::System),
+        }
+    }
+
+    async fn current(&self, ctx: &mut Context) -> Result<(BookingWindows, bool)> {
+        Ok(match self.target {
+            WindowsTarget::Program(id) => {
+                let program = ctx
+                    .ledger
+                    .programs
+                    .get_by_id(&mut ctx.session, id)
+                    .await?
+                    .ok_or_else(|| eyre::eyre!("Program not found"))?;
+                (program.windows, false)
+            }
+            WindowsTarget::Training(id) => {
+                let training = ctx
+                    .ledger
+                    .calendar
+                    .get_training_by_id(&mut ctx.session, id)
+                    .await?
+                    .ok_or_else(|| eyre::eyre!("Training not found"))?;
+                (training.windows, training.custom_windows)
+            }
+            WindowsTarget::Personal => {
+                let settings = ctx.ledger.settings.get(&mut ctx.session).await?;
+                (settings.personal_windows, false)
+            }
+        })
+    }
+
+    async fn save(&self, ctx: &mut Context, windows: Option<BookingWindows>) -> Result<()> {
+        match (self.target, windows) {
+            (WindowsTarget::Program(id), Some(windows)) => {
+                ctx.ledger
+                    .edit_program_windows(&mut ctx.session, id, windows)
+                    .await?;
+            }
+            (WindowsTarget::Training(id), windows) => {
+                ctx.ledger
+                    .calendar
+                    .set_training_windows(&mut ctx.session, id, windows)
+                    .await?;
+            }
+            (WindowsTarget::Personal, Some(windows)) => {
+                ctx.ledger
+                    .settings
+                    .set_personal_windows(&mut ctx.session, windows)
+                    .await?;
+            }
+            (_, None) => {}
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl View for EditWindows {
+    fn name(&self) -> &'static str {
+        "EditWindows"
+    }
+
+    async fn show(&mut self, ctx: &mut Context) -> Result<()> {
+        self.ensure(ctx)?;
+        let (windows, custom) = self.current(ctx).await?;
+        let title = match self.target {
+            WindowsTarget::Program(_) => "⏳ *Правила записи программы*",
+            WindowsTarget::Training(_) if custom => "⏳ *Особые правила записи тренировки*",
+            WindowsTarget::Training(_) => "⏳ *Правила записи тренировки*",
+            WindowsTarget::Personal => "⏳ *Правила записи на персональные тренировки*",
+        };
+        let msg = format!(
+            "{}\n\n{}\n\nВведите через пробел, за сколько часов до начала:\n• открывается запись \\(\\- если сразу\\)\n• закрывается запись, если никто не записан\n• заканчивается бесплатная отмена\nНапример: _48 3 12_",
+            title,
+            fmt_booking_windows(&windows)
+        );
+        let mut keymap = InlineKeyboardMarkup::default();
+        if custom {
+            keymap = keymap.append_row(Callback::Reset.btn_row("🔄 Как в программе"));
+        }
+        ctx.edit_origin(&msg, keymap).await?;
+        Ok(())
+    }
+
+    async fn handle_message(&mut self, ctx: &mut Context, msg: &Message) -> Result<Jmp> {
+        self.ensure(ctx)?;
+        ctx.delete_msg(msg.id).await?;
+        let Some(windows) = parse_windows(msg.text().unwrap_or_default()) else {
+            ctx.send_notification("Неверный формат").await;
+            return Ok(Jmp::Stay);
+        };
+        self.save(ctx, Some(windows)).await?;
+        ctx.send_notification("Правила записи сохранены").await;
+        Ok(Jmp::Back)
+    }
+
+    async fn handle_callback(&mut self, ctx: &mut Context, data: &str) -> Result<Jmp> {
+        self.ensure(ctx)?;
+        match calldata!(data) {
+            Callback::Reset => {
+                self.save(ctx, None).await?;
+                Ok(Jmp::Stay)
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+enum Callback {
+    Reset,
+}
+
+fn parse_windows(text: &str) -> Option<BookingWindows> {
+    let mut parts = text.split_whitespace();
+    let open = parts.next()?;
+    let sign_up_open_min = if open == "-" {
+        None
+    } else {
+        Some(parse_hours(open)?)
+    };
+    let sign_up_close_min = parse_hours(parts.next()?)?;
+    let cancel_min = parse_hours(parts.next()?)?;
+    if parts.next().is_some() {
+        return None;
+    }
+    Some(BookingWindows {
+        sign_up_open_min,
+        sign_up_close_min,
+        cancel_min,
+    })
+}
+
+fn parse_hours(text: &str) -> Option<u32> {
+    let hours = text.replace(',', ".").parse::<f64>().ok()?;
+    if !hours.is_finite() || hours < 0.0 {
+        return None;
+    }
+    Some((hours * 60.0).round() as u32)
+}
@@ -0,0 +1,78 @@
+use async_trait::async_trait;
+use bot_core::{
+    callback_data::Calldata as _,
+    calldata,
+    context::Context,
+    widget::{Jmp, View},
+};
+use bot_viewer::user::render_debt;
+use eyre::Error;
+use model::rights::Rule;
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use teloxide::{types::InlineKeyboardMarkup, utils::markdown::escape};
+
+/// Installment debts of the client, the manager accepts the next payment here.
+pub struct DebtsView {
+    id: ObjectId,
+}
+
+impl DebtsView {
+    pub fn new(id: ObjectId) -> Self {
+        Self { id }
+    }
+}
+
+#[async_trait]
+impl View for DebtsView {
+    fn name(&self) -> &'static str {
+        "DebtsView"
+    }
+
+    async fn show(&mut self, ctx: &mut Context) -> Result<(), Error> {
+        ctx.ensure(Rule::SellSubscription)?;
+        let user = ctx.ledger.get_user(&mut ctx.session, self.id).await?;
+        let payer = user.payer()?;
+        let debts = &payer.as_ref().debts;
+
+        let mut msg = "💳 *Рассрочка*\n\n".to_string();
+        let mut keymap = InlineKeyboardMarkup::default();
+        if debts.is_empty() {
+            msg.push_str("_Нет долгов_");
+        }
+        for debt in debts {
+            msg.push_str(&render_debt(debt));
+            msg.push_str("\n\n");
+            if let Some(next) = debt.next_payment() {
+                keymap = keymap.append_row(Callback::Pay(debt.id.bytes()).btn_row(format!(
+                    "Принять платеж {} руб. за {}",
+                    next.amount, debt.name
+                )));
+            }
+        }
+        ctx.edit_origin(&msg, keymap).await?;
+        Ok(())
+    }
+
+    async fn handle_callback(&mut self, ctx: &mut Context, data: &str) -> Result<Jmp, Error> {
+        ctx.ensure(Rule::SellSubscription)?;
+        match calldata!(data) {
+            Callback::Pay(debt_id) => {
+                let user = ctx.ledger.get_user(&mut ctx.session, self.id).await?;
+                let payer_id = user.payer()?.as_ref().id;
+                let amount = ctx
+                    .ledger
+                    .pay_installment(&mut ctx.session, payer_id, ObjectId::from_bytes(debt_id))
+                    .await?;
+                ctx.send_notification(&escape(&format!("Платеж принят: {} руб.", amount)))
+                    .await;
+            }
+        }
+        Ok(Jmp::Stay)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+enum Callback {
+    Pay([u8; 12]),
+}
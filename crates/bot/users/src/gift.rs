@@ -0,0 +1,130 @@
+use async_trait::async_trait;
+use bot_core::{
+    callback_data::Calldata as _,
+    calldata,
+    context::Context,
+    widget::{Jmp, View},
+};
+use bot_viewer::gift::fmt_gift;
+use chrono::Utc;
+use eyre::Error;
+use model::{gift::GiftCertificate, rights::Rule};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use teloxide::{
+    types::{InlineKeyboardMarkup, Message},
+    utils::markdown::escape,
+};
+
+/// Exchanges a gift certificate code for a subscription.
+pub struct RedeemGiftCertificate {
+    user_id: ObjectId,
+    certificate: Option<GiftCertificate>,
+}
+
+impl RedeemGiftCertificate {
+    pub fn new(user_id: ObjectId) -> Self {
+        Self {
+            user_id,
+            certificate: None,
+        }
+    }
+
+    fn ensure(&self, ctx: &mut Context) -> Result<(), Error> {
+        if !ctx.is_me(self.user_id) {
+            ctx.ensure(Rule::SellGiftCertificate)?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl View for RedeemGiftCertificate {
+    fn name(&self) -> &'static str {
+        "RedeemGiftCertificate"
+    }
+
+    async fn show(&mut self, ctx: &mut Context) -> Result<(), Error> {
+        self.ensure(ctx)?;
+        let mut keymap = InlineKeyboardMarkup::default();
+        let Some(certificate) = &self.certificate else {
+            ctx.edit_origin("🎁 Введите код подарочного сертификата", keymap)
+                .await?;
+            return Ok(());
+        };
+
+        let mut msg = fmt_gift(certificate);
+        let subscriptions = ctx.ledger.subscriptions.get_all(&mut ctx.session).await?;
+        let mut available = subscriptions
+            .into_iter()
+            .filter(|sub| !sub.trial && certificate.covers(sub))
+            .peekable();
+        if available.peek().is_none() {
+            msg.push_str("\nНет абонементов, доступных по сертификату");
+        } else {
+            msg.push_str("\nВыберите абонемент:");
+        }
+        for sub in available {
+            keymap = keymap
+                .append_row(Callback::Redeem(sub.id.bytes()).btn_row(format!("🎁 {}", sub.name)));
+        }
+        ctx.edit_origin(&msg, keymap).await?;
+        Ok(())
+    }
+
+    async fn handle_message(&mut self, ctx: &mut Context, message: &Message) -> Result<Jmp, Error> {
+        self.ensure(ctx)?;
+        ctx.delete_msg(message.id).await?;
+        let code = message.text().unwrap_or_default();
+        let certificate = ctx.ledger.gifts.get_by_code(&mut ctx.session, code).await?;
+        match certificate {
+            Some(certificate) if certificate.is_redeemed() => {
+                ctx.send_notification("Сертификат уже использован").await;
+            }
+            Some(certificate) if certificate.is_expired(Utc::now()) => {
+                ctx.send_notification("Срок действия сертификата истек")
+                    .await;
+            }
+            Some(certificate) => {
+                self.certificate = Some(certificate);
+            }
+            None => {
+                ctx.send_notification(&format!(
+                    "Сертификат {} не найден",
+                    escape(&GiftCertificate::normalize(code))
+                ))
+                .await;
+            }
+        }
+        Ok(Jmp::Stay)
+    }
+
+    async fn handle_callback(&mut self, ctx: &mut Context, data: &str) -> Result<Jmp, Error> {
+        self.ensure(ctx)?;
+        match calldata!(data) {
+            Callback::Redeem(id) => {
+                let Some(certificate) = &self.certificate else {
+                    return Ok(Jmp::Stay);
+                };
+                let sub = ctx
+                    .ledger
+                    .redeem_gift_certificate(
+                        &mut ctx.session,
+                        self.user_id,
+                        certificate.code.clone(),
+                        ObjectId::from_bytes(id),
+                    )
+                    .await?;
+                ctx.send_notification(&format!("🎁 Абонемент *{}* получен", escape(&sub.name)))
+                    .await;
+                ctx.reload_user().await?;
+                Ok(Jmp::Back)
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+enum Callback {
+    Redeem([u8; 12]),
+}
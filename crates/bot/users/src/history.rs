@@ -9,6 +9,8 @@ use bot_viewer::{day::fmt_dt, fmt_phone, user::link_to_user};
 use chrono::Local;
 use eyre::Result;
 use model::{
+    decimal::Decimal,
+    gift::GiftValue,
     history::HistoryRow,
     rights::{Rights, Rule},
     statistics::source::Source,
@@ -186,6 +188,47 @@ async fn fmt_row(ctx: &mut Context, log: &HistoryRow) -> Result<String> {
                 )
             }
         }
+        model::history::Action::LateSignOut {
+            start_at,
+            name,
+            room_id: _,
+            penalty,
+            burned,
+        } => {
+            let penalty = format!(
+                "штраф {}% занятия{}",
+                (*penalty * Decimal::int(100)).int_part(),
+                if *burned { ", занятие списано" } else { "" }
+            );
+            if ctx.has_right(Rule::HistoryViewer) {
+                let sub = if let Some(subject) = log.sub_actors.first() {
+                    let user = ctx.ledger.get_user(&mut ctx.session, *subject).await?;
+                    format!(
+                        "{} {}",
+                        link_to_user(&user),
+                        fmt_phone(user.phone.as_deref())
+                    )
+                } else {
+                    "-".to_string()
+                };
+
+                format!(
+                    "Пользователь \\(@{}\\) поздно отменил запись на тренировку *{}* на {} пользователю {}: _{}_",
+                    escape(&actor.name.tg_user_name.unwrap_or_default()),
+                    escape(name),
+                    fmt_dt(start_at),
+                    sub,
+                    escape(&penalty)
+                )
+            } else {
+                format!(
+                    "Вы поздно отменили запись на тренировку *{}* на {}: _{}_",
+                    escape(name),
+                    fmt_dt(start_at),
+                    escape(&penalty)
+                )
+            }
+        }
         model::history::Action::SellSub {
             subscription,
             discount: _,
@@ -213,6 +256,47 @@ async fn fmt_row(ctx: &mut Context, log: &HistoryRow) -> Result<String> {
                 )
             }
         }
+        model::history::Action::SellGiftCertificate { code, value, price } => {
+            let value = match value {
+                GiftValue::Amount(amount) => format!("на сумму _{}_", escape(&amount.to_string())),
+                GiftValue::Subscription { name, .. } => {
+                    format!("на абонемент *{}*", escape(name))
+                }
+            };
+            if is_actor {
+                let sub = if let Some(subject) = log.sub_actors.first() {
+                    ctx.ledger
+                        .get_user(&mut ctx.session, *subject)
+                        .await?
+                        .name
+                        .to_string()
+                } else {
+                    "-".to_string()
+                };
+                format!(
+                    "Вы продали подарочный сертификат `{}` {}\nCумма:_{}_\nПользователю {}",
+                    escape(code),
+                    value,
+                    escape(&price.to_string()),
+                    escape(&sub)
+                )
+            } else {
+                format!(
+                    "Вы купили подарочный сертификат `{}` {}\nСумма:_{}_",
+                    escape(code),
+                    value,
+                    escape(&price.to_string())
+                )
+            }
+        }
+        model::history::Action::RedeemGiftCertificate { code, subscription } => {
+            format!(
+                "Абонемент *{}* получен по подарочному сертификату `{}`\nКоличество занятий:_{}_",
+                escape(&subscription.name),
+                escape(code),
+                subscription.items
+            )
+        }
         model::history::Action::PreSellSub {
             subscription,
             phone,
@@ -246,6 +330,8 @@ async fn fmt_row(ctx: &mut Context, log: &HistoryRow) -> Result<String> {
             name,
             start_at,
             room_id: _,
+            no_show,
+            excused,
         } => {
             if is_actor {
                 format!(
@@ -254,8 +340,16 @@ async fn fmt_row(ctx: &mut Context, log: &HistoryRow) -> Result<String> {
                     fmt_dt(&start_at.with_timezone(&Local))
                 )
             } else {
+                let action = if no_show.contains(&ctx.me.id) {
+                    "Вы пропустили тренировку"
+                } else if excused.contains(&ctx.me.id) {
+                    "Вы пропустили по уважительной причине тренировку"
+                } else {
+                    "Вы посетили тренировку"
+                };
                 format!(
-                    "Вы посетили тренировку *{}* в _{}_",
+                    "{} *{}* в _{}_",
+                    action,
                     escape(name),
                     fmt_dt(&start_at.with_timezone(&Local))
                 )
@@ -328,6 +422,155 @@ async fn fmt_row(ctx: &mut Context, log: &HistoryRow) -> Result<String> {
                 "Ваш абонемент разморозили".to_string()
             }
         }
+        model::history::Action::FreezeSubscription { name, days } => {
+            let sub = if let Some(subject) = log.sub_actors.first() {
+                ctx.ledger
+                    .get_user(&mut ctx.session, *subject)
+                    .await?
+                    .name
+                    .to_string()
+            } else {
+                "-".to_string()
+            };
+            if is_actor {
+                format!(
+                    "Вы поставили на паузу абонемент _{}_ пользователя _{}_ на _{}_ дней",
+                    escape(name),
+                    escape(&sub),
+                    days
+                )
+            } else {
+                format!(
+                    "Ваш абонемент _{}_ поставили на паузу на _{}_ дней",
+                    escape(name),
+                    days
+                )
+            }
+        }
+        model::history::Action::UnfreezeSubscription { name } => {
+            let sub = if let Some(subject) = log.sub_actors.first() {
+                ctx.ledger
+                    .get_user(&mut ctx.session, *subject)
+                    .await?
+                    .name
+                    .to_string()
+            } else {
+                "-".to_string()
+            };
+            if is_actor {
+                format!(
+                    "Вы сняли с паузы абонемент _{}_ пользователя _{}_",
+                    escape(name),
+                    escape(&sub)
+                )
+            } else {
+                format!("Ваш абонемент _{}_ снят с паузы", escape(name))
+            }
+        }
+        model::history::Action::TransferSubscriptionOut { name, items } => {
+            let other = if let Some(other) = log.sub_actors.get(1) {
+                ctx.ledger
+                    .get_user(&mut ctx.session, *other)
+                    .await?
+                    .name
+                    .to_string()
+            } else {
+                "-".to_string()
+            };
+            format!(
+                "Передано _{}_ занятий абонемента _{}_ пользователю _{}_",
+                items,
+                escape(name),
+                escape(&other)
+            )
+        }
+        model::history::Action::TransferSubscriptionIn { name, items } => {
+            let other = if let Some(other) = log.sub_actors.get(1) {
+                ctx.ledger
+                    .get_user(&mut ctx.session, *other)
+                    .await?
+                    .name
+                    .to_string()
+            } else {
+                "-".to_string()
+            };
+            format!(
+                "Получено _{}_ занятий абонемента _{}_ от пользователя _{}_",
+                items,
+                escape(name),
+                escape(&other)
+            )
+        }
+        model::history::Action::RefundSubscription {
+            subscription,
+            amount,
+            fee,
+        } => {
+            let sub = if let Some(subject) = log.sub_actors.first() {
+                ctx.ledger
+                    .get_user(&mut ctx.session, *subject)
+                    .await?
+                    .name
+                    .to_string()
+            } else {
+                "-".to_string()
+            };
+            format!(
+                "Возврат за _{}_ занятий абонемента _{}_ пользователю _{}_: _{}_ руб\\. Удержано: _{}_ руб\\.",
+                subscription.balance,
+                escape(&subscription.name),
+                escape(&sub),
+                escape(&amount.to_string()),
+                escape(&fee.to_string())
+            )
+        }
+        model::history::Action::UpgradeSubscription {
+            from,
+            subscription,
+            credit,
+            amount,
+        } => {
+            let sub = if let Some(subject) = log.sub_actors.first() {
+                ctx.ledger
+                    .get_user(&mut ctx.session, *subject)
+                    .await?
+                    .name
+                    .to_string()
+            } else {
+                "-".to_string()
+            };
+            format!(
+                "Переход пользователя _{}_ с абонемента _{}_ на *{}*\nЗачтено _{}_ занятий на _{}_ руб\\. Доплата: _{}_ руб\\.",
+                escape(&sub),
+                escape(&from.name),
+                escape(&subscription.name),
+                from.balance,
+                escape(&credit.to_string()),
+                escape(&amount.to_string())
+            )
+        }
+        model::history::Action::PayInstallment {
+            name,
+            amount,
+            remaining,
+        } => {
+            let sub = if let Some(subject) = log.sub_actors.first() {
+                ctx.ledger
+                    .get_user(&mut ctx.session, *subject)
+                    .await?
+                    .name
+                    .to_string()
+            } else {
+                "-".to_string()
+            };
+            format!(
+                "Платеж пользователя _{}_ по рассрочке за абонемент _{}_: _{}_ руб\\. Осталось: _{}_ руб\\.",
+                escape(&sub),
+                escape(name),
+                escape(&amount.to_string()),
+                escape(&remaining.to_string())
+            )
+        }
         model::history::Action::ChangeBalance { amount } => {
             let sub = if let Some(subject) = log.sub_actors.first() {
                 ctx.ledger
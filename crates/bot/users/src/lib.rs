@@ -18,10 +18,14 @@ use teloxide::{
 };
 
 pub mod come_from;
+pub mod debts;
 pub mod family;
 pub mod freeze;
+pub mod gift;
 pub mod history;
+pub mod loyalty;
 pub mod notification;
+pub mod pause;
 pub mod profile;
 pub mod rewards;
 pub mod rights;
@@ -0,0 +1,117 @@
+use async_trait::async_trait;
+use bot_core::{
+    callback_data::Calldata as _,
+    calldata,
+    context::Context,
+    widget::{Jmp, View},
+};
+use bot_viewer::loyalty::fmt_points_entry;
+use chrono::Utc;
+use eyre::Error;
+use model::{loyalty::is_free_lesson_option, rights::Rule};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use teloxide::{types::InlineKeyboardMarkup, utils::markdown::escape};
+
+pub const LIMIT: u64 = 7;
+
+/// Points balance and operations of the client, the points are exchanged for a free lesson here.
+pub struct LoyaltyView {
+    id: ObjectId,
+    offset: u64,
+}
+
+impl LoyaltyView {
+    pub fn new(id: ObjectId) -> Self {
+        Self { id, offset: 0 }
+    }
+
+    fn ensure(&self, ctx: &mut Context) -> Result<(), Error> {
+        if !ctx.is_me(self.id) {
+            ctx.ensure(Rule::SellSubscription)?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl View for LoyaltyView {
+    fn name(&self) -> &'static str {
+        "LoyaltyView"
+    }
+
+    async fn show(&mut self, ctx: &mut Context) -> Result<(), Error> {
+        self.ensure(ctx)?;
+        let policy = ctx.ledger.settings.get(&mut ctx.session).await?.loyalty;
+        let balance = ctx
+            .ledger
+            .loyalty
+            .balance(&mut ctx.session, self.id, Utc::now())
+            .await?;
+        let entries = ctx
+            .ledger
+            .loyalty
+            .find_by_user(&mut ctx.session, self.id, LIMIT as i64, self.offset)
+            .await?;
+
+        let mut msg = format!("🎁 *Баллы лояльности*\n\nБаланс: *{}*\n", balance);
+        if !policy.enabled {
+            msg.push_str("_Программа лояльности отключена_\n");
+        }
+        for entry in &entries {
+            msg.push_str(&format!("\n{}", fmt_points_entry(entry)));
+        }
+
+        let mut keymap = InlineKeyboardMarkup::default();
+        if policy.enabled && balance >= policy.free_lesson_cost {
+            msg.push_str(&format!(
+                "\n\nБесплатное занятие за _{}_ баллов:",
+                policy.free_lesson_cost
+            ));
+            let subscriptions = ctx.ledger.subscriptions.get_all(&mut ctx.session).await?;
+            for sub in subscriptions.into_iter().filter(is_free_lesson_option) {
+                keymap = keymap.append_row(
+                    Callback::FreeLesson(sub.id.bytes()).btn_row(format!("🎁 {}", sub.name)),
+                );
+            }
+        }
+
+        let mut row = vec![];
+        if self.offset > 0 {
+            row.push(Callback::Offset(self.offset.saturating_sub(LIMIT)).button("⬅️"));
+        }
+        if entries.len() as u64 == LIMIT {
+            row.push(Callback::Offset(self.offset + LIMIT).button("➡️"));
+        }
+        if !row.is_empty() {
+            keymap = keymap.append_row(row);
+        }
+        ctx.edit_origin(&msg, keymap).await?;
+        Ok(())
+    }
+
+    async fn handle_callback(&mut self, ctx: &mut Context, data: &str) -> Result<Jmp, Error> {
+        self.ensure(ctx)?;
+        match calldata!(data) {
+            Callback::Offset(offset) => {
+                self.offset = offset;
+            }
+            Callback::FreeLesson(id) => {
+                let sub = ctx
+                    .ledger
+                    .redeem_points_for_lesson(&mut ctx.session, self.id, ObjectId::from_bytes(id))
+                    .await?;
+                ctx.send_notification(&format!("🎁 Абонемент *{}* получен", escape(&sub.name)))
+                    .await;
+                ctx.reload_user().await?;
+            }
+        }
+        Ok(Jmp::Stay)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+enum Callback {
+    Offset(u64),
+    FreeLesson([u8; 12]),
+}
@@ -0,0 +1,191 @@
+use async_trait::async_trait;
+use bot_core::{
+    callback_data::Calldata as _,
+    calldata,
+    context::Context,
+    widget::{Jmp, View},
+};
+use bot_viewer::day::fmt_date;
+use chrono::Local;
+use eyre::{bail, Result};
+use model::{rights::Rule, subscription::UserSubscription};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use std::num::NonZero;
+use teloxide::{
+    types::{InlineKeyboardMarkup, Message},
+    utils::markdown::escape,
+};
+
+/// Pauses a single subscription within its freeze budget.
+pub struct PauseSubscription {
+    id: ObjectId,
+    sub: Option<ObjectId>,
+    days: Option<u32>,
+}
+
+impl PauseSubscription {
+    pub fn new(id: ObjectId) -> PauseSubscription {
+        PauseSubscription {
+            id,
+            sub: None,
+            days: None,
+        }
+    }
+
+    async fn subscriptions(&self, ctx: &mut Context) -> Result<Vec<UserSubscription>> {
+        let can_force = ctx.has_right(Rule::FreezeUsers);
+        if !can_force && ctx.me.id != self.id {
+            bail!("User has no rights to perform this action");
+        }
+        let user = ctx.ledger.get_user(&mut ctx.session, self.id).await?;
+        let payer = user.payer()?;
+        if !can_force && !payer.is_owner() {
+            bail!("Only owner can pause subscriptions");
+        }
+        Ok(payer
+            .subscriptions()
+            .iter()
+            .filter(|sub| sub.is_active())
+            .cloned()
+            .collect())
+    }
+}
+
+#[async_trait]
+impl View for PauseSubscription {
+    fn name(&self) -> &'static str {
+        "PauseSubscription"
+    }
+
+    async fn show(&mut self, ctx: &mut Context) -> Result<()> {
+        let can_force = ctx.has_right(Rule::FreezeUsers);
+        let subs = self.subscriptions(ctx).await?;
+        let mut keymap = InlineKeyboardMarkup::default();
+
+        let selected = self.sub.and_then(|id| subs.iter().find(|sub| sub.id == id));
+        let Some(sub) = selected else {
+            let mut msg = "⏸ *Пауза абонемента*\n".to_string();
+            if subs.is_empty() {
+                msg.push_str("Нет активных абонементов");
+            } else {
+                msg.push_str("Выберите абонемент:");
+            }
+            for sub in &subs {
+                if let Some(freeze) = &sub.freeze {
+                    msg.push_str(&format!(
+                        "\n⏸ _{}_ на паузе до _{}_",
+                        escape(&sub.name),
+                        fmt_date(&freeze.freeze_end.with_timezone(&Local))
+                    ));
+                    if can_force {
+                        keymap = keymap.append_row(
+                            Callback::Resume(sub.id.bytes())
+                                .btn_row(format!("▶️ Снять паузу {}", sub.name)),
+                        );
+                    }
+                } else if can_force || sub.freeze_days > 0 {
+                    keymap = keymap.append_row(
+                        Callback::Select(sub.id.bytes())
+                            .btn_row(format!("⏸ {} ({} дн.)", sub.name, sub.freeze_days)),
+                    );
+                }
+            }
+            ctx.edit_origin(&msg, keymap).await?;
+            return Ok(());
+        };
+
+        if let Some(days) = self.days {
+            let msg = format!(
+                "Ставим на паузу абонемент _{}_\\. Количество дней:_{}_\nВсе верно?",
+                escape(&sub.name),
+                days
+            );
+            keymap = keymap.append_row(vec![
+                Callback::Yes.button("✅ Да"),
+                Callback::No.button("❌ Отмена"),
+            ]);
+            ctx.edit_origin(&msg, keymap).await?;
+        } else {
+            let msg = format!(
+                "Абонемент _{}_\nОсталось дней паузы:_{}_\nНа сколько дней поставить абонемент на паузу?",
+                escape(&sub.name),
+                sub.freeze_days
+            );
+            keymap = keymap.append_row(Callback::No.btn_row("❌ Отмена"));
+            ctx.edit_origin(&msg, keymap).await?;
+        }
+        Ok(())
+    }
+
+    async fn handle_message(&mut self, ctx: &mut Context, message: &Message) -> Result<Jmp> {
+        ctx.delete_msg(message.id).await?;
+        if self.sub.is_none() || self.days.is_some() {
+            return Ok(Jmp::Stay);
+        }
+        match message.text().unwrap_or_default().parse::<NonZero<u32>>() {
+            Ok(days) => self.days = Some(days.get()),
+            Err(_) => ctx.send_notification("Введите число").await,
+        }
+        Ok(Jmp::Stay)
+    }
+
+    async fn handle_callback(&mut self, ctx: &mut Context, data: &str) -> Result<Jmp> {
+        match calldata!(data) {
+            Callback::Select(id) => {
+                self.sub = Some(ObjectId::from_bytes(id));
+                self.days = None;
+            }
+            Callback::Resume(id) => {
+                ctx.ensure(Rule::FreezeUsers)?;
+                ctx.ledger
+                    .users
+                    .unfreeze_subscription(&mut ctx.session, self.id, ObjectId::from_bytes(id))
+                    .await?;
+                ctx.send_notification("Пауза снята").await;
+            }
+            Callback::Yes => {
+                let (Some(sub), Some(days)) = (self.sub, self.days) else {
+                    return Ok(Jmp::Stay);
+                };
+                let can_force = ctx.has_right(Rule::FreezeUsers);
+                let subs = self.subscriptions(ctx).await?;
+                let Some(sub) = subs.iter().find(|s| s.id == sub) else {
+                    bail!("Subscription not found");
+                };
+                if !can_force && sub.freeze_days < days {
+                    self.days = None;
+                    ctx.send_notification("Недостаточно дней паузы").await;
+                    return Ok(Jmp::Stay);
+                }
+                ctx.ledger
+                    .users
+                    .freeze_subscription(&mut ctx.session, self.id, sub.id, days, can_force)
+                    .await?;
+                ctx.reload_user().await?;
+                self.sub = None;
+                self.days = None;
+                ctx.send_notification("Абонемент поставлен на паузу").await;
+            }
+            Callback::No => {
+                self.sub = None;
+                self.days = None;
+            }
+        }
+        Ok(Jmp::Stay)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+enum Callback {
+    Select([u8; 12]),
+    Resume([u8; 12]),
+    Yes,
+    No,
+}
+
+/// Whether the user may open the pause view for their own subscriptions.
+pub fn can_pause(subs: &[UserSubscription]) -> bool {
+    subs.iter()
+        .any(|sub| sub.is_active() && sub.freeze.is_none() && sub.freeze_days > 0)
+}
@@ -1,6 +1,15 @@
 use crate::{
-    come_from::MarketingInfoView, comments::Comments, family::FamilyView, history::HistoryList,
-    notification::NotificationView, rewards::RewardsList, subscriptions::SubscriptionsList,
+    come_from::MarketingInfoView,
+    comments::Comments,
+    debts::DebtsView,
+    family::FamilyView,
+    gift::RedeemGiftCertificate,
+    history::HistoryList,
+    loyalty::LoyaltyView,
+    notification::NotificationView,
+    pause::{can_pause, PauseSubscription},
+    rewards::RewardsList,
+    subscriptions::SubscriptionsList,
 };
 
 use super::{
@@ -14,7 +23,7 @@ use bot_core::{
     context::Context,
     widget::{Jmp, View},
 };
-use bot_trainigs::list::TrainingList;
+use bot_trainigs::{list::TrainingList, view::notify_promoted};
 use bot_viewer::user::render_profile_msg;
 use eyre::Error;
 use model::{
@@ -49,9 +58,13 @@ impl UserProfile {
             .get(&mut ctx.session, self.id)
             .await?
             .ok_or_else(|| eyre::eyre!("User not found"))?;
-        ctx.ledger
+        let promoted = ctx
+            .ledger
             .block_user(&mut ctx.session, self.id, !user.is_active)
             .await?;
+        for (training, waiter) in promoted {
+            notify_promoted(ctx, &training, Some(waiter)).await?;
+        }
         ctx.reload_user().await?;
         Ok(Jmp::Stay)
     }
@@ -63,6 +76,13 @@ impl UserProfile {
         Ok(FreezeProfile::new(self.id).into())
     }
 
+    async fn pause_subscription(&mut self, ctx: &mut Context) -> Result<Jmp, eyre::Error> {
+        if !ctx.has_right(Rule::FreezeUsers) && ctx.me.id != self.id {
+            return Err(eyre::eyre!("User has no rights to perform this action"));
+        }
+        Ok(PauseSubscription::new(self.id).into())
+    }
+
     async fn edit_rights(&mut self, ctx: &mut Context) -> Result<Jmp, eyre::Error> {
         ctx.ensure(Rule::EditUserRights)?;
         Ok(UserRightsView::new(self.id).into())
@@ -188,10 +208,12 @@ impl UserProfile {
 
 fn print_training_stat(training: &TrainingsStat, name: String) -> String {
     format!(
-        "{}\nВсего посещяно *{}* тренировок\nОтменено *{}* тренировок\n\n",
+        "{}\nВсего посещяно *{}* тренировок\nОтменено *{}* тренировок\nПропущено *{}* тренировок\nПропущено по уважительной причине *{}* тренировок\n\n",
         escape(&name),
         training.count,
-        training.cancellations_count
+        training.cancellations_count,
+        training.no_show_count,
+        training.excused_count
     )
 }
 
@@ -280,8 +302,27 @@ impl View for UserProfile {
             }
             Callback::FamilyView => self.family_view(ctx, self.id).await,
             Callback::UnFreeze => self.unfreeze_user(ctx).await,
+            Callback::Pause => self.pause_subscription(ctx).await,
             Callback::Comments => Ok(Comments::new(self.id).into()),
             Callback::Statistics => self.show_statistics(ctx).await,
+            Callback::RedeemGift => {
+                if !ctx.is_me(self.id) {
+                    ctx.ensure(Rule::SellGiftCertificate)?;
+                }
+                Ok(RedeemGiftCertificate::new(self.id).into())
+            }
+            Callback::Debts => {
+                ctx.ensure(Rule::SellSubscription)?;
+                Ok(DebtsView::new(self.id).into())
+            }
+            Callback::Loyalty => {
+                let user = ctx.ledger.get_user(&mut ctx.session, self.id).await?;
+                let payer = user.payer()?.as_ref().id;
+                if !ctx.is_me(payer) {
+                    ctx.ensure(Rule::SellSubscription)?;
+                }
+                Ok(LoyaltyView::new(payer).into())
+            }
         }
     }
 }
@@ -317,6 +358,18 @@ async fn render_user_profile(
         keymap = keymap.append_row(Callback::UnFreeze.btn_row("Разморозить ❄"));
     }
 
+    if ctx.has_right(Rule::FreezeUsers)
+        || (ctx.me.tg_id == user.tg_id
+            && user.payer()?.is_owner()
+            && can_pause(user.payer()?.subscriptions()))
+    {
+        keymap = keymap.append_row(Callback::Pause.btn_row("Пауза абонемента ⏸"));
+    }
+
+    if ctx.is_me(id) || ctx.has_right(Rule::SellGiftCertificate) {
+        keymap = keymap.append_row(Callback::RedeemGift.btn_row("Активировать сертификат 🎁"));
+    }
+
     if user.employee.is_some() {
         keymap = keymap.append_row(Callback::TrainingList.btn_row("Тренировки 📝"));
     } else {
@@ -347,6 +400,16 @@ async fn render_user_profile(
         keymap = keymap.append_row(Callback::SubscriptionsList.btn_row("Абонементы 📝"));
     }
 
+    if ctx.has_right(Rule::SellSubscription) && !user.payer()?.as_ref().debts.is_empty() {
+        keymap = keymap.append_row(Callback::Debts.btn_row("Рассрочка 💳"));
+    }
+
+    if user.employee.is_none()
+        && (ctx.is_me(user.payer()?.as_ref().id) || ctx.has_right(Rule::SellSubscription))
+    {
+        keymap = keymap.append_row(Callback::Loyalty.btn_row("Баллы 🎁"));
+    }
+
     if ctx.has_right(Rule::EditUserRights) {
         keymap = keymap.append_row(Callback::EditRights.btn_row("Права 🔒"));
     }
@@ -386,4 +449,8 @@ pub enum Callback {
     UnFreeze,
     Comments,
     Statistics,
+    Pause,
+    RedeemGift,
+    Debts,
+    Loyalty,
 }
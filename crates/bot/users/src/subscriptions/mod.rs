@@ -1,5 +1,8 @@
 pub mod item_price;
 pub mod program;
+pub mod refund;
+pub mod transfer;
+pub mod upgrade;
 
 use async_trait::async_trait;
 use bot_core::{
@@ -12,8 +15,11 @@ use bot_viewer::user::render_sub;
 use item_price::SetItemPrice;
 use model::rights::Rule;
 use mongodb::bson::oid::ObjectId;
+use refund::RefundSubscription;
 use serde::{Deserialize, Serialize};
 use teloxide::types::InlineKeyboardMarkup;
+use transfer::TransferSubscription;
+use upgrade::UpgradeSubscription;
 
 pub struct SubscriptionsList {
     id: ObjectId,
@@ -78,6 +84,15 @@ impl View for SubscriptionsList {
                 Calldata::Programs.button("Программы"),
                 Calldata::ItemPrice.button("Цена занятия"),
             ]);
+            if ctx.has_right(Rule::TransferSubscription) {
+                keymap = keymap.append_row(Calldata::Transfer.btn_row("Передать другому клиенту"));
+            }
+            if ctx.has_right(Rule::RefundSubscription) {
+                keymap = keymap.append_row(Calldata::Refund.btn_row("Оформить возврат"));
+            }
+            if ctx.has_right(Rule::SellSubscription) {
+                keymap = keymap.append_row(Calldata::Upgrade.btn_row("Перейти на другой абонемент"));
+            }
         }
 
         ctx.edit_origin(&txt, keymap).await?;
@@ -137,6 +152,30 @@ impl View for SubscriptionsList {
                 let sub = &payer.subscriptions()[self.index];
                 return Ok(SetItemPrice::new(self.id, sub.id).into());
             }
+            Calldata::Transfer => {
+                ctx.ensure(Rule::TransferSubscription)?;
+                if self.index >= payer.subscriptions().len() {
+                    return Ok(Jmp::Stay);
+                }
+                let sub = &payer.subscriptions()[self.index];
+                return Ok(TransferSubscription::new(self.id, sub.id).into());
+            }
+            Calldata::Refund => {
+                ctx.ensure(Rule::RefundSubscription)?;
+                if self.index >= payer.subscriptions().len() {
+                    return Ok(Jmp::Stay);
+                }
+                let sub = &payer.subscriptions()[self.index];
+                return Ok(RefundSubscription::new(self.id, sub.id).into());
+            }
+            Calldata::Upgrade => {
+                ctx.ensure(Rule::SellSubscription)?;
+                if self.index >= payer.subscriptions().len() {
+                    return Ok(Jmp::Stay);
+                }
+                let sub = &payer.subscriptions()[self.index];
+                return Ok(UpgradeSubscription::new(self.id, sub.id).into());
+            }
         }
 
         Ok(Jmp::Stay)
@@ -151,4 +190,7 @@ enum Calldata {
     ChangeDays(i32),
     Programs,
     ItemPrice,
+    Transfer,
+    Refund,
+    Upgrade,
 }
@@ -0,0 +1,130 @@
+use async_trait::async_trait;
+use bot_core::{
+    callback_data::Calldata as _,
+    calldata,
+    context::Context,
+    widget::{Jmp, View},
+};
+use bot_viewer::user::render_sub;
+use eyre::Error;
+use model::{decimal::Decimal, rights::Rule};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use std::num::NonZero;
+use teloxide::{
+    types::{InlineKeyboardMarkup, Message},
+    utils::markdown::escape,
+};
+
+/// Returns money for the remaining lessons of a subscription.
+pub struct RefundSubscription {
+    user_id: ObjectId,
+    id: ObjectId,
+    /// `Some(None)` refunds the whole remaining balance.
+    items: Option<Option<u32>>,
+}
+
+impl RefundSubscription {
+    pub fn new(user_id: ObjectId, id: ObjectId) -> Self {
+        Self {
+            user_id,
+            id,
+            items: None,
+        }
+    }
+}
+
+#[async_trait]
+impl View for RefundSubscription {
+    fn name(&self) -> &'static str {
+        "RefundSubscription"
+    }
+
+    async fn show(&mut self, ctx: &mut Context) -> Result<(), Error> {
+        ctx.ensure(Rule::RefundSubscription)?;
+        let user = ctx.ledger.get_user(&mut ctx.session, self.user_id).await?;
+        let payer = user.payer()?;
+        let sub = payer
+            .subscriptions()
+            .iter()
+            .find(|s| s.id == self.id)
+            .ok_or_else(|| eyre::eyre!("Subscription not found"))?;
+
+        let mut msg = format!(
+            "*Возврат абонемента*\n{}\n",
+            render_sub(sub, payer.is_owner())
+        );
+        let mut keymap = InlineKeyboardMarkup::default();
+
+        match self.items {
+            None => {
+                msg.push_str("\nСколько занятий вернуть?");
+                keymap = keymap.append_row(Callback::All.btn_row("Весь остаток"));
+            }
+            Some(items) => {
+                let items = items.unwrap_or(sub.balance);
+                let settings = ctx.ledger.settings.get(&mut ctx.session).await?;
+                let total = sub.item_price() * Decimal::from(items);
+                let fee = total * settings.refund_fee();
+                msg.push_str(&format!(
+                    "\nВозвращаем занятий: _{}_\nСтоимость занятий: _{}_\nУдержание: _{}_\nК выплате: *{}*\nВсе верно?",
+                    items,
+                    escape(&total.to_string()),
+                    escape(&fee.to_string()),
+                    escape(&(total - fee).to_string())
+                ));
+                keymap = keymap.append_row(vec![
+                    Callback::Yes.button("✅ Да"),
+                    Callback::No.button("❌ Нет"),
+                ]);
+            }
+        }
+        ctx.edit_origin(&msg, keymap).await?;
+        Ok(())
+    }
+
+    async fn handle_message(&mut self, ctx: &mut Context, msg: &Message) -> Result<Jmp, Error> {
+        ctx.ensure(Rule::RefundSubscription)?;
+        ctx.delete_msg(msg.id).await?;
+        if self.items.is_none() {
+            match msg.text().unwrap_or_default().parse::<NonZero<u32>>() {
+                Ok(items) => self.items = Some(Some(items.get())),
+                Err(_) => ctx.send_notification("Введите число").await,
+            }
+        }
+        Ok(Jmp::Stay)
+    }
+
+    async fn handle_callback(&mut self, ctx: &mut Context, data: &str) -> Result<Jmp, Error> {
+        ctx.ensure(Rule::RefundSubscription)?;
+        match calldata!(data) {
+            Callback::All => {
+                self.items = Some(None);
+                Ok(Jmp::Stay)
+            }
+            Callback::Yes => {
+                let Some(items) = self.items else {
+                    return Ok(Jmp::Stay);
+                };
+                let amount = ctx
+                    .ledger
+                    .refund_subscription(&mut ctx.session, self.user_id, self.id, items)
+                    .await?;
+                ctx.send_notification(&escape(&format!("Возврат оформлен: {} руб.", amount)))
+                    .await;
+                Ok(Jmp::Back)
+            }
+            Callback::No => {
+                self.items = None;
+                Ok(Jmp::Stay)
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+enum Callback {
+    All,
+    Yes,
+    No,
+}
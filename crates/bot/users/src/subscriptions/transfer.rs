@@ -0,0 +1,147 @@
+use async_trait::async_trait;
+use bot_core::{
+    callback_data::Calldata as _,
+    calldata,
+    context::Context,
+    widget::{Jmp, View},
+};
+use bot_viewer::user::render_sub;
+use eyre::Error;
+use model::{rights::Rule, user::sanitize_phone};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use std::num::NonZero;
+use teloxide::{
+    types::{InlineKeyboardMarkup, Message},
+    utils::markdown::escape,
+};
+
+/// Hands a subscription or part of its lessons over to another client.
+pub struct TransferSubscription {
+    user_id: ObjectId,
+    id: ObjectId,
+    receiver: Option<ObjectId>,
+    /// `Some(None)` moves the whole subscription.
+    items: Option<Option<u32>>,
+}
+
+impl TransferSubscription {
+    pub fn new(user_id: ObjectId, id: ObjectId) -> Self {
+        Self {
+            user_id,
+            id,
+            receiver: None,
+            items: None,
+        }
+    }
+}
+
+#[async_trait]
+impl View for TransferSubscription {
+    fn name(&self) -> &'static str {
+        "TransferSubscription"
+    }
+
+    async fn show(&mut self, ctx: &mut Context) -> Result<(), Error> {
+        ctx.ensure(Rule::TransferSubscription)?;
+        let user = ctx.ledger.get_user(&mut ctx.session, self.user_id).await?;
+        let payer = user.payer()?;
+        let sub = payer
+            .subscriptions()
+            .iter()
+            .find(|s| s.id == self.id)
+            .ok_or_else(|| eyre::eyre!("Subscription not found"))?;
+
+        let mut msg = format!(
+            "*Передача абонемента*\n{}\n",
+            render_sub(sub, payer.is_owner())
+        );
+        let mut keymap = InlineKeyboardMarkup::default();
+
+        let Some(receiver) = self.receiver else {
+            msg.push_str("\nВведите телефон получателя");
+            ctx.edit_origin(&msg, keymap).await?;
+            return Ok(());
+        };
+        let receiver = ctx.ledger.get_user(&mut ctx.session, receiver).await?;
+        msg.push_str(&format!(
+            "\nПолучатель: _{}_\n",
+            escape(&receiver.name.to_string())
+        ));
+
+        match self.items {
+            None => {
+                msg.push_str("Сколько занятий передать?");
+                keymap = keymap.append_row(Callback::All.btn_row("Весь абонемент"));
+            }
+            Some(items) => {
+                let items = match items {
+                    Some(items) => items.to_string(),
+                    None => "весь абонемент".to_string(),
+                };
+                msg.push_str(&format!("Передаем: _{}_\nВсе верно?", escape(&items)));
+                keymap = keymap.append_row(vec![
+                    Callback::Yes.button("✅ Да"),
+                    Callback::No.button("❌ Нет"),
+                ]);
+            }
+        }
+        ctx.edit_origin(&msg, keymap).await?;
+        Ok(())
+    }
+
+    async fn handle_message(&mut self, ctx: &mut Context, msg: &Message) -> Result<Jmp, Error> {
+        ctx.ensure(Rule::TransferSubscription)?;
+        ctx.delete_msg(msg.id).await?;
+        let text = msg.text().unwrap_or_default();
+        if self.receiver.is_none() {
+            let receiver = ctx
+                .ledger
+                .users
+                .get_by_phone(&mut ctx.session, &sanitize_phone(text))
+                .await?;
+            match receiver {
+                Some(receiver) => self.receiver = Some(receiver.id),
+                None => ctx.send_notification("Пользователь не найден").await,
+            }
+        } else if self.items.is_none() {
+            match text.parse::<NonZero<u32>>() {
+                Ok(items) => self.items = Some(Some(items.get())),
+                Err(_) => ctx.send_notification("Введите число").await,
+            }
+        }
+        Ok(Jmp::Stay)
+    }
+
+    async fn handle_callback(&mut self, ctx: &mut Context, data: &str) -> Result<Jmp, Error> {
+        ctx.ensure(Rule::TransferSubscription)?;
+        match calldata!(data) {
+            Callback::All => {
+                self.items = Some(None);
+                Ok(Jmp::Stay)
+            }
+            Callback::Yes => {
+                let (Some(receiver), Some(items)) = (self.receiver, self.items) else {
+                    return Ok(Jmp::Stay);
+                };
+                ctx.ledger
+                    .transfer_subscription(&mut ctx.session, self.user_id, receiver, self.id, items)
+                    .await?;
+                ctx.send_notification("Абонемент передан").await;
+                Ok(Jmp::Back)
+            }
+            Callback::No => {
+                self.receiver = None;
+                self.items = None;
+                Ok(Jmp::Stay)
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+enum Callback {
+    All,
+    Yes,
+    No,
+}
@@ -0,0 +1,128 @@
+use async_trait::async_trait;
+use bot_core::{
+    callback_data::Calldata as _,
+    calldata,
+    context::Context,
+    widget::{Jmp, View},
+};
+use bot_viewer::user::render_sub;
+use eyre::Error;
+use model::rights::Rule;
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use teloxide::{types::InlineKeyboardMarkup, utils::markdown::escape};
+
+/// Moves the client to another subscription, the remaining lessons count toward its price.
+pub struct UpgradeSubscription {
+    user_id: ObjectId,
+    id: ObjectId,
+    target: Option<ObjectId>,
+}
+
+impl UpgradeSubscription {
+    pub fn new(user_id: ObjectId, id: ObjectId) -> Self {
+        Self {
+            user_id,
+            id,
+            target: None,
+        }
+    }
+}
+
+#[async_trait]
+impl View for UpgradeSubscription {
+    fn name(&self) -> &'static str {
+        "UpgradeSubscription"
+    }
+
+    async fn show(&mut self, ctx: &mut Context) -> Result<(), Error> {
+        ctx.ensure(Rule::SellSubscription)?;
+        let user = ctx.ledger.get_user(&mut ctx.session, self.user_id).await?;
+        let payer = user.payer()?;
+        let sub = payer
+            .subscriptions()
+            .iter()
+            .find(|s| s.id == self.id)
+            .ok_or_else(|| eyre::eyre!("Subscription not found"))?;
+        let credit = sub.remaining_value();
+
+        let mut msg = format!(
+            "*Переход на другой абонемент*\n{}\nЗачтем: _{}_ руб\\.\n",
+            render_sub(sub, payer.is_owner()),
+            escape(&credit.to_string())
+        );
+        let mut keymap = InlineKeyboardMarkup::default();
+
+        match self.target {
+            None => {
+                msg.push_str("\nВыберите новый абонемент:");
+                let subscriptions = ctx.ledger.subscriptions.get_all(&mut ctx.session).await?;
+                for target in subscriptions
+                    .iter()
+                    .filter(|target| !target.trial && target.price >= credit)
+                {
+                    keymap = keymap.append_row(
+                        Callback::Select(target.id.bytes())
+                            .btn_row(format!("{} - {} руб.", target.name, target.price)),
+                    );
+                }
+            }
+            Some(target) => {
+                let target = ctx
+                    .ledger
+                    .subscriptions
+                    .get_for_sale(&mut ctx.session, target)
+                    .await?
+                    .ok_or_else(|| eyre::eyre!("Subscription not found"))?;
+                msg.push_str(&format!(
+                    "\nНовый абонемент: *{}*\nЦена: _{}_ руб\\.\nК оплате: *{}* руб\\.\nВсе верно?",
+                    escape(&target.name),
+                    escape(&target.price.to_string()),
+                    escape(&(target.price - credit).to_string())
+                ));
+                keymap = keymap.append_row(vec![
+                    Callback::Yes.button("✅ Да"),
+                    Callback::No.button("❌ Нет"),
+                ]);
+            }
+        }
+        ctx.edit_origin(&msg, keymap).await?;
+        Ok(())
+    }
+
+    async fn handle_callback(&mut self, ctx: &mut Context, data: &str) -> Result<Jmp, Error> {
+        ctx.ensure(Rule::SellSubscription)?;
+        match calldata!(data) {
+            Callback::Select(id) => {
+                self.target = Some(ObjectId::from_bytes(id));
+                Ok(Jmp::Stay)
+            }
+            Callback::Yes => {
+                let Some(target) = self.target else {
+                    return Ok(Jmp::Stay);
+                };
+                let amount = ctx
+                    .ledger
+                    .upgrade_subscription(&mut ctx.session, self.user_id, self.id, target)
+                    .await?;
+                ctx.send_notification(&escape(&format!(
+                    "Переход оформлен. К оплате: {} руб.",
+                    amount
+                )))
+                .await;
+                Ok(Jmp::Back)
+            }
+            Callback::No => {
+                self.target = None;
+                Ok(Jmp::Stay)
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+enum Callback {
+    Select([u8; 12]),
+    Yes,
+    No,
+}
@@ -0,0 +1,32 @@
+use chrono::{Local, Utc};
+use model::gift::{GiftCertificate, GiftValue};
+use teloxide::utils::markdown::escape;
+
+use crate::day::fmt_date;
+
+pub fn fmt_gift_value(value: &GiftValue) -> String {
+    match value {
+        GiftValue::Amount(amount) => format!("на сумму {} руб\\.", escape(&amount.to_string())),
+        GiftValue::Subscription { name, .. } => format!("на абонемент {}", escape(name)),
+    }
+}
+
+pub fn fmt_gift(gift: &GiftCertificate) -> String {
+    let status = if let Some(redeemed) = &gift.redeemed {
+        format!(
+            "использован {}",
+            fmt_date(&redeemed.at.with_timezone(&Local))
+        )
+    } else if gift.is_expired(Utc::now()) {
+        "истек".to_string()
+    } else {
+        "активен".to_string()
+    };
+    format!(
+        "🎁 Сертификат `{}`\n_{}_\nДействует до: _{}_\nСтатус: _{}_\n",
+        gift.code,
+        fmt_gift_value(&gift.value),
+        fmt_date(&gift.expires_at.with_timezone(&Local)),
+        status
+    )
+}
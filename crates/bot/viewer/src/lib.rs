@@ -1,6 +1,9 @@
 use teloxide::utils::markdown::escape;
 
 pub mod day;
+pub mod gift;
+pub mod loyalty;
+pub mod promo;
 pub mod request;
 pub mod rooms;
 pub mod subscription;
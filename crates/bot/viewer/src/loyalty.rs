@@ -0,0 +1,38 @@
+use chrono::Local;
+use model::loyalty::{PointsEntry, PointsOperation};
+use teloxide::utils::markdown::escape;
+
+use crate::day::{fmt_date, fmt_dt};
+
+pub fn fmt_points_operation(operation: &PointsOperation) -> String {
+    match operation {
+        PointsOperation::Training { name, .. } => format!("тренировка {}", escape(name)),
+        PointsOperation::Purchase { name, .. } => format!("покупка абонемента {}", escape(name)),
+        PointsOperation::Birthday { year, .. } => format!("день рождения {}", year),
+        PointsOperation::Discount { name, .. } => format!("скидка на абонемент {}", escape(name)),
+        PointsOperation::FreeLesson { name, .. } => {
+            format!("бесплатное занятие {}", escape(name))
+        }
+        PointsOperation::Expired => "сгорели".to_string(),
+    }
+}
+
+pub fn fmt_points_entry(entry: &PointsEntry) -> String {
+    let mut msg = format!(
+        "{} _{}{}_ {}",
+        fmt_dt(&entry.created_at.with_timezone(&Local)),
+        if entry.points > 0 { "\\+" } else { "\\-" },
+        entry.points.unsigned_abs(),
+        fmt_points_operation(&entry.operation)
+    );
+    if entry.remaining > 0 {
+        if let Some(expires_at) = entry.expires_at {
+            msg.push_str(&format!(
+                "\n    _{}_ сгорят {}",
+                entry.remaining,
+                fmt_date(&expires_at.with_timezone(&Local))
+            ));
+        }
+    }
+    msg
+}
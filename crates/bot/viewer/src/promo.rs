@@ -0,0 +1,42 @@
+use chrono::Local;
+use model::promo::{PromoCode, PromoDiscount};
+use teloxide::utils::markdown::escape;
+
+use crate::day::fmt_date;
+
+pub fn fmt_promo_discount(discount: &PromoDiscount) -> String {
+    match discount {
+        PromoDiscount::Percent(percent) => format!("{}%", percent),
+        PromoDiscount::Fixed(amount) => format!("{} руб\\.", escape(&amount.to_string())),
+    }
+}
+
+fn fmt_limit(limit: Option<u32>) -> String {
+    limit
+        .map(|limit| limit.to_string())
+        .unwrap_or_else(|| "без ограничений".to_string())
+}
+
+pub fn fmt_promo(promo: &PromoCode) -> String {
+    let period = match (promo.valid_from, promo.valid_to) {
+        (None, None) => "бессрочно".to_string(),
+        (Some(from), None) => format!("с {}", fmt_date(&from.with_timezone(&Local))),
+        (None, Some(to)) => format!("до {}", fmt_date(&to.with_timezone(&Local))),
+        (Some(from), Some(to)) => format!(
+            "с {} до {}",
+            fmt_date(&from.with_timezone(&Local)),
+            fmt_date(&to.with_timezone(&Local))
+        ),
+    };
+    format!(
+        "🎟 *{}*{}\nСкидка: _{}_\nДействует: _{}_\nИсточник: _{}_\nИспользован: _{}_ из _{}_\nНа клиента: _{}_\n",
+        escape(&promo.code),
+        if promo.is_archived { " \\(в архиве\\)" } else { "" },
+        fmt_promo_discount(&promo.discount),
+        period,
+        escape(promo.source.name()),
+        promo.uses,
+        fmt_limit(promo.max_uses),
+        fmt_limit(promo.max_uses_per_client),
+    )
+}
@@ -23,12 +23,25 @@ pub fn fmt_request(request: &Request) -> String {
             fmt_dt(&remind_later.date_time.with_timezone(&Local))
         );
     }
+    let mut trial = String::new();
+    if let Some(lesson) = &request.trial {
+        trial = format!(
+            "Пробное занятие: _{}_\n",
+            fmt_dt(&lesson.training.start_at())
+        );
+        if let Some(converted_at) = &lesson.converted_at {
+            trial.push_str(&format!(
+                "Купил абонемент: _{}_\n",
+                fmt_date(&converted_at.with_timezone(&Local))
+            ));
+        }
+    }
 
     format!(
         "Заявка от {} \n*{}*\n\
         Комментарий: _{}_\n\
         Имя:  {} {}\n\
-        Дата: _{}_\n{}\
+        Дата: _{}_\n{}{}\
         История: {}",
         fmt_phone(Some(&request.phone)),
         request.come_from.name(),
@@ -37,6 +50,7 @@ pub fn fmt_request(request: &Request) -> String {
         escape(request.last_name.as_deref().unwrap_or("?")),
         fmt_dt(&request.modified.with_timezone(&Local)),
         remind_me,
+        trial,
         history
     )
 }
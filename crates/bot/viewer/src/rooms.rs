@@ -1,15 +1,9 @@
 use model::rooms::Room;
 
-pub fn fmt_room(room: Room) -> &'static str {
-    match room {
-        Room::Adult => "🧘 Взрослые",
-        Room::Child => "🧒 Дети",
-    }
+pub fn fmt_room(room: &Room) -> String {
+    format!("{} {}", room.emoji, room.name)
 }
 
-pub fn fmt_room_emoji(room: Room) -> &'static str {
-    match room {
-        Room::Adult => "🧘",
-        Room::Child => "🧒",
-    }
-}
\ No newline at end of file
+pub fn fmt_room_emoji(room: Option<&Room>) -> &str {
+    room.map(|r| r.emoji.as_str()).unwrap_or("❓")
+}
@@ -1,9 +1,14 @@
 use bot_core::context::Context;
 use eyre::Error;
 use log::warn;
-use model::subscription::SubscriptionType;
+use model::{
+    restriction::{TimeRestriction, Uncovered},
+    subscription::{SubscriptionType, UserSubscription},
+};
 use teloxide::utils::markdown::escape;
 
+use crate::day::fmt_weekday;
+
 pub async fn fmt_subscription_type(
     ctx: &mut Context,
     tp: &SubscriptionType,
@@ -43,3 +48,75 @@ pub async fn fmt_subscription_type(
         }
     })
 }
+
+pub fn fmt_minutes(min: u32) -> String {
+    format!("{:02}:{:02}", min / 60, min % 60)
+}
+
+pub fn fmt_restriction(restriction: &TimeRestriction) -> String {
+    let mut msg = String::new();
+    if !restriction.weekdays.is_empty() {
+        let days = restriction
+            .weekdays
+            .iter()
+            .map(|day| fmt_weekday(*day))
+            .collect::<Vec<_>>()
+            .join(", ");
+        msg.push_str(&format!("Дни: _{}_\n", days));
+    }
+    if !restriction.windows.is_empty() {
+        let windows = restriction
+            .windows
+            .iter()
+            .map(|window| {
+                format!(
+                    "{} \\- {}",
+                    fmt_minutes(window.from_min),
+                    fmt_minutes(window.to_min)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        msg.push_str(&format!("Время: _{}_\n", windows));
+    }
+    if let Some(limit) = restriction.weekly_limit {
+        msg.push_str(&format!("Не больше _{}_ занятий в неделю\n", limit));
+    }
+    msg
+}
+
+/// Explains to the client why the subscription can't pay for the training.
+pub fn fmt_uncovered(sub: &UserSubscription, reason: Uncovered) -> String {
+    let name = escape(&sub.name);
+    let restriction = sub.restriction.clone().unwrap_or_default();
+    match reason {
+        Uncovered::Weekday => format!(
+            "Абонемент _{}_ действует только по дням: {}",
+            name,
+            restriction
+                .weekdays
+                .iter()
+                .map(|day| fmt_weekday(*day))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Uncovered::Time => format!(
+            "Абонемент _{}_ действует только в часы: {}",
+            name,
+            restriction
+                .windows
+                .iter()
+                .map(|window| format!(
+                    "{} \\- {}",
+                    fmt_minutes(window.from_min),
+                    fmt_minutes(window.to_min)
+                ))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Uncovered::WeeklyLimit(limit) => format!(
+            "По абонементу _{}_ можно ходить не больше {} раз в неделю, на этой неделе лимит исчерпан",
+            name, limit
+        ),
+    }
+}
@@ -1,4 +1,8 @@
-use model::{day::StatisticsSummary, program::TrainingType, training::TrainingStatus};
+use model::{
+    day::StatisticsSummary,
+    program::{BookingWindows, TrainingType},
+    training::TrainingStatus,
+};
 
 pub fn fmt_training_status(
     training: TrainingStatus,
@@ -30,6 +34,7 @@ pub fn fmt_training_status(
                     "🟢"
                 }
             }
+            TrainingStatus::NotOpenToSignup => "⚪",
             TrainingStatus::ClosedToSignup => "🟠",
             TrainingStatus::InProgress => "🔵",
             TrainingStatus::Cancelled => {
@@ -72,15 +77,36 @@ pub fn fmt_training_type(tp: TrainingType) -> String {
     }
 }
 
+pub fn fmt_booking_windows(windows: &BookingWindows) -> String {
+    let open = match windows.sign_up_open_min {
+        Some(min) => format!("за {}", fmt_minutes(min)),
+        None => "сразу".to_string(),
+    };
+    format!(
+        "*Запись открывается*: _{}_\n*Запись закрывается*: _за {}_\n*Бесплатная отмена*: _за {}_",
+        open,
+        fmt_minutes(windows.sign_up_close_min),
+        fmt_minutes(windows.cancel_min)
+    )
+}
+
+fn fmt_minutes(min: u32) -> String {
+    match (min / 60, min % 60) {
+        (0, m) => format!("{}мин", m),
+        (h, 0) => format!("{}ч", h),
+        (h, m) => format!("{}ч {}мин", h, m),
+    }
+}
 
 pub fn fmt_statistics_summary(stat: &StatisticsSummary) -> String {
     format!(
-        "Статистика дня:\nЗаработано {}\nНаграда инструктора {}\nКоличество тренировок:{}\nКоличество тренировок без клиентов:{}\nКлиентов:{}\nСредняя цена занятия:{}",
+        "Статистика дня:\nЗаработано {}\nНаграда инструктора {}\nКоличество тренировок:{}\nКоличество тренировок без клиентов:{}\nКлиентов:{}\nНеявок:{}\nСредняя цена занятия:{}",
         stat.earned, 
         stat.couch_rewards,
         stat.training_count,
         stat.training_without_rewards,
         stat.clients_count,
+        stat.no_show_count,
         stat.sub_avg
     )
 }
\ No newline at end of file
@@ -3,6 +3,7 @@ use chrono::{Local, Utc};
 use eyre::Error;
 use eyre::Result;
 use model::decimal::Decimal;
+use model::installment::Debt;
 use model::user::employee::Employee;
 use model::user::rate::Rate;
 use model::{
@@ -13,14 +14,14 @@ use model::{
 use mongodb::bson::oid::ObjectId;
 use teloxide::utils::markdown::escape;
 
-use crate::{day::fmt_date, fmt_phone};
+use crate::{day::fmt_date, fmt_phone, subscription::fmt_restriction};
 
 pub fn render_sub(sub: &UserSubscription, is_owner: bool) -> String {
     let now = Utc::now();
 
     let emoji = if is_owner { "💳" } else { "🎟" };
 
-    match sub.status {
+    let mut msg = match sub.status {
         Status::NotActive => {
             if sub.unlimited {
                 format!(
@@ -69,7 +70,20 @@ pub fn render_sub(sub: &UserSubscription, is_owner: bool) -> String {
                 )
             }
         }
+    };
+    if let Some(freeze) = &sub.freeze {
+        msg.push_str(&format!(
+            "\n⏸ На паузе до _{}_",
+            fmt_date(&freeze.freeze_end.with_timezone(&Local))
+        ));
+    }
+    if sub.freeze_days > 0 {
+        msg.push_str(&format!("\nДней паузы: _{}_", sub.freeze_days));
+    }
+    if let Some(restriction) = &sub.restriction {
+        msg.push_str(&format!("\n🌙 {}", fmt_restriction(restriction).trim_end()));
     }
+    msg
 }
 
 pub async fn render_profile_msg(
@@ -88,11 +102,28 @@ pub async fn render_profile_msg(
         render_employee_info(ctx, id, &mut msg, employee);
     } else {
         render_subscriptions(&mut msg, &user)?;
+        render_points(ctx, &mut msg, &user).await?;
         render_trainings(ctx, &mut msg, &user).await?;
     }
     Ok((msg, user, extension))
 }
 
+async fn render_points(ctx: &mut Context, msg: &mut String, user: &User) -> Result<(), Error> {
+    let settings = ctx.ledger.settings.get(&mut ctx.session).await?;
+    if !settings.loyalty.enabled {
+        return Ok(());
+    }
+    let payer = user.payer()?.as_ref().id;
+    let balance = ctx
+        .ledger
+        .loyalty
+        .balance(&mut ctx.session, payer, Utc::now())
+        .await?;
+    msg.push_str(&format!("🎁 Баллы лояльности: *{}*\n", balance));
+    msg.push_str("➖➖➖➖➖➖➖➖➖➖\n");
+    Ok(())
+}
+
 async fn render_trainings(ctx: &mut Context, msg: &mut String, user: &User) -> Result<(), Error> {
     let trainings = ctx
         .ledger
@@ -155,9 +186,38 @@ fn render_subscriptions(msg: &mut String, user: &User) -> Result<()> {
         msg.push_str("*нет абонементов*🥺\n");
     }
     msg.push_str("➖➖➖➖➖➖➖➖➖➖\n");
+    let debts = &payer.as_ref().debts;
+    if !debts.is_empty() {
+        for debt in debts {
+            msg.push_str(&render_debt(debt));
+            msg.push('\n');
+        }
+        msg.push_str("➖➖➖➖➖➖➖➖➖➖\n");
+    }
     Ok(())
 }
 
+pub fn render_debt(debt: &Debt) -> String {
+    let mut msg = format!(
+        "💳 Рассрочка за _{}_: осталось _{}_ руб\\.",
+        escape(&debt.name),
+        escape(&debt.remaining().to_string())
+    );
+    if let Some(next) = debt.next_payment() {
+        msg.push_str(&format!(
+            "\n{}Следующий платеж _{}_ руб\\. до _{}_",
+            if debt.is_overdue(Utc::now()) {
+                "❗️"
+            } else {
+                ""
+            },
+            escape(&next.amount.to_string()),
+            fmt_date(&next.due.with_timezone(&Local))
+        ));
+    }
+    msg
+}
+
 pub fn user_base_info(user: &User, extension: &UserExtension) -> String {
     let empty = "?".to_string();
 
@@ -0,0 +1,145 @@
+use crate::Ledger;
+use chrono::{Duration, Utc};
+use eyre::eyre;
+use model::{
+    errors::LedgerError,
+    gift::{GiftCertificate, GiftRedemption, GiftValue},
+    session::Session,
+    subscription::Subscription,
+};
+use mongodb::bson::oid::ObjectId;
+use tx_macro::tx;
+
+/// Attempts to find a free random code before giving up.
+const CODE_ATTEMPTS: usize = 5;
+
+impl Ledger {
+    /// Sells a gift certificate valid for `days`. The certificate price goes to the
+    /// treasury right away, the subscription is handed out on redemption.
+    #[tx]
+    pub async fn sell_gift_certificate(
+        &self,
+        session: &mut Session,
+        buyer: ObjectId,
+        value: GiftValue,
+        days: u32,
+    ) -> Result<GiftCertificate, LedgerError> {
+        self.users
+            .get(session, buyer)
+            .await?
+            .ok_or(LedgerError::UserNotFound(buyer))?;
+
+        let price = match &value {
+            GiftValue::Amount(amount) => *amount,
+            GiftValue::Subscription { id, .. } => {
+                self.subscriptions
+                    .get_for_sale(session, *id)
+                    .await?
+                    .ok_or(LedgerError::SubscriptionNotFound(*id))?
+                    .price
+            }
+        };
+
+        let now = Utc::now();
+        let mut certificate =
+            GiftCertificate::new(value, buyer, price, now, now + Duration::days(days as i64));
+        let mut attempts = 0;
+        while self
+            .gifts
+            .get_by_code(session, &certificate.code)
+            .await?
+            .is_some()
+        {
+            attempts += 1;
+            if attempts >= CODE_ATTEMPTS {
+                return Err(eyre!("Failed to generate gift certificate code").into());
+            }
+            certificate.code = GiftCertificate::generate_code();
+        }
+
+        self.gifts.insert(session, &certificate).await?;
+        self.treasury
+            .sell_gift_certificate(session, &certificate)
+            .await?;
+        self.history
+            .sell_gift_certificate(session, buyer, &certificate)
+            .await?;
+        Ok(certificate)
+    }
+
+    /// Gives the subscription to the payer of the user for the certificate. Amount
+    /// certificates are spent whole on a single subscription priced up to the amount.
+    #[tx]
+    pub async fn redeem_gift_certificate(
+        &self,
+        session: &mut Session,
+        user_id: ObjectId,
+        code: String,
+        subscription: ObjectId,
+    ) -> Result<Subscription, LedgerError> {
+        let mut certificate = self
+            .gifts
+            .get_by_code(session, &code)
+            .await?
+            .ok_or_else(|| LedgerError::GiftCertificateNotFound {
+                code: GiftCertificate::normalize(&code),
+            })?;
+        if certificate.is_redeemed() {
+            return Err(LedgerError::GiftCertificateRedeemed {
+                code: certificate.code,
+            });
+        }
+        let now = Utc::now();
+        if certificate.is_expired(now) {
+            return Err(LedgerError::GiftCertificateExpired {
+                code: certificate.code,
+            });
+        }
+
+        let sub = self
+            .subscriptions
+            .get_for_sale(session, subscription)
+            .await?
+            .ok_or(LedgerError::SubscriptionNotFound(subscription))?;
+        if !certificate.covers(&sub) {
+            return Err(LedgerError::GiftCertificateNotApplicable {
+                code: certificate.code,
+            });
+        }
+        let mut user = self
+            .users
+            .get(session, user_id)
+            .await?
+            .ok_or(LedgerError::UserNotFound(user_id))?;
+        self.users.resolve_family(session, &mut user).await?;
+        if sub.trial {
+            self.ensure_trial_available(session, &user).await?;
+        }
+
+        // Lessons are valued at what the buyer paid, not at today's catalog price.
+        let mut paid = sub.clone();
+        paid.take_off(sub.price - certificate.price.min(sub.price));
+        self.users
+            .add_subscription(session, user.payer()?.as_ref().id, paid, None)
+            .await?;
+        if !sub.trial {
+            self.mark_trial_converted(session, &user).await?;
+        }
+        self.history
+            .redeem_gift_certificate(session, user_id, certificate.code.clone(), sub.clone())
+            .await?;
+        self.treasury
+            .redeem_gift_certificate(session, user_id, &certificate, sub.clone())
+            .await?;
+
+        certificate.redeemed = Some(GiftRedemption {
+            user_id,
+            subscription_id: sub.id,
+            subscription_name: sub.name.clone(),
+            at: now,
+        });
+        certificate.version += 1;
+        self.gifts.update(session, &certificate).await?;
+        Ok(sub)
+    }
+}
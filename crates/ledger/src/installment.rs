@@ -0,0 +1,94 @@
+use crate::Ledger;
+use chrono::{DateTime, Utc};
+use eyre::Result;
+use model::{
+    decimal::Decimal,
+    errors::LedgerError,
+    installment::{Debt, Installment, Reminder},
+    session::Session,
+    user::User,
+};
+use mongodb::bson::oid::ObjectId;
+use tx_macro::tx;
+
+impl Ledger {
+    /// Accepts the next installment of the debt and credits the purchase points for it.
+    /// The debt lives on the family payer and is dropped once it is paid off.
+    /// Returns the paid amount.
+    #[tx]
+    pub async fn pay_installment(
+        &self,
+        session: &mut Session,
+        user_id: ObjectId,
+        debt_id: ObjectId,
+    ) -> Result<Decimal, LedgerError> {
+        let mut user = self
+            .users
+            .get(session, user_id)
+            .await?
+            .ok_or(LedgerError::UserNotFound(user_id))?;
+        self.users.resolve_family(session, &mut user).await?;
+        let mut payer = user.payer_mut()?;
+        let payer_id = payer.id;
+        let debt = payer
+            .debts
+            .iter_mut()
+            .find(|debt| debt.id == debt_id)
+            .ok_or(LedgerError::DebtNotFound(debt_id))?;
+        let amount = debt.pay_next().ok_or(LedgerError::DebtNotFound(debt_id))?;
+        let debt = debt.clone();
+        payer.debts.retain(|debt| !debt.is_paid());
+        self.users
+            .set_debts(session, payer_id, &payer.debts)
+            .await?;
+
+        self.treasury
+            .pay_installment(session, payer_id, &debt, amount)
+            .await?;
+        self.history
+            .pay_installment(session, payer_id, &debt, amount)
+            .await?;
+        self.award_purchase_points(session, payer_id, debt.subscription_id, debt.name, amount)
+            .await?;
+        Ok(amount)
+    }
+
+    /// Collects the installments the clients should be reminded about and marks them
+    /// as reminded, so every reminder goes out once.
+    #[tx]
+    pub async fn take_installment_reminders(
+        &self,
+        session: &mut Session,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<(User, Debt, Installment, Reminder)>> {
+        let mut reminders = vec![];
+        for mut user in self.users.find_users_with_debts(session).await? {
+            let mut found = vec![];
+            for debt in user.debts.iter_mut() {
+                let taken = debt
+                    .installments
+                    .iter_mut()
+                    .filter_map(|installment| {
+                        let reminder = installment.take_reminder(now)?;
+                        Some((installment.clone(), reminder))
+                    })
+                    .collect::<Vec<_>>();
+                found.extend(
+                    taken
+                        .into_iter()
+                        .map(|(installment, reminder)| (debt.clone(), installment, reminder)),
+                );
+            }
+            if found.is_empty() {
+                continue;
+            }
+            self.users.set_debts(session, user.id, &user.debts).await?;
+            reminders.extend(
+                found.into_iter().map(|(debt, installment, reminder)| {
+                    (user.clone(), debt, installment, reminder)
+                }),
+            );
+        }
+        Ok(reminders)
+    }
+}
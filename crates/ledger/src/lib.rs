@@ -1,24 +1,34 @@
 use std::sync::Arc;
 
 use ai::Ai;
-use chrono::Local;
+use chrono::{Local, Utc};
 use env::Env;
 use eyre::{eyre, Context as _, Result};
 use log::error;
 use model::decimal::Decimal;
 use model::errors::LedgerError;
+use model::installment::{Debt, InstallmentPlan};
+use model::program::BookingWindows;
 use model::session::Session;
-use model::training::TrainingStatus;
+use model::subscription::Subscription;
+use model::training::{Training, TrainingStatus};
 use model::treasury::subs::UserId;
-use model::user::family::FindFor;
 use model::user::{sanitize_phone, User};
 use mongodb::bson::oid::ObjectId;
 use service::backup::Backup;
 use service::calendar::Calendar;
+use service::gift::Gifts;
 use service::history::{self, History};
+use service::loyalty::Loyalty;
+use service::notification::NotificationService;
+use service::personal_series::PersonalSeriesList;
 use service::programs::Programs;
+use service::promo::Promo;
 use service::requests::Requests;
 use service::rewards::Rewards;
+use service::rooms::Rooms;
+use service::schedule::ScheduleTemplates;
+use service::settings::Settings;
 use service::subscriptions::Subscriptions;
 use service::treasury::Treasury;
 use service::users::Users;
@@ -28,13 +38,29 @@ use storage::Storage;
 use thiserror::Error;
 use tx_macro::tx;
 
+pub mod gift;
+pub mod installment;
+pub mod loyalty;
+pub mod personal_series;
+pub mod promo;
+pub mod refund;
 pub mod service;
 pub mod training;
+pub mod transfer;
+pub mod trial;
+pub mod upgrade;
 
 pub struct Ledger {
     pub db: Arc<Db>,
     pub users: Users,
     pub calendar: Calendar,
+    pub personal_series: PersonalSeriesList,
+    pub promo: Promo,
+    pub gifts: Gifts,
+    pub loyalty: Loyalty,
+    pub schedule: ScheduleTemplates,
+    pub rooms: Rooms,
+    pub settings: Settings,
     pub programs: Programs,
     pub treasury: Treasury,
     pub subscriptions: Subscriptions,
@@ -43,6 +69,7 @@ pub struct Ledger {
     pub statistics: statistics::Statistics,
     pub backup: backup::Backup,
     pub requests: Requests,
+    pub notifications: NotificationService,
     pub yookassa: yookassa::Yookassa,
     pub ai: Ai,
 }
@@ -57,9 +84,25 @@ impl Ledger {
         let ai = Ai::new(env.ai_base_url().to_owned(), env.ai_api_key().to_owned());
 
         let users = Users::new(storage.users, history.clone(), ai.clone());
-        let calendar = Calendar::new(storage.calendar, users.clone(), programs.clone());
-
-        let treasury = Treasury::new(storage.treasury, history.clone());
+        let rooms = Rooms::new(storage.rooms);
+        let settings = Settings::new(storage.settings);
+        let calendar = Calendar::new(
+            storage.calendar,
+            storage.schedule.clone(),
+            storage.availability,
+            rooms.clone(),
+            users.clone(),
+            programs.clone(),
+            settings.clone(),
+        );
+        let personal_series = PersonalSeriesList::new(storage.personal_series);
+        let promo = Promo::new(storage.promo);
+        let gifts = Gifts::new(storage.gift.clone());
+        let loyalty = Loyalty::new(storage.loyalty);
+        let schedule =
+            ScheduleTemplates::new(storage.schedule, calendar.clone(), programs.clone());
+
+        let treasury = Treasury::new(storage.treasury, storage.gift, history.clone());
         let subscriptions = Subscriptions::new(
             storage.subscriptions,
             history.clone(),
@@ -68,6 +111,7 @@ impl Ledger {
         );
         let rewards = Rewards::new(storage.rewards);
         let requests = Requests::new(storage.requests, users.clone());
+        let notifications = NotificationService::new(storage.notification);
 
         let statistics = statistics::Statistics::new(
             calendar.clone(),
@@ -81,6 +125,13 @@ impl Ledger {
         Ledger {
             users,
             calendar,
+            personal_series,
+            promo,
+            gifts,
+            loyalty,
+            schedule,
+            rooms,
+            settings,
             programs,
             db: storage.db,
             treasury,
@@ -90,6 +141,7 @@ impl Ledger {
             statistics,
             backup,
             requests,
+            notifications,
             yookassa: yookassa::Yookassa::new(&env),
             ai,
         }
@@ -106,22 +158,22 @@ impl Ledger {
         Ok(user)
     }
 
+    /// Returns the trainings whose freed seats went to the waitlist, with the promoted clients.
     #[tx]
     pub async fn block_user(
         &self,
         session: &mut Session,
         id: ObjectId,
         is_active: bool,
-    ) -> Result<()> {
-        let mut user = self
+    ) -> Result<Vec<(Training, ObjectId)>> {
+        let user = self
             .users
             .get(session, id)
             .await?
             .ok_or_else(|| eyre!("User not found"))?;
         let user_id = user.id;
-        self.users.resolve_family(session, &mut user).await?;
-        let mut payer = user.payer_mut()?;
 
+        let mut promoted = vec![];
         if !is_active {
             let users_training = self
                 .calendar
@@ -143,20 +195,19 @@ impl Ledger {
                 }
 
                 if training.tp.is_not_free() {
-                    let sub = payer
-                        .find_subscription(FindFor::Unlock, &training)
-                        .ok_or_else(|| eyre!("User not found"))?;
-                    sub.unlock_balance();
-                    self.calendar
-                        .sign_out(session, training.id(), user_id)
+                    self.sign_out_tx_less(session, &training, user_id, true)
                         .await?;
+                    if let Some(waiter) =
+                        self.promote_from_waitlist(session, training.id()).await?
+                    {
+                        promoted.push((training, waiter));
+                    }
                 }
             }
-            self.users.update(session, &mut payer).await?;
         }
         self.history.block_user(session, user_id, is_active).await?;
         self.users.block_user(session, user_id, is_active).await?;
-        Ok(())
+        Ok(promoted)
     }
 
     #[tx]
@@ -166,29 +217,96 @@ impl Ledger {
         subscription: ObjectId,
         buyer: ObjectId,
         discount: Option<Decimal>,
+        installments: Option<InstallmentPlan>,
     ) -> Result<(), SellSubscriptionError> {
-        let buyer = self
+        let subscription = self
+            .subscriptions
+            .get_for_sale(session, subscription)
+            .await?
+            .ok_or(SellSubscriptionError::SubscriptionNotFound(subscription))?;
+        self.sell(session, subscription, buyer, discount, installments)
+            .await
+    }
+
+    /// Sells the subscription as given, the price may differ from the catalog one.
+    pub(crate) async fn sell(
+        &self,
+        session: &mut Session,
+        subscription: Subscription,
+        buyer: ObjectId,
+        discount: Option<Decimal>,
+        installments: Option<InstallmentPlan>,
+    ) -> Result<(), SellSubscriptionError> {
+        let mut buyer = self
             .users
             .get(session, buyer)
             .await?
-            .ok_or_else(|| SellSubscriptionError::UserNotFound)?;
+            .ok_or(SellSubscriptionError::UserNotFound(buyer))?;
+        self.users.resolve_family(session, &mut buyer).await?;
 
-        let subscription = self
-            .subscriptions
-            .get(session, subscription)
-            .await?
-            .ok_or_else(|| eyre!("User not found"))?;
+        if subscription.trial {
+            self.ensure_trial_available(session, &buyer).await?;
+        }
+
+        let mut price = subscription.price;
+        if let Some(discount) = discount {
+            price -= subscription.price * discount;
+        }
+        let credit = if let Some(plan) = installments {
+            if plan.parts == 0 || plan.first_payment.is_negative() || plan.first_payment >= price
+            {
+                return Err(SellSubscriptionError::InvalidParams);
+            }
+            Some((price - plan.first_payment, plan.parts))
+        } else {
+            None
+        };
 
         self.history
             .sell_subscription(session, subscription.clone(), buyer.id, discount)
             .await?;
 
-        self.users
-            .add_subscription(session, buyer.id, subscription.clone(), discount)
+        // The subscription and its debt go to the payer: `sign_up` checks the payer's debts.
+        let payer_id = buyer.payer()?.as_ref().id;
+        let user_subscriptions = self
+            .users
+            .add_subscription(session, payer_id, subscription.clone(), discount)
             .await?;
 
+        if !subscription.trial {
+            self.mark_trial_converted(session, &buyer).await?;
+        }
+
+        let remaining = credit.map(|(amount, _)| amount);
+        self.award_purchase_points(
+            session,
+            payer_id,
+            subscription.id,
+            subscription.name.clone(),
+            price - remaining.unwrap_or_default(),
+        )
+        .await?;
+
+        if let Some((amount, parts)) = credit {
+            let mut debt = Debt::new(
+                subscription.id,
+                subscription.name.clone(),
+                amount,
+                parts,
+                Utc::now(),
+            );
+            debt.user_subscriptions = user_subscriptions;
+            self.users.add_debt(session, payer_id, &debt).await?;
+        }
+
         self.treasury
-            .sell(session, buyer.id, subscription, discount)
+            .sell(
+                session,
+                buyer.id,
+                subscription,
+                discount,
+                remaining.unwrap_or_default(),
+            )
             .await?;
         Ok(())
     }
@@ -215,9 +333,12 @@ impl Ledger {
 
         let subscription = self
             .subscriptions
-            .get(session, sub_id)
+            .get_for_sale(session, sub_id)
             .await?
             .ok_or_else(|| eyre!("User not found"))?;
+        if subscription.trial {
+            self.ensure_trial_available(session, &buyer).await?;
+        }
         self.history
             .sell_subscription(session, subscription.clone(), buyer.id, discount)
             .await?;
@@ -226,8 +347,12 @@ impl Ledger {
             .add_subscription(session, buyer.id, subscription.clone(), discount)
             .await?;
 
+        if !subscription.trial {
+            self.mark_trial_converted(session, &buyer).await?;
+        }
+
         self.treasury
-            .sell(session, buyer.id, subscription, discount)
+            .sell(session, buyer.id, subscription, discount, Decimal::zero())
             .await?;
         Ok(())
     }
@@ -264,6 +389,22 @@ impl Ledger {
         Ok(())
     }
 
+    #[tx]
+    pub async fn edit_program_windows(
+        &self,
+        session: &mut Session,
+        program_id: ObjectId,
+        windows: BookingWindows,
+    ) -> Result<()> {
+        self.programs
+            .edit_windows(session, program_id, windows)
+            .await?;
+        self.calendar
+            .edit_windows(session, program_id, windows)
+            .await?;
+        Ok(())
+    }
+
     #[tx]
     pub async fn edit_program_name(
         &self,
@@ -371,18 +512,41 @@ impl Ledger {
 
 #[derive(Error, Debug)]
 pub enum SellSubscriptionError {
-    #[error("Subscription not found")]
-    SubscriptionNotFound,
+    #[error("Subscription not found:{0}")]
+    SubscriptionNotFound(ObjectId),
     #[error("Subscription already sold")]
     SubscriptionAlreadySold,
-    #[error("User not found")]
-    UserNotFound,
+    #[error("User not found:{0}")]
+    UserNotFound(ObjectId),
     #[error("invalid params")]
     InvalidParams,
+    #[error(transparent)]
+    Ledger(Box<LedgerError>),
     #[error("{0:?}")]
     Common(#[from] eyre::Error),
 }
 
+impl From<LedgerError> for SellSubscriptionError {
+    fn from(value: LedgerError) -> Self {
+        SellSubscriptionError::Ledger(Box::new(value))
+    }
+}
+
+impl From<SellSubscriptionError> for LedgerError {
+    fn from(value: SellSubscriptionError) -> Self {
+        match value {
+            SellSubscriptionError::SubscriptionNotFound(id) => LedgerError::SubscriptionNotFound(id),
+            SellSubscriptionError::UserNotFound(id) => LedgerError::UserNotFound(id),
+            SellSubscriptionError::InvalidParams => LedgerError::InvalidSaleParams,
+            SellSubscriptionError::Ledger(err) => *err,
+            SellSubscriptionError::SubscriptionAlreadySold => {
+                LedgerError::Eyre(eyre!("Subscription already sold"))
+            }
+            SellSubscriptionError::Common(err) => LedgerError::Eyre(err),
+        }
+    }
+}
+
 impl From<mongodb::error::Error> for SellSubscriptionError {
     fn from(value: mongodb::error::Error) -> Self {
         SellSubscriptionError::Common(value.into())
@@ -0,0 +1,285 @@
+use crate::Ledger;
+use chrono::{DateTime, Utc};
+use eyre::{eyre, Result};
+use model::{
+    decimal::Decimal,
+    errors::LedgerError,
+    loyalty::{is_free_lesson_option, points_discount, take_points, PointsEntry, PointsOperation},
+    session::Session,
+    settings::LoyaltyPolicy,
+    subscription::Subscription,
+    training::{Attendance, Training},
+};
+use mongodb::bson::oid::ObjectId;
+use tx_macro::tx;
+
+impl Ledger {
+    /// Credits the payers of the clients who attended the finalized training.
+    #[tx]
+    pub async fn award_training_points(
+        &self,
+        session: &mut Session,
+        training: &Training,
+    ) -> Result<()> {
+        let policy = self.settings.get(session).await?.loyalty;
+        if !policy.enabled || policy.training_points == 0 {
+            return Ok(());
+        }
+        for client in &training.clients {
+            if training.attendance(*client).unwrap_or(Attendance::Attended) != Attendance::Attended
+            {
+                continue;
+            }
+            let user = self.get_user(session, *client).await?;
+            let payer = user.payer()?.as_ref().id;
+            self.earn_points(
+                session,
+                payer,
+                policy.training_points,
+                PointsOperation::Training {
+                    training_id: training.id(),
+                    name: training.name.clone(),
+                },
+                &policy,
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Credits the payer with a share of the paid amount. Runs inside the sale transaction
+    /// and once more for every installment of the debt.
+    pub(crate) async fn award_purchase_points(
+        &self,
+        session: &mut Session,
+        payer: ObjectId,
+        subscription_id: ObjectId,
+        name: String,
+        paid: Decimal,
+    ) -> Result<()> {
+        let policy = self.settings.get(session).await?.loyalty;
+        if !policy.enabled {
+            return Ok(());
+        }
+        self.earn_points(
+            session,
+            payer,
+            policy.purchase_points(paid),
+            PointsOperation::Purchase {
+                subscription_id,
+                name,
+            },
+            &policy,
+        )
+        .await
+    }
+
+    /// Credits the payer of the user. Returns the awarded points, zero if the user
+    /// already got them this year.
+    #[tx]
+    pub async fn award_birthday_points(
+        &self,
+        session: &mut Session,
+        user_id: ObjectId,
+        year: i32,
+    ) -> Result<u32> {
+        let policy = self.settings.get(session).await?.loyalty;
+        if !policy.enabled || policy.birthday_points == 0 {
+            return Ok(0);
+        }
+        if self
+            .loyalty
+            .has_birthday_points(session, user_id, year)
+            .await?
+        {
+            return Ok(0);
+        }
+        let payer = self.get_user(session, user_id).await?.payer()?.as_ref().id;
+        self.earn_points(
+            session,
+            payer,
+            policy.birthday_points,
+            PointsOperation::Birthday {
+                year,
+                client_id: Some(user_id),
+            },
+            &policy,
+        )
+        .await?;
+        Ok(policy.birthday_points)
+    }
+
+    /// Sells the subscription with the payer's points spent as a discount.
+    /// Returns the spent points.
+    #[tx]
+    pub async fn sell_subscription_with_points(
+        &self,
+        session: &mut Session,
+        subscription: ObjectId,
+        buyer: ObjectId,
+    ) -> Result<u32, LedgerError> {
+        let policy = self.settings.get(session).await?.loyalty;
+        if !policy.enabled {
+            return Err(LedgerError::LoyaltyDisabled);
+        }
+        let sub = self
+            .subscriptions
+            .get_for_sale(session, subscription)
+            .await?
+            .ok_or(LedgerError::SubscriptionNotFound(subscription))?;
+        let payer = self.get_user(session, buyer).await?.payer()?.as_ref().id;
+        let balance = self.loyalty.balance(session, payer, Utc::now()).await?;
+        let (discount, points) = points_discount(balance, sub.price);
+        if points == 0 {
+            return Err(LedgerError::NotEnoughPoints {
+                balance,
+                required: required_for_discount(&sub),
+            });
+        }
+
+        self.sell_subscription_txless(session, subscription, buyer, Some(discount), None)
+            .await?;
+        self.spend_points(
+            session,
+            payer,
+            points,
+            PointsOperation::Discount {
+                subscription_id: sub.id,
+                name: sub.name,
+            },
+        )
+        .await?;
+        Ok(points)
+    }
+
+    /// Gives the payer of the user a single lesson subscription for points.
+    #[tx]
+    pub async fn redeem_points_for_lesson(
+        &self,
+        session: &mut Session,
+        user_id: ObjectId,
+        subscription: ObjectId,
+    ) -> Result<Subscription, LedgerError> {
+        let policy = self.settings.get(session).await?.loyalty;
+        if !policy.enabled {
+            return Err(LedgerError::LoyaltyDisabled);
+        }
+        let sub = self
+            .subscriptions
+            .get_for_sale(session, subscription)
+            .await?
+            .ok_or(LedgerError::SubscriptionNotFound(subscription))?;
+        if !is_free_lesson_option(&sub) {
+            return Err(eyre!("Subscription {} is not a single lesson", sub.id).into());
+        }
+        let mut user = self
+            .users
+            .get(session, user_id)
+            .await?
+            .ok_or(LedgerError::UserNotFound(user_id))?;
+        self.users.resolve_family(session, &mut user).await?;
+        let payer = user.payer()?.as_ref().id;
+
+        self.spend_points(
+            session,
+            payer,
+            policy.free_lesson_cost,
+            PointsOperation::FreeLesson {
+                subscription_id: sub.id,
+                name: sub.name.clone(),
+            },
+        )
+        .await?;
+        let discount = Some(Decimal::int(1));
+        self.history
+            .sell_subscription(session, sub.clone(), payer, discount)
+            .await?;
+        self.users
+            .add_subscription(session, payer, sub.clone(), discount)
+            .await?;
+        Ok(sub)
+    }
+
+    /// Writes off the points that outlived the program's lifetime.
+    /// Returns the expiry entries, one per affected earning.
+    #[tx]
+    pub async fn expire_points(
+        &self,
+        session: &mut Session,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<PointsEntry>> {
+        let mut expired = vec![];
+        for earning in self.loyalty.expired(session, now).await? {
+            let entry = PointsEntry::spend(
+                earning.user_id,
+                earning.remaining,
+                PointsOperation::Expired,
+                session.actor(),
+                now,
+            );
+            self.loyalty.set_remaining(session, earning.id, 0).await?;
+            self.loyalty.insert(session, &entry).await?;
+            expired.push(entry);
+        }
+        Ok(expired)
+    }
+
+    async fn earn_points(
+        &self,
+        session: &mut Session,
+        user_id: ObjectId,
+        points: u32,
+        operation: PointsOperation,
+        policy: &LoyaltyPolicy,
+    ) -> Result<()> {
+        if points == 0 {
+            return Ok(());
+        }
+        let entry = PointsEntry::earn(
+            user_id,
+            points,
+            operation,
+            session.actor(),
+            Utc::now(),
+            policy.lifetime_days,
+        );
+        self.loyalty.insert(session, &entry).await
+    }
+
+    async fn spend_points(
+        &self,
+        session: &mut Session,
+        user_id: ObjectId,
+        points: u32,
+        operation: PointsOperation,
+    ) -> Result<(), LedgerError> {
+        let now = Utc::now();
+        let mut earnings = self.loyalty.spendable(session, user_id, now).await?;
+        let before = earnings
+            .iter()
+            .map(|earning| (earning.id, earning.remaining))
+            .collect::<Vec<_>>();
+        if !take_points(&mut earnings, points) {
+            return Err(LedgerError::NotEnoughPoints {
+                balance: before.iter().map(|(_, remaining)| remaining).sum(),
+                required: points,
+            });
+        }
+        for (earning, (_, remaining)) in earnings.iter().zip(before) {
+            if earning.remaining != remaining {
+                self.loyalty
+                    .set_remaining(session, earning.id, earning.remaining)
+                    .await?;
+            }
+        }
+        let entry = PointsEntry::spend(user_id, points, operation, session.actor(), now);
+        self.loyalty.insert(session, &entry).await?;
+        Ok(())
+    }
+}
+
+/// Points needed for the smallest whole percent discount.
+fn required_for_discount(sub: &Subscription) -> u32 {
+    let percent = sub.price / Decimal::int(100);
+    u32::try_from((percent.inner() + 99) / 100).unwrap_or(u32::MAX)
+}
@@ -0,0 +1,151 @@
+use crate::Ledger;
+use chrono::{DateTime, Local, Utc};
+use model::{
+    errors::LedgerError,
+    personal_series::PersonalSeries,
+    session::Session,
+    slot::Slot,
+    training::{Training, TrainingId},
+};
+use mongodb::bson::oid::ObjectId;
+use tx_macro::tx;
+
+impl Ledger {
+    /// Saves a new weekly personal series and schedules its sessions that fall into
+    /// the horizon right away. Each session locks one item of the client's subscription.
+    #[tx]
+    pub async fn schedule_personal_series(
+        &self,
+        session: &mut Session,
+        series: PersonalSeries,
+    ) -> Result<PersonalSeries, LedgerError> {
+        let mut series = series;
+        self.personal_series.insert(session, &series).await?;
+        self.schedule_due_sessions(session, &mut series).await?;
+        Ok(series)
+    }
+
+    /// Schedules the next session of the series if it entered the horizon, skipping the
+    /// ones that already started. Each session goes in its own transaction, so a failed
+    /// one doesn't roll back the others. Returns `None` once nothing is due.
+    #[tx]
+    pub async fn extend_personal_series(
+        &self,
+        session: &mut Session,
+        id: ObjectId,
+    ) -> Result<Option<TrainingId>, LedgerError> {
+        let mut series = self
+            .personal_series
+            .get(session, id)
+            .await?
+            .ok_or_else(|| eyre::eyre!("Personal series not found: {}", id))?;
+        let now = Local::now();
+        series.skip_past(now);
+        let scheduled = match series.due(now) {
+            Some(start_at) => Some(
+                self.schedule_session(session, &mut series, start_at)
+                    .await?,
+            ),
+            None => None,
+        };
+        self.personal_series.update(session, &series).await?;
+        Ok(scheduled)
+    }
+
+    /// Gives up on the sessions of the series up to `start_at`, e.g. when the one at
+    /// `start_at` can't be scheduled, so the following weeks are not blocked by it.
+    #[tx]
+    pub async fn skip_personal_session(
+        &self,
+        session: &mut Session,
+        id: ObjectId,
+        start_at: DateTime<Local>,
+    ) -> Result<(), LedgerError> {
+        let mut series = self
+            .personal_series
+            .get(session, id)
+            .await?
+            .ok_or_else(|| eyre::eyre!("Personal series not found: {}", id))?;
+        while series.next_at() <= start_at && !series.is_finished() {
+            series.advance(false);
+        }
+        self.personal_series.update(session, &series).await?;
+        Ok(())
+    }
+
+    /// Stops the series and signs the client out of its upcoming sessions that can
+    /// still be cancelled for free. Returns the cancelled sessions.
+    #[tx]
+    pub async fn cancel_personal_series(
+        &self,
+        session: &mut Session,
+        id: ObjectId,
+    ) -> Result<Vec<Training>, LedgerError> {
+        let mut series = self
+            .personal_series
+            .get(session, id)
+            .await?
+            .ok_or_else(|| eyre::eyre!("Personal series not found: {}", id))?;
+        series.is_canceled = true;
+        self.personal_series.update(session, &series).await?;
+
+        let now = Local::now();
+        let mut canceled = vec![];
+        for training in self.calendar.find_series_trainings(session, id).await? {
+            if training.is_processed || !training.status(now).can_sign_out() {
+                continue;
+            }
+            if training.clients.contains(&series.client) {
+                self.sign_out_tx_less(session, &training, series.client, false)
+                    .await?;
+            }
+            self.calendar
+                .delete_training_txless(session, training.id(), false)
+                .await?;
+            canceled.push(training);
+        }
+        Ok(canceled)
+    }
+
+    async fn schedule_due_sessions(
+        &self,
+        session: &mut Session,
+        series: &mut PersonalSeries,
+    ) -> Result<Vec<TrainingId>, LedgerError> {
+        let now = Local::now();
+        series.skip_past(now);
+        let mut scheduled = vec![];
+        while let Some(start_at) = series.due(now) {
+            scheduled.push(self.schedule_session(session, series, start_at).await?);
+        }
+        self.personal_series.update(session, series).await?;
+        Ok(scheduled)
+    }
+
+    /// Books the client on the session at `start_at` and moves the series to the next week.
+    async fn schedule_session(
+        &self,
+        session: &mut Session,
+        series: &mut PersonalSeries,
+        start_at: DateTime<Local>,
+    ) -> Result<TrainingId, LedgerError> {
+        let id = self
+            .calendar
+            .schedule_personal_training(
+                session,
+                series.client,
+                series.instructor,
+                Slot::new(
+                    start_at.with_timezone(&Utc),
+                    series.duration_min,
+                    series.room,
+                ),
+                Some(series.id),
+            )
+            .await?;
+        self.sign_up_txless(session, id, series.client, true)
+            .await?;
+        series.advance(true);
+        Ok(id)
+    }
+}
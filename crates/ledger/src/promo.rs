@@ -0,0 +1,45 @@
+use crate::Ledger;
+use chrono::Utc;
+use model::{errors::LedgerError, promo::PromoRedemption, session::Session};
+use mongodb::bson::oid::ObjectId;
+use tx_macro::tx;
+
+impl Ledger {
+    /// Sells the subscription with the promo code discount and records the redemption
+    /// for the campaign statistics.
+    #[tx]
+    pub async fn sell_subscription_with_promo(
+        &self,
+        session: &mut Session,
+        subscription: ObjectId,
+        buyer: ObjectId,
+        code: String,
+    ) -> Result<(), LedgerError> {
+        let sub = self
+            .subscriptions
+            .get_for_sale(session, subscription)
+            .await?
+            .ok_or(LedgerError::SubscriptionNotFound(subscription))?;
+        let promo = self.promo.check(session, &code, &sub, buyer).await?;
+        let discount = promo.discount_amount(sub.price);
+        let mut sold = sub.clone();
+        sold.take_off(discount);
+
+        self.sell(session, sold, buyer, None, None).await?;
+
+        let redemption = PromoRedemption {
+            id: ObjectId::new(),
+            promo_id: promo.id,
+            code: promo.code,
+            source: promo.source,
+            user_id: buyer,
+            subscription_id: sub.id,
+            subscription_name: sub.name,
+            price: sub.price - discount,
+            discount,
+            date_time: Utc::now(),
+        };
+        self.promo.add_redemption(session, &redemption).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,70 @@
+use crate::Ledger;
+use eyre::eyre;
+use model::{decimal::Decimal, errors::LedgerError, session::Session};
+use mongodb::bson::oid::ObjectId;
+use tx_macro::tx;
+
+impl Ledger {
+    /// Returns money for `items` remaining lessons of the subscription, or for the whole
+    /// remaining balance. Lessons are valued at `item_price()` and the studio keeps the
+    /// refund fee from the settings. Returns the amount paid back to the client.
+    #[tx]
+    pub async fn refund_subscription(
+        &self,
+        session: &mut Session,
+        user_id: ObjectId,
+        subscription: ObjectId,
+        items: Option<u32>,
+    ) -> Result<Decimal, LedgerError> {
+        let mut user = self
+            .users
+            .get(session, user_id)
+            .await?
+            .ok_or(LedgerError::UserNotFound(user_id))?;
+        self.users.resolve_family(session, &mut user).await?;
+        let mut payer = user.payer_mut()?;
+        let payer_id = payer.id;
+
+        if payer.debt_for(subscription).is_some() {
+            return Err(LedgerError::SubscriptionHasDebt { subscription });
+        }
+
+        let subs = payer.subscriptions_mut();
+        let idx = subs
+            .iter()
+            .position(|sub| sub.id == subscription)
+            .ok_or(LedgerError::SubscriptionNotFound(subscription))?;
+        if subs[idx].unlimited {
+            return Err(eyre!(
+                "Unlimited subscription can't be refunded: {}",
+                subs[idx].name
+            )
+            .into());
+        }
+        let whole = items.is_none_or(|items| items == subs[idx].balance);
+        let refunded = if whole {
+            if subs[idx].locked_balance > 0 {
+                return Err(LedgerError::SubscriptionHasLockedBalance { subscription });
+            }
+            subs.remove(idx)
+        } else {
+            subs[idx]
+                .split(items.unwrap_or_default())
+                .ok_or(LedgerError::NotEnoughBalance(payer_id))?
+        };
+
+        let settings = self.settings.get(session).await?;
+        let total = refunded.item_price() * Decimal::from(refunded.balance);
+        let fee = total * settings.refund_fee();
+        let amount = total - fee;
+
+        self.treasury
+            .refund(session, payer_id, &refunded, amount, fee)
+            .await?;
+        self.history
+            .refund_subscription(session, payer_id, refunded, amount, fee)
+            .await?;
+        self.users.update(session, &mut payer).await?;
+        Ok(amount)
+    }
+}
@@ -1,35 +1,90 @@
 use std::{ops::Deref, sync::Arc};
 
-use chrono::{DateTime, Local, Utc};
+use chrono::{DateTime, Local, Timelike as _, Utc, Weekday};
 use eyre::{Error, Result};
+use log::warn;
 use model::{
+    availability::{Absence, AvailabilityWindow, PERSONAL_SLOT_MIN},
+    day::Day,
     decimal::Decimal,
     errors::LedgerError,
+    ical,
     ids::DayId,
+    program::BookingWindows,
+    schedule::TemplateTraining,
     session::Session,
     slot::Slot,
-    training::{Training, TrainingId, TrainingStatus},
+    training::{Attendance, Filter, SeriesScope, Training, TrainingId, TrainingStatus},
+    user::User,
 };
 use mongodb::bson::oid::ObjectId;
-use storage::calendar::CalendarStore;
+use storage::{
+    availability::AvailabilityStore, calendar::CalendarStore, schedule::ScheduleTemplateStore,
+};
 use tx_macro::tx;
 
-use super::{programs::Programs, users::Users};
+use super::{programs::Programs, rooms::Rooms, settings::Settings, users::Users};
+
+const ICAL_LIMIT: usize = 500;
 
 #[derive(Clone)]
 pub struct Calendar {
     calendar: Arc<CalendarStore>,
+    templates: Arc<ScheduleTemplateStore>,
+    absences: Arc<AvailabilityStore>,
+    rooms: Rooms,
     users: Users,
     programs: Programs,
+    settings: Settings,
 }
 
 impl Calendar {
-    pub(crate) fn new(calendar: Arc<CalendarStore>, users: Users, programs: Programs) -> Self {
+    pub(crate) fn new(
+        calendar: Arc<CalendarStore>,
+        templates: Arc<ScheduleTemplateStore>,
+        absences: Arc<AvailabilityStore>,
+        rooms: Rooms,
+        users: Users,
+        programs: Programs,
+        settings: Settings,
+    ) -> Self {
         Calendar {
             calendar,
+            templates,
+            absences,
+            rooms,
             users,
             programs,
+            settings,
+        }
+    }
+
+    /// Returns the day, materializing it from the effective schedule template on first access.
+    /// Without a template the day is not stored, so a template added later still applies to it.
+    pub async fn get_day(&self, session: &mut Session, id: DayId) -> Result<Day> {
+        if let Some(day) = self.calendar.find_day(session, id).await? {
+            return Ok(day);
+        }
+
+        if Utc::now() + chrono::Duration::days(365 * 2) < id.id() {
+            return Err(eyre::eyre!("Day is too far in the future:{:?}", id));
+        }
+
+        let mut day = Day::new(id);
+        let Some(template) = self.templates.find_effective(session, id).await? else {
+            return Ok(day);
+        };
+        for entry in template.day_trainings(id) {
+            if let Some(program) = self.programs.get_by_id(session, entry.proto_id).await? {
+                let mut training = entry.materialize(id, program);
+                self.fit_to_room(session, &mut training).await?;
+                day.training.push(training);
+            } else {
+                warn!("Program not found: {:?}", entry.proto_id);
+            }
         }
+        self.calendar.insert_day(session, &day).await?;
+        Ok(day)
     }
 
     pub async fn get_training_by_id(
@@ -74,6 +129,9 @@ impl Calendar {
             self.calendar
                 .set_cancel_flag(session, training.id(), false)
                 .await?;
+            self.calendar
+                .set_canceled_clients(session, training.id(), &[])
+                .await?;
             Ok(())
         } else {
             return Err(eyre::eyre!("Training not found"));
@@ -86,8 +144,79 @@ impl Calendar {
         session: &mut Session,
         id: TrainingId,
         name: &str,
+        scope: SeriesScope,
     ) -> Result<(), Error> {
-        self.calendar.change_name(session, id, name).await?;
+        let series = self.series(session, id, scope).await?;
+        let series_id = series
+            .first()
+            .map(|training| training.id)
+            .ok_or(LedgerError::TrainingNotFound(id))?;
+        for training in series {
+            self.calendar
+                .change_name(session, training.id(), name)
+                .await?;
+        }
+
+        if scope.is_series() {
+            self.update_template_training(session, series_id, |entry| {
+                entry.name = Some(name.to_string());
+            })
+            .await?;
+        }
+        Ok(())
+    }
+
+    #[tx]
+    pub async fn mark_attendance(
+        &self,
+        session: &mut Session,
+        id: TrainingId,
+        client: ObjectId,
+        status: Attendance,
+    ) -> Result<(), LedgerError> {
+        let mut training = self
+            .get_training_by_id(session, id)
+            .await?
+            .ok_or(LedgerError::TrainingNotFound(id))?;
+        if !training.can_mark_attendance(Local::now()) {
+            return Err(LedgerError::AttendanceIsNotOpen(id));
+        }
+        if !training.clients.contains(&client) {
+            return Err(LedgerError::ClientNotSignedUp(client, id));
+        }
+        training.set_attendance(client, status);
+        self.calendar
+            .set_attendance(session, id, &training.attendance)
+            .await?;
+        Ok(())
+    }
+
+    /// Overrides windows of a single training. `None` returns it to the program (or studio) windows.
+    #[tx]
+    pub async fn set_training_windows(
+        &self,
+        session: &mut Session,
+        id: TrainingId,
+        windows: Option<BookingWindows>,
+    ) -> Result<(), LedgerError> {
+        let training = self
+            .get_training_by_id(session, id)
+            .await?
+            .ok_or(LedgerError::TrainingNotFound(id))?;
+        let custom = windows.is_some();
+        let windows = match windows {
+            Some(windows) => windows,
+            None if training.is_personal() => self.settings.get(session).await?.personal_windows,
+            None => self
+                .programs
+                .get_by_id(session, training.proto_id)
+                .await?
+                .map(|program| program.windows)
+                .unwrap_or_default(),
+        };
+        self.calendar
+            .set_windows(session, id, windows, custom)
+            .await?;
         Ok(())
     }
 
@@ -97,7 +226,7 @@ impl Calendar {
         session: &mut Session,
         id: TrainingId,
         new_slot: Slot,
-        all: bool,
+        scope: SeriesScope,
     ) -> Result<(), LedgerError> {
         if id.day_id() != new_slot.day_id() {
             return Err(LedgerError::DayIdMismatch {
@@ -105,48 +234,43 @@ impl Calendar {
                 new: new_slot.day_id(),
             });
         }
+        if id.room != new_slot.room() {
+            self.rooms.get_active_room(session, new_slot.room()).await?;
+        }
 
-        let mut training = self
-            .get_training_by_id(session, id)
-            .await?
+        let series = self.series(session, id, scope).await?;
+        let series_id = series
+            .first()
+            .map(|training| training.id)
             .ok_or(LedgerError::TrainingNotFound(id))?;
+        for mut training in series {
+            if training.is_processed {
+                return Err(LedgerError::TrainingIsProcessed(training.id()));
+            }
+            self.calendar
+                .delete_training(session, training.id())
+                .await?;
 
-        if training.is_processed {
-            return Err(LedgerError::TrainingIsProcessed(training.id()));
-        }
+            let new_slot = new_slot.with_day(training.day_id());
+            training.set_slot(new_slot);
 
-        training.set_slot(new_slot);
-        self.calendar.delete_training(session, id).await?;
-        let collision = self.check_time_slot(session, new_slot, true).await?;
-        if let Some(collision) = collision {
-            return Err(LedgerError::TimeSlotCollision(collision));
+            let collision = self.check_time_slot(session, new_slot, true).await?;
+            if let Some(collision) = collision {
+                return Err(LedgerError::TimeSlotCollision(collision));
+            }
+            self.check_instructor(session, training.instructor, new_slot, true, None)
+                .await?;
+            self.calendar.add_training(session, &training).await?;
         }
-        self.calendar.add_training(session, &training).await?;
 
-        let day_id = DayId::from(training.get_slot().start_at());
-        if all {
-            let mut cursor = self.calendar.week_days_after(session, day_id).await?;
-            while let Some(day) = cursor.next(session).await {
-                let mut day = day?;
-                let training = day.training.iter_mut().find(|slot| slot.id == training.id);
-                if let Some(training) = training {
-                    if training.is_processed {
-                        return Err(LedgerError::TrainingIsProcessed(training.id()));
-                    }
-                    self.calendar
-                        .delete_training(session, training.id())
-                        .await?;
-
-                    let new_slot = new_slot.with_day(training.day_id());
-                    training.set_slot(new_slot);
-
-                    let collision = self.check_time_slot(session, new_slot, true).await?;
-                    if let Some(collision) = dbg!(collision) {
-                        return Err(LedgerError::TimeSlotCollision(collision));
-                    }
-                    self.calendar.add_training(session, training).await?;
-                }
-            }
+        if scope.is_series() {
+            let start_at = new_slot.start_at();
+            self.update_template_training(session, series_id, |entry| {
+                entry.hour = start_at.hour();
+                entry.minute = start_at.minute();
+                entry.room = new_slot.room();
+            })
+            .await?;
         }
 
         Ok(())
@@ -158,29 +282,86 @@ impl Calendar {
         session: &mut Session,
         id: TrainingId,
         new_couch: ObjectId,
-        all: bool,
-    ) -> Result<(), Error> {
-        if let Some(training) = self.get_training_by_id(session, id).await? {
-            self.calendar.change_couch(session, id, new_couch).await?;
+        scope: SeriesScope,
+    ) -> Result<(), LedgerError> {
+        let series = self.series(session, id, scope).await?;
+        let series_id = series
+            .first()
+            .map(|training| training.id)
+            .ok_or(LedgerError::TrainingNotFound(id))?;
+        for training in series {
+            self.check_instructor(
+                session,
+                new_couch,
+                training.get_slot(),
+                true,
+                Some(training.id),
+            )
+            .await?;
+            self.calendar
+                .change_couch(session, training.id(), new_couch)
+                .await?;
+        }
 
-            let day_id = DayId::from(training.get_slot().start_at());
-            if all {
-                let mut cursor = self.calendar.week_days_after(session, day_id).await?;
-                while let Some(day) = cursor.next(session).await {
-                    let day = day?;
-                    let training = day.training.iter().find(|slot| slot.id == training.id);
-                    if let Some(training) = training {
-                        self.calendar
-                            .change_couch(session, training.id(), new_couch)
-                            .await?;
-                    }
+        if scope.is_series() {
+            self.update_template_training(session, series_id, |entry| {
+                entry.instructor = new_couch;
+            })
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Instances of the recurring training the edit with `scope` applies to,
+    /// ordered by start. Finished instances other than the selected one are skipped.
+    pub async fn series(
+        &self,
+        session: &mut Session,
+        id: TrainingId,
+        scope: SeriesScope,
+    ) -> Result<Vec<Training>, LedgerError> {
+        let selected = self
+            .get_training_by_id(session, id)
+            .await?
+            .ok_or(LedgerError::TrainingNotFound(id))?;
+        let day_id = selected.day_id();
+        let series_id = selected.id;
+        let mut series = vec![];
+
+        if scope == SeriesScope::All {
+            let today = DayId::from(Local::now());
+            let mut cursor = self
+                .calendar
+                .find_range(session, Some(today.local()), Some(day_id.local()))
+                .await?;
+            while let Some(day) = cursor.next(session).await {
+                let day = day?;
+                if day.day_id().week_day() != day_id.week_day() {
+                    continue;
                 }
+                series.extend(
+                    day.training
+                        .into_iter()
+                        .filter(|t| t.id == series_id && !t.is_processed),
+                );
             }
-        } else {
-            return Err(eyre::eyre!("Training not found:{:?}", id));
         }
 
-        Ok(())
+        series.push(selected);
+
+        if scope.is_series() {
+            let mut cursor = self.calendar.week_days_after(session, day_id).await?;
+            while let Some(day) = cursor.next(session).await {
+                series.extend(
+                    day?.training
+                        .into_iter()
+                        .filter(|t| t.id == series_id && !t.is_processed),
+                );
+            }
+        }
+        series.sort_by_key(|training| training.start_at_utc());
+        Ok(series)
     }
 
     #[tx]
@@ -212,6 +393,7 @@ impl Calendar {
                             .await?;
                     }
                 }
+                self.templates.remove_training(session, training.id).await?;
             }
         } else {
             return Err(LedgerError::TrainingNotFound(id));
@@ -230,6 +412,7 @@ impl Calendar {
         price: Decimal,
         renter: String,
     ) -> Result<(), LedgerError> {
+        self.rooms.get_active_room(session, room).await?;
         let slot = Slot::new(start_at.with_timezone(&Utc), duration_min, room);
         let collision = self.check_time_slot(session, slot, true).await?;
         if let Some(collision) = collision {
@@ -252,9 +435,8 @@ impl Calendar {
         session: &mut Session,
         client: ObjectId,
         instructor: ObjectId,
-        start_at: DateTime<Local>,
-        duration_min: u32,
-        room: ObjectId,
+        slot: Slot,
+        series: Option<ObjectId>,
     ) -> Result<TrainingId, LedgerError> {
         let instructor = self
             .users
@@ -269,12 +451,14 @@ impl Calendar {
             .get(session, client)
             .await?
             .ok_or(LedgerError::ClientNotFound(client))?;
+        self.rooms.get_active_room(session, slot.room()).await?;
 
-        let slot = Slot::new(start_at.with_timezone(&Utc), duration_min, room);
         let collision = self.check_time_slot(session, slot, true).await?;
         if let Some(collision) = collision {
             return Err(LedgerError::TimeSlotCollision(collision));
         }
+        self.check_instructor(session, instructor.id, slot, true, None)
+            .await?;
 
         let name = format!(
             "Инди:{}/{}",
@@ -284,14 +468,16 @@ impl Calendar {
             .employee
             .map(|e| e.description.clone())
             .unwrap_or_default();
-        let training = Training::new_personal(
-            start_at,
-            room,
+        let mut training = Training::new_personal(
+            slot.start_at(),
+            slot.room(),
             instructor.id,
-            duration_min,
+            slot.duration_min(),
             name,
             description,
         );
+        training.windows = self.settings.get(session).await?.personal_windows;
+        training.personal_series = series;
 
         self.calendar.add_training(session, &training).await?;
         Ok(training.id())
@@ -321,22 +507,39 @@ impl Calendar {
         if !instructor.is_couch() {
             return Err(LedgerError::InstructorHasNoRights(instructor.id));
         }
+        let room = self.rooms.get_active_room(session, room).await?;
 
         let day_id = DayId::from(start_at);
-        let slot = Slot::new(start_at.with_timezone(&Utc), program.duration_min, room);
+        let slot = Slot::new(start_at.with_timezone(&Utc), program.duration_min, room.id);
         let collision = self.check_time_slot(session, slot, is_one_time).await?;
         if let Some(collision) = collision {
             return Err(LedgerError::TimeSlotCollision(collision));
         }
+        self.check_instructor(session, instructor.id, slot, is_one_time, None)
+            .await?;
 
-        let mut training = Training::new_group(program, start_at, instructor.id, is_one_time, room);
-        if !training.status(Local::now()).can_sign_in() {
+        let mut training =
+            Training::new_group(program, start_at, instructor.id, is_one_time, room.id);
+        training.capacity = room.limit_capacity(training.capacity);
+        if !matches!(
+            training.status(Local::now()),
+            TrainingStatus::NotOpenToSignup | TrainingStatus::OpenToSignup { .. }
+        ) {
             return Err(LedgerError::TooCloseToStart { start_at });
         }
 
         self.calendar.add_training(session, &training).await?;
 
         if !is_one_time {
+            let entry = TemplateTraining::from_training(&training);
+            for template in self.templates.get_all(session).await? {
+                if template.effective_to.is_none_or(|to| to > day_id.id()) {
+                    self.templates
+                        .add_training(session, template.id, &entry)
+                        .await?;
+                }
+            }
+
             let mut cursor = self.calendar.week_days_after(session, day_id).await?;
             while let Some(day) = cursor.next(session).await {
                 let day = day?;
@@ -377,9 +580,316 @@ impl Calendar {
 
         Ok(None)
     }
+
+    /// An instructor can't lead two trainings at once, even in different rooms,
+    /// and can't be scheduled while absent. `skip` excludes the series being edited.
+    pub async fn check_instructor(
+        &self,
+        session: &mut Session,
+        instructor: ObjectId,
+        slot: Slot,
+        is_one_time: bool,
+        skip: Option<ObjectId>,
+    ) -> Result<(), LedgerError> {
+        let busy = |day: Day, slot: &Slot| {
+            day.training.into_iter().find(|training| {
+                training.instructor == instructor
+                    && !training.is_canceled
+                    && Some(training.id) != skip
+                    && training.get_slot().overlaps(slot)
+            })
+        };
+
+        let day_id = slot.day_id();
+        if let Some(training) = busy(self.get_day(session, day_id).await?, &slot) {
+            return Err(LedgerError::InstructorIsBusy(training));
+        }
+        if !is_one_time {
+            let mut cursor = self.calendar.week_days_after(session, day_id).await?;
+            while let Some(day) = cursor.next(session).await {
+                let day = day?;
+                let slot = slot.with_day(day.day_id());
+                if let Some(training) = busy(day, &slot) {
+                    return Err(LedgerError::InstructorIsBusy(training));
+                }
+            }
+        }
+
+        let to = if is_one_time {
+            slot.end_at()
+        } else {
+            slot.start_at() + chrono::Duration::days(365 * 2)
+        };
+        let absences = self
+            .absences
+            .find_overlapping(session, instructor, slot.start_at(), to)
+            .await?;
+        for absence in absences {
+            let mut slot = slot;
+            while slot.start_at() < absence.to() {
+                if absence.covers(&slot) {
+                    return Err(LedgerError::InstructorIsUnavailable(absence));
+                }
+                if is_one_time {
+                    break;
+                }
+                slot = slot.with_day(DayId::from(slot.start_at() + chrono::Duration::days(7)));
+            }
+        }
+        Ok(())
+    }
+
+    /// Upcoming trainings in `[from, to)` that a studio closure would cancel.
+    /// Days that are not materialized yet are created from the template first.
+    pub async fn find_to_close(
+        &self,
+        session: &mut Session,
+        from: DateTime<Local>,
+        to: DateTime<Local>,
+        room: Option<ObjectId>,
+    ) -> Result<Vec<Training>> {
+        let now = Local::now();
+        let mut trainings = vec![];
+        let mut day_id = DayId::from(from);
+        while day_id.local() < to {
+            let day = self.get_day(session, day_id).await?;
+            day_id = day_id.next();
+            for training in day.training {
+                let start_at = training.get_slot().start_at();
+                if start_at >= from
+                    && start_at < to
+                    && start_at > now
+                    && !training.is_canceled
+                    && !training.is_processed
+                    && room.is_none_or(|room| training.room() == room)
+                {
+                    trainings.push(training);
+                }
+            }
+        }
+        trainings.sort_by_key(|training| training.start_at_utc());
+        Ok(trainings)
+    }
+
+    /// Upcoming trainings of the user as a client and as an instructor in iCalendar format.
+    pub async fn ical_feed(&self, session: &mut Session, user: &User) -> Result<String> {
+        let mut trainings = self
+            .calendar
+            .find_trainings(session, Filter::Client(user.id), ICAL_LIMIT, 0)
+            .await?;
+        let mut filters = vec![Filter::Canceled(user.id)];
+        if user.is_couch() {
+            filters.push(Filter::Instructor(user.id));
+        }
+        for filter in filters {
+            let found = self
+                .calendar
+                .find_trainings(session, filter, ICAL_LIMIT, 0)
+                .await?;
+            for training in found {
+                if !trainings.iter().any(|t| t.id() == training.id()) {
+                    trainings.push(training);
+                }
+            }
+        }
+        trainings.sort_by_key(|training| training.start_at_utc());
+
+        let rooms = self.rooms.get_all(session).await?;
+        let name = format!("Тренировки: {}", user.name.first_name);
+        Ok(ical::render(
+            &name,
+            &trainings,
+            |id| rooms.iter().find(|r| r.id == id).map(|r| r.name.clone()),
+            Utc::now(),
+        ))
+    }
+
+    pub async fn absences(
+        &self,
+        session: &mut Session,
+        instructor: ObjectId,
+    ) -> Result<Vec<Absence>> {
+        self.absences.find_by_instructor(session, instructor).await
+    }
+
+    /// Registers the absence and returns the instructor's upcoming trainings
+    /// that fall into it and have to be reassigned.
+    #[tx]
+    pub async fn add_absence(
+        &self,
+        session: &mut Session,
+        instructor: ObjectId,
+        from: DateTime<Local>,
+        to: DateTime<Local>,
+        reason: String,
+    ) -> Result<Vec<Training>, LedgerError> {
+        if from >= to {
+            return Err(eyre::eyre!("Invalid absence period").into());
+        }
+        let absence = Absence::new(instructor, from, to, reason);
+        self.absences.insert(session, &absence).await?;
+        Ok(self.affected_trainings(session, &absence).await?)
+    }
+
+    #[tx]
+    pub async fn remove_absence(&self, session: &mut Session, id: ObjectId) -> Result<()> {
+        self.absences.delete(session, id).await
+    }
+
+    pub async fn affected_trainings(
+        &self,
+        session: &mut Session,
+        absence: &Absence,
+    ) -> Result<Vec<Training>> {
+        let from = absence.from().max(Local::now());
+        let mut trainings = vec![];
+        let mut cursor = self
+            .calendar
+            .find_range(session, Some(DayId::from(from).local()), Some(absence.to()))
+            .await?;
+        while let Some(day) = cursor.next(session).await {
+            for training in day?.training {
+                if training.instructor == absence.instructor
+                    && !training.is_canceled
+                    && training.get_slot().start_at() >= from
+                    && absence.covers(&training.get_slot())
+                {
+                    trainings.push(training);
+                }
+            }
+        }
+        trainings.sort_by_key(|training| training.get_slot().start_at());
+        Ok(trainings)
+    }
+
+    pub async fn availability_windows(
+        &self,
+        session: &mut Session,
+        instructor: ObjectId,
+    ) -> Result<Vec<AvailabilityWindow>> {
+        self.absences.find_windows(session, instructor).await
+    }
+
+    #[tx]
+    pub async fn add_availability_window(
+        &self,
+        session: &mut Session,
+        instructor: ObjectId,
+        weekday: Weekday,
+        from_min: u32,
+        to_min: u32,
+        room: ObjectId,
+    ) -> Result<(), LedgerError> {
+        if from_min >= to_min || to_min > 24 * 60 {
+            return Err(eyre::eyre!("Invalid availability window").into());
+        }
+        self.rooms.get_active_room(session, room).await?;
+        let windows = self.absences.find_windows(session, instructor).await?;
+        if windows
+            .iter()
+            .any(|w| w.weekday == weekday && w.from_min < to_min && from_min < w.to_min)
+        {
+            return Err(eyre::eyre!("Availability window overlaps another one").into());
+        }
+        let window = AvailabilityWindow::new(instructor, weekday, from_min, to_min, room);
+        self.absences.insert_window(session, &window).await?;
+        Ok(())
+    }
+
+    #[tx]
+    pub async fn remove_availability_window(
+        &self,
+        session: &mut Session,
+        id: ObjectId,
+    ) -> Result<()> {
+        self.absences.delete_window(session, id).await
+    }
+
+    /// Window of the instructor that fits a personal training starting at `start_at`.
+    pub async fn find_availability_window(
+        &self,
+        session: &mut Session,
+        instructor: ObjectId,
+        start_at: DateTime<Local>,
+    ) -> Result<Option<AvailabilityWindow>> {
+        let windows = self.absences.find_windows(session, instructor).await?;
+        Ok(windows
+            .into_iter()
+            .find(|w| w.covers(start_at, PERSONAL_SLOT_MIN)))
+    }
+
+    /// Slots of the instructor's availability windows for the next `days` days
+    /// that are open to sign up, the room is free and the instructor is neither busy nor absent.
+    pub async fn free_slots(
+        &self,
+        session: &mut Session,
+        instructor: ObjectId,
+        days: u32,
+    ) -> Result<Vec<Slot>, LedgerError> {
+        let windows = self.absences.find_windows(session, instructor).await?;
+        if windows.is_empty() {
+            return Ok(vec![]);
+        }
+        let booking = self.settings.get(session).await?.personal_windows;
+        let now = Local::now();
+
+        let mut slots = vec![];
+        let mut day = DayId::from(now);
+        for _ in 0..days {
+            for window in windows.iter().filter(|w| w.weekday == day.week_day()) {
+                for slot in window.slots(day, PERSONAL_SLOT_MIN) {
+                    if !booking.accepts(slot.start_at(), now) {
+                        continue;
+                    }
+                    if self.check_time_slot(session, slot, true).await?.is_some() {
+                        continue;
+                    }
+                    match self
+                        .check_instructor(session, instructor, slot, true, None)
+                        .await
+                    {
+                        Ok(()) => slots.push(slot),
+                        Err(LedgerError::InstructorIsBusy(_))
+                        | Err(LedgerError::InstructorIsUnavailable(_)) => {}
+                        Err(err) => return Err(err),
+                    }
+                }
+            }
+            day = day.next();
+        }
+        slots.sort_by_key(|slot| slot.start_at());
+        Ok(slots)
+    }
 }
 
 impl Calendar {
+    /// Trainings can't take more clients than their room holds.
+    pub(crate) async fn fit_to_room(&self, session: &mut Session, training: &mut Training) -> Result<()> {
+        if let Some(room) = self.rooms.get_by_id(session, training.room()).await? {
+            training.capacity = room.limit_capacity(training.capacity);
+        }
+        Ok(())
+    }
+
+    async fn update_template_training(
+        &self,
+        session: &mut Session,
+        id: ObjectId,
+        update: impl FnOnce(&mut TemplateTraining),
+    ) -> Result<()> {
+        let entry = self
+            .templates
+            .get_all(session)
+            .await?
+            .into_iter()
+            .find_map(|t| t.trainings.into_iter().find(|t| t.id == id));
+        if let Some(mut entry) = entry {
+            update(&mut entry);
+            self.templates.update_training(session, &entry).await?;
+        }
+        Ok(())
+    }
+
     pub(crate) async fn edit_duration(
         &self,
         session: &mut Session,
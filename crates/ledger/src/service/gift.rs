@@ -0,0 +1,22 @@
+use std::{ops::Deref, sync::Arc};
+
+use storage::gift::GiftStore;
+
+#[derive(Clone)]
+pub struct Gifts {
+    store: Arc<GiftStore>,
+}
+
+impl Gifts {
+    pub(crate) fn new(store: Arc<GiftStore>) -> Self {
+        Gifts { store }
+    }
+}
+
+impl Deref for Gifts {
+    type Target = GiftStore;
+
+    fn deref(&self) -> &Self::Target {
+        &self.store
+    }
+}
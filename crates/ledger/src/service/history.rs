@@ -4,11 +4,12 @@ use chrono::{DateTime, Local, Utc};
 use eyre::Result;
 use model::{
     decimal::Decimal,
+    gift::GiftCertificate,
     history::{Action, HistoryRow},
-    rooms::Room,
+    installment::Debt,
     session::Session,
     subscription::{Subscription, UserSubscription},
-    training::Training,
+    training::{Attendance, Training},
     user::UserName,
 };
 use mongodb::bson::oid::ObjectId;
@@ -87,6 +88,154 @@ impl History {
         self.store.store(session, entry).await
     }
 
+    pub async fn freeze_subscription(
+        &self,
+        session: &mut Session,
+        user: ObjectId,
+        name: String,
+        days: u32,
+    ) -> Result<()> {
+        let entry = HistoryRow::with_sub_actors(
+            session.actor(),
+            vec![user],
+            Action::FreezeSubscription { name, days },
+        );
+        self.store.store(session, entry).await
+    }
+
+    pub async fn unfreeze_subscription(
+        &self,
+        session: &mut Session,
+        user: ObjectId,
+        name: String,
+    ) -> Result<()> {
+        let entry = HistoryRow::with_sub_actors(
+            session.actor(),
+            vec![user],
+            Action::UnfreezeSubscription { name },
+        );
+        self.store.store(session, entry).await
+    }
+
+    pub async fn transfer_subscription(
+        &self,
+        session: &mut Session,
+        from: ObjectId,
+        to: ObjectId,
+        name: String,
+        items: u32,
+    ) -> Result<()> {
+        let entry = HistoryRow::with_sub_actors(
+            session.actor(),
+            vec![from, to],
+            Action::TransferSubscriptionOut {
+                name: name.clone(),
+                items,
+            },
+        );
+        self.store.store(session, entry).await?;
+        let entry = HistoryRow::with_sub_actors(
+            session.actor(),
+            vec![to, from],
+            Action::TransferSubscriptionIn { name, items },
+        );
+        self.store.store(session, entry).await
+    }
+
+    pub async fn refund_subscription(
+        &self,
+        session: &mut Session,
+        user: ObjectId,
+        subscription: UserSubscription,
+        amount: Decimal,
+        fee: Decimal,
+    ) -> Result<()> {
+        let entry = HistoryRow::with_sub_actors(
+            session.actor(),
+            vec![user],
+            Action::RefundSubscription {
+                subscription,
+                amount,
+                fee,
+            },
+        );
+        self.store.store(session, entry).await
+    }
+
+    pub async fn upgrade_subscription(
+        &self,
+        session: &mut Session,
+        user: ObjectId,
+        from: UserSubscription,
+        subscription: Subscription,
+        credit: Decimal,
+        amount: Decimal,
+    ) -> Result<()> {
+        let entry = HistoryRow::with_sub_actors(
+            session.actor(),
+            vec![user],
+            Action::UpgradeSubscription {
+                from,
+                subscription,
+                credit,
+                amount,
+            },
+        );
+        self.store.store(session, entry).await
+    }
+
+    pub async fn pay_installment(
+        &self,
+        session: &mut Session,
+        user: ObjectId,
+        debt: &Debt,
+        amount: Decimal,
+    ) -> Result<()> {
+        let entry = HistoryRow::with_sub_actors(
+            session.actor(),
+            vec![user],
+            Action::PayInstallment {
+                name: debt.name.clone(),
+                amount,
+                remaining: debt.remaining(),
+            },
+        );
+        self.store.store(session, entry).await
+    }
+
+    pub async fn sell_gift_certificate(
+        &self,
+        session: &mut Session,
+        buyer: ObjectId,
+        certificate: &GiftCertificate,
+    ) -> Result<()> {
+        let entry = HistoryRow::with_sub_actors(
+            session.actor(),
+            vec![buyer],
+            Action::SellGiftCertificate {
+                code: certificate.code.clone(),
+                value: certificate.value.clone(),
+                price: certificate.price,
+            },
+        );
+        self.store.store(session, entry).await
+    }
+
+    pub async fn redeem_gift_certificate(
+        &self,
+        session: &mut Session,
+        user: ObjectId,
+        code: String,
+        subscription: Subscription,
+    ) -> Result<()> {
+        let entry = HistoryRow::with_sub_actors(
+            session.actor(),
+            vec![user],
+            Action::RedeemGiftCertificate { code, subscription },
+        );
+        self.store.store(session, entry).await
+    }
+
     pub async fn change_balance(
         &self,
         session: &mut Session,
@@ -153,7 +302,7 @@ impl History {
         user_id: ObjectId,
         start_at: DateTime<Local>,
         name: String,
-        room_id: Room,
+        room_id: ObjectId,
     ) -> Result<()> {
         self.store
             .store(
@@ -177,7 +326,7 @@ impl History {
         user_id: ObjectId,
         start_at: DateTime<Local>,
         name: String,
-        room_id: Room,
+        room_id: ObjectId,
     ) -> Result<()> {
         self.store
             .store(
@@ -195,6 +344,33 @@ impl History {
             .await
     }
 
+    pub async fn late_sign_out(
+        &self,
+        session: &mut Session,
+        user_id: ObjectId,
+        training: &Training,
+        penalty: Decimal,
+        burned: bool,
+    ) -> Result<()> {
+        let slot = training.id();
+        self.store
+            .store(
+                session,
+                HistoryRow::with_sub_actors(
+                    session.actor(),
+                    vec![user_id],
+                    Action::LateSignOut {
+                        start_at: slot.start_at(),
+                        name: training.name.clone(),
+                        room_id: slot.room,
+                        penalty,
+                        burned,
+                    },
+                ),
+            )
+            .await
+    }
+
     pub async fn block_user(
         &self,
         session: &mut Session,
@@ -215,6 +391,14 @@ impl History {
 
     pub async fn process_finished(&self, session: &mut Session, training: &Training) -> Result<()> {
         let sub_actors = training.clients.to_vec();
+        let with_status = |status| {
+            training
+                .clients
+                .iter()
+                .filter(|client| training.attendance(**client) == Some(status))
+                .copied()
+                .collect()
+        };
 
         let slot = training.id();
         let entry = HistoryRow::with_sub_actors(
@@ -223,7 +407,9 @@ impl History {
             Action::FinalizedTraining {
                 name: training.name.clone(),
                 start_at: slot.start_at,
-                room_id: Room::from(slot.room),
+                room_id: slot.room,
+                no_show: with_status(Attendance::NoShow),
+                excused: with_status(Attendance::Excused),
             },
         );
         self.store.store(session, entry).await
@@ -239,7 +425,7 @@ impl History {
             Action::FinalizedCanceledTraining {
                 name: training.name.clone(),
                 start_at: slot.start_at,
-                room_id: Room::from(slot.room),
+                room_id: slot.room,
             },
         );
         self.store.store(session, entry).await
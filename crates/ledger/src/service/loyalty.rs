@@ -0,0 +1,22 @@
+use std::{ops::Deref, sync::Arc};
+
+use storage::loyalty::LoyaltyStore;
+
+#[derive(Clone)]
+pub struct Loyalty {
+    store: Arc<LoyaltyStore>,
+}
+
+impl Loyalty {
+    pub(crate) fn new(store: Arc<LoyaltyStore>) -> Self {
+        Loyalty { store }
+    }
+}
+
+impl Deref for Loyalty {
+    type Target = LoyaltyStore;
+
+    fn deref(&self) -> &Self::Target {
+        &self.store
+    }
+}
@@ -1,8 +1,15 @@
 pub mod backup;
 pub mod calendar;
+pub mod gift;
 pub mod history;
+pub mod loyalty;
+pub mod personal_series;
 pub mod programs;
+pub mod promo;
 pub mod rewards;
+pub mod rooms;
+pub mod schedule;
+pub mod settings;
 pub mod statistics;
 pub mod subscriptions;
 pub mod treasury;
@@ -1,5 +1,4 @@
-use eyre::Result;
-use std::sync::Arc;
+use std::{ops::Deref, sync::Arc};
 use storage::notification::NotificationStore;
 
 pub struct NotificationService {
@@ -7,7 +6,15 @@ pub struct NotificationService {
 }
 
 impl NotificationService {
-    pub async fn new(store: Arc<NotificationStore>) -> Result<Self> {
-        Ok(NotificationService { store })
+    pub(crate) fn new(store: Arc<NotificationStore>) -> Self {
+        NotificationService { store }
+    }
+}
+
+impl Deref for NotificationService {
+    type Target = NotificationStore;
+
+    fn deref(&self) -> &Self::Target {
+        &self.store
     }
 }
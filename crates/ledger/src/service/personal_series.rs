@@ -0,0 +1,21 @@
+use std::{ops::Deref, sync::Arc};
+use storage::personal_series::PersonalSeriesStore;
+
+#[derive(Clone)]
+pub struct PersonalSeriesList {
+    store: Arc<PersonalSeriesStore>,
+}
+
+impl PersonalSeriesList {
+    pub(crate) fn new(store: Arc<PersonalSeriesStore>) -> Self {
+        PersonalSeriesList { store }
+    }
+}
+
+impl Deref for PersonalSeriesList {
+    type Target = PersonalSeriesStore;
+
+    fn deref(&self) -> &Self::Target {
+        &self.store
+    }
+}
@@ -37,6 +37,7 @@ impl Programs {
             version: 0,
             tp,
             visible: true,
+            windows: Default::default(),
         };
         let training = self.get_by_name(session, &proto.name).await?;
         if training.is_some() {
@@ -0,0 +1,75 @@
+use chrono::Utc;
+use model::{errors::LedgerError, promo::PromoCode, session::Session, subscription::Subscription};
+use mongodb::bson::oid::ObjectId;
+use std::{ops::Deref, sync::Arc};
+use storage::promo::PromoStore;
+use tx_macro::tx;
+
+#[derive(Clone)]
+pub struct Promo {
+    store: Arc<PromoStore>,
+}
+
+impl Promo {
+    pub(crate) fn new(store: Arc<PromoStore>) -> Self {
+        Promo { store }
+    }
+
+    #[tx]
+    pub async fn create(&self, session: &mut Session, promo: PromoCode) -> Result<(), LedgerError> {
+        if self
+            .store
+            .get_by_code(session, &promo.code)
+            .await?
+            .is_some()
+        {
+            return Err(LedgerError::PromoCodeAlreadyExists { code: promo.code });
+        }
+        self.store.insert(session, &promo).await?;
+        Ok(())
+    }
+
+    /// Finds the code and checks it can be used by the client for the subscription.
+    pub async fn check(
+        &self,
+        session: &mut Session,
+        code: &str,
+        subscription: &Subscription,
+        user: ObjectId,
+    ) -> Result<PromoCode, LedgerError> {
+        let promo = self
+            .store
+            .get_by_code(session, code)
+            .await?
+            .ok_or_else(|| LedgerError::PromoCodeNotFound {
+                code: PromoCode::normalize(code),
+            })?;
+        if !promo.is_valid_at(Utc::now()) {
+            return Err(LedgerError::PromoCodeExpired { code: promo.code });
+        }
+        if !promo.applies_to(subscription.id) {
+            return Err(LedgerError::PromoCodeNotApplicable { code: promo.code });
+        }
+        if promo.is_exhausted() {
+            return Err(LedgerError::PromoCodeExhausted { code: promo.code });
+        }
+        if let Some(max) = promo.max_uses_per_client {
+            let used = self
+                .store
+                .client_redemptions(session, promo.id, user)
+                .await?;
+            if used >= max as u64 {
+                return Err(LedgerError::PromoCodeClientLimit { code: promo.code });
+            }
+        }
+        Ok(promo)
+    }
+}
+
+impl Deref for Promo {
+    type Target = PromoStore;
+
+    fn deref(&self) -> &Self::Target {
+        &self.store
+    }
+}
@@ -144,6 +144,20 @@ impl Requests {
         Ok(())
     }
 
+    /// Marks the lead's trial lesson as converted once they buy a regular subscription.
+    pub async fn mark_trial_converted(&self, session: &mut Session, phone: &str) -> Result<()> {
+        let phone = sanitize_phone(phone);
+        if let Some(mut request) = self.requests.get_by_phone(session, &phone).await? {
+            if let Some(trial) = request.trial.as_mut() {
+                if trial.converted_at.is_none() {
+                    trial.converted_at = Some(Utc::now());
+                    self.requests.update(session, &request).await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
     pub async fn come_from(&self, session: &mut Session, phone: &str) -> Result<Source, Error> {
         let phone = model::user::sanitize_phone(phone);
         self.requests
@@ -0,0 +1,93 @@
+use std::{ops::Deref, sync::Arc};
+
+use eyre::{eyre, Result};
+use model::{errors::LedgerError, rooms::Room, session::Session};
+use mongodb::bson::oid::ObjectId;
+use storage::rooms::RoomStore;
+use tx_macro::tx;
+
+#[derive(Clone)]
+pub struct Rooms {
+    store: Arc<RoomStore>,
+}
+
+impl Rooms {
+    pub(crate) fn new(store: Arc<RoomStore>) -> Self {
+        Rooms { store }
+    }
+
+    #[tx]
+    pub async fn create(
+        &self,
+        session: &mut Session,
+        name: String,
+        emoji: String,
+        capacity: u32,
+    ) -> Result<Room> {
+        let rooms = self.store.get_all(session).await?;
+        if rooms.iter().any(|r| r.name == name) {
+            return Err(eyre!("Room with this name already exists"));
+        }
+        let ordering = rooms.iter().map(|r| r.ordering + 1).max().unwrap_or_default();
+        let room = Room::new(name, emoji, capacity, ordering);
+        self.store.insert(session, &room).await?;
+        Ok(room)
+    }
+
+    /// Swaps the room with its neighbour in the display order.
+    #[tx]
+    pub async fn move_room(&self, session: &mut Session, id: ObjectId, up: bool) -> Result<()> {
+        let rooms = self.store.get_all(session).await?;
+        let idx = rooms
+            .iter()
+            .position(|r| r.id == id)
+            .ok_or_else(|| eyre!("Room not found:{}", id))?;
+        let other = if up {
+            idx.checked_sub(1)
+        } else {
+            Some(idx + 1).filter(|i| *i < rooms.len())
+        };
+        let Some(other) = other else {
+            return Ok(());
+        };
+
+        let (room, other) = (&rooms[idx], &rooms[other]);
+        let (room_ordering, other_ordering) = if room.ordering == other.ordering {
+            (other.ordering + u32::from(!up), room.ordering + u32::from(up))
+        } else {
+            (other.ordering, room.ordering)
+        };
+        self.store
+            .set_ordering(session, room.id, room_ordering)
+            .await?;
+        self.store
+            .set_ordering(session, other.id, other_ordering)
+            .await?;
+        Ok(())
+    }
+
+    /// Returns the room if new trainings can be scheduled in it.
+    pub async fn get_active_room(
+        &self,
+        session: &mut Session,
+        id: ObjectId,
+    ) -> Result<Room, LedgerError> {
+        let room = self
+            .store
+            .get_by_id(session, id)
+            .await?
+            .ok_or(LedgerError::RoomNotFound(id))?;
+        if !room.is_active {
+            return Err(LedgerError::RoomIsNotActive(id));
+        }
+        Ok(room)
+    }
+}
+
+impl Deref for Rooms {
+    type Target = RoomStore;
+
+    fn deref(&self) -> &Self::Target {
+        &self.store
+    }
+}
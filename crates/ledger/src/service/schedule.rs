@@ -0,0 +1,202 @@
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    ops::Deref,
+    sync::Arc,
+};
+
+use chrono::Weekday;
+use eyre::Result;
+use model::{
+    errors::LedgerError,
+    ids::{DayId, WeekId},
+    program::Program,
+    schedule::{DayDiff, ScheduleTemplate, TemplateTraining},
+    session::Session,
+};
+use mongodb::bson::oid::ObjectId;
+use storage::schedule::ScheduleTemplateStore;
+use tx_macro::tx;
+
+use super::{calendar::Calendar, programs::Programs};
+
+#[derive(Clone)]
+pub struct ScheduleTemplates {
+    store: Arc<ScheduleTemplateStore>,
+    calendar: Calendar,
+    programs: Programs,
+}
+
+impl ScheduleTemplates {
+    pub(crate) fn new(
+        store: Arc<ScheduleTemplateStore>,
+        calendar: Calendar,
+        programs: Programs,
+    ) -> Self {
+        ScheduleTemplates {
+            store,
+            calendar,
+            programs,
+        }
+    }
+
+    /// Snapshots the recurring group trainings of the week into a new template.
+    /// Templates that are still open at `effective_from` are closed on that day.
+    #[tx]
+    pub async fn create_from_week(
+        &self,
+        session: &mut Session,
+        name: String,
+        week: WeekId,
+        effective_from: DayId,
+    ) -> Result<ScheduleTemplate> {
+        let mut trainings = vec![];
+        let mut weekday = Weekday::Mon;
+        for _ in 0..7 {
+            let day = self.calendar.get_day(session, week.day(weekday)).await?;
+            weekday = weekday.succ();
+            trainings.extend(
+                day.training
+                    .iter()
+                    .filter(|t| t.is_group() && !t.is_one_time && !t.is_canceled)
+                    .map(TemplateTraining::from_training),
+            );
+        }
+
+        for template in self.store.get_all(session).await? {
+            if template.effective_from >= effective_from.id() {
+                return Err(eyre::eyre!(
+                    "Template {} already starts after {}",
+                    template.name,
+                    effective_from.local().format("%d.%m.%Y")
+                ));
+            }
+            if template.effective_to.is_none_or(|to| to > effective_from.id()) {
+                self.store
+                    .set_effective_to(session, template.id, Some(effective_from.id()))
+                    .await?;
+            }
+        }
+
+        let template = ScheduleTemplate::new(name, effective_from, trainings);
+        self.store.insert(session, &template).await?;
+        Ok(template)
+    }
+
+    /// Differences between the template and the days that are already materialized.
+    pub async fn preview(&self, session: &mut Session, id: ObjectId) -> Result<Vec<DayDiff>> {
+        let template = self
+            .store
+            .get_by_id(session, id)
+            .await?
+            .ok_or_else(|| eyre::eyre!("Template not found:{}", id))?;
+
+        let tomorrow = DayId::default().next();
+        let from = if template.effective_from > tomorrow.id() {
+            unsafe { DayId::from_utc(template.effective_from) }
+        } else {
+            tomorrow
+        };
+        let to = template
+            .effective_to
+            .map(|to| unsafe { DayId::from_utc(to) }.local());
+
+        let mut days = vec![];
+        let mut cursor = self
+            .calendar
+            .find_range(session, Some(from.local()), to)
+            .await?;
+        while let Some(day) = cursor.next(session).await {
+            days.push(day?);
+        }
+
+        let mut programs: HashMap<ObjectId, Program> = HashMap::new();
+        let mut diffs = vec![];
+        for day in days {
+            let day_id = day.day_id();
+            if !template.is_effective(day_id) {
+                continue;
+            }
+
+            let mut planned = vec![];
+            for entry in template.day_trainings(day_id) {
+                let program = match programs.entry(entry.proto_id) {
+                    Entry::Occupied(program) => program.get().clone(),
+                    Entry::Vacant(vacant) => {
+                        let program = self
+                            .programs
+                            .get_by_id(session, entry.proto_id)
+                            .await?
+                            .ok_or_else(|| LedgerError::ProgramNotFound(entry.proto_id))?;
+                        vacant.insert(program).clone()
+                    }
+                };
+                planned.push(entry.materialize(day_id, program));
+            }
+
+            let diff = DayDiff::new(&day, planned);
+            if !diff.is_empty() {
+                diffs.push(diff);
+            }
+        }
+        Ok(diffs)
+    }
+
+    /// Rebuilds the materialized future days after the template.
+    /// Trainings with signed up clients are left untouched.
+    #[tx]
+    pub async fn apply(
+        &self,
+        session: &mut Session,
+        id: ObjectId,
+    ) -> Result<Vec<DayDiff>, LedgerError> {
+        let diffs = self.preview(session, id).await?;
+        for diff in &diffs {
+            for training in diff.removed_ids() {
+                self.calendar
+                    .delete_training_txless(session, training, false)
+                    .await?;
+            }
+            for training in &diff.add {
+                let mut training = training.clone();
+                self.calendar.fit_to_room(session, &mut training).await?;
+                let collision = self
+                    .calendar
+                    .check_time_slot(session, training.get_slot(), true)
+                    .await?;
+                if let Some(collision) = collision {
+                    return Err(LedgerError::TimeSlotCollision(collision));
+                }
+                self.calendar.add_training(session, &training).await?;
+            }
+        }
+        Ok(diffs)
+    }
+
+    #[tx]
+    pub async fn delete(&self, session: &mut Session, id: ObjectId) -> Result<()> {
+        let template = self
+            .store
+            .get_by_id(session, id)
+            .await?
+            .ok_or_else(|| eyre::eyre!("Template not found:{}", id))?;
+        if template.effective_from <= DayId::default().id() {
+            return Err(eyre::eyre!("Template is already in use"));
+        }
+        for prev in self.store.get_all(session).await? {
+            if prev.effective_to == Some(template.effective_from) {
+                self.store
+                    .set_effective_to(session, prev.id, template.effective_to)
+                    .await?;
+            }
+        }
+        self.store.delete(session, id).await
+    }
+}
+
+impl Deref for ScheduleTemplates {
+    type Target = ScheduleTemplateStore;
+
+    fn deref(&self) -> &Self::Target {
+        &self.store
+    }
+}
@@ -0,0 +1,21 @@
+use std::{ops::Deref, sync::Arc};
+use storage::settings::SettingsStore;
+
+#[derive(Clone)]
+pub struct Settings {
+    store: Arc<SettingsStore>,
+}
+
+impl Settings {
+    pub(crate) fn new(store: Arc<SettingsStore>) -> Self {
+        Settings { store }
+    }
+}
+
+impl Deref for Settings {
+    type Target = SettingsStore;
+
+    fn deref(&self) -> &Self::Target {
+        &self.store
+    }
+}
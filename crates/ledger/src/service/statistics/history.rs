@@ -31,15 +31,23 @@ pub async fn load_requests_and_history(
             .entry(req.come_from)
             .or_insert_with(|| SourceStat {
                 buy_test: 0,
+                trial_converted: 0,
                 buy_subscription: 0,
                 requests_count: 0,
                 earned: 0,
+                refunded: 0,
                 spent: 0,
             });
         stat.requests_count += 1;
+        if let Some(trial) = &req.trial {
+            stat.buy_test += 1;
+            if trial.converted_at.is_some() {
+                stat.trial_converted += 1;
+            }
+        }
 
         if let Some(user) = users.find_by_phone(session, &req.phone).await? {
-            user_for_marketing.insert(user.id, (req.come_from, 0));
+            user_for_marketing.insert(user.id, (req.come_from, 0, req.trial.is_some()));
         }
     }
 
@@ -58,13 +66,20 @@ pub async fn load_requests_and_history(
                         .entry(user.0)
                         .or_insert_with(|| SourceStat {
                             buy_test: 0,
+                            trial_converted: 0,
                             buy_subscription: 0,
                             requests_count: 0,
                             earned: 0,
+                            refunded: 0,
                             spent: 0,
                         });
-                    if user.1 == 0 {
-                        stat.buy_test += 1;
+                    // Trials booked from the request are already counted above. Older leads
+                    // have no trial record, so their first purchase is taken as the trial.
+                    let (_, purchases, tracked) = *user;
+                    if subscription.trial || (!tracked && purchases == 0) {
+                        if !tracked {
+                            stat.buy_test += 1;
+                        }
                     } else {
                         stat.buy_subscription += 1;
                     }
@@ -93,6 +108,31 @@ pub async fn load_requests_and_history(
                         .int_part(),
                         burned_training: 0,
                         discount: (subscription.price * discount.unwrap_or_default()).int_part(),
+                        refunded: 0,
+                    });
+                }
+            }
+            Action::UpgradeSubscription {
+                subscription,
+                amount,
+                ..
+            } => {
+                let amount = amount.int_part();
+                if let Some(sub) = month
+                    .subscriptions
+                    .iter_mut()
+                    .find(|s| s.name == subscription.name)
+                {
+                    sub.count += 1;
+                    sub.earned += amount;
+                } else {
+                    month.subscriptions.push(SubscriptionStat {
+                        name: subscription.name,
+                        count: 1,
+                        earned: amount,
+                        burned_training: 0,
+                        discount: 0,
+                        refunded: 0,
                     });
                 }
             }
@@ -110,6 +150,40 @@ pub async fn load_requests_and_history(
                         earned: 0,
                         burned_training: subscription.balance as u64,
                         discount: 0,
+                        refunded: 0,
+                    });
+                }
+            }
+            Action::RefundSubscription {
+                subscription,
+                amount,
+                ..
+            } => {
+                let amount = amount.int_part();
+                if let Some(user) = row
+                    .sub_actors
+                    .first()
+                    .and_then(|id| user_for_marketing.get(id))
+                {
+                    if let Some(stat) = month.marketing.source.get_mut(&user.0) {
+                        stat.refunded += amount;
+                    }
+                }
+
+                if let Some(sub) = month
+                    .subscriptions
+                    .iter_mut()
+                    .find(|s| s.name == subscription.name)
+                {
+                    sub.refunded += amount;
+                } else {
+                    month.subscriptions.push(SubscriptionStat {
+                        name: subscription.name,
+                        count: 0,
+                        earned: 0,
+                        burned_training: 0,
+                        discount: 0,
+                        refunded: amount,
                     });
                 }
             }
@@ -124,11 +198,19 @@ pub async fn load_requests_and_history(
             | Action::Unfreeze {}
             | Action::SignUp { .. }
             | Action::SignOut { .. }
+            | Action::LateSignOut { .. }
             | Action::BlockUser { .. }
             | Action::ChangeBalance { .. }
             | Action::ChangeReservedBalance { .. }
             | Action::RemoveFamilyMember {}
-            | Action::AddFamilyMember {} => {
+            | Action::AddFamilyMember {}
+            | Action::FreezeSubscription { .. }
+            | Action::UnfreezeSubscription { .. }
+            | Action::TransferSubscriptionOut { .. }
+            | Action::TransferSubscriptionIn { .. }
+            | Action::SellGiftCertificate { .. }
+            | Action::RedeemGiftCertificate { .. }
+            | Action::PayInstallment { .. } => {
                 continue;
             }
             Action::ChangeSubscriptionDays { .. } => {
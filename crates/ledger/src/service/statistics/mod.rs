@@ -2,6 +2,7 @@ pub mod aggregation;
 pub mod calendar;
 pub mod history;
 pub mod prompt;
+pub mod promo;
 pub mod treasury;
 pub mod clients;
 
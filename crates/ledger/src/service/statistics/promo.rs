@@ -0,0 +1,39 @@
+use chrono::NaiveDate;
+use eyre::Error;
+use model::{
+    session::Session,
+    statistics::month::{MonthStatistics, PromoStat},
+};
+
+use crate::service::promo::Promo;
+
+use super::aggregation::month_range;
+
+pub async fn load_promo(
+    session: &mut Session,
+    month_id: NaiveDate,
+    promo: &Promo,
+    stat: &mut MonthStatistics,
+) -> Result<(), Error> {
+    let (start, end) = month_range(&month_id);
+    let mut rows = promo
+        .find_redemptions_range(session, Some(start), Some(end))
+        .await?;
+    while let Some(row) = rows.next(session).await {
+        let row = row?;
+        let promo_stat = stat
+            .marketing
+            .promo
+            .entry(row.code)
+            .or_insert_with(|| PromoStat {
+                source: row.source,
+                redemptions: 0,
+                earned: 0,
+                discount: 0,
+            });
+        promo_stat.redemptions += 1;
+        promo_stat.earned += row.price.int_part();
+        promo_stat.discount += row.discount.int_part();
+    }
+    Ok(())
+}
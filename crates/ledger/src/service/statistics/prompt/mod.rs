@@ -1,7 +1,7 @@
 use crate::service::users::Users;
 use chrono::NaiveDate;
 use eyre::Result;
-use model::{session::Session, statistics::month::MonthStatistics};
+use model::{rooms::Room, session::Session, statistics::month::MonthStatistics};
 use std::collections::HashMap;
 
 mod render;
@@ -42,9 +42,10 @@ marketing_statistics - статистика по маркетингу
 pub async fn make_prompt(
     state: &HashMap<NaiveDate, MonthStatistics>,
     users: &Users,
+    rooms: &[Room],
     session: &mut Session,
 ) -> Result<String> {
-    let bases = render::render_statistic(state, users, session).await?;
+    let bases = render::render_statistic(state, users, rooms, session).await?;
     Ok(format!("Вот агрегация данных из базы в формате CSV. Ты — бизнес-аналитик, и твоя задача — отвечать на вопросы, связанные с бизнесом:\n{}.
 Ответы отправляются через Telegram в виде сообщения. 
 Твои инструкции:
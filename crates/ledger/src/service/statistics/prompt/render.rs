@@ -17,10 +17,12 @@ use crate::service::users::Users;
 pub async fn render_statistic(
     state: &HashMap<NaiveDate, MonthStatistics>,
     users: &Users,
+    rooms: &[Room],
     session: &mut Session,
 ) -> Result<String> {
-    let mut trainigs = TrainingWriter::new()?;
+    let mut trainigs = TrainingWriter::new(rooms)?;
     let mut marketing = MarketingWriter::new()?;
+    let mut promo = PromoWriter::new()?;
     let mut subscriptions = SubscriptionStatWriter::new()?;
 
     let employees = state
@@ -35,19 +37,21 @@ pub async fn render_statistic(
             .write(month, &month_stats.training, users, session)
             .await?;
         marketing.write(month, &month_stats.marketing)?;
+        promo.write(month, &month_stats.marketing)?;
         subscriptions.write(month, &month_stats.subscriptions)?;
         treasury.write(month, &month_stats.treasury)?;
     }
 
     let trainings_db = trainigs.finish()?;
     let marketing_db = marketing.finish()?;
+    let promo_db = promo.finish()?;
     let subscriptions_db = subscriptions.finish()?;
     let treasury_db = treasury.finish()?;
 
     let now = chrono::Local::now();
     Ok(format!(
-        "trainings aggregation:\n{}request aggregation:\n{}subscription aggregation:\n{}:financial statistics\n{}\nnow:{}",
-        trainings_db, marketing_db, subscriptions_db, treasury_db, now.format("%Y-%m-%d %H:%M")
+        "trainings aggregation:\n{}request aggregation:\n{}promo code aggregation:\n{}subscription aggregation:\n{}:financial statistics\n{}\nnow:{}",
+        trainings_db, marketing_db, promo_db, subscriptions_db, treasury_db, now.format("%Y-%m-%d %H:%M")
     ))
 }
 
@@ -67,6 +71,8 @@ impl TreasuryIOWriter {
             "other expenses".to_string(),
             "other income".to_string(),
             "Subscriptions sold for a total amount of".to_string(),
+            "Refunded for subscriptions".to_string(),
+            "Gift certificates sold for a total amount of".to_string(),
         ];
         let employees = employees.into_iter().collect::<Vec<_>>();
         for employee in &employees {
@@ -90,6 +96,8 @@ impl TreasuryIOWriter {
             stat.other_expense.to_string(),
             stat.income_other.to_string(),
             stat.sell_subscriptions.to_string(),
+            stat.refunds.to_string(),
+            stat.gift_certificates.to_string(),
         ];
 
         for emp in self.employees.iter() {
@@ -133,6 +141,7 @@ impl SubscriptionStatWriter {
             "earned on sales",
             "user subscriptions burned out",
             "discounts were issued for the amount",
+            "refunded to clients",
         ])?;
         Ok(Self { wtr })
     }
@@ -146,6 +155,7 @@ impl SubscriptionStatWriter {
                 subscription.earned.to_string(),
                 subscription.burned_training.to_string(),
                 subscription.discount.to_string(),
+                subscription.refunded.to_string(),
             ])?;
         }
 
@@ -169,9 +179,11 @@ impl MarketingWriter {
             "month",
             "where did the client come from(direction)",
             "clients who purchased a trial lesson",
+            "trial lessons converted to a subscription",
             "clients who purchased a subscription",
             "number of applications from this direction",
             "earned from this direction",
+            "refunded to clients from this direction",
             "spent on advertising in this direction",
         ])?;
         Ok(Self { wtr })
@@ -183,9 +195,11 @@ impl MarketingWriter {
                 month.format("%Y-%m").to_string(),
                 source.name().to_string(),
                 source_stat.buy_test.to_string(),
+                source_stat.trial_converted.to_string(),
                 source_stat.buy_subscription.to_string(),
                 source_stat.requests_count.to_string(),
                 source_stat.earned.to_string(),
+                source_stat.refunded.to_string(),
                 source_stat.spent.to_string(),
             ])?;
         }
@@ -199,6 +213,45 @@ impl MarketingWriter {
     }
 }
 
+pub struct PromoWriter {
+    wtr: csv::Writer<Vec<u8>>,
+}
+
+impl PromoWriter {
+    pub fn new() -> Result<Self> {
+        let mut wtr = csv::Writer::from_writer(vec![]);
+        wtr.write_record([
+            "month",
+            "promo code",
+            "campaign direction",
+            "subscriptions sold with the code",
+            "earned with the code",
+            "discounts were issued for the amount",
+        ])?;
+        Ok(Self { wtr })
+    }
+
+    pub fn write(&mut self, month: &NaiveDate, stat: &MarketingStat) -> Result<()> {
+        for (code, promo) in stat.promo.iter() {
+            self.wtr.write_record(&[
+                month.format("%Y-%m").to_string(),
+                code.clone(),
+                promo.source.name().to_string(),
+                promo.redemptions.to_string(),
+                promo.earned.to_string(),
+                promo.discount.to_string(),
+            ])?;
+        }
+
+        Ok(())
+    }
+
+    pub fn finish(self) -> Result<String> {
+        let buff = self.wtr.into_inner()?;
+        Ok(String::from_utf8(buff)?)
+    }
+}
+
 pub struct TrainingWriter {
     by_program: csv::Writer<Vec<u8>>,
     by_instructor: csv::Writer<Vec<u8>>,
@@ -208,10 +261,11 @@ pub struct TrainingWriter {
     by_time: csv::Writer<Vec<u8>>,
 
     instructors: HashMap<ObjectId, String>,
+    rooms: HashMap<ObjectId, String>,
 }
 
 impl TrainingWriter {
-    pub fn new() -> Result<Self> {
+    pub fn new(rooms: &[Room]) -> Result<Self> {
         let mut by_program = csv::Writer::from_writer(vec![]);
         let mut by_instructor = csv::Writer::from_writer(vec![]);
         let mut by_room = csv::Writer::from_writer(vec![]);
@@ -287,6 +341,7 @@ impl TrainingWriter {
             by_weekday,
             by_time,
             instructors: HashMap::new(),
+            rooms: rooms.iter().map(|r| (r.id, r.name.clone())).collect(),
         })
     }
 
@@ -336,7 +391,7 @@ impl TrainingWriter {
 
         for (room, stat) in training.by_room.iter() {
             self.by_room.write_record(&[
-                Room::from(*room).to_string(),
+                self.rooms.get(room).cloned().unwrap_or_default(),
                 month.format("%Y-%m").to_string(),
                 stat.trainings_count.to_string(),
                 stat.total_clients.to_string(),
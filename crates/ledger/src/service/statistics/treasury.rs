@@ -25,9 +25,18 @@ pub async fn load_treasury(
         let row = row?;
         let sum = row.sum().int_part().abs();
         match row.event {
-            Event::SellSubscription(_) => {
+            Event::SellSubscription(_)
+            | Event::UpgradeSubscription(_)
+            | Event::PayInstallment(_) => {
                 stat.treasury.sell_subscriptions += sum;
             }
+            Event::Refund(_) => {
+                stat.treasury.refunds += sum;
+            }
+            Event::SellGiftCertificate(_) => {
+                stat.treasury.gift_certificates += sum;
+            }
+            Event::RedeemGiftCertificate(_) => {}
             Event::Income(_) => {
                 stat.treasury.income_other += sum;
             }
@@ -70,9 +79,11 @@ pub async fn load_treasury(
                 let stat = stat.marketing.source.entry(source).or_insert_with(|| {
                     model::statistics::month::SourceStat {
                         buy_test: 0,
+                        trial_converted: 0,
                         buy_subscription: 0,
                         requests_count: 0,
                         earned: 0,
+                        refunded: 0,
                         spent: 0,
                     }
                 });
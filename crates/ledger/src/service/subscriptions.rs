@@ -1,5 +1,10 @@
-use eyre::{eyre, Error};
-use model::{decimal::Decimal, session::Session, subscription::Subscription};
+use chrono::{DateTime, Utc};
+use eyre::{bail, eyre, Error};
+use model::{
+    decimal::Decimal,
+    session::Session,
+    subscription::{BundlePool, Subscription},
+};
 use mongodb::bson::oid::ObjectId;
 use storage::subscription::SubscriptionsStore;
 use thiserror::Error;
@@ -32,14 +37,92 @@ impl Subscriptions {
     }
 
     pub async fn get_all(&self, session: &mut Session) -> Result<Vec<Subscription>, Error> {
+        let now = Utc::now();
         let mut cursor = self.store.cursor(session).await?;
         let mut result = Vec::new();
         while let Some(subscription) = cursor.next(session).await {
-            result.push(subscription?);
+            let mut subscription = subscription?;
+            subscription.apply_price_at(now);
+            result.push(subscription);
         }
         Ok(result)
     }
 
+    /// Subscription with the price effective right now, even if the scheduled price
+    /// has not been activated by the background task yet.
+    pub async fn get_for_sale(
+        &self,
+        session: &mut Session,
+        id: ObjectId,
+    ) -> Result<Option<Subscription>, Error> {
+        let mut subscription = self.store.get(session, id).await?;
+        if let Some(subscription) = subscription.as_mut() {
+            subscription.apply_price_at(Utc::now());
+        }
+        Ok(subscription)
+    }
+
+    /// Adds a price version. It takes effect right away if `effective_from` is not in the future.
+    #[tx]
+    pub async fn change_price(
+        &self,
+        session: &mut Session,
+        id: ObjectId,
+        price: Decimal,
+        effective_from: DateTime<Utc>,
+    ) -> Result<(), Error> {
+        if price < Decimal::zero() {
+            return Err(eyre!("Invalid price"));
+        }
+        let mut subscription = self
+            .get(session, id)
+            .await?
+            .ok_or_else(|| eyre!("Subscription not found"))?;
+        subscription.schedule_price(price, effective_from, Utc::now());
+        self.store.update(session, &subscription).await?;
+        Ok(())
+    }
+
+    #[tx]
+    pub async fn cancel_scheduled_price(
+        &self,
+        session: &mut Session,
+        id: ObjectId,
+        version: u32,
+    ) -> Result<(), Error> {
+        let mut subscription = self
+            .get(session, id)
+            .await?
+            .ok_or_else(|| eyre!("Subscription not found"))?;
+        if !subscription.cancel_scheduled_price(version, Utc::now()) {
+            return Err(eyre!("Price version {} is not scheduled", version));
+        }
+        self.store.update(session, &subscription).await?;
+        Ok(())
+    }
+
+    /// Switches subscriptions to the scheduled prices that became effective.
+    /// Returns the switched subscriptions with their previous prices.
+    #[tx]
+    pub async fn activate_scheduled_prices(
+        &self,
+        session: &mut Session,
+    ) -> Result<Vec<(Subscription, Decimal)>, Error> {
+        let now = Utc::now();
+        let mut cursor = self.store.cursor(session).await?;
+        let mut activated = Vec::new();
+        while let Some(subscription) = cursor.next(session).await {
+            let mut subscription = subscription?;
+            if let Some(old_price) = subscription.apply_price_at(now) {
+                activated.push((subscription, old_price));
+            }
+        }
+        for (subscription, _) in &activated {
+            self.store.update(session, subscription).await?;
+        }
+        Ok(activated)
+    }
+
     #[tx]
     pub async fn create_subscription(
         &self,
@@ -60,6 +143,50 @@ impl Subscriptions {
         Ok(())
     }
 
+    /// Adds a pool of lessons of another training type to the subscription.
+    /// Its price is taken from the price of the main pool.
+    #[tx]
+    pub async fn add_bundle_pool(
+        &self,
+        session: &mut Session,
+        id: ObjectId,
+        pool: BundlePool,
+    ) -> Result<(), Error> {
+        let mut subscription = self
+            .get(session, id)
+            .await?
+            .ok_or_else(|| eyre!("Subscription not found"))?;
+        if subscription.unlimited || subscription.trial {
+            bail!("Only regular subscriptions can be bundled");
+        }
+        if pool.items == 0 {
+            bail!("Pool must have lessons");
+        }
+        if pool.price < Decimal::zero() || pool.price > subscription.main_price() {
+            bail!("Pool price exceeds the subscription price");
+        }
+        subscription.pools.push(pool);
+        self.store.update(session, &subscription).await
+    }
+
+    #[tx]
+    pub async fn remove_bundle_pool(
+        &self,
+        session: &mut Session,
+        id: ObjectId,
+        idx: usize,
+    ) -> Result<(), Error> {
+        let mut subscription = self
+            .get(session, id)
+            .await?
+            .ok_or_else(|| eyre!("Subscription not found"))?;
+        if idx >= subscription.pools.len() {
+            return Ok(());
+        }
+        subscription.pools.remove(idx);
+        self.store.update(session, &subscription).await
+    }
+
     #[tx]
     pub async fn edit_program_list(
         &self,
@@ -2,19 +2,24 @@ use chrono::{DateTime, Local, Utc};
 use eyre::Error;
 use model::{
     decimal::Decimal,
+    gift::GiftCertificate,
+    installment::Debt,
     session::Session,
     statistics::source::Source,
-    subscription::Subscription,
+    subscription::{Subscription, UserSubscription},
     treasury::{
-        aggregate::{AggIncome, AggOutcome, TreasuryAggregate},
+        aggregate::{AggGifts, AggIncome, AggOutcome, TreasuryAggregate},
         income::Income,
         outcome::Outcome,
-        subs::{SellSubscription, UserId},
+        subs::{
+            PayInstallment, RedeemGiftCertificate, RefundSubscription, SellGiftCertificate,
+            SellSubscription, UpgradeSubscription, UserId,
+        },
         Event, TreasuryEvent,
     },
 };
 use mongodb::bson::oid::ObjectId;
-use storage::treasury::TreasuryStore;
+use storage::{gift::GiftStore, treasury::TreasuryStore};
 use tx_macro::tx;
 
 use std::{ops::Deref, sync::Arc};
@@ -24,12 +29,13 @@ use super::history::History;
 #[derive(Clone)]
 pub struct Treasury {
     store: Arc<TreasuryStore>,
+    gifts: Arc<GiftStore>,
     logs: History,
 }
 
 impl Treasury {
-    pub fn new(store: Arc<TreasuryStore>, logs: History) -> Self {
-        Treasury { store, logs }
+    pub fn new(store: Arc<TreasuryStore>, gifts: Arc<GiftStore>, logs: History) -> Self {
+        Treasury { store, gifts, logs }
     }
 
     pub async fn page(
@@ -47,17 +53,20 @@ impl Treasury {
         buyer_id: ObjectId,
         sub: Subscription,
         discount: Option<Decimal>,
+        debt: Decimal,
     ) -> Result<(), Error> {
         let mut debit = sub.price;
 
         if let Some(discount) = discount {
             debit -= sub.price * discount;
         }
+        debit -= debt;
 
         let sub = SellSubscription {
             info: sub.into(),
             buyer_id: UserId::Id(buyer_id),
             discount,
+            debt,
         };
 
         let event = TreasuryEvent {
@@ -73,6 +82,149 @@ impl Treasury {
         Ok(())
     }
 
+    pub(crate) async fn refund(
+        &self,
+        session: &mut Session,
+        buyer_id: ObjectId,
+        sub: &UserSubscription,
+        amount: Decimal,
+        fee: Decimal,
+    ) -> Result<(), Error> {
+        let refund = RefundSubscription {
+            buyer_id: UserId::Id(buyer_id),
+            subscription_id: sub.subscription_id,
+            name: sub.name.clone(),
+            items: sub.balance,
+            item_price: sub.item_price(),
+            fee,
+        };
+
+        let event = TreasuryEvent {
+            id: ObjectId::new(),
+            date_time: Utc::now(),
+            event: Event::Refund(refund),
+            debit: Decimal::zero(),
+            credit: amount,
+            actor: session.actor(),
+            description: None,
+        };
+        self.store.insert(session, event).await?;
+        Ok(())
+    }
+
+    pub(crate) async fn sell_gift_certificate(
+        &self,
+        session: &mut Session,
+        certificate: &GiftCertificate,
+    ) -> Result<(), Error> {
+        let sell = SellGiftCertificate {
+            buyer_id: UserId::Id(certificate.buyer),
+            certificate_id: certificate.id,
+            code: certificate.code.clone(),
+            value: certificate.value.clone(),
+        };
+
+        let event = TreasuryEvent {
+            id: ObjectId::new(),
+            date_time: Utc::now(),
+            event: Event::SellGiftCertificate(sell),
+            debit: certificate.price,
+            credit: Decimal::zero(),
+            actor: session.actor(),
+            description: None,
+        };
+        self.store.insert(session, event).await?;
+        Ok(())
+    }
+
+    pub(crate) async fn redeem_gift_certificate(
+        &self,
+        session: &mut Session,
+        user_id: ObjectId,
+        certificate: &GiftCertificate,
+        sub: Subscription,
+    ) -> Result<(), Error> {
+        let redeem = RedeemGiftCertificate {
+            user_id: UserId::Id(user_id),
+            certificate_id: certificate.id,
+            code: certificate.code.clone(),
+            info: sub.into(),
+            amount: certificate.price,
+        };
+
+        let event = TreasuryEvent {
+            id: ObjectId::new(),
+            date_time: Utc::now(),
+            event: Event::RedeemGiftCertificate(redeem),
+            debit: Decimal::zero(),
+            credit: Decimal::zero(),
+            actor: session.actor(),
+            description: None,
+        };
+        self.store.insert(session, event).await?;
+        Ok(())
+    }
+
+    /// Debits the difference between the new subscription and the credit for the old one.
+    pub(crate) async fn upgrade(
+        &self,
+        session: &mut Session,
+        buyer_id: ObjectId,
+        from: &UserSubscription,
+        sub: Subscription,
+        credit: Decimal,
+    ) -> Result<(), Error> {
+        let debit = sub.price - credit;
+        let upgrade = UpgradeSubscription {
+            buyer_id: UserId::Id(buyer_id),
+            subscription_id: from.subscription_id,
+            name: from.name.clone(),
+            items: from.balance,
+            item_price: from.item_price(),
+            info: sub.into(),
+            credit,
+        };
+
+        let event = TreasuryEvent {
+            id: ObjectId::new(),
+            date_time: Utc::now(),
+            event: Event::UpgradeSubscription(upgrade),
+            debit,
+            credit: Decimal::zero(),
+            actor: session.actor(),
+            description: None,
+        };
+        self.store.insert(session, event).await?;
+        Ok(())
+    }
+
+    pub(crate) async fn pay_installment(
+        &self,
+        session: &mut Session,
+        buyer_id: ObjectId,
+        debt: &Debt,
+        amount: Decimal,
+    ) -> Result<(), Error> {
+        let pay = PayInstallment {
+            buyer_id: UserId::Id(buyer_id),
+            debt_id: debt.id,
+            name: debt.name.clone(),
+            remaining: debt.remaining(),
+        };
+
+        let event = TreasuryEvent {
+            id: ObjectId::new(),
+            date_time: Utc::now(),
+            event: Event::PayInstallment(pay),
+            debit: amount,
+            credit: Decimal::zero(),
+            actor: session.actor(),
+            description: None,
+        };
+        self.store.insert(session, event).await?;
+        Ok(())
+    }
+
     #[tx]
     pub async fn payment(
         &self,
@@ -220,11 +372,13 @@ impl Treasury {
         from: Option<DateTime<Local>>,
         to: Option<DateTime<Local>>,
     ) -> Result<TreasuryAggregate, Error> {
+        let liability_at = to.map(|to| to.with_timezone(&Utc)).unwrap_or_else(Utc::now);
         let txs = self.store.range(session, from, to).await?;
         let mut debit = Decimal::zero();
         let mut credit = Decimal::zero();
         let mut income = AggIncome::default();
         let mut outcome = AggOutcome::default();
+        let mut gifts = AggGifts::default();
 
         let mut from = txs
             .first()
@@ -263,9 +417,28 @@ impl Treasury {
                         .or_default()
                         .add(tx.credit);
                 }
+                Event::Refund(_) => {
+                    outcome.refunds.add(tx.credit);
+                }
+                Event::SellGiftCertificate(_) => {
+                    gifts.sold.add(tx.debit);
+                }
+                Event::RedeemGiftCertificate(redeem) => {
+                    gifts.redeemed.add(redeem.amount);
+                }
+                Event::UpgradeSubscription(_) | Event::PayInstallment(_) => {
+                    income.subscriptions.add(tx.debit);
+                }
             }
         }
 
+        gifts.liability = self
+            .gifts
+            .outstanding(session, liability_at)
+            .await?
+            .iter()
+            .fold(Decimal::zero(), |sum, gift| sum + gift.price);
+
         Ok(TreasuryAggregate {
             from,
             to,
@@ -273,6 +446,7 @@ impl Treasury {
             credit,
             income,
             outcome,
+            gifts,
         })
     }
 }
@@ -70,7 +70,7 @@ fn system_prompt(
 
     prompt.push_str("История операций:\n");
     for row in history {
-        if let Some(sub) = history_row_to_prompt(&row) {
+        if let Some(sub) = history_row_to_prompt(&row, user.id) {
             prompt.push_str(&sub);
         }
     }
@@ -115,7 +115,7 @@ fn user_sub_to_prompt(sub: &UserSubscription) -> String {
     prompt
 }
 
-fn history_row_to_prompt(row: &HistoryRow) -> Option<String> {
+fn history_row_to_prompt(row: &HistoryRow, user: ObjectId) -> Option<String> {
     let dt = row.date_time.with_timezone(&Local);
     let msg = match &row.action {
         model::history::Action::BlockUser { is_active } => Some(if *is_active {
@@ -133,6 +133,17 @@ fn history_row_to_prompt(row: &HistoryRow) -> Option<String> {
             start_at.with_timezone(&Local),
             name
         )),
+        model::history::Action::LateSignOut {
+            start_at,
+            name,
+            penalty,
+            ..
+        } => Some(format!(
+            "поздно отписан от тренировки {} {} штраф {} занятия",
+            start_at.with_timezone(&Local),
+            name,
+            penalty
+        )),
         model::history::Action::SellSub {
             subscription,
             discount,
@@ -152,8 +163,21 @@ fn history_row_to_prompt(row: &HistoryRow) -> Option<String> {
         }
         model::history::Action::PreSellSub { .. } => None,
         model::history::Action::FinalizedCanceledTraining { .. } => None,
-        model::history::Action::FinalizedTraining { name, start_at, .. } => Some(format!(
-            "посетил тренировку {} {}",
+        model::history::Action::FinalizedTraining {
+            name,
+            start_at,
+            no_show,
+            excused,
+            ..
+        } => Some(format!(
+            "{} {} {}",
+            if no_show.contains(&user) {
+                "не пришел на тренировку"
+            } else if excused.contains(&user) {
+                "пропустил по уважительной причине тренировку"
+            } else {
+                "посетил тренировку"
+            },
             start_at.with_timezone(&Local),
             name
         )),
@@ -171,6 +195,54 @@ fn history_row_to_prompt(row: &HistoryRow) -> Option<String> {
         )),
         model::history::Action::RemoveFamilyMember {} => None,
         model::history::Action::AddFamilyMember {} => None,
+        model::history::Action::FreezeSubscription { name, days } => Some(format!(
+            "абонемент {} поставлен на паузу на {} дней",
+            name, days
+        )),
+        model::history::Action::UnfreezeSubscription { name } => {
+            Some(format!("абонемент {} снят с паузы", name))
+        }
+        model::history::Action::TransferSubscriptionOut { name, items } => Some(format!(
+            "передал другому клиенту {} занятий абонемента {}",
+            items, name
+        )),
+        model::history::Action::TransferSubscriptionIn { name, items } => Some(format!(
+            "получил от другого клиента {} занятий абонемента {}",
+            items, name
+        )),
+        model::history::Action::RefundSubscription {
+            subscription,
+            amount,
+            ..
+        } => Some(format!(
+            "вернул {} занятий абонемента {} за {} руб.",
+            subscription.balance, subscription.name, amount
+        )),
+        model::history::Action::SellGiftCertificate { code, price, .. } => Some(format!(
+            "купил подарочный сертификат {} за {} руб.",
+            code, price
+        )),
+        model::history::Action::RedeemGiftCertificate { code, subscription } => Some(format!(
+            "получил абонемент {} по подарочному сертификату {}",
+            subscription.name, code
+        )),
+        model::history::Action::UpgradeSubscription {
+            from,
+            subscription,
+            credit,
+            amount,
+        } => Some(format!(
+            "перешел с абонемента {} ({} занятий на {} руб.) на абонемент {} с доплатой {} руб.",
+            from.name, from.balance, credit, subscription.name, amount
+        )),
+        model::history::Action::PayInstallment {
+            name,
+            amount,
+            remaining,
+        } => Some(format!(
+            "внес платеж {} руб. по рассрочке за абонемент {}, осталось {} руб.",
+            amount, name, remaining
+        )),
         model::history::Action::ChangeSubscriptionDays { .. } => None,
     };
     msg.map(|msg| format!("{} {}\n", dt, msg))
@@ -78,6 +78,7 @@ impl Users {
                         notification_mask: Default::default(),
                         ai_message_prompt: None,
                         comments: Default::default(),
+                        calendar_token: None,
                     },
                 )
                 .await?;
@@ -124,6 +125,7 @@ impl Users {
                     notification_mask: Default::default(),
                     ai_message_prompt: None,
                     comments: Default::default(),
+                    calendar_token: None,
                 },
             )
             .await?;
@@ -176,6 +178,19 @@ impl Users {
         Ok(())
     }
 
+    /// Secret for the user's calendar feed, issued on first request.
+    #[tx]
+    pub async fn calendar_token(&self, session: &mut Session, id: ObjectId) -> Result<String> {
+        let mut user = self.store.get_extension(session, id).await?;
+        if let Some(token) = &user.calendar_token {
+            return Ok(token.clone());
+        }
+        let token = model::ical::new_feed_token();
+        user.calendar_token = Some(token.clone());
+        self.store.update_extension(session, user).await?;
+        Ok(token)
+    }
+
     #[tx]
     pub async fn set_ai_prompt(
         &self,
@@ -1,10 +1,8 @@
 use bson::oid::ObjectId;
-use chrono::{Duration, Local};
 use model::{
     decimal::Decimal,
     session::Session,
     statistics::user::{Statistics, SubscriptionStat},
-    training::CLOSE_SING_UP,
 };
 
 use super::Users;
@@ -24,25 +22,27 @@ impl Users {
                 | model::history::Action::AddFamilyMember {}
                 | model::history::Action::PayReward { .. }
                 | model::history::Action::Unfreeze {}
+                | model::history::Action::UnfreezeSubscription { .. }
+                | model::history::Action::TransferSubscriptionOut { .. }
+                | model::history::Action::TransferSubscriptionIn { .. }
+                | model::history::Action::SellGiftCertificate { .. }
+                | model::history::Action::PayInstallment { .. }
                 | model::history::Action::Deposit { .. }
                 | model::history::Action::CreateUser { .. }
                 | model::history::Action::Payment { .. }
                 | model::history::Action::FinalizedCanceledTraining { .. }
                 | model::history::Action::PreSellSub { .. }
                 | model::history::Action::SignUp { .. }
+                | model::history::Action::SignOut { .. }
                 | model::history::Action::BlockUser { .. } => {
                     //no-op
                 }
-                model::history::Action::SignOut { start_at, name, .. } => {
-                    if row.date_time.with_timezone(&Local) + Duration::minutes(CLOSE_SING_UP as i64)
-                        > start_at
-                    {
-                        statistics
-                            .training
-                            .entry(name)
-                            .or_default()
-                            .cancellations_count += 1;
-                    }
+                model::history::Action::LateSignOut { name, .. } => {
+                    statistics
+                        .training
+                        .entry(name)
+                        .or_default()
+                        .cancellations_count += 1;
                 }
                 model::history::Action::SellSub {
                     subscription,
@@ -62,11 +62,42 @@ impl Users {
                         stat.spent += subscription.price;
                     }
                 }
-                model::history::Action::FinalizedTraining { name, .. } => {
+                model::history::Action::UpgradeSubscription {
+                    subscription,
+                    amount,
+                    ..
+                } => {
+                    let stat = statistics
+                        .subscriptions
+                        .entry(subscription.id)
+                        .or_insert_with(|| SubscriptionStat::new(subscription.name.clone()));
+                    stat.soult_count += 1;
+                    stat.spent += amount;
+                }
+                model::history::Action::RedeemGiftCertificate { subscription, .. } => {
+                    statistics
+                        .subscriptions
+                        .entry(subscription.id)
+                        .or_insert_with(|| SubscriptionStat::new(subscription.name.clone()))
+                        .soult_count += 1;
+                }
+                model::history::Action::FinalizedTraining {
+                    name,
+                    no_show,
+                    excused,
+                    ..
+                } => {
                     let training = statistics.training.entry(name).or_default();
-                    training.count += 1;
+                    if no_show.contains(user) {
+                        training.no_show_count += 1;
+                    } else if excused.contains(user) {
+                        training.excused_count += 1;
+                    } else {
+                        training.count += 1;
+                    }
                 }
-                model::history::Action::Freeze { days } => {
+                model::history::Action::Freeze { days }
+                | model::history::Action::FreezeSubscription { days, .. } => {
                     statistics.total_freeze += days;
                 }
                 model::history::Action::ChangeBalance { amount } => {
@@ -88,6 +119,17 @@ impl Users {
                         subscription.item_price() * Decimal::int(subscription.balance as i64);
                     stat.expired_trainings += subscription.balance as u64;
                 }
+                model::history::Action::RefundSubscription {
+                    subscription,
+                    amount,
+                    ..
+                } => {
+                    let stat = statistics
+                        .subscriptions
+                        .entry(subscription.subscription_id)
+                        .or_insert_with(|| SubscriptionStat::new(subscription.name.clone()));
+                    stat.refunds_sum += amount;
+                }
             }
         }
 
@@ -1,6 +1,6 @@
 use super::Users;
 use chrono::Utc;
-use eyre::{eyre, Result};
+use eyre::{bail, eyre, Result};
 use log::info;
 use model::{decimal::Decimal, session::Session, subscription::UserSubscription};
 use mongodb::bson::oid::ObjectId;
@@ -34,6 +34,40 @@ impl Users {
         Ok(())
     }
 
+    /// Prolongs the active subscriptions of the given clients, once per payer.
+    #[tx]
+    pub async fn extend_clients_subscriptions(
+        &self,
+        session: &mut Session,
+        clients: &[ObjectId],
+        days: u32,
+    ) -> Result<()> {
+        let mut extended = vec![];
+        for client in clients {
+            let Some(mut user) = self.store.get(session, *client).await? else {
+                continue;
+            };
+            self.resolve_family(session, &mut user).await?;
+            let mut payer = user.payer_mut()?;
+            if extended.contains(&payer.id) {
+                continue;
+            }
+            extended.push(payer.id);
+
+            for sub in payer.subscriptions_mut() {
+                if let model::subscription::Status::Active {
+                    start_date: _,
+                    end_date,
+                } = &mut sub.status
+                {
+                    *end_date += chrono::Duration::days(days as i64);
+                }
+            }
+            self.store.update(session, &mut payer).await?;
+        }
+        Ok(())
+    }
+
     #[tx]
     pub async fn change_subscription_program(
         &self,
@@ -189,6 +223,85 @@ impl Users {
         Ok(())
     }
 
+    /// Pauses one subscription of the payer. Without `force` the pause must fit
+    /// into the subscription's remaining freeze budget.
+    #[tx]
+    pub async fn freeze_subscription(
+        &self,
+        session: &mut Session,
+        user_id: ObjectId,
+        id: ObjectId,
+        days: u32,
+        force: bool,
+    ) -> Result<()> {
+        info!("Freezing subscription {} for user {}", id, user_id);
+        let mut user = self
+            .store
+            .get(session, user_id)
+            .await?
+            .ok_or_else(|| eyre!("User not found"))?;
+
+        self.resolve_family(session, &mut user).await?;
+        let mut payer = user.payer_mut()?;
+        let payer_id = payer.id;
+
+        let sub = payer
+            .subscriptions_mut()
+            .iter_mut()
+            .find(|sub| sub.id == id)
+            .ok_or_else(|| eyre!("Subscription not found"))?;
+        if !force && sub.freeze_days < days {
+            bail!("Not enough freeze days");
+        }
+        if !sub.freeze(days, Utc::now()) {
+            bail!("Subscription is not active or already frozen");
+        }
+        let name = sub.name.clone();
+        payer.sync_bundle(id);
+
+        self.logs
+            .freeze_subscription(session, payer_id, name, days)
+            .await?;
+        self.store.update(session, &mut payer).await?;
+        Ok(())
+    }
+
+    #[tx]
+    pub async fn unfreeze_subscription(
+        &self,
+        session: &mut Session,
+        user_id: ObjectId,
+        id: ObjectId,
+    ) -> Result<()> {
+        info!("Unfreezing subscription {} for user {}", id, user_id);
+        let mut user = self
+            .store
+            .get(session, user_id)
+            .await?
+            .ok_or_else(|| eyre!("User not found"))?;
+
+        self.resolve_family(session, &mut user).await?;
+        let mut payer = user.payer_mut()?;
+        let payer_id = payer.id;
+
+        let sub = payer
+            .subscriptions_mut()
+            .iter_mut()
+            .find(|sub| sub.id == id)
+            .ok_or_else(|| eyre!("Subscription not found"))?;
+        if !sub.unfreeze(Utc::now()) {
+            return Ok(());
+        }
+        let name = sub.name.clone();
+        payer.sync_bundle(id);
+
+        self.logs
+            .unfreeze_subscription(session, payer_id, name)
+            .await?;
+        self.store.update(session, &mut payer).await?;
+        Ok(())
+    }
+
     #[tx]
     pub async fn expire_subscription(
         &self,
@@ -1,7 +1,14 @@
 use crate::Ledger;
-use chrono::{DateTime, Local};
+use chrono::{DateTime, Local, Utc};
 use model::{
-    errors::LedgerError, rooms::Room, session::Session, training::{Training, TrainingId}, user::family::FindFor
+    availability::PERSONAL_SLOT_MIN,
+    decimal::Decimal,
+    errors::LedgerError,
+    session::Session,
+    slot::Slot,
+    training::{ClientsPolicy, SeriesScope, Training, TrainingId},
+    user::{employee::UserRewardContribution, family::FindFor},
+    waitlist,
 };
 use mongodb::bson::oid::ObjectId;
 use tx_macro::tx;
@@ -13,6 +20,10 @@ impl Ledger {
         session: &mut Session,
         training: &Training,
     ) -> Result<Vec<ObjectId>, LedgerError> {
+        self.calendar.clear_waitlist(session, training.id()).await?;
+        self.calendar
+            .set_canceled_clients(session, training.id(), &training.clients)
+            .await?;
         for client in &training.clients {
             self.sign_out_tx_less(session, training, *client, true)
                 .await?;
@@ -26,6 +37,136 @@ impl Ledger {
         Ok(training.clients)
     }
 
+    /// Cancels every upcoming training in `[from, to)`, optionally only in one room,
+    /// and returns them as they were before cancellation so clients can be notified.
+    /// With `extend` active subscriptions are prolonged by the number of closed days:
+    /// all of them for the whole studio, only the booked clients' ones for a single room.
+    #[tx]
+    pub async fn close_studio(
+        &self,
+        session: &mut Session,
+        from: DateTime<Local>,
+        to: DateTime<Local>,
+        room: Option<ObjectId>,
+        extend: bool,
+    ) -> Result<Vec<Training>, LedgerError> {
+        let trainings = self.calendar.find_to_close(session, from, to, room).await?;
+        for training in &trainings {
+            self.cancel_training_txless(session, training).await?;
+        }
+        if extend {
+            let days = (to - from).num_days().max(0) as u32;
+            if days > 0 {
+                if room.is_some() {
+                    let clients = trainings
+                        .iter()
+                        .flat_map(|training| training.clients.iter().copied())
+                        .collect::<Vec<_>>();
+                    self.users
+                        .extend_clients_subscriptions_txless(session, &clients, days)
+                        .await?;
+                } else {
+                    self.users.extend_subscriptions_txless(session, days).await?;
+                }
+            }
+        }
+        Ok(trainings)
+    }
+
+    /// Moves the training or a part of its series to `new_slot`. Returns the affected
+    /// instances as they were before the change and the bookings that were signed out,
+    /// so their clients can be notified.
+    #[tx]
+    pub async fn reschedule_training(
+        &self,
+        session: &mut Session,
+        id: TrainingId,
+        new_slot: Slot,
+        scope: SeriesScope,
+        clients: ClientsPolicy,
+    ) -> Result<(Vec<Training>, Vec<(TrainingId, ObjectId)>), LedgerError> {
+        let (series, released) = self.release_clients(session, id, scope, clients).await?;
+        self.calendar
+            .change_slot_txless(session, id, new_slot, scope)
+            .await?;
+        if clients == ClientsPolicy::Move {
+            for training in &series {
+                let moved = new_slot.with_day(training.day_id());
+                for client in &training.clients {
+                    self.move_visit(session, *client, &training.get_slot(), &moved)
+                        .await?;
+                }
+            }
+        }
+        Ok((series, released))
+    }
+
+    /// Keeps the weekly limit visits of the client in line with the moved training.
+    async fn move_visit(
+        &self,
+        session: &mut Session,
+        client: ObjectId,
+        from: &Slot,
+        to: &Slot,
+    ) -> Result<(), LedgerError> {
+        let mut user = self
+            .users
+            .get(session, client)
+            .await?
+            .ok_or(LedgerError::UserNotFound(client))?;
+        self.users.resolve_family(session, &mut user).await?;
+        let mut payer = user.payer_mut()?;
+        let moved = payer
+            .subscriptions_mut()
+            .iter_mut()
+            .any(|sub| sub.move_visit(from, to));
+        if moved {
+            self.users.update(session, &mut payer).await?;
+        }
+        Ok(())
+    }
+
+    /// Hands the training or a part of its series over to another instructor.
+    #[tx]
+    pub async fn replace_instructor(
+        &self,
+        session: &mut Session,
+        id: TrainingId,
+        new_couch: ObjectId,
+        scope: SeriesScope,
+        clients: ClientsPolicy,
+    ) -> Result<(Vec<Training>, Vec<(TrainingId, ObjectId)>), LedgerError> {
+        let released = self.release_clients(session, id, scope, clients).await?;
+        self.calendar
+            .change_couch_txless(session, id, new_couch, scope)
+            .await?;
+        Ok(released)
+    }
+
+    /// Signs the clients out of the group trainings of the series with `ClientsPolicy::SignOut`.
+    /// Personal trainings keep their clients. Returns the series and the released bookings.
+    async fn release_clients(
+        &self,
+        session: &mut Session,
+        id: TrainingId,
+        scope: SeriesScope,
+        clients: ClientsPolicy,
+    ) -> Result<(Vec<Training>, Vec<(TrainingId, ObjectId)>), LedgerError> {
+        let series = self.calendar.series(session, id, scope).await?;
+        let mut released = vec![];
+        if clients == ClientsPolicy::SignOut {
+            for training in series.iter().filter(|t| t.is_group()) {
+                self.calendar.clear_waitlist(session, training.id()).await?;
+                for client in &training.clients {
+                    self.sign_out_tx_less(session, training, *client, true)
+                        .await?;
+                    released.push((training.id(), *client));
+                }
+            }
+        }
+        Ok((series, released))
+    }
+
     #[tx]
     pub async fn schedule_personal_training(
         &self,
@@ -38,12 +179,48 @@ impl Ledger {
     ) -> Result<(), LedgerError> {
         let id = self
             .calendar
-            .schedule_personal_training(session, client, instructor, start_at, duration_min, room)
+            .schedule_personal_training(
+                session,
+                client,
+                instructor,
+                Slot::new(start_at.with_timezone(&Utc), duration_min, room),
+                None,
+            )
             .await?;
         self.sign_up_txless(session, id, client, true).await?;
         Ok(())
     }
 
+    /// Client books a personal training in one of the instructor's availability windows.
+    /// The room and instructor checks and the balance lock happen in one transaction,
+    /// so a client without a matching personal subscription leaves no training behind.
+    #[tx]
+    pub async fn book_personal_training(
+        &self,
+        session: &mut Session,
+        client: ObjectId,
+        instructor: ObjectId,
+        start_at: DateTime<Local>,
+    ) -> Result<TrainingId, LedgerError> {
+        let window = self
+            .calendar
+            .find_availability_window(session, instructor, start_at)
+            .await?
+            .ok_or(LedgerError::SlotNotAvailable { start_at })?;
+        let id = self
+            .calendar
+            .schedule_personal_training(
+                session,
+                client,
+                instructor,
+                Slot::new(start_at.with_timezone(&Utc), PERSONAL_SLOT_MIN, window.room),
+                None,
+            )
+            .await?;
+        self.sign_up_txless(session, id, client, false).await?;
+        Ok(id)
+    }
+
     #[tx]
     pub async fn sign_up(
         &self,
@@ -84,6 +261,13 @@ impl Ledger {
         self.users.resolve_family(session, &mut user).await?;
         let mut payer = user.payer_mut()?;
 
+        if !forced
+            && payer.has_overdue_debt(Utc::now())
+            && self.settings.get(session).await?.block_overdue_sign_up
+        {
+            return Err(LedgerError::PaymentOverdue(user_id));
+        }
+
         if training.tp.is_not_free() {
             let subscription = payer
                 .find_subscription(FindFor::Lock, &training)
@@ -92,19 +276,25 @@ impl Ledger {
             if !subscription.lock_balance() {
                 return Err(LedgerError::NotEnoughBalance(user_id));
             }
+            subscription.add_visit(&training.get_slot());
             self.users.update(session, &mut payer).await?;
         }
 
         self.calendar
             .sign_up(session, training.id(), client)
             .await?;
+        if training.waitlist.contains(&client) {
+            self.calendar
+                .remove_from_waitlist(session, training.id(), client)
+                .await?;
+        }
         self.history
             .sign_up(
                 session,
                 user_id,
                 training.get_slot().start_at(),
                 training.name,
-                Room::from(training.room),
+                training.room,
             )
             .await?;
         Ok(())
@@ -117,7 +307,7 @@ impl Ledger {
         id: TrainingId,
         client: ObjectId,
         forced: bool,
-    ) -> Result<(), LedgerError> {
+    ) -> Result<Option<ObjectId>, LedgerError> {
         let training = self
             .calendar
             .get_training_by_id(session, id)
@@ -125,15 +315,17 @@ impl Ledger {
             .ok_or_else(|| LedgerError::TrainingNotFound(id))?;
         self.sign_out_tx_less(session, &training, client, forced)
             .await?;
+        let promoted = self.promote_from_waitlist(session, training.id()).await?;
 
         if training.tp.is_personal() {
             self.calendar
                 .delete_training_txless(session, training.id(), false)
                 .await?;
         }
-        Ok(())
+        Ok(promoted)
     }
 
+    /// Leaves the freed seat empty: callers that keep the training promote from the waitlist.
     pub(crate) async fn sign_out_tx_less(
         &self,
         session: &mut Session,
@@ -142,9 +334,15 @@ impl Ledger {
         forced: bool,
     ) -> Result<(), LedgerError> {
         let status = training.status(Local::now());
-        if !forced && !status.can_sign_out() {
-            return Err(LedgerError::TrainingNotOpenToSignOut(training.id()));
-        }
+        let late_cancel = if !forced && !status.can_sign_out() {
+            let policy = self.settings.get(session).await?.late_cancel;
+            if !policy.allowed || !status.can_sign_out_late() {
+                return Err(LedgerError::TrainingNotOpenToSignOut(training.id()));
+            }
+            Some(policy)
+        } else {
+            None
+        };
 
         if training.is_processed {
             return Err(LedgerError::TrainingNotOpenToSignOut(training.id()));
@@ -164,13 +362,35 @@ impl Ledger {
         let user_id = user.id;
         let mut payer = user.payer_mut()?;
 
+        let mut penalty = None;
         if training.tp.is_not_free() {
-            let sub = payer
-                .find_subscription(FindFor::Unlock, training)
-                .ok_or_else(|| LedgerError::NotEnoughReservedBalance(client))?;
+            if let Some(policy) = late_cancel {
+                let sub = payer
+                    .find_subscription(FindFor::Charge, training)
+                    .ok_or_else(|| LedgerError::NotEnoughReservedBalance(client))?;
+                let share = policy.penalty();
+                let burned = sub
+                    .burn_locked_balance(training, share)
+                    .ok_or_else(|| LedgerError::NotEnoughReservedBalance(client))?;
+                sub.remove_visit(&training.get_slot());
+                let contribution = UserRewardContribution {
+                    user: client,
+                    lesson_price: sub.item_price() * share,
+                    subscription_price: sub.subscription_price(),
+                    lessons_count: sub.items(),
+                };
+                let sub_id = sub.id;
+                payer.sync_bundle(sub_id);
+                penalty = Some((share, burned, contribution));
+            } else {
+                let sub = payer
+                    .find_subscription(FindFor::Unlock, training)
+                    .ok_or_else(|| LedgerError::NotEnoughReservedBalance(client))?;
 
-            if !sub.unlock_balance() {
-                return Err(LedgerError::NotEnoughReservedBalance(client));
+                if !sub.unlock_balance() {
+                    return Err(LedgerError::NotEnoughReservedBalance(client));
+                }
+                sub.remove_visit(&training.get_slot());
             }
             self.users.update(session, &mut payer).await?;
         }
@@ -178,15 +398,189 @@ impl Ledger {
         self.calendar
             .sign_out(session, training.id(), client)
             .await?;
+
+        if let Some(policy) = late_cancel {
+            match penalty {
+                Some((share, burned, contribution)) => {
+                    self.history
+                        .late_sign_out(session, user_id, training, share, burned)
+                        .await?;
+                    if policy.credit_instructor {
+                        self.credit_late_cancel(session, training, contribution)
+                            .await?;
+                    }
+                }
+                None => {
+                    self.history
+                        .late_sign_out(session, user_id, training, Decimal::zero(), false)
+                        .await?;
+                }
+            }
+        } else {
+            self.history
+                .sign_out(
+                    session,
+                    user_id,
+                    training.get_slot().start_at(),
+                    training.name.clone(),
+                    training.room,
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn credit_late_cancel(
+        &self,
+        session: &mut Session,
+        training: &Training,
+        contribution: UserRewardContribution,
+    ) -> Result<(), LedgerError> {
+        let mut couch = self
+            .users
+            .get(session, training.instructor)
+            .await?
+            .ok_or_else(|| LedgerError::InstructorNotFound(training.instructor))?;
+        let Some(couch_info) = couch.employee.as_mut() else {
+            return Err(LedgerError::InstructorHasNoRights(training.instructor));
+        };
+        if let Some(reward) = couch_info.collect_late_cancel_reward(training, contribution) {
+            self.rewards.add_reward(session, reward).await?;
+            self.users
+                .update_employee_reward_and_rates(
+                    session,
+                    training.instructor,
+                    couch_info.reward,
+                    None,
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    #[tx]
+    pub async fn join_waitlist(
+        &self,
+        session: &mut Session,
+        id: TrainingId,
+        client: ObjectId,
+    ) -> Result<(), LedgerError> {
+        let training = self
+            .calendar
+            .get_training_by_id(session, id)
+            .await?
+            .ok_or_else(|| LedgerError::TrainingNotFound(id))?;
+        let status = training.status(Local::now());
+        if !status.can_sign_in() || training.is_processed || !training.is_group() {
+            return Err(LedgerError::TrainingNotOpenToSignUp(id, status));
+        }
+
+        if training.clients.contains(&client) {
+            return Err(LedgerError::ClientAlreadySignedUp(client, id));
+        }
+
+        if training.waitlist.contains(&client) {
+            return Err(LedgerError::ClientAlreadyInWaitlist(client, id));
+        }
+
+        if !training.is_full() {
+            return Err(LedgerError::TrainingIsNotFull(id));
+        }
+
+        let mut user = self
+            .users
+            .get(session, client)
+            .await?
+            .ok_or_else(|| LedgerError::ClientNotFound(client))?;
+        self.users.resolve_family(session, &mut user).await?;
+        let mut payer = user.payer_mut()?;
+        if training.tp.is_not_free() {
+            let has_balance = payer
+                .find_subscription(FindFor::Lock, &training)
+                .map(|sub| sub.unlimited || sub.balance > 0)
+                .unwrap_or_default();
+            if !has_balance {
+                return Err(LedgerError::NotEnoughBalance(client));
+            }
+        }
+
+        self.calendar
+            .add_to_waitlist(session, training.id(), client)
+            .await?;
+        Ok(())
+    }
+
+    #[tx]
+    pub async fn leave_waitlist(
+        &self,
+        session: &mut Session,
+        id: TrainingId,
+        client: ObjectId,
+    ) -> Result<(), LedgerError> {
+        let training = self
+            .calendar
+            .get_training_by_id(session, id)
+            .await?
+            .ok_or_else(|| LedgerError::TrainingNotFound(id))?;
+        if !training.waitlist.contains(&client) {
+            return Err(LedgerError::ClientNotInWaitlist(client, id));
+        }
+        self.calendar
+            .remove_from_waitlist(session, training.id(), client)
+            .await?;
+        Ok(())
+    }
+
+    /// Signs up the first waiter who passes the sign-up checks.
+    /// Waiters without balance or blocked for an overdue debt stay in the list.
+    pub(crate) async fn promote_from_waitlist(
+        &self,
+        session: &mut Session,
+        id: TrainingId,
+    ) -> Result<Option<ObjectId>, LedgerError> {
+        let training = self
+            .calendar
+            .get_training_by_id(session, id)
+            .await?
+            .ok_or_else(|| LedgerError::TrainingNotFound(id))?;
+        let now = Local::now();
+        if !waitlist::has_free_seat(&training, now) {
+            return Ok(None);
+        }
+
+        let mut waiters = vec![];
+        for waiter in &training.waitlist {
+            if let Some(mut user) = self.users.get(session, *waiter).await? {
+                self.users.resolve_family(session, &mut user).await?;
+                waiters.push(user);
+            }
+        }
+        let block_overdue = self.settings.get(session).await?.block_overdue_sign_up;
+        let Some(idx) = waitlist::promote(&training, &mut waiters, now, block_overdue) else {
+            return Ok(None);
+        };
+        let user = &mut waiters[idx];
+        let user_id = user.id;
+        if training.tp.is_not_free() {
+            let mut payer = user.payer_mut()?;
+            self.users.update(session, &mut payer).await?;
+        }
+
+        self.calendar
+            .remove_from_waitlist(session, training.id(), user_id)
+            .await?;
+        self.calendar
+            .sign_up(session, training.id(), user_id)
+            .await?;
         self.history
-            .sign_out(
+            .sign_up(
                 session,
                 user_id,
                 training.get_slot().start_at(),
                 training.name.clone(),
-                Room::from(training.room),
+                training.room,
             )
             .await?;
-        Ok(())
+        Ok(Some(user_id))
     }
 }
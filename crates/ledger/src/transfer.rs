@@ -0,0 +1,75 @@
+use crate::Ledger;
+use eyre::eyre;
+use model::{errors::LedgerError, session::Session};
+use mongodb::bson::oid::ObjectId;
+use tx_macro::tx;
+
+impl Ledger {
+    /// Hands a subscription over to another client, either whole or `items` of its
+    /// remaining lessons. The lesson price is kept, so instructor rewards don't change.
+    /// Locked lessons never move.
+    #[tx]
+    pub async fn transfer_subscription(
+        &self,
+        session: &mut Session,
+        from: ObjectId,
+        to: ObjectId,
+        subscription: ObjectId,
+        items: Option<u32>,
+    ) -> Result<(), LedgerError> {
+        let mut sender = self
+            .users
+            .get(session, from)
+            .await?
+            .ok_or(LedgerError::UserNotFound(from))?;
+        self.users.resolve_family(session, &mut sender).await?;
+        let mut sender = sender.payer_mut()?;
+
+        let mut receiver = self
+            .users
+            .get(session, to)
+            .await?
+            .ok_or(LedgerError::UserNotFound(to))?;
+        self.users.resolve_family(session, &mut receiver).await?;
+        let mut receiver = receiver.payer_mut()?;
+        let (sender_id, receiver_id) = (sender.id, receiver.id);
+        if sender_id == receiver_id {
+            return Err(eyre!("Can't transfer subscription to the same payer").into());
+        }
+
+        if sender.debt_for(subscription).is_some() {
+            return Err(LedgerError::SubscriptionHasDebt { subscription });
+        }
+
+        let subs = sender.subscriptions_mut();
+        let idx = subs
+            .iter()
+            .position(|sub| sub.id == subscription)
+            .ok_or(LedgerError::SubscriptionNotFound(subscription))?;
+        let whole = items.is_none_or(|items| items == subs[idx].balance);
+        let transferred = if whole {
+            if subs[idx].locked_balance > 0 {
+                return Err(LedgerError::SubscriptionHasLockedBalance { subscription });
+            }
+            subs.remove(idx)
+        } else {
+            subs[idx]
+                .split(items.unwrap_or_default())
+                .ok_or(LedgerError::NotEnoughBalance(sender_id))?
+        };
+
+        self.history
+            .transfer_subscription(
+                session,
+                sender_id,
+                receiver_id,
+                transferred.name.clone(),
+                transferred.balance,
+            )
+            .await?;
+        receiver.subscriptions_mut().push(transferred);
+        self.users.update(session, &mut sender).await?;
+        self.users.update(session, &mut receiver).await?;
+        Ok(())
+    }
+}
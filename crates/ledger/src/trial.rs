@@ -0,0 +1,114 @@
+use crate::Ledger;
+use chrono::Utc;
+use eyre::eyre;
+use model::{
+    decimal::Decimal, errors::LedgerError, request::TrialLesson, session::Session,
+    training::TrainingId, user::User,
+};
+use mongodb::bson::oid::ObjectId;
+use tx_macro::tx;
+
+impl Ledger {
+    /// Sells a trial subscription to the lead and signs them up for the training.
+    /// A phone gets only one trial lesson; the client is created if it doesn't exist yet.
+    #[tx]
+    pub async fn book_trial(
+        &self,
+        session: &mut Session,
+        request_id: ObjectId,
+        subscription_id: ObjectId,
+        training: TrainingId,
+    ) -> Result<User, LedgerError> {
+        let mut request = self
+            .requests
+            .get(session, request_id)
+            .await?
+            .ok_or(LedgerError::RequestNotFound { id: request_id })?;
+        if request.trial.is_some() {
+            return Err(LedgerError::TrialAlreadyUsed {
+                phone: request.phone,
+            });
+        }
+
+        let subscription = self
+            .subscriptions
+            .get_for_sale(session, subscription_id)
+            .await?
+            .ok_or_else(|| eyre!("Subscription not found: {}", subscription_id))?;
+        if !subscription.trial {
+            return Err(eyre!("Subscription is not a trial: {}", subscription.name).into());
+        }
+
+        let client = if let Some(user) = self.users.get_by_phone(session, &request.phone).await? {
+            self.ensure_trial_available(session, &user).await?;
+            user
+        } else {
+            self.users
+                .create_uninit(
+                    session,
+                    request.phone.clone(),
+                    request.first_name.clone().unwrap_or_default(),
+                    request.last_name.clone(),
+                    request.come_from,
+                )
+                .await?
+        };
+
+        self.history
+            .sell_subscription(session, subscription.clone(), client.id, None)
+            .await?;
+        self.users
+            .add_subscription(session, client.id, subscription.clone(), None)
+            .await?;
+        self.treasury
+            .sell(session, client.id, subscription, None, Decimal::zero())
+            .await?;
+
+        self.sign_up_txless(session, training, client.id, false)
+            .await?;
+
+        request.trial = Some(TrialLesson {
+            user_id: client.id,
+            training,
+            booked_at: Utc::now(),
+            converted_at: None,
+        });
+        self.requests.update(session, &request).await?;
+        Ok(client)
+    }
+
+    /// A phone gets only one trial lesson, whichever way the trial subscription is sold.
+    pub(crate) async fn ensure_trial_available(
+        &self,
+        session: &mut Session,
+        user: &User,
+    ) -> Result<(), LedgerError> {
+        let booked = match user.phone.as_deref() {
+            Some(phone) => self
+                .requests
+                .get_by_phone(session, phone)
+                .await?
+                .is_some_and(|request| request.trial.is_some()),
+            None => false,
+        };
+        if booked || self.history.has_trial(session, user.id).await? {
+            return Err(LedgerError::TrialAlreadyUsed {
+                phone: user.phone.clone().unwrap_or_default(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Counts the trial as converted once the client gets a paid subscription,
+    /// whichever way it is sold.
+    pub(crate) async fn mark_trial_converted(
+        &self,
+        session: &mut Session,
+        user: &User,
+    ) -> Result<(), LedgerError> {
+        if let Some(phone) = user.phone.as_deref() {
+            self.requests.mark_trial_converted(session, phone).await?;
+        }
+        Ok(())
+    }
+}
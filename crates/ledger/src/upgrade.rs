@@ -0,0 +1,86 @@
+use crate::Ledger;
+use eyre::eyre;
+use model::{decimal::Decimal, errors::LedgerError, session::Session};
+use mongodb::bson::oid::ObjectId;
+use tx_macro::tx;
+
+impl Ledger {
+    /// Replaces the subscription with a new one from the catalog. The remaining lessons
+    /// are credited toward the new price, the old subscription is retired.
+    /// The purchase points are awarded for the amount the client pays on top of the credit,
+    /// which is returned.
+    #[tx]
+    pub async fn upgrade_subscription(
+        &self,
+        session: &mut Session,
+        user_id: ObjectId,
+        subscription: ObjectId,
+        target: ObjectId,
+    ) -> Result<Decimal, LedgerError> {
+        let mut user = self
+            .users
+            .get(session, user_id)
+            .await?
+            .ok_or(LedgerError::UserNotFound(user_id))?;
+        self.users.resolve_family(session, &mut user).await?;
+        let mut payer = user.payer_mut()?;
+        let payer_id = payer.id;
+
+        let target = self
+            .subscriptions
+            .get_for_sale(session, target)
+            .await?
+            .ok_or(LedgerError::SubscriptionNotFound(target))?;
+        if target.trial {
+            return Err(eyre!(
+                "Trial subscription can't be an upgrade target: {}",
+                target.name
+            )
+            .into());
+        }
+
+        if payer.debt_for(subscription).is_some() {
+            return Err(LedgerError::SubscriptionHasDebt { subscription });
+        }
+
+        let subs = payer.subscriptions_mut();
+        let idx = subs
+            .iter()
+            .position(|sub| sub.id == subscription)
+            .ok_or(LedgerError::SubscriptionNotFound(subscription))?;
+        if subs[idx].unlimited {
+            return Err(eyre!(
+                "Unlimited subscription can't be upgraded: {}",
+                subs[idx].name
+            )
+            .into());
+        }
+        if subs[idx].locked_balance > 0 {
+            return Err(LedgerError::SubscriptionHasLockedBalance { subscription });
+        }
+        let credit = subs[idx].remaining_value();
+        if credit > target.price {
+            return Err(LedgerError::UpgradeCreditExceedsPrice {
+                credit,
+                price: target.price,
+            });
+        }
+        let retired = subs.remove(idx);
+        let amount = target.price - credit;
+        self.users.update(session, &mut payer).await?;
+
+        self.users
+            .add_subscription(session, payer_id, target.clone(), None)
+            .await?;
+        self.mark_trial_converted(session, &user).await?;
+        self.award_purchase_points(session, payer_id, target.id, target.name.clone(), amount)
+            .await?;
+        self.treasury
+            .upgrade(session, payer_id, &retired, target.clone(), credit)
+            .await?;
+        self.history
+            .upgrade_subscription(session, payer_id, retired, target, credit, amount)
+            .await?;
+        Ok(amount)
+    }
+}
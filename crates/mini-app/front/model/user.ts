@@ -13,6 +13,7 @@ export class UserView {
     employee?: Employee;
     family: Family;
     birthday?: Birthday;
+    points?: number;
 
     constructor(data: any) {
         this.id = data.id;
@@ -28,6 +29,7 @@ export class UserView {
         this.employee = data.employee;
         this.family = data.family;
         this.birthday = data.birthday;
+        this.points = data.points;
     }
 }
 
@@ -0,0 +1,50 @@
+use axum::{
+    extract::{Path, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
+    routing::get,
+    Router,
+};
+use eyre::Context as _;
+use ledger::Ledger;
+use std::sync::Arc;
+
+use crate::internal_error;
+
+/// Calendar apps can't authorize, so the feed lives outside of the auth middleware
+/// and is guarded by the user's secret token.
+pub fn routes(ledger: Arc<Ledger>) -> Router {
+    Router::new()
+        .route("/ical/:token", get(feed))
+        .with_state(ledger)
+}
+
+async fn feed(
+    State(ledger): State<Arc<Ledger>>,
+    Path(token): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let token = token.trim_end_matches(".ics");
+    let mut session = ledger
+        .db
+        .start_session()
+        .await
+        .context("Failed to start session")
+        .map_err(internal_error)?;
+    let user = ledger
+        .users
+        .find_by_calendar_token(&mut session, token)
+        .await
+        .context("Failed to find user")
+        .map_err(internal_error)?
+        .ok_or_else(|| (StatusCode::NOT_FOUND, "Not found".to_string()))?;
+    let ics = ledger
+        .calendar
+        .ical_feed(&mut session, &user)
+        .await
+        .context("Failed to build calendar")
+        .map_err(internal_error)?;
+    Ok((
+        [(header::CONTENT_TYPE, "text/calendar; charset=utf-8")],
+        ics,
+    ))
+}
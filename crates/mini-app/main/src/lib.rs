@@ -6,25 +6,32 @@ use contex::middleware as build_ctx;
 use eyre::{Error, Result};
 use jwt::JwtToken;
 use ledger::Ledger;
+use model::errors::LedgerError;
 use std::sync::Arc;
 
 pub mod auth;
 pub mod contex;
+pub mod ical;
 pub mod jwt;
 pub mod schedule;
+pub mod subscriptions;
 pub mod users;
 pub mod view;
 
 pub fn spawn(ledger: Arc<Ledger>, bot: BotApp) -> Result<()> {
+    let feeds = ical::routes(ledger.clone());
     let ctx_builder = contex::ContextBuilder::new(ledger, bot);
     tokio::spawn(async move {
         let app = Router::new()
             .merge(users::routes())
+            .merge(schedule::routes())
+            .merge(subscriptions::routes())
             .route("/auth", post(auth))
             .layer(middleware::from_fn_with_state(
                 ctx_builder.clone(),
                 build_ctx,
-            ));
+            ))
+            .merge(feeds);
         let listener = tokio::net::TcpListener::bind("0.0.0.0:3000").await.unwrap();
         log::debug!("listening on {}", listener.local_addr().unwrap());
         axum::serve(listener, app).await.unwrap();
@@ -40,3 +47,11 @@ pub fn internal_error(err: Error) -> (StatusCode, String) {
     log::error!("Internal error: {:#?}", err);
     (StatusCode::INTERNAL_SERVER_ERROR, format!("{:#?}", err))
 }
+
+pub fn ledger_error(err: LedgerError) -> (StatusCode, String) {
+    match err {
+        LedgerError::Eyre(err) => internal_error(err),
+        LedgerError::MongoError(err) => internal_error(err.into()),
+        err => (StatusCode::BAD_REQUEST, err.to_string()),
+    }
+}
@@ -0,0 +1,43 @@
+use axum::{http::StatusCode, Extension, Json};
+use bot_core::context::Context;
+use eyre::Context as _;
+use model::{
+    rights::Rule,
+    training::{Attendance, TrainingId},
+};
+use mongodb::bson::oid::ObjectId;
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::{contex::WebContext as _, internal_error, ledger_error};
+
+#[derive(Deserialize)]
+pub struct Mark {
+    id: TrainingId,
+    client: ObjectId,
+    status: Attendance,
+}
+
+pub(crate) async fn mark(
+    Extension(mut ctx): Extension<Arc<Context>>,
+    Json(Mark { id, client, status }): Json<Mark>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let ctx = Arc::get_mut(&mut ctx).expect("Context is shared");
+    let training = ctx
+        .ledger
+        .calendar
+        .get_training_by_id(&mut ctx.session, id)
+        .await
+        .context("Failed to get training")
+        .map_err(internal_error)?
+        .ok_or_else(|| (StatusCode::NOT_FOUND, "Training not found".to_string()))?;
+    if training.instructor != ctx.me.id {
+        ctx.check_rule(Rule::EditTrainingClientsList)?;
+    }
+    ctx.ledger
+        .calendar
+        .mark_attendance(&mut ctx.session, id, client, status)
+        .await
+        .map_err(ledger_error)?;
+    Ok(StatusCode::OK)
+}
@@ -0,0 +1,19 @@
+use axum::{
+    routing::{get, post},
+    Router,
+};
+
+mod attendance;
+mod personal;
+mod waitlist;
+
+pub fn routes() -> Router {
+    Router::new()
+        .route(
+            "/training/waitlist",
+            post(waitlist::join).delete(waitlist::leave),
+        )
+        .route("/training/attendance", post(attendance::mark))
+        .route("/training/personal/slots/:instructor", get(personal::slots))
+        .route("/training/personal/book", post(personal::book))
+}
@@ -0,0 +1,65 @@
+use axum::{extract::Path, http::StatusCode, Extension, Json};
+use bot_core::context::Context;
+use chrono::{DateTime, Local};
+use model::training::TrainingId;
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::ledger_error;
+
+const BOOKING_DAYS: u32 = 14;
+
+#[derive(Serialize)]
+pub struct FreeSlot {
+    start_at: DateTime<Local>,
+    duration_min: u32,
+    #[serde(serialize_with = "bson::serde_helpers::serialize_object_id_as_hex_string")]
+    room: ObjectId,
+}
+
+pub(crate) async fn slots(
+    Extension(mut ctx): Extension<Arc<Context>>,
+    Path(instructor): Path<ObjectId>,
+) -> Result<Json<Vec<FreeSlot>>, (StatusCode, String)> {
+    let ctx = Arc::get_mut(&mut ctx).expect("Context is shared");
+    let slots = ctx
+        .ledger
+        .calendar
+        .free_slots(&mut ctx.session, instructor, BOOKING_DAYS)
+        .await
+        .map_err(ledger_error)?;
+    Ok(Json(
+        slots
+            .into_iter()
+            .map(|slot| FreeSlot {
+                start_at: slot.start_at(),
+                duration_min: slot.duration_min(),
+                room: slot.room(),
+            })
+            .collect(),
+    ))
+}
+
+#[derive(Deserialize)]
+pub struct Book {
+    instructor: ObjectId,
+    start_at: DateTime<Local>,
+}
+
+pub(crate) async fn book(
+    Extension(mut ctx): Extension<Arc<Context>>,
+    Json(Book {
+        instructor,
+        start_at,
+    }): Json<Book>,
+) -> Result<Json<TrainingId>, (StatusCode, String)> {
+    let ctx = Arc::get_mut(&mut ctx).expect("Context is shared");
+    let client = ctx.me.id;
+    let id = ctx
+        .ledger
+        .book_personal_training(&mut ctx.session, client, instructor, start_at)
+        .await
+        .map_err(ledger_error)?;
+    Ok(Json(id))
+}
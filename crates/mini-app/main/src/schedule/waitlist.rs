@@ -0,0 +1,32 @@
+use axum::{http::StatusCode, Extension, Json};
+use bot_core::context::Context;
+use model::training::TrainingId;
+use std::sync::Arc;
+
+use crate::ledger_error;
+
+pub(crate) async fn join(
+    Extension(mut ctx): Extension<Arc<Context>>,
+    Json(id): Json<TrainingId>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let ctx = Arc::get_mut(&mut ctx).expect("Context is shared");
+    let user_id = ctx.me.id;
+    ctx.ledger
+        .join_waitlist(&mut ctx.session, id, user_id)
+        .await
+        .map_err(ledger_error)?;
+    Ok(StatusCode::OK)
+}
+
+pub(crate) async fn leave(
+    Extension(mut ctx): Extension<Arc<Context>>,
+    Json(id): Json<TrainingId>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let ctx = Arc::get_mut(&mut ctx).expect("Context is shared");
+    let user_id = ctx.me.id;
+    ctx.ledger
+        .leave_waitlist(&mut ctx.session, id, user_id)
+        .await
+        .map_err(ledger_error)?;
+    Ok(StatusCode::OK)
+}
@@ -0,0 +1,7 @@
+use axum::{routing::post, Router};
+
+mod promo;
+
+pub fn routes() -> Router {
+    Router::new().route("/subscription/promo", post(promo::quote))
+}
@@ -0,0 +1,51 @@
+use axum::{http::StatusCode, Extension, Json};
+use bot_core::context::Context;
+use model::{decimal::Decimal, errors::LedgerError};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::ledger_error;
+
+#[derive(Deserialize)]
+pub struct QuoteRequest {
+    subscription: ObjectId,
+    code: String,
+}
+
+#[derive(Serialize)]
+pub struct Quote {
+    code: String,
+    price: Decimal,
+    discount: Decimal,
+    total: Decimal,
+}
+
+/// Price of the subscription for the current user with the promo code applied.
+pub(crate) async fn quote(
+    Extension(mut ctx): Extension<Arc<Context>>,
+    Json(QuoteRequest { subscription, code }): Json<QuoteRequest>,
+) -> Result<Json<Quote>, (StatusCode, String)> {
+    let ctx = Arc::get_mut(&mut ctx).expect("Context is shared");
+    let sub = ctx
+        .ledger
+        .subscriptions
+        .get_for_sale(&mut ctx.session, subscription)
+        .await
+        .map_err(|err| ledger_error(err.into()))?
+        .ok_or_else(|| ledger_error(LedgerError::SubscriptionNotFound(subscription)))?;
+    let user_id = ctx.me.id;
+    let promo = ctx
+        .ledger
+        .promo
+        .check(&mut ctx.session, &code, &sub, user_id)
+        .await
+        .map_err(ledger_error)?;
+    let discount = promo.discount_amount(sub.price);
+    Ok(Json(Quote {
+        code: promo.code,
+        price: sub.price,
+        discount,
+        total: sub.price - discount,
+    }))
+}
@@ -1,5 +1,6 @@
 use axum::{extract::Path, http::StatusCode, Extension, Json};
 use bot_core::context::Context;
+use chrono::Utc;
 use eyre::Context as _;
 use model::{rights::Rule, user::sanitize_phone};
 use mongodb::bson::oid::ObjectId;
@@ -73,8 +74,30 @@ pub(crate) async fn get(
         .context("Failed to get user extension")
         .map_err(internal_error)?;
 
+    let settings = ctx
+        .ledger
+        .settings
+        .get(&mut ctx.session)
+        .await
+        .context("Failed to get settings")
+        .map_err(internal_error)?;
+    let points = if settings.loyalty.enabled {
+        let payer = user.payer().map_err(internal_error)?.as_ref().id;
+        Some(
+            ctx.ledger
+                .loyalty
+                .balance(&mut ctx.session, payer, Utc::now())
+                .await
+                .context("Failed to get loyalty points")
+                .map_err(internal_error)?,
+        )
+    } else {
+        None
+    };
+
     let mut user_view = UserView::try_from(user).map_err(internal_error)?;
     user_view.birthday = extension.birthday;
+    user_view.points = points;
 
     Ok(Json(user_view))
 }
@@ -53,6 +53,8 @@ pub struct UserView {
     pub come_from: Source,
     pub family: FamilyView,
     pub birthday: Option<Birthday>,
+    /// Loyalty points balance, `None` while the program is disabled.
+    pub points: Option<u32>,
 }
 
 impl TryFrom<User> for UserView {
@@ -82,6 +84,7 @@ impl TryFrom<User> for UserView {
             come_from: value.come_from,
             family: value.family.into(),
             birthday: None,
+            points: None,
         })
     }
 }
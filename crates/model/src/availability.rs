@@ -0,0 +1,173 @@
+use bson::oid::ObjectId;
+use chrono::{DateTime, Datelike as _, Duration, Local, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+
+use crate::{ids::DayId, slot::Slot};
+
+/// Length of a self-booked personal training, in minutes.
+pub const PERSONAL_SLOT_MIN: u32 = 60;
+
+/// Period when an instructor can't lead trainings: vacation, sick leave, etc.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Absence {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    pub instructor: ObjectId,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub from: DateTime<Utc>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub to: DateTime<Utc>,
+    pub reason: String,
+    #[serde(default)]
+    pub version: u64,
+}
+
+impl Absence {
+    pub fn new(
+        instructor: ObjectId,
+        from: DateTime<Local>,
+        to: DateTime<Local>,
+        reason: String,
+    ) -> Absence {
+        Absence {
+            id: ObjectId::new(),
+            instructor,
+            from: from.with_timezone(&Utc),
+            to: to.with_timezone(&Utc),
+            reason,
+            version: 0,
+        }
+    }
+
+    pub fn from(&self) -> DateTime<Local> {
+        self.from.with_timezone(&Local)
+    }
+
+    pub fn to(&self) -> DateTime<Local> {
+        self.to.with_timezone(&Local)
+    }
+
+    pub fn covers(&self, slot: &Slot) -> bool {
+        slot.overlaps_range(self.from(), self.to())
+    }
+}
+
+/// Weekly hours when an instructor takes personal trainings that clients can book themselves.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct AvailabilityWindow {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    pub instructor: ObjectId,
+    pub weekday: Weekday,
+    /// Minutes since midnight.
+    pub from_min: u32,
+    /// Minutes since midnight, exclusive.
+    pub to_min: u32,
+    pub room: ObjectId,
+    #[serde(default)]
+    pub version: u64,
+}
+
+impl AvailabilityWindow {
+    pub fn new(
+        instructor: ObjectId,
+        weekday: Weekday,
+        from_min: u32,
+        to_min: u32,
+        room: ObjectId,
+    ) -> AvailabilityWindow {
+        AvailabilityWindow {
+            id: ObjectId::new(),
+            instructor,
+            weekday,
+            from_min,
+            to_min,
+            room,
+            version: 0,
+        }
+    }
+
+    /// Candidate slots of the window on the given day, back to back.
+    pub fn slots(&self, day: DayId, duration_min: u32) -> Vec<Slot> {
+        if day.week_day() != self.weekday || duration_min == 0 {
+            return vec![];
+        }
+        let midnight = day.local();
+        (self.from_min..)
+            .step_by(duration_min as usize)
+            .take_while(|start| start + duration_min <= self.to_min)
+            .map(|start| {
+                Slot::new(
+                    (midnight + Duration::minutes(start as i64)).with_timezone(&Utc),
+                    duration_min,
+                    self.room,
+                )
+            })
+            .collect()
+    }
+
+    pub fn covers(&self, start_at: DateTime<Local>, duration_min: u32) -> bool {
+        if start_at.weekday() != self.weekday {
+            return false;
+        }
+        let start = (start_at - DayId::from(start_at).local()).num_minutes();
+        start >= self.from_min as i64 && start + duration_min as i64 <= self.to_min as i64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone as _;
+
+    #[test]
+    fn test_covers() {
+        let from = Local.with_ymd_and_hms(2030, 1, 10, 0, 0, 0).unwrap();
+        let to = Local.with_ymd_and_hms(2030, 1, 12, 0, 0, 0).unwrap();
+        let absence = Absence::new(ObjectId::new(), from, to, "vacation".to_string());
+
+        let slot = |day, hour| {
+            Slot::new(
+                Local
+                    .with_ymd_and_hms(2030, 1, day, hour, 0, 0)
+                    .unwrap()
+                    .with_timezone(&Utc),
+                60,
+                ObjectId::new(),
+            )
+        };
+        assert!(!absence.covers(&slot(9, 23)));
+        assert!(absence.covers(&slot(10, 0)));
+        assert!(absence.covers(&slot(11, 18)));
+        assert!(!absence.covers(&slot(12, 0)));
+    }
+
+    #[test]
+    fn test_window_slots() {
+        let day = DayId::from(Local.with_ymd_and_hms(2030, 1, 7, 12, 0, 0).unwrap());
+        let window = AvailabilityWindow::new(
+            ObjectId::new(),
+            Weekday::Mon,
+            10 * 60,
+            13 * 60 + 30,
+            ObjectId::new(),
+        );
+
+        let slots = window.slots(day, 60);
+        assert_eq!(slots.len(), 3);
+        assert_eq!(
+            slots[0].start_at(),
+            Local.with_ymd_and_hms(2030, 1, 7, 10, 0, 0).unwrap()
+        );
+        assert_eq!(
+            slots[2].start_at(),
+            Local.with_ymd_and_hms(2030, 1, 7, 12, 0, 0).unwrap()
+        );
+        assert!(window.slots(day.next(), 60).is_empty());
+
+        assert!(window.covers(Local.with_ymd_and_hms(2030, 1, 7, 12, 30, 0).unwrap(), 60));
+        assert!(!window.covers(Local.with_ymd_and_hms(2030, 1, 7, 12, 31, 0).unwrap(), 60));
+        assert!(!window.covers(Local.with_ymd_and_hms(2030, 1, 7, 9, 0, 0).unwrap(), 60));
+        assert!(!window.covers(Local.with_ymd_and_hms(2030, 1, 8, 10, 0, 0).unwrap(), 60));
+    }
+}
@@ -49,23 +49,6 @@ impl Day {
         None
     }
 
-    pub fn copy_day(id: DayId, day: Day) -> Day {
-        let training = day
-            .training
-            .into_iter()
-            .filter(|t| !t.is_one_time)
-            .map(|t| Training::with_day_and_training(id, t))
-            .collect::<Vec<_>>();
-
-        Day {
-            id: ObjectId::new(),
-            date_time: id.id(),
-            weekday: id.week_day(),
-            training,
-            version: 0,
-        }
-    }
-
     pub fn day_id(&self) -> DayId {
         unsafe { DayId::from_utc(self.date_time) }
     }
@@ -113,6 +96,8 @@ pub struct StatisticsSummary {
     pub training_count: u32,
     pub training_without_rewards: u32,
     pub clients_count: u32,
+    #[serde(default)]
+    pub no_show_count: u32,
     pub sub_avg: Decimal,
 }
 
@@ -125,6 +110,7 @@ impl StatisticsSummary {
                 acc.couch_rewards += s.couch_rewards;
                 acc.training_count += 1;
                 acc.clients_count += clients_count;
+                acc.no_show_count += s.no_show;
                 if clients_count == 0 {
                     acc.training_without_rewards += 1;
                 }
@@ -3,6 +3,8 @@ use chrono::Local;
 use thiserror::Error;
 
 use crate::{
+    availability::Absence,
+    decimal::Decimal,
     ids::DayId,
     training::{Training, TrainingId, TrainingStatus},
     user::rate::Rate,
@@ -52,6 +54,16 @@ pub enum LedgerError {
     WrongTrainingClients { training_id: TrainingId },
     #[error("Request not found")]
     RequestNotFound { id: ObjectId },
+    #[error("Subscription has locked balance:{subscription}")]
+    SubscriptionHasLockedBalance { subscription: ObjectId },
+    #[error("Subscription is not paid off:{subscription}")]
+    SubscriptionHasDebt { subscription: ObjectId },
+    #[error("Subscription not found:{0}")]
+    SubscriptionNotFound(ObjectId),
+    #[error("Invalid sale params")]
+    InvalidSaleParams,
+    #[error("Trial lesson already used:{phone}")]
+    TrialAlreadyUsed { phone: String },
 
     //new training
     #[error("Program not found:{0}")]
@@ -66,10 +78,20 @@ pub enum LedgerError {
     TooCloseToStart { start_at: chrono::DateTime<Local> },
     #[error("Time slot collision:{0:?}")]
     TimeSlotCollision(Training),
+    #[error("Instructor is busy:{0:?}")]
+    InstructorIsBusy(Training),
+    #[error("Instructor is unavailable:{0:?}")]
+    InstructorIsUnavailable(Absence),
+    #[error("Slot is not available:{start_at}")]
+    SlotNotAvailable { start_at: chrono::DateTime<Local> },
     #[error("Day id mismatch")]
     DayIdMismatch { old: DayId, new: DayId },
     #[error("Training is processed")]
     TrainingIsProcessed(TrainingId),
+    #[error("Room not found:{0}")]
+    RoomNotFound(ObjectId),
+    #[error("Room is not active:{0}")]
+    RoomIsNotActive(ObjectId),
     //signin
     #[error("Training not open to sign up")]
     TrainingNotOpenToSignUp(TrainingId, TrainingStatus),
@@ -80,6 +102,14 @@ pub enum LedgerError {
     #[error("Not enough balance:{0:?}")]
     NotEnoughBalance(ObjectId),
 
+    //waitlist
+    #[error("Training is not full:{0:?}")]
+    TrainingIsNotFull(TrainingId),
+    #[error("Client already in waitlist:{0:?} {1:?}")]
+    ClientAlreadyInWaitlist(ObjectId, TrainingId),
+    #[error("Client not in waitlist:{0:?} {1:?}")]
+    ClientNotInWaitlist(ObjectId, TrainingId),
+
     //signout
     #[error("Training not found:{0:?}")]
     TrainingNotFound(TrainingId),
@@ -90,7 +120,47 @@ pub enum LedgerError {
     #[error("Not enough reserved balance:{0:?}")]
     NotEnoughReservedBalance(ObjectId),
 
+    //attendance
+    #[error("Attendance can't be marked:{0:?}")]
+    AttendanceIsNotOpen(TrainingId),
+
     // delete training
     #[error("Training has clients")]
     TrainingHasClients(TrainingId),
+
+    // promo codes
+    #[error("Promo code not found:{code}")]
+    PromoCodeNotFound { code: String },
+    #[error("Promo code already exists:{code}")]
+    PromoCodeAlreadyExists { code: String },
+    #[error("Promo code is not valid now:{code}")]
+    PromoCodeExpired { code: String },
+    #[error("Promo code is not applicable to the subscription:{code}")]
+    PromoCodeNotApplicable { code: String },
+    #[error("Promo code usage limit reached:{code}")]
+    PromoCodeExhausted { code: String },
+    #[error("Promo code client usage limit reached:{code}")]
+    PromoCodeClientLimit { code: String },
+    // gift certificates
+    #[error("Gift certificate not found:{code}")]
+    GiftCertificateNotFound { code: String },
+    #[error("Gift certificate expired:{code}")]
+    GiftCertificateExpired { code: String },
+    #[error("Gift certificate already redeemed:{code}")]
+    GiftCertificateRedeemed { code: String },
+    #[error("Gift certificate doesn't cover the subscription:{code}")]
+    GiftCertificateNotApplicable { code: String },
+    // upgrades
+    #[error("Upgrade credit {credit} exceeds the subscription price {price}")]
+    UpgradeCreditExceedsPrice { credit: Decimal, price: Decimal },
+    // installments
+    #[error("Debt not found:{0}")]
+    DebtNotFound(ObjectId),
+    #[error("Client has an overdue payment:{0}")]
+    PaymentOverdue(ObjectId),
+    // loyalty points
+    #[error("Loyalty program is disabled")]
+    LoyaltyDisabled,
+    #[error("Not enough points:{balance} of {required}")]
+    NotEnoughPoints { balance: u32, required: u32 },
 }
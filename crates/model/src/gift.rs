@@ -0,0 +1,178 @@
+use bson::oid::ObjectId;
+use chrono::{DateTime, Utc};
+use rand::Rng as _;
+use serde::{Deserialize, Serialize};
+
+use crate::{decimal::Decimal, subscription::Subscription};
+
+const CODE_LEN: usize = 8;
+/// No `0`/`O` and `1`/`I` to keep codes readable when dictated.
+const CODE_ALPHABET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub enum GiftValue {
+    /// Any subscription priced up to the amount.
+    Amount(Decimal),
+    Subscription {
+        id: ObjectId,
+        name: String,
+    },
+}
+
+/// Prepaid gift certificate. The buyer pays for it up front, the studio owes the
+/// value until the recipient redeems it for a subscription.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GiftCertificate {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    /// Stored in upper case, see [`GiftCertificate::normalize`].
+    pub code: String,
+    pub value: GiftValue,
+    pub buyer: ObjectId,
+    /// Paid by the buyer.
+    pub price: Decimal,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub sold_at: DateTime<Utc>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub expires_at: DateTime<Utc>,
+    #[serde(default)]
+    pub redeemed: Option<GiftRedemption>,
+    #[serde(default)]
+    pub version: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GiftRedemption {
+    pub user_id: ObjectId,
+    pub subscription_id: ObjectId,
+    pub subscription_name: String,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub at: DateTime<Utc>,
+}
+
+impl GiftCertificate {
+    pub fn new(
+        value: GiftValue,
+        buyer: ObjectId,
+        price: Decimal,
+        sold_at: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
+    ) -> GiftCertificate {
+        GiftCertificate {
+            id: ObjectId::new(),
+            code: GiftCertificate::generate_code(),
+            value,
+            buyer,
+            price,
+            sold_at,
+            expires_at,
+            redeemed: None,
+            version: 0,
+        }
+    }
+
+    pub fn generate_code() -> String {
+        let mut rng = rand::thread_rng();
+        (0..CODE_LEN)
+            .map(|_| CODE_ALPHABET[rng.gen_range(0..CODE_ALPHABET.len())] as char)
+            .collect()
+    }
+
+    pub fn normalize(code: &str) -> String {
+        code.trim().to_uppercase()
+    }
+
+    pub fn is_redeemed(&self) -> bool {
+        self.redeemed.is_some()
+    }
+
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at <= now
+    }
+
+    /// The studio still owes the certificate value at the given moment.
+    pub fn is_outstanding(&self, at: DateTime<Utc>) -> bool {
+        self.sold_at <= at
+            && !self.is_expired(at)
+            && self.redeemed.as_ref().is_none_or(|r| r.at > at)
+    }
+
+    pub fn covers(&self, subscription: &Subscription) -> bool {
+        match &self.value {
+            GiftValue::Amount(amount) => subscription.price <= *amount,
+            GiftValue::Subscription { id, .. } => subscription.id == *id,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn subscription(price: i64) -> Subscription {
+        Subscription {
+            price: Decimal::int(price),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_code() {
+        let code = GiftCertificate::generate_code();
+        assert_eq!(code.len(), CODE_LEN);
+        assert_eq!(GiftCertificate::normalize(&code.to_lowercase()), code);
+    }
+
+    #[test]
+    fn test_covers() {
+        let now = Utc::now();
+        let sub = subscription(5000);
+        let gift = GiftCertificate::new(
+            GiftValue::Amount(Decimal::int(5000)),
+            ObjectId::new(),
+            Decimal::int(5000),
+            now,
+            now + Duration::days(30),
+        );
+        assert!(gift.covers(&sub));
+        assert!(!gift.covers(&subscription(6000)));
+
+        let gift = GiftCertificate::new(
+            GiftValue::Subscription {
+                id: sub.id,
+                name: sub.name.clone(),
+            },
+            ObjectId::new(),
+            Decimal::int(5000),
+            now,
+            now + Duration::days(30),
+        );
+        assert!(gift.covers(&sub));
+        assert!(!gift.covers(&subscription(5000)));
+    }
+
+    #[test]
+    fn test_outstanding() {
+        let now = Utc::now();
+        let mut gift = GiftCertificate::new(
+            GiftValue::Amount(Decimal::int(1000)),
+            ObjectId::new(),
+            Decimal::int(1000),
+            now,
+            now + Duration::days(30),
+        );
+        assert!(!gift.is_outstanding(now - Duration::days(1)));
+        assert!(gift.is_outstanding(now + Duration::days(1)));
+        assert!(!gift.is_outstanding(now + Duration::days(30)));
+
+        gift.redeemed = Some(GiftRedemption {
+            user_id: ObjectId::new(),
+            subscription_id: ObjectId::new(),
+            subscription_name: "sub".to_string(),
+            at: now + Duration::days(2),
+        });
+        assert!(gift.is_outstanding(now + Duration::days(1)));
+        assert!(!gift.is_outstanding(now + Duration::days(3)));
+    }
+}
@@ -4,7 +4,8 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     decimal::Decimal,
-    rooms::Room,
+    gift::GiftValue,
+    rooms::default_room_id,
     subscription::{Subscription, UserSubscription},
     user::UserName,
 };
@@ -50,14 +51,24 @@ pub enum Action {
     SignUp {
         start_at: DateTime<Local>,
         name: String,
-        #[serde(default)]
-        room_id: Room,
+        #[serde(default = "default_room_id")]
+        room_id: ObjectId,
     },
     SignOut {
         start_at: DateTime<Local>,
         name: String,
-        #[serde(default)]
-        room_id: Room,
+        #[serde(default = "default_room_id")]
+        room_id: ObjectId,
+    },
+    /// Sign out after the free cancellation deadline.
+    LateSignOut {
+        start_at: DateTime<Local>,
+        name: String,
+        room_id: ObjectId,
+        /// Part of the lesson written off.
+        penalty: Decimal,
+        /// A whole lesson was burned by this cancellation.
+        burned: bool,
     },
     SellSub {
         subscription: Subscription,
@@ -72,14 +83,18 @@ pub enum Action {
     FinalizedCanceledTraining {
         name: String,
         start_at: DateTime<Utc>,
-        #[serde(default)]
-        room_id: Room,
+        #[serde(default = "default_room_id")]
+        room_id: ObjectId,
     },
     FinalizedTraining {
         name: String,
         start_at: DateTime<Utc>,
+        #[serde(default = "default_room_id")]
+        room_id: ObjectId,
+        #[serde(default)]
+        no_show: Vec<ObjectId>,
         #[serde(default)]
-        room_id: Room,
+        excused: Vec<ObjectId>,
     },
     Payment {
         amount: Decimal,
@@ -116,4 +131,51 @@ pub enum Action {
     },
     RemoveFamilyMember {},
     AddFamilyMember {},
+    FreezeSubscription {
+        name: String,
+        days: u32,
+    },
+    UnfreezeSubscription {
+        name: String,
+    },
+    /// Lessons handed over to another client. The receiver is the second sub actor.
+    TransferSubscriptionOut {
+        name: String,
+        items: u32,
+    },
+    /// Lessons received from another client. The sender is the second sub actor.
+    TransferSubscriptionIn {
+        name: String,
+        items: u32,
+    },
+    /// Remaining lessons returned for money. `amount` is paid back after the fee.
+    RefundSubscription {
+        subscription: UserSubscription,
+        amount: Decimal,
+        fee: Decimal,
+    },
+    /// Gift certificate bought by the sub actor.
+    SellGiftCertificate {
+        code: String,
+        value: GiftValue,
+        price: Decimal,
+    },
+    /// Subscription received for a gift certificate by the sub actor.
+    RedeemGiftCertificate {
+        code: String,
+        subscription: Subscription,
+    },
+    /// Remaining lessons of `from` credited toward `subscription`, `amount` paid on top.
+    UpgradeSubscription {
+        from: UserSubscription,
+        subscription: Subscription,
+        credit: Decimal,
+        amount: Decimal,
+    },
+    /// Next installment of the subscription `name` paid, `remaining` still owed.
+    PayInstallment {
+        name: String,
+        amount: Decimal,
+        remaining: Decimal,
+    },
 }
@@ -0,0 +1,165 @@
+//! RFC 5545 export of trainings, so clients and instructors can subscribe to
+//! their bookings from a phone calendar.
+use bson::oid::ObjectId;
+use chrono::{DateTime, Utc};
+use rand::{distributions::Alphanumeric, Rng as _};
+
+use crate::training::Training;
+
+const PRODID: &str = "-//fitness-ledger//trainings//RU";
+const TOKEN_LEN: usize = 32;
+
+/// Secret used in the feed url instead of the user id.
+pub fn new_feed_token() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(TOKEN_LEN)
+        .map(char::from)
+        .collect()
+}
+
+/// Calendar apps match events by uid, so it has to survive renames, cancellations and
+/// moves. Trainings are only moved within their day, so the series id and the day are stable.
+pub fn uid(training: &Training) -> String {
+    format!(
+        "{}-{}@fitness-ledger",
+        training.id.to_hex(),
+        training.day_id().local().format("%Y%m%d")
+    )
+}
+
+pub fn render(
+    name: &str,
+    trainings: &[Training],
+    room_name: impl Fn(ObjectId) -> Option<String>,
+    now: DateTime<Utc>,
+) -> String {
+    let mut out = String::new();
+    line(&mut out, "BEGIN:VCALENDAR");
+    line(&mut out, "VERSION:2.0");
+    line(&mut out, &format!("PRODID:{}", PRODID));
+    line(&mut out, "CALSCALE:GREGORIAN");
+    line(&mut out, "METHOD:PUBLISH");
+    line(&mut out, &format!("X-WR-CALNAME:{}", escape(name)));
+    for training in trainings {
+        let slot = training.get_slot();
+        line(&mut out, "BEGIN:VEVENT");
+        line(&mut out, &format!("UID:{}", uid(training)));
+        line(&mut out, &format!("DTSTAMP:{}", fmt_dt(now)));
+        line(
+            &mut out,
+            &format!("DTSTART:{}", fmt_dt(training.start_at_utc())),
+        );
+        line(
+            &mut out,
+            &format!("DTEND:{}", fmt_dt(slot.end_at().with_timezone(&Utc))),
+        );
+        line(&mut out, &format!("SUMMARY:{}", escape(&training.name)));
+        if !training.description.is_empty() {
+            line(
+                &mut out,
+                &format!("DESCRIPTION:{}", escape(&training.description)),
+            );
+        }
+        if let Some(room) = room_name(slot.room()) {
+            line(&mut out, &format!("LOCATION:{}", escape(&room)));
+        }
+        let status = if training.is_canceled {
+            "CANCELLED"
+        } else {
+            "CONFIRMED"
+        };
+        line(&mut out, &format!("STATUS:{}", status));
+        line(&mut out, "END:VEVENT");
+    }
+    line(&mut out, "END:VCALENDAR");
+    out
+}
+
+fn fmt_dt(dt: DateTime<Utc>) -> String {
+    dt.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '\\' => out.push_str("\\\\"),
+            ';' => out.push_str("\\;"),
+            ',' => out.push_str("\\,"),
+            '\n' => out.push_str("\\n"),
+            '\r' => {}
+            ch => out.push(ch),
+        }
+    }
+    out
+}
+
+/// Content lines are limited to 75 octets and folded with a leading space.
+fn line(out: &mut String, content: &str) {
+    let mut len = 0;
+    for ch in content.chars() {
+        if len + ch.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            len = 1;
+        }
+        out.push(ch);
+        len += ch.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::slot::Slot;
+    use chrono::{Local, TimeZone as _};
+
+    fn training() -> Training {
+        let start_at = Local.with_ymd_and_hms(2030, 1, 10, 18, 0, 0).unwrap();
+        Training::new_personal(
+            start_at,
+            ObjectId::new(),
+            ObjectId::new(),
+            60,
+            "Инди:Анна/Мария".to_string(),
+            "растяжка; баланс, сила".to_string(),
+        )
+    }
+
+    #[test]
+    fn test_render() {
+        let mut training = training();
+        let ics = render("Мои тренировки", &[training.clone()], |_| None, Utc::now());
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert!(ics.contains(&format!("UID:{}\r\n", uid(&training))));
+        assert!(ics.contains("DESCRIPTION:растяжка\\; баланс\\, сила\r\n"));
+        assert!(ics.contains("STATUS:CONFIRMED"));
+
+        training.is_canceled = true;
+        training.name = "новое имя".to_string();
+        let canceled = render("Мои тренировки", &[training.clone()], |_| None, Utc::now());
+        assert!(canceled.contains(&format!("UID:{}\r\n", uid(&training))));
+        assert!(canceled.contains("STATUS:CANCELLED"));
+    }
+
+    #[test]
+    fn test_uid_survives_move() {
+        let mut training = training();
+        let before = uid(&training);
+        let start_at = Local.with_ymd_and_hms(2030, 1, 10, 20, 30, 0).unwrap();
+        training.set_slot(Slot::new(start_at.with_timezone(&Utc), 60, ObjectId::new()));
+        assert_eq!(uid(&training), before);
+    }
+
+    #[test]
+    fn test_fold() {
+        let mut out = String::new();
+        line(&mut out, &"й".repeat(100));
+        for row in out.split("\r\n") {
+            assert!(row.len() <= 75);
+        }
+        assert_eq!(out.replace("\r\n ", ""), format!("{}\r\n", "й".repeat(100)));
+    }
+}
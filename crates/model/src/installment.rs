@@ -0,0 +1,210 @@
+use bson::oid::ObjectId;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{decimal::Decimal, subscription::UserSubscription};
+
+/// Days between two installments.
+pub const INSTALLMENT_INTERVAL_DAYS: i64 = 30;
+/// How early the client is reminded about the next payment.
+const REMIND_BEFORE_DAYS: i64 = 1;
+
+/// How a subscription is paid off: the first payment at the sale, the rest in equal parts.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct InstallmentPlan {
+    pub first_payment: Decimal,
+    pub parts: u32,
+}
+
+/// Part of the subscription price the client owes after an installment sale.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Debt {
+    pub id: ObjectId,
+    /// Catalog subscription.
+    pub subscription_id: ObjectId,
+    /// Client subscriptions the debt pays for, all pools of a bundle.
+    #[serde(default)]
+    pub user_subscriptions: Vec<ObjectId>,
+    pub name: String,
+    pub installments: Vec<Installment>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Installment {
+    pub amount: Decimal,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub due: DateTime<Utc>,
+    #[serde(default)]
+    pub paid: bool,
+    /// The client was reminded about the upcoming payment.
+    #[serde(default)]
+    pub reminded: bool,
+    /// The client and the managers were told the payment is overdue.
+    #[serde(default)]
+    pub overdue_notified: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reminder {
+    Upcoming,
+    Overdue,
+}
+
+impl Debt {
+    /// Splits `amount` into `parts` payments, one every `INSTALLMENT_INTERVAL_DAYS` after `start`.
+    /// The last payment takes the rounding remainder.
+    pub fn new(
+        subscription_id: ObjectId,
+        name: String,
+        amount: Decimal,
+        parts: u32,
+        start: DateTime<Utc>,
+    ) -> Debt {
+        let parts = parts.max(1);
+        let part = amount / Decimal::from(parts);
+        let installments = (1..=parts)
+            .map(|idx| Installment {
+                amount: if idx == parts {
+                    amount - part * Decimal::from(parts - 1)
+                } else {
+                    part
+                },
+                due: start + Duration::days(INSTALLMENT_INTERVAL_DAYS * idx as i64),
+                paid: false,
+                reminded: false,
+                overdue_notified: false,
+            })
+            .collect();
+        Debt {
+            id: ObjectId::new(),
+            subscription_id,
+            user_subscriptions: vec![],
+            name,
+            installments,
+        }
+    }
+
+    /// Debts sold before the link to the client subscriptions fall back to the catalog id.
+    pub fn covers(&self, sub: &UserSubscription) -> bool {
+        if self.user_subscriptions.is_empty() {
+            self.subscription_id == sub.subscription_id
+        } else {
+            self.user_subscriptions.contains(&sub.id)
+        }
+    }
+
+    pub fn remaining(&self) -> Decimal {
+        self.installments
+            .iter()
+            .filter(|installment| !installment.paid)
+            .map(|installment| installment.amount)
+            .sum()
+    }
+
+    pub fn is_paid(&self) -> bool {
+        self.installments.iter().all(|installment| installment.paid)
+    }
+
+    pub fn next_payment(&self) -> Option<&Installment> {
+        self.installments
+            .iter()
+            .find(|installment| !installment.paid)
+    }
+
+    pub fn is_overdue(&self, now: DateTime<Utc>) -> bool {
+        self.next_payment()
+            .is_some_and(|installment| installment.due <= now)
+    }
+
+    /// Marks the earliest unpaid installment as paid and returns its amount.
+    pub fn pay_next(&mut self) -> Option<Decimal> {
+        let installment = self
+            .installments
+            .iter_mut()
+            .find(|installment| !installment.paid)?;
+        installment.paid = true;
+        Some(installment.amount)
+    }
+}
+
+impl Installment {
+    /// Returns the reminder that is due at `now` and remembers it was sent.
+    pub fn take_reminder(&mut self, now: DateTime<Utc>) -> Option<Reminder> {
+        if self.paid {
+            return None;
+        }
+        if self.due <= now {
+            if self.overdue_notified {
+                return None;
+            }
+            self.overdue_notified = true;
+            self.reminded = true;
+            return Some(Reminder::Overdue);
+        }
+        if !self.reminded && self.due - Duration::days(REMIND_BEFORE_DAYS) <= now {
+            self.reminded = true;
+            return Some(Reminder::Upcoming);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone as _;
+
+    #[test]
+    fn test_schedule() {
+        let start = Utc.with_ymd_and_hms(2030, 1, 1, 12, 0, 0).unwrap();
+        let mut debt = Debt::new(
+            ObjectId::new(),
+            "test".to_string(),
+            Decimal::int(1000),
+            3,
+            start,
+        );
+        assert_eq!(debt.installments.len(), 3);
+        assert_eq!(debt.installments[0].amount.inner(), 33333);
+        assert_eq!(debt.installments[2].amount.inner(), 33334);
+        assert_eq!(debt.remaining(), Decimal::int(1000));
+        assert_eq!(debt.installments[1].due, start + Duration::days(60));
+
+        assert!(!debt.is_overdue(start + Duration::days(29)));
+        assert!(debt.is_overdue(start + Duration::days(30)));
+
+        assert_eq!(debt.pay_next().map(|amount| amount.inner()), Some(33333));
+        assert_eq!(debt.remaining().inner(), 66667);
+        assert!(!debt.is_overdue(start + Duration::days(30)));
+        debt.pay_next();
+        debt.pay_next();
+        assert!(debt.is_paid());
+        assert_eq!(debt.pay_next(), None);
+        assert_eq!(debt.remaining(), Decimal::zero());
+    }
+
+    #[test]
+    fn test_reminders() {
+        let start = Utc.with_ymd_and_hms(2030, 1, 1, 12, 0, 0).unwrap();
+        let mut debt = Debt::new(
+            ObjectId::new(),
+            "test".to_string(),
+            Decimal::int(100),
+            1,
+            start,
+        );
+        let installment = &mut debt.installments[0];
+
+        assert_eq!(installment.take_reminder(start + Duration::days(20)), None);
+        assert_eq!(
+            installment.take_reminder(start + Duration::days(29)),
+            Some(Reminder::Upcoming)
+        );
+        assert_eq!(installment.take_reminder(start + Duration::days(29)), None);
+        assert_eq!(
+            installment.take_reminder(start + Duration::days(31)),
+            Some(Reminder::Overdue)
+        );
+        assert_eq!(installment.take_reminder(start + Duration::days(32)), None);
+    }
+}
@@ -14,6 +14,18 @@ pub mod statistics;
 pub mod request;
 pub mod payment;
 pub mod rooms;
+pub mod schedule;
+pub mod settings;
+pub mod availability;
+pub mod personal_series;
+pub mod ical;
 pub mod reward;
 pub mod notification;
-pub mod errors;
\ No newline at end of file
+pub mod errors;
+pub mod promo;
+pub mod gift;
+pub mod restriction;
+pub mod installment;
+pub mod waitlist;
+
+pub mod loyalty;
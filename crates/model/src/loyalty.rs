@@ -0,0 +1,206 @@
+use bson::oid::ObjectId;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{decimal::Decimal, subscription::Subscription, training::TrainingId};
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub enum PointsOperation {
+    Training {
+        training_id: TrainingId,
+        name: String,
+    },
+    Purchase {
+        subscription_id: ObjectId,
+        name: String,
+    },
+    Birthday {
+        year: i32,
+        /// Whose birthday it is, the points go to the payer of the family.
+        #[serde(default)]
+        client_id: Option<ObjectId>,
+    },
+    /// Spent as a discount on a subscription purchase.
+    Discount {
+        subscription_id: ObjectId,
+        name: String,
+    },
+    /// Exchanged for a free single lesson subscription.
+    FreeLesson {
+        subscription_id: ObjectId,
+        name: String,
+    },
+    Expired,
+}
+
+/// A single move on the client's points account. Entries are never deleted,
+/// so the collection doubles as the audit trail.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PointsEntry {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    pub user_id: ObjectId,
+    /// Positive for earned points, negative for spent and expired ones.
+    pub points: i64,
+    /// Earned points that are neither spent nor expired yet.
+    #[serde(default)]
+    pub remaining: u32,
+    pub operation: PointsOperation,
+    /// Who triggered the operation, the system user for background jobs.
+    pub actor: ObjectId,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl PointsEntry {
+    /// Earned points. They never expire if `lifetime_days` is zero.
+    pub fn earn(
+        user_id: ObjectId,
+        points: u32,
+        operation: PointsOperation,
+        actor: ObjectId,
+        now: DateTime<Utc>,
+        lifetime_days: u32,
+    ) -> PointsEntry {
+        PointsEntry {
+            id: ObjectId::new(),
+            user_id,
+            points: i64::from(points),
+            remaining: points,
+            operation,
+            actor,
+            created_at: now,
+            expires_at: (lifetime_days > 0)
+                .then(|| now + Duration::days(i64::from(lifetime_days))),
+        }
+    }
+
+    pub fn spend(
+        user_id: ObjectId,
+        points: u32,
+        operation: PointsOperation,
+        actor: ObjectId,
+        now: DateTime<Utc>,
+    ) -> PointsEntry {
+        PointsEntry {
+            id: ObjectId::new(),
+            user_id,
+            points: -i64::from(points),
+            remaining: 0,
+            operation,
+            actor,
+            created_at: now,
+            expires_at: None,
+        }
+    }
+
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+}
+
+/// Takes `points` from the earnings in the given order, so pass the oldest first.
+/// Leaves the earnings untouched if they don't cover the amount.
+pub fn take_points(earnings: &mut [PointsEntry], points: u32) -> bool {
+    let available: u64 = earnings.iter().map(|e| u64::from(e.remaining)).sum();
+    if available < u64::from(points) {
+        return false;
+    }
+    let mut left = points;
+    for earning in earnings.iter_mut() {
+        if left == 0 {
+            break;
+        }
+        let taken = earning.remaining.min(left);
+        earning.remaining -= taken;
+        left -= taken;
+    }
+    true
+}
+
+/// The discount `points` buy on a subscription priced `price` and the points it costs.
+/// Discounts are whole percents, so the leftover points stay on the account.
+pub fn points_discount(points: u32, price: Decimal) -> (Decimal, u32) {
+    let price_int = price.int_part();
+    if price_int <= 0 {
+        return (Decimal::zero(), 0);
+    }
+    let percent = (i64::from(points) * 100 / price_int).min(100);
+    let discount = Decimal::int(percent) / Decimal::int(100);
+    let cost = (price * discount).inner();
+    let spent = (cost + 99) / 100;
+    (discount, u32::try_from(spent).unwrap_or(points).min(points))
+}
+
+/// Only plain single lessons can be taken for points.
+pub fn is_free_lesson_option(sub: &Subscription) -> bool {
+    sub.items == 1 && !sub.unlimited && !sub.trial && sub.pools.is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn earning(points: u32, now: DateTime<Utc>, lifetime_days: u32) -> PointsEntry {
+        PointsEntry::earn(
+            ObjectId::new(),
+            points,
+            PointsOperation::Birthday {
+                year: 2024,
+                client_id: None,
+            },
+            ObjectId::new(),
+            now,
+            lifetime_days,
+        )
+    }
+
+    #[test]
+    fn test_expiry() {
+        let now = Utc::now();
+        let entry = earning(100, now, 30);
+        assert!(!entry.is_expired(now + Duration::days(29)));
+        assert!(entry.is_expired(now + Duration::days(30)));
+
+        let forever = earning(100, now, 0);
+        assert_eq!(forever.expires_at, None);
+        assert!(!forever.is_expired(now + Duration::days(10_000)));
+    }
+
+    #[test]
+    fn test_take_points_oldest_first() {
+        let now = Utc::now();
+        let mut earnings = vec![earning(30, now, 30), earning(50, now, 30)];
+        assert!(take_points(&mut earnings, 40));
+        assert_eq!(earnings[0].remaining, 0);
+        assert_eq!(earnings[1].remaining, 40);
+
+        assert!(!take_points(&mut earnings, 41));
+        assert_eq!(earnings[1].remaining, 40);
+
+        assert!(take_points(&mut earnings, 40));
+        assert_eq!(earnings[1].remaining, 0);
+        assert!(take_points(&mut earnings, 0));
+    }
+
+    #[test]
+    fn test_points_discount() {
+        assert_eq!(
+            points_discount(150, Decimal::int(5000)),
+            (Decimal::int(3) / Decimal::int(100), 150)
+        );
+        assert_eq!(
+            points_discount(137, Decimal::int(5000)),
+            (Decimal::int(2) / Decimal::int(100), 100)
+        );
+        assert_eq!(
+            points_discount(149, Decimal::int(4999)),
+            (Decimal::int(2) / Decimal::int(100), 100)
+        );
+        assert_eq!(points_discount(10_000, Decimal::int(4000)), (Decimal::int(1), 4000));
+        assert_eq!(points_discount(10, Decimal::int(5000)), (Decimal::zero(), 0));
+        assert_eq!(points_discount(10, Decimal::zero()), (Decimal::zero(), 0));
+    }
+}
@@ -49,7 +49,7 @@ impl Notification {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Hash)]
+#[derive(Debug, Clone, Serialize, Deserialize, Hash)]
 pub enum NotificationId {
     NotifyAboutTomorrowTraining {
         training_id: TrainingId,
@@ -74,6 +74,11 @@ pub enum NotificationId {
     RequestNotification {
         request_id: ObjectId,
     },
+    ExpiryReminder {
+        client_id: ObjectId,
+        subscription_id: ObjectId,
+        days: u32,
+    },
 }
 
 impl NotificationId {
@@ -0,0 +1,160 @@
+use bson::oid::ObjectId;
+use chrono::{DateTime, Duration, Local, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Sessions are scheduled this many days ahead, so a series doesn't lock
+/// the whole subscription at once.
+pub const SERIES_HORIZON_DAYS: i64 = 14;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum SeriesEnd {
+    /// Total number of sessions.
+    Count(u32),
+    /// No sessions start at or after this moment.
+    Until(#[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")] DateTime<Utc>),
+}
+
+/// Weekly personal trainings of one client with one instructor at the same time and room.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct PersonalSeries {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    pub client: ObjectId,
+    pub instructor: ObjectId,
+    pub room: ObjectId,
+    pub duration_min: u32,
+    pub end: SeriesEnd,
+    /// Start of the next session that is not scheduled yet.
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    next_at: DateTime<Utc>,
+    /// Sessions scheduled so far.
+    pub scheduled: u32,
+    pub is_canceled: bool,
+    #[serde(default)]
+    pub version: u64,
+}
+
+impl PersonalSeries {
+    pub fn new(
+        client: ObjectId,
+        instructor: ObjectId,
+        room: ObjectId,
+        first: DateTime<Local>,
+        duration_min: u32,
+        end: SeriesEnd,
+    ) -> PersonalSeries {
+        PersonalSeries {
+            id: ObjectId::new(),
+            client,
+            instructor,
+            room,
+            duration_min,
+            end,
+            next_at: first.with_timezone(&Utc),
+            scheduled: 0,
+            is_canceled: false,
+            version: 0,
+        }
+    }
+
+    pub fn next_at(&self) -> DateTime<Local> {
+        self.next_at.with_timezone(&Local)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.is_canceled
+            || match self.end {
+                SeriesEnd::Count(count) => self.scheduled >= count,
+                SeriesEnd::Until(until) => self.next_at >= until,
+            }
+    }
+
+    /// Next session to schedule if it falls into the horizon.
+    pub fn due(&self, now: DateTime<Local>) -> Option<DateTime<Local>> {
+        if self.is_finished() || self.next_at() > now + Duration::days(SERIES_HORIZON_DAYS) {
+            None
+        } else {
+            Some(self.next_at())
+        }
+    }
+
+    /// Moves to the next week. `scheduled` is false when the session was skipped.
+    pub fn advance(&mut self, scheduled: bool) {
+        if scheduled {
+            self.scheduled += 1;
+        }
+        self.next_at += Duration::days(7);
+    }
+
+    /// Skips the sessions that already started.
+    pub fn skip_past(&mut self, now: DateTime<Local>) {
+        while self.due(now).is_some_and(|start_at| start_at <= now) {
+            self.advance(false);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone as _;
+
+    #[test]
+    fn test_due() {
+        let now = Local.with_ymd_and_hms(2030, 1, 1, 12, 0, 0).unwrap();
+        let mut series = PersonalSeries::new(
+            ObjectId::new(),
+            ObjectId::new(),
+            ObjectId::new(),
+            Local.with_ymd_and_hms(2030, 1, 3, 10, 0, 0).unwrap(),
+            60,
+            SeriesEnd::Count(3),
+        );
+
+        let mut sessions = vec![];
+        while let Some(start_at) = series.due(now) {
+            sessions.push(start_at);
+            series.advance(true);
+        }
+        assert_eq!(sessions.len(), 2);
+        assert!(!series.is_finished());
+        assert!(series.due(now + Duration::days(7)).is_some());
+        series.advance(true);
+        assert!(series.is_finished());
+    }
+
+    #[test]
+    fn test_until() {
+        let now = Local.with_ymd_and_hms(2030, 1, 1, 12, 0, 0).unwrap();
+        let mut series = PersonalSeries::new(
+            ObjectId::new(),
+            ObjectId::new(),
+            ObjectId::new(),
+            Local.with_ymd_and_hms(2030, 1, 3, 10, 0, 0).unwrap(),
+            60,
+            SeriesEnd::Until(Local.with_ymd_and_hms(2030, 1, 10, 10, 0, 0).unwrap().into()),
+        );
+        assert!(series.due(now).is_some());
+        series.advance(false);
+        assert_eq!(series.scheduled, 0);
+        assert!(series.is_finished());
+    }
+
+    #[test]
+    fn test_skip_past() {
+        let mut series = PersonalSeries::new(
+            ObjectId::new(),
+            ObjectId::new(),
+            ObjectId::new(),
+            Local.with_ymd_and_hms(2030, 1, 3, 10, 0, 0).unwrap(),
+            60,
+            SeriesEnd::Count(5),
+        );
+        series.skip_past(Local.with_ymd_and_hms(2030, 1, 12, 12, 0, 0).unwrap());
+        assert_eq!(
+            series.next_at(),
+            Local.with_ymd_and_hms(2030, 1, 17, 10, 0, 0).unwrap()
+        );
+        assert_eq!(series.scheduled, 0);
+    }
+}
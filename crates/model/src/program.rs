@@ -1,4 +1,5 @@
 use bson::oid::ObjectId;
+use chrono::{DateTime, Local};
 use serde::{Deserialize, Serialize};
 
 use crate::decimal::Decimal;
@@ -17,6 +18,8 @@ pub struct Program {
     pub tp: TrainingType,
     #[serde(default = "default_visible")]
     pub visible: bool,
+    #[serde(default)]
+    pub windows: BookingWindows,
 }
 
 fn default_visible() -> bool {
@@ -34,6 +37,40 @@ impl Default for Program {
             version: 0,
             tp: TrainingType::Group { is_free: false },
             visible: true,
+            windows: BookingWindows::default(),
+        }
+    }
+}
+
+/// Sign up and cancellation rules, in minutes before the start of a training.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct BookingWindows {
+    /// Sign up opens this long before the start. `None` - as soon as the training is scheduled.
+    #[serde(default)]
+    pub sign_up_open_min: Option<u32>,
+    /// Trainings without clients stop accepting sign ups this long before the start.
+    pub sign_up_close_min: u32,
+    /// Clients can cancel their sign up without penalty until this long before the start.
+    pub cancel_min: u32,
+}
+
+impl BookingWindows {
+    /// Whether a new training starting at `start_at` can still take its first sign up.
+    pub fn accepts(&self, start_at: DateTime<Local>, now: DateTime<Local>) -> bool {
+        let close_at = start_at - chrono::Duration::minutes(self.sign_up_close_min as i64);
+        let opened = self
+            .sign_up_open_min
+            .is_none_or(|min| start_at - chrono::Duration::minutes(min as i64) <= now);
+        opened && now <= close_at
+    }
+}
+
+impl Default for BookingWindows {
+    fn default() -> Self {
+        BookingWindows {
+            sign_up_open_min: None,
+            sign_up_close_min: 3 * 60,
+            cancel_min: 3 * 60,
         }
     }
 }
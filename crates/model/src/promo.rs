@@ -0,0 +1,157 @@
+use bson::oid::ObjectId;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{decimal::Decimal, statistics::source::Source};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum PromoDiscount {
+    Percent(u32),
+    /// Amount off the subscription price, never more than the price itself.
+    Fixed(Decimal),
+}
+
+/// Promo code of a discount campaign.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PromoCode {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    /// Stored in upper case, see [`PromoCode::normalize`].
+    pub code: String,
+    pub discount: PromoDiscount,
+    /// Subscriptions the code applies to. Empty means any subscription.
+    #[serde(default)]
+    pub subscriptions: Vec<ObjectId>,
+    #[serde(default)]
+    pub valid_from: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub valid_to: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub max_uses: Option<u32>,
+    #[serde(default)]
+    pub max_uses_per_client: Option<u32>,
+    /// Campaign channel the sales are attributed to.
+    pub source: Source,
+    #[serde(default)]
+    pub uses: u32,
+    #[serde(default)]
+    pub is_archived: bool,
+    #[serde(default)]
+    pub version: u64,
+}
+
+impl PromoCode {
+    pub fn new(code: &str, discount: PromoDiscount, source: Source) -> PromoCode {
+        PromoCode {
+            id: ObjectId::new(),
+            code: PromoCode::normalize(code),
+            discount,
+            subscriptions: vec![],
+            valid_from: None,
+            valid_to: None,
+            max_uses: None,
+            max_uses_per_client: None,
+            source,
+            uses: 0,
+            is_archived: false,
+            version: 0,
+        }
+    }
+
+    pub fn normalize(code: &str) -> String {
+        code.trim().to_uppercase()
+    }
+
+    pub fn is_valid_at(&self, now: DateTime<Utc>) -> bool {
+        !self.is_archived
+            && self.valid_from.is_none_or(|from| from <= now)
+            && self.valid_to.is_none_or(|to| now < to)
+    }
+
+    pub fn applies_to(&self, subscription: ObjectId) -> bool {
+        self.subscriptions.is_empty() || self.subscriptions.contains(&subscription)
+    }
+
+    pub fn is_exhausted(&self) -> bool {
+        self.max_uses.is_some_and(|max| self.uses >= max)
+    }
+
+    /// Amount taken off the price, never more than the price itself.
+    pub fn discount_amount(&self, price: Decimal) -> Decimal {
+        if price <= Decimal::zero() {
+            return Decimal::zero();
+        }
+        match self.discount {
+            PromoDiscount::Percent(percent) => {
+                price * Decimal::from(percent.min(100)) / Decimal::int(100)
+            }
+            PromoDiscount::Fixed(amount) => amount.min(price),
+        }
+    }
+}
+
+/// A sale made with a promo code.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PromoRedemption {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    pub promo_id: ObjectId,
+    pub code: String,
+    pub source: Source,
+    pub user_id: ObjectId,
+    pub subscription_id: ObjectId,
+    pub subscription_name: String,
+    /// Paid by the client.
+    pub price: Decimal,
+    /// Amount taken off the full price.
+    pub discount: Decimal,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub date_time: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_discount() {
+        let promo = PromoCode::new("spring", PromoDiscount::Percent(15), Source::Other {});
+        assert_eq!(promo.code, "SPRING");
+        assert_eq!(promo.discount_amount(Decimal::int(1000)), Decimal::int(150));
+
+        let promo = PromoCode::new(
+            "minus",
+            PromoDiscount::Fixed(Decimal::int(500)),
+            Source::VK {},
+        );
+        assert_eq!(promo.discount_amount(Decimal::int(2000)), Decimal::int(500));
+        assert_eq!(promo.discount_amount(Decimal::int(1500)), Decimal::int(500));
+        assert_eq!(promo.discount_amount(Decimal::int(300)), Decimal::int(300));
+    }
+
+    #[test]
+    fn test_validity() {
+        let now = Utc::now();
+        let mut promo = PromoCode::new("code", PromoDiscount::Percent(10), Source::Other {});
+        assert!(promo.is_valid_at(now));
+
+        promo.valid_from = Some(now + Duration::days(1));
+        assert!(!promo.is_valid_at(now));
+        promo.valid_from = Some(now - Duration::days(2));
+        promo.valid_to = Some(now - Duration::days(1));
+        assert!(!promo.is_valid_at(now));
+        promo.valid_to = None;
+        assert!(promo.is_valid_at(now));
+
+        let sub = ObjectId::new();
+        assert!(promo.applies_to(sub));
+        promo.subscriptions = vec![ObjectId::new()];
+        assert!(!promo.applies_to(sub));
+
+        promo.max_uses = Some(1);
+        assert!(!promo.is_exhausted());
+        promo.uses = 1;
+        assert!(promo.is_exhausted());
+    }
+}
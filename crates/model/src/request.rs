@@ -2,7 +2,7 @@ use bson::oid::ObjectId;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-use crate::statistics::source::Source;
+use crate::{statistics::source::Source, training::TrainingId};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Request {
@@ -21,6 +21,8 @@ pub struct Request {
     pub history: Vec<RequestHistoryRow>,
     #[serde(default)]
     pub remind_later: Option<RemindLater>,
+    #[serde(default)]
+    pub trial: Option<TrialLesson>,
 }
 
 impl Request {
@@ -41,6 +43,7 @@ impl Request {
             last_name,
             history: vec![],
             remind_later,
+            trial: None,
             created: Utc::now(),
             modified: Utc::now(),
         }
@@ -60,3 +63,15 @@ pub struct RemindLater {
     pub date_time: DateTime<Utc>,
     pub user_id: ObjectId,
 }
+
+/// Trial lesson booked for the lead.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TrialLesson {
+    pub user_id: ObjectId,
+    pub training: TrainingId,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub booked_at: DateTime<Utc>,
+    /// Set when the client buys a regular subscription after the trial.
+    #[serde(default)]
+    pub converted_at: Option<DateTime<Utc>>,
+}
@@ -0,0 +1,222 @@
+use chrono::{DateTime, Datelike as _, Duration, Local, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ids::{DayId, WeekId},
+    slot::Slot,
+};
+
+/// Visits older than this are no longer needed to check the weekly limit.
+const VISITS_TTL_DAYS: i64 = 14;
+
+/// Limits a subscription to certain days and hours, e.g. a morning pass.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+pub struct TimeRestriction {
+    /// Empty means any day.
+    #[serde(default)]
+    pub weekdays: Vec<Weekday>,
+    /// Empty means any time of day.
+    #[serde(default)]
+    pub windows: Vec<TimeWindow>,
+    #[serde(default)]
+    pub weekly_limit: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TimeWindow {
+    /// Minutes since midnight.
+    pub from_min: u32,
+    /// Minutes since midnight, exclusive.
+    pub to_min: u32,
+}
+
+impl TimeWindow {
+    pub fn new(from_min: u32, to_min: u32) -> Option<TimeWindow> {
+        (from_min < to_min && to_min <= 24 * 60).then_some(TimeWindow { from_min, to_min })
+    }
+
+    pub fn covers(&self, slot: &Slot) -> bool {
+        let start = (slot.start_at() - DayId::from(slot.start_at()).local()).num_minutes();
+        start >= self.from_min as i64 && start + slot.duration_min() as i64 <= self.to_min as i64
+    }
+}
+
+/// Why a subscription can't pay for a training.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Uncovered {
+    Weekday,
+    Time,
+    WeeklyLimit(u32),
+}
+
+impl TimeRestriction {
+    pub fn is_empty(&self) -> bool {
+        self.weekdays.is_empty() && self.windows.is_empty() && self.weekly_limit.is_none()
+    }
+
+    pub fn toggle_weekday(&mut self, weekday: Weekday) {
+        if self.weekdays.contains(&weekday) {
+            self.weekdays.retain(|day| *day != weekday);
+        } else {
+            self.weekdays.push(weekday);
+            self.weekdays.sort_by_key(|day| day.num_days_from_monday());
+        }
+    }
+
+    pub fn add_window(&mut self, window: TimeWindow) {
+        if !self.windows.contains(&window) {
+            self.windows.push(window);
+            self.windows.sort_by_key(|window| window.from_min);
+        }
+    }
+
+    /// Checks the day and the hours of the training.
+    pub fn check_slot(&self, slot: &Slot) -> Result<(), Uncovered> {
+        if !self.weekdays.is_empty() && !self.weekdays.contains(&slot.start_at().weekday()) {
+            return Err(Uncovered::Weekday);
+        }
+        if !self.windows.is_empty() && !self.windows.iter().any(|window| window.covers(slot)) {
+            return Err(Uncovered::Time);
+        }
+        Ok(())
+    }
+
+    /// Checks that one more visit in the week of the training fits the weekly limit.
+    pub fn check_limit(&self, slot: &Slot, visits: &[DateTime<Utc>]) -> Result<(), Uncovered> {
+        let Some(limit) = self.weekly_limit else {
+            return Ok(());
+        };
+        let week = WeekId::new(slot.start_at()).local();
+        let used = visits
+            .iter()
+            .filter(|visit| WeekId::new(visit.with_timezone(&Local)).local() == week)
+            .count() as u32;
+        if used >= limit {
+            Err(Uncovered::WeeklyLimit(limit))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Remembers the training in `visits` and drops the ones too old to matter.
+/// The age is counted from `now`: a booking weeks ahead must keep this week's visits.
+pub fn add_visit(visits: &mut Vec<DateTime<Utc>>, start_at: DateTime<Utc>, now: DateTime<Utc>) {
+    let ttl = now - Duration::days(VISITS_TTL_DAYS);
+    visits.retain(|visit| *visit > ttl);
+    visits.push(start_at);
+}
+
+pub fn remove_visit(visits: &mut Vec<DateTime<Utc>>, start_at: DateTime<Utc>) {
+    if let Some(idx) = visits.iter().position(|visit| *visit == start_at) {
+        visits.remove(idx);
+    }
+}
+
+pub fn move_visit(visits: &mut [DateTime<Utc>], from: DateTime<Utc>, to: DateTime<Utc>) -> bool {
+    if let Some(visit) = visits.iter_mut().find(|visit| **visit == from) {
+        *visit = to;
+        true
+    } else {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bson::oid::ObjectId;
+    use chrono::TimeZone as _;
+
+    fn slot(day: u32, hour: u32, duration_min: u32) -> Slot {
+        // 2030-01-07 is Monday.
+        Slot::new(
+            Local
+                .with_ymd_and_hms(2030, 1, day, hour, 0, 0)
+                .unwrap()
+                .with_timezone(&Utc),
+            duration_min,
+            ObjectId::new(),
+        )
+    }
+
+    #[test]
+    fn test_check_slot() {
+        let mut restriction = TimeRestriction::default();
+        assert!(restriction.is_empty());
+        assert_eq!(restriction.check_slot(&slot(12, 20, 60)), Ok(()));
+
+        restriction.toggle_weekday(Weekday::Tue);
+        restriction.toggle_weekday(Weekday::Mon);
+        assert_eq!(restriction.weekdays, vec![Weekday::Mon, Weekday::Tue]);
+        assert_eq!(restriction.check_slot(&slot(7, 20, 60)), Ok(()));
+        assert_eq!(
+            restriction.check_slot(&slot(9, 20, 60)),
+            Err(Uncovered::Weekday)
+        );
+
+        restriction.add_window(TimeWindow::new(8 * 60, 12 * 60).unwrap());
+        assert_eq!(restriction.check_slot(&slot(7, 8, 60)), Ok(()));
+        assert_eq!(restriction.check_slot(&slot(8, 11, 60)), Ok(()));
+        assert_eq!(
+            restriction.check_slot(&slot(8, 11, 90)),
+            Err(Uncovered::Time)
+        );
+        assert_eq!(
+            restriction.check_slot(&slot(7, 20, 60)),
+            Err(Uncovered::Time)
+        );
+        assert!(TimeWindow::new(12 * 60, 8 * 60).is_none());
+    }
+
+    #[test]
+    fn test_check_limit() {
+        let restriction = TimeRestriction {
+            weekly_limit: Some(2),
+            ..Default::default()
+        };
+        let now = slot(6, 10, 60).start_at_utc();
+        let mut visits = vec![];
+        add_visit(&mut visits, slot(7, 10, 60).start_at_utc(), now);
+        assert_eq!(restriction.check_limit(&slot(9, 10, 60), &visits), Ok(()));
+        add_visit(&mut visits, slot(9, 10, 60).start_at_utc(), now);
+        assert_eq!(
+            restriction.check_limit(&slot(11, 10, 60), &visits),
+            Err(Uncovered::WeeklyLimit(2))
+        );
+        assert_eq!(restriction.check_limit(&slot(14, 10, 60), &visits), Ok(()));
+
+        remove_visit(&mut visits, slot(9, 10, 60).start_at_utc());
+        assert_eq!(restriction.check_limit(&slot(11, 10, 60), &visits), Ok(()));
+
+        let moved = slot(7, 18, 60).start_at_utc();
+        assert!(move_visit(
+            &mut visits,
+            slot(7, 10, 60).start_at_utc(),
+            moved
+        ));
+        assert_eq!(visits, vec![moved]);
+        assert!(!move_visit(
+            &mut visits,
+            slot(7, 10, 60).start_at_utc(),
+            moved
+        ));
+
+        // A booking weeks ahead keeps this week's visits.
+        add_visit(&mut visits, slot(28, 10, 60).start_at_utc(), now);
+        assert_eq!(visits.len(), 2);
+        assert_eq!(restriction.check_limit(&slot(8, 10, 60), &visits), Ok(()));
+        add_visit(&mut visits, slot(8, 10, 60).start_at_utc(), now);
+        assert_eq!(
+            restriction.check_limit(&slot(10, 10, 60), &visits),
+            Err(Uncovered::WeeklyLimit(2))
+        );
+
+        add_visit(
+            &mut visits,
+            slot(29, 10, 60).start_at_utc(),
+            slot(28, 10, 60).start_at_utc(),
+        );
+        assert_eq!(visits.len(), 2);
+    }
+}
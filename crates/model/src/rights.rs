@@ -165,6 +165,22 @@ pub enum Rule {
     AIStatistic,
     AIUserInfo,
     SelectModel,
+
+    //schedule
+    EditScheduleTemplates,
+    EditRooms,
+
+    //subscription transfer
+    TransferSubscription,
+
+    // refunds
+    RefundSubscription,
+
+    // promo codes
+    EditPromoCodes,
+
+    // gift certificates
+    SellGiftCertificate,
 }
 
 impl Rule {
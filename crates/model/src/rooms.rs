@@ -1,37 +1,90 @@
 use bson::oid::ObjectId;
 use serde::{Deserialize, Serialize};
-use std::fmt;
-use std::fmt::Display;
-use std::fmt::Formatter;
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
-pub enum Room {
-    #[default]
-    Adult,
-    Child,
+
+/// Rooms that existed before rooms became a collection. Trainings and history keep their ids.
+pub const ADULT_ROOM_ID: [u8; 12] = *b"adult0000000";
+pub const CHILD_ROOM_ID: [u8; 12] = *b"child0000000";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Room {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    pub name: String,
+    pub emoji: String,
+    pub capacity: u32,
+    pub ordering: u32,
+    pub is_active: bool,
+    #[serde(default)]
+    pub version: u64,
 }
 
 impl Room {
-    pub fn id(&self) -> ObjectId {
-        match self {
-            Room::Adult => ObjectId::from_bytes(*b"adult0000000"),
-            Room::Child => ObjectId::from_bytes(*b"child0000000"),
+    pub fn new(name: String, emoji: String, capacity: u32, ordering: u32) -> Room {
+        Room {
+            id: ObjectId::new(),
+            name,
+            emoji,
+            capacity,
+            ordering,
+            is_active: true,
+            version: 0,
         }
     }
-}
 
-impl From<ObjectId> for Room {
-    fn from(id: ObjectId) -> Self {
-        match &id.bytes() {
-            b"adult0000000" => Room::Adult,
-            b"child0000000" => Room::Child,
-            _ => Room::default(),
+    pub fn legacy() -> Vec<Room> {
+        vec![
+            Room {
+                id: ObjectId::from_bytes(ADULT_ROOM_ID),
+                name: "Взрослые".to_string(),
+                emoji: "🧘".to_string(),
+                capacity: 0,
+                ordering: 0,
+                is_active: true,
+                version: 0,
+            },
+            Room {
+                id: ObjectId::from_bytes(CHILD_ROOM_ID),
+                name: "Дети".to_string(),
+                emoji: "🧒".to_string(),
+                capacity: 0,
+                ordering: 1,
+                is_active: true,
+                version: 0,
+            },
+        ]
+    }
+
+    /// Capacity of zero means the room does not limit trainings.
+    pub fn limit_capacity(&self, capacity: u32) -> u32 {
+        if self.capacity == 0 {
+            capacity
+        } else {
+            capacity.min(self.capacity)
         }
     }
 }
 
-impl Display for Room {
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "{:?}", self)
+pub fn default_room_id() -> ObjectId {
+    ObjectId::from_bytes(ADULT_ROOM_ID)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limit_capacity() {
+        let mut room = Room::new("hall".to_string(), "🏠".to_string(), 0, 0);
+        assert_eq!(room.limit_capacity(12), 12);
+        room.capacity = 8;
+        assert_eq!(room.limit_capacity(12), 8);
+        assert_eq!(room.limit_capacity(4), 4);
+    }
+
+    #[test]
+    fn test_legacy_ids() {
+        let legacy = Room::legacy();
+        assert_eq!(legacy[0].id, default_room_id());
+        assert_eq!(legacy[1].id, ObjectId::from_bytes(CHILD_ROOM_ID));
     }
 }
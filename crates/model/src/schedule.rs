@@ -0,0 +1,251 @@
+use chrono::{DateTime, Local, Timelike as _, Utc, Weekday};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    day::Day,
+    ids::DayId,
+    program::Program,
+    training::{Training, TrainingId},
+};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ScheduleTemplate {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    pub name: String,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub effective_from: DateTime<Utc>,
+    #[serde(default)]
+    pub effective_to: Option<DateTime<Utc>>,
+    pub trainings: Vec<TemplateTraining>,
+    #[serde(default)]
+    pub version: u64,
+}
+
+impl ScheduleTemplate {
+    pub fn new(name: String, effective_from: DayId, trainings: Vec<TemplateTraining>) -> Self {
+        ScheduleTemplate {
+            id: ObjectId::new(),
+            name,
+            effective_from: effective_from.id(),
+            effective_to: None,
+            trainings,
+            version: 0,
+        }
+    }
+
+    pub fn is_effective(&self, day: DayId) -> bool {
+        self.effective_from <= day.id() && self.effective_to.is_none_or(|to| day.id() < to)
+    }
+
+    pub fn day_trainings(&self, day: DayId) -> impl Iterator<Item = &TemplateTraining> {
+        let weekday = day.week_day();
+        self.trainings.iter().filter(move |t| t.weekday == weekday)
+    }
+}
+
+/// Recurring group training. Every training materialized from the entry shares its id.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct TemplateTraining {
+    pub id: ObjectId,
+    pub weekday: Weekday,
+    pub hour: u32,
+    pub minute: u32,
+    pub proto_id: ObjectId,
+    pub instructor: ObjectId,
+    pub room: ObjectId,
+    /// Replaces the program name, set when the whole series is renamed.
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+impl TemplateTraining {
+    pub fn from_training(training: &Training) -> Self {
+        let start_at = training.get_slot().start_at();
+        TemplateTraining {
+            id: training.id,
+            weekday: training.day_id().week_day(),
+            hour: start_at.hour(),
+            minute: start_at.minute(),
+            proto_id: training.proto_id,
+            instructor: training.instructor,
+            room: training.room(),
+            name: None,
+        }
+    }
+
+    pub fn start_at(&self, day: DayId) -> DateTime<Local> {
+        day.local()
+            .with_hour(self.hour)
+            .and_then(|d| d.with_minute(self.minute))
+            .unwrap_or_else(|| day.local())
+    }
+
+    pub fn materialize(&self, day: DayId, program: Program) -> Training {
+        let mut training =
+            Training::new_group(program, self.start_at(day), self.instructor, false, self.room);
+        training.id = self.id;
+        if let Some(name) = &self.name {
+            training.name = name.clone();
+        }
+        training
+    }
+}
+
+/// Changes required to bring an already materialized day in line with a template.
+#[derive(Debug, Clone)]
+pub struct DayDiff {
+    pub day_id: DayId,
+    pub add: Vec<Training>,
+    pub remove: Vec<Training>,
+    /// Trainings that differ from the template but have clients and are left as is.
+    pub blocked: Vec<Training>,
+}
+
+impl DayDiff {
+    pub fn new(day: &Day, planned: Vec<Training>) -> DayDiff {
+        let mut add = vec![];
+        let mut remove = vec![];
+        let mut blocked = vec![];
+
+        let same = |old: &Training, new: &Training| {
+            old.id == new.id
+                && old.id() == new.id()
+                && old.instructor == new.instructor
+                && old.proto_id == new.proto_id
+        };
+
+        for old in &day.training {
+            if old.is_one_time || old.is_processed || old.is_canceled || !old.is_group() {
+                continue;
+            }
+            if planned.iter().any(|new| same(old, new)) {
+                continue;
+            }
+            if old.clients.is_empty() {
+                remove.push(old.clone());
+            } else {
+                blocked.push(old.clone());
+            }
+        }
+
+        for new in planned {
+            let exists = day.training.iter().any(|old| same(old, &new));
+            let blocked_by = blocked.iter().any(|old| old.id == new.id);
+            if !exists && !blocked_by {
+                add.push(new);
+            }
+        }
+
+        DayDiff {
+            day_id: day.day_id(),
+            add,
+            remove,
+            blocked,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.add.is_empty() && self.remove.is_empty() && self.blocked.is_empty()
+    }
+
+    pub fn removed_ids(&self) -> impl Iterator<Item = TrainingId> + '_ {
+        self.remove.iter().map(|t| t.id())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::program::TrainingType;
+    use chrono::TimeZone as _;
+
+    fn program() -> Program {
+        Program {
+            id: ObjectId::new(),
+            name: "yoga".to_string(),
+            description: "".to_string(),
+            duration_min: 60,
+            capacity: 10,
+            version: 0,
+            tp: TrainingType::Group { is_free: false },
+            visible: true,
+            windows: Default::default(),
+        }
+    }
+
+    fn day_id() -> DayId {
+        DayId::from(Local.with_ymd_and_hms(2030, 1, 7, 0, 0, 0).unwrap())
+    }
+
+    fn entry(program: &Program, hour: u32) -> TemplateTraining {
+        TemplateTraining {
+            id: ObjectId::new(),
+            weekday: Weekday::Mon,
+            hour,
+            minute: 0,
+            proto_id: program.id,
+            instructor: ObjectId::from_bytes([1; 12]),
+            room: ObjectId::from_bytes([2; 12]),
+            name: None,
+        }
+    }
+
+    #[test]
+    fn test_materialize() {
+        let program = program();
+        let entry = entry(&program, 10);
+        let training = entry.materialize(day_id(), program.clone());
+        assert_eq!(training.id, entry.id);
+        assert_eq!(training.get_slot().start_at().hour(), 10);
+        assert!(!training.is_one_time);
+        assert_eq!(TemplateTraining::from_training(&training), entry);
+
+        let renamed = TemplateTraining {
+            name: Some("stretching".to_string()),
+            ..entry
+        };
+        assert_eq!(renamed.materialize(day_id(), program).name, "stretching");
+    }
+
+    #[test]
+    fn test_is_effective() {
+        let mut template = ScheduleTemplate::new("t".to_string(), day_id(), vec![]);
+        assert!(template.is_effective(day_id()));
+        assert!(!template.is_effective(day_id().prev()));
+        template.effective_to = Some(day_id().next().id());
+        assert!(template.is_effective(day_id()));
+        assert!(!template.is_effective(day_id().next()));
+    }
+
+    #[test]
+    fn test_diff() {
+        let program = program();
+        let kept = entry(&program, 10);
+        let moved = entry(&program, 12);
+        let new = entry(&program, 18);
+
+        let mut day = Day::new(day_id());
+        day.training.push(kept.materialize(day_id(), program.clone()));
+        let mut busy = moved.materialize(day_id(), program.clone());
+        busy.clients.push(ObjectId::new());
+        day.training.push(busy);
+        let dropped = entry(&program, 20).materialize(day_id(), program.clone());
+        day.training.push(dropped.clone());
+
+        let mut moved = moved;
+        moved.hour = 13;
+        let planned = [kept, moved, new.clone()]
+            .iter()
+            .map(|e| e.materialize(day_id(), program.clone()))
+            .collect();
+
+        let diff = DayDiff::new(&day, planned);
+        assert_eq!(diff.add.len(), 1);
+        assert_eq!(diff.add[0].id, new.id);
+        assert_eq!(diff.remove.len(), 1);
+        assert_eq!(diff.remove[0].id, dropped.id);
+        assert_eq!(diff.blocked.len(), 1);
+    }
+}
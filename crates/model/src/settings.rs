@@ -0,0 +1,145 @@
+use bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+use crate::{decimal::Decimal, program::BookingWindows};
+
+const SETTINGS_ID: [u8; 12] = *b"settings0000";
+
+/// Studio wide rules. Stored as a single document.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Settings {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    #[serde(default)]
+    pub no_show: NoShowPolicy,
+    /// How long after the end of a training the instructor can mark attendance.
+    #[serde(default = "default_attendance_window")]
+    pub attendance_window_min: u32,
+    /// Personal trainings have no program, so their windows are set here.
+    #[serde(default)]
+    pub personal_windows: BookingWindows,
+    #[serde(default)]
+    pub late_cancel: LateCancelPolicy,
+    /// Part of the refundable amount kept by the studio, in percent.
+    #[serde(default)]
+    pub refund_fee_percent: u32,
+    /// Clients with an overdue installment can't sign up for trainings.
+    #[serde(default)]
+    pub block_overdue_sign_up: bool,
+    /// Days before the end of a subscription to warn the client about unused lessons.
+    #[serde(default = "default_expiry_reminder_days")]
+    pub expiry_reminder_days: Vec<u32>,
+    #[serde(default)]
+    pub loyalty: LoyaltyPolicy,
+    #[serde(default)]
+    pub version: u64,
+}
+
+impl Settings {
+    pub fn id() -> ObjectId {
+        ObjectId::from_bytes(SETTINGS_ID)
+    }
+
+    pub fn refund_fee(&self) -> Decimal {
+        Decimal::from(self.refund_fee_percent.min(100)) / Decimal::int(100)
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            id: Settings::id(),
+            no_show: NoShowPolicy::default(),
+            attendance_window_min: default_attendance_window(),
+            personal_windows: BookingWindows::default(),
+            late_cancel: LateCancelPolicy::default(),
+            refund_fee_percent: 0,
+            block_overdue_sign_up: false,
+            expiry_reminder_days: default_expiry_reminder_days(),
+            loyalty: LoyaltyPolicy::default(),
+            version: 0,
+        }
+    }
+}
+
+fn default_attendance_window() -> u32 {
+    2 * 60
+}
+
+fn default_expiry_reminder_days() -> Vec<u32> {
+    vec![3]
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NoShowPolicy {
+    /// The lesson is written off as if the client attended.
+    #[default]
+    Charge,
+    /// The locked lesson is returned to the subscription.
+    Free,
+}
+
+/// What happens when a client cancels after the free cancellation deadline.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct LateCancelPolicy {
+    /// Without it clients can't cancel after the deadline at all.
+    pub allowed: bool,
+    /// Part of the lesson written off, in percent.
+    pub penalty_percent: u32,
+    /// The written off part counts towards the instructor reward.
+    pub credit_instructor: bool,
+}
+
+impl LateCancelPolicy {
+    pub fn penalty(&self) -> Decimal {
+        Decimal::from(self.penalty_percent.min(100)) / Decimal::int(100)
+    }
+}
+
+impl Default for LateCancelPolicy {
+    fn default() -> Self {
+        LateCancelPolicy {
+            allowed: false,
+            penalty_percent: 100,
+            credit_instructor: false,
+        }
+    }
+}
+
+/// Loyalty points program. A point is worth a rouble of discount.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct LoyaltyPolicy {
+    pub enabled: bool,
+    /// Earned for every finalized training the client attended.
+    pub training_points: u32,
+    /// Earned for a subscription purchase, in percent of the paid price.
+    pub purchase_percent: u32,
+    pub birthday_points: u32,
+    /// Price of a free single lesson in points.
+    pub free_lesson_cost: u32,
+    /// Points expire after this many days, zero keeps them forever.
+    pub lifetime_days: u32,
+}
+
+impl LoyaltyPolicy {
+    pub fn purchase_points(&self, paid: Decimal) -> u32 {
+        if paid.is_negative() {
+            return 0;
+        }
+        let points = paid.int_part() * i64::from(self.purchase_percent) / 100;
+        u32::try_from(points).unwrap_or(u32::MAX)
+    }
+}
+
+impl Default for LoyaltyPolicy {
+    fn default() -> Self {
+        LoyaltyPolicy {
+            enabled: false,
+            training_points: 10,
+            purchase_percent: 5,
+            birthday_points: 500,
+            free_lesson_cost: 1000,
+            lifetime_days: 365,
+        }
+    }
+}
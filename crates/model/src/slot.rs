@@ -56,10 +56,11 @@ impl Slot {
     }
 
     pub fn has_conflict(&self, other: &Slot) -> bool {
-        if self.room != other.room {
-            return false;
-        }
+        self.room == other.room && self.overlaps(other)
+    }
 
+    /// Same as `has_conflict`, but rooms are not taken into account.
+    pub fn overlaps(&self, other: &Slot) -> bool {
         let this_start = self.start_at + chrono::Duration::milliseconds(1);
         let this_end = self.start_at + chrono::Duration::minutes(self.duration_min as i64)
             - chrono::Duration::milliseconds(1);
@@ -83,6 +84,10 @@ impl Slot {
         false
     }
 
+    pub fn overlaps_range(&self, from: DateTime<Local>, to: DateTime<Local>) -> bool {
+        self.start_at() < to && from < self.end_at()
+    }
+
     pub fn day_id(&self) -> crate::ids::DayId {
         DayId::from(self.start_at())
     }
@@ -483,4 +488,17 @@ mod tests {
 
         assert!(!slot.in_slot(time));
     }
+
+    #[test]
+    fn test_overlaps_other_room() {
+        let start_at = Utc
+            .with_ymd_and_hms(2023, 10, 1, 12, 0, 0)
+            .single()
+            .unwrap();
+        let slot1 = Slot::new(start_at, 60, ObjectId::new());
+        let slot2 = Slot::new(start_at + chrono::Duration::minutes(30), 60, ObjectId::new());
+
+        assert!(!slot1.has_conflict(&slot2));
+        assert!(slot1.overlaps(&slot2));
+    }
 }
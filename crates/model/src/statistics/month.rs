@@ -15,6 +15,7 @@ impl Default for MonthStatistics {
         MonthStatistics {
             marketing: MarketingStat {
                 source: HashMap::new(),
+                promo: HashMap::new(),
             },
             subscriptions: vec![],
             treasury: TreasuryIO::default(),
@@ -32,13 +33,26 @@ pub struct EmployeeStat {
 
 pub struct MarketingStat {
     pub source: HashMap<Source, SourceStat>,
+    /// Sales with promo codes, by code.
+    pub promo: HashMap<String, PromoStat>,
+}
+
+pub struct PromoStat {
+    pub source: Source,
+    pub redemptions: u64,
+    pub earned: i64,
+    pub discount: i64,
 }
 
 pub struct SourceStat {
     pub buy_test: u64,
+    /// Trial lessons followed by a regular subscription.
+    pub trial_converted: u64,
     pub buy_subscription: u64,
     pub requests_count: u64,
     pub earned: i64,
+    /// Paid back to clients from this direction.
+    pub refunded: i64,
     pub spent: i64,
 }
 
@@ -48,6 +62,7 @@ pub struct SubscriptionStat {
     pub earned: i64,
     pub burned_training: u64,
     pub discount: i64,
+    pub refunded: i64,
 }
 
 impl SubscriptionStat {
@@ -58,6 +73,7 @@ impl SubscriptionStat {
             earned: 0,
             burned_training: 0,
             discount: 0,
+            refunded: 0,
         }
     }
 }
@@ -70,5 +86,7 @@ pub struct TreasuryIO {
     pub income_other: i64,
     pub employees: Vec<EmployeeStat>,
     pub sell_subscriptions: i64,
+    pub refunds: i64,
+    pub gift_certificates: i64,
     pub marketing: HashMap<Source, i64>,
 }
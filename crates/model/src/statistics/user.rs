@@ -18,12 +18,16 @@ pub struct Statistics {
 pub struct TrainingsStat {
     pub count: u64,
     pub cancellations_count: u64,
+    pub no_show_count: u64,
+    pub excused_count: u64,
 }
 
 impl TrainingsStat {
     pub fn join(&mut self, other: &Self) {
         self.count += other.count;
         self.cancellations_count += other.cancellations_count;
+        self.no_show_count += other.no_show_count;
+        self.excused_count += other.excused_count;
     }
 }
 
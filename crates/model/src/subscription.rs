@@ -1,4 +1,10 @@
-use crate::{decimal::Decimal, training::Training};
+use crate::{
+    decimal::Decimal,
+    restriction::{self, TimeRestriction, Uncovered},
+    slot::Slot,
+    training::Training,
+    user::Freeze,
+};
 use bson::oid::ObjectId;
 use chrono::{DateTime, Local, TimeZone, Utc};
 use serde::{Deserialize, Serialize};
@@ -21,6 +27,37 @@ pub struct Subscription {
     pub subscription_type: SubscriptionType,
     #[serde(default)]
     pub unlimited: bool,
+    /// Trial lesson sold to leads, at most once per phone.
+    #[serde(default)]
+    pub trial: bool,
+    /// Price history and scheduled changes. `price` and `version` mirror the effective one.
+    #[serde(default)]
+    pub prices: Vec<PriceVersion>,
+    /// Days and hours the subscription can be used at.
+    #[serde(default)]
+    pub restriction: Option<TimeRestriction>,
+    /// Lessons of other training types sold in the same package, e.g. personal
+    /// trainings added to a group subscription. `items` counts the main pool only.
+    #[serde(default)]
+    pub pools: Vec<BundlePool>,
+}
+
+/// Separate pool of lessons in a bundle subscription. The pool has its own balance
+/// and lesson price, the expiry is shared with the rest of the bundle.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BundlePool {
+    pub tp: SubscriptionType,
+    pub items: u32,
+    /// Part of the subscription price paid for the pool.
+    pub price: Decimal,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct PriceVersion {
+    pub version: u32,
+    pub price: Decimal,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub effective_from: DateTime<Utc>,
 }
 
 pub type CostOfLesson = Decimal;
@@ -51,12 +88,136 @@ impl Subscription {
             user_can_buy,
             subscription_type,
             unlimited,
+            trial: false,
+            prices: vec![],
+            restriction: None,
+            pools: vec![],
+        }
+    }
+
+    pub fn is_bundle(&self) -> bool {
+        !self.pools.is_empty()
+    }
+
+    /// Part of the price left for the main pool once the other pools are paid for.
+    pub fn main_price(&self) -> Decimal {
+        self.pools
+            .iter()
+            .fold(self.price, |price, pool| price - pool.price)
+    }
+
+    /// Takes a fixed amount off the price. In a bundle the main pool pays first,
+    /// the rest comes off the other pools in order.
+    pub fn take_off(&mut self, amount: Decimal) {
+        let amount = amount.min(self.price);
+        let mut left = amount - amount.min(self.main_price());
+        for pool in &mut self.pools {
+            let part = left.min(pool.price);
+            pool.price -= part;
+            left -= part;
         }
+        self.price -= amount;
+    }
+
+    /// Subscriptions the buyer gets: one per pool, linked by a common bundle id.
+    pub fn user_subscriptions(&self, discount: Option<Decimal>) -> Vec<UserSubscription> {
+        let mut main = UserSubscription::from(self.clone());
+        main.discount = discount;
+        if !self.is_bundle() {
+            return vec![main];
+        }
+
+        let bundle_id = Some(ObjectId::new());
+        main.price = self.main_price();
+        main.bundle_id = bundle_id;
+        let mut subscriptions = vec![main];
+        for pool in &self.pools {
+            let mut sub = UserSubscription::from(self.clone());
+            sub.tp = pool.tp.clone();
+            sub.items = pool.items;
+            sub.balance = pool.items;
+            sub.price = pool.price;
+            sub.unlimited = false;
+            sub.discount = discount;
+            sub.freeze_days = 0;
+            sub.bundle_id = bundle_id;
+            subscriptions.push(sub);
+        }
+        subscriptions
     }
 
     pub fn can_user_buy(&self) -> bool {
         self.user_can_buy
     }
+
+    /// Price version effective at the moment. `None` for subscriptions without price history.
+    pub fn price_version_at(&self, at: DateTime<Utc>) -> Option<&PriceVersion> {
+        self.prices
+            .iter()
+            .filter(|price| price.effective_from <= at)
+            .max_by_key(|price| (price.effective_from, price.version))
+    }
+
+    /// Switches `price` and `version` to the version effective at the moment.
+    /// Returns the previous price if it changed.
+    pub fn apply_price_at(&mut self, at: DateTime<Utc>) -> Option<Decimal> {
+        let effective = self.price_version_at(at)?;
+        if effective.version == self.version {
+            return None;
+        }
+        let (version, price) = (effective.version, effective.price);
+        self.version = version;
+        Some(std::mem::replace(&mut self.price, price))
+    }
+
+    /// Adds a price version effective from the given moment and applies it if it is already due.
+    /// The first change keeps the current price as the initial version.
+    pub fn schedule_price(
+        &mut self,
+        price: Decimal,
+        effective_from: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> u32 {
+        if self.prices.is_empty() {
+            self.prices.push(PriceVersion {
+                version: self.version,
+                price: self.price,
+                effective_from: DateTime::<Utc>::default(),
+            });
+        }
+        let version = self
+            .prices
+            .iter()
+            .map(|price| price.version)
+            .max()
+            .unwrap_or(self.version)
+            + 1;
+        self.prices.push(PriceVersion {
+            version,
+            price,
+            effective_from,
+        });
+        self.apply_price_at(now);
+        version
+    }
+
+    /// Removes a price version that has not taken effect yet.
+    pub fn cancel_scheduled_price(&mut self, version: u32, now: DateTime<Utc>) -> bool {
+        let len = self.prices.len();
+        self.prices
+            .retain(|price| price.version != version || price.effective_from <= now);
+        len != self.prices.len()
+    }
+
+    pub fn scheduled_prices(&self, now: DateTime<Utc>) -> Vec<&PriceVersion> {
+        let mut prices = self
+            .prices
+            .iter()
+            .filter(|price| price.effective_from > now)
+            .collect::<Vec<_>>();
+        prices.sort_by_key(|price| price.effective_from);
+        prices
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Hash)]
@@ -85,6 +246,27 @@ pub struct UserSubscription {
     pub discount: Option<Decimal>,
     #[serde(default)]
     pub item_price: Option<Decimal>,
+    /// Share of a lesson owed for late cancellations that did not add up to a whole lesson yet.
+    #[serde(default)]
+    pub late_cancel_penalty: Decimal,
+    #[serde(default)]
+    pub trial: bool,
+    /// Days left to pause this subscription.
+    #[serde(default)]
+    pub freeze_days: u32,
+    #[serde(default)]
+    pub freeze: Option<Freeze>,
+    /// Every pause of the subscription, including the current one.
+    #[serde(default)]
+    pub freeze_history: Vec<Freeze>,
+    #[serde(default)]
+    pub restriction: Option<TimeRestriction>,
+    /// Starts of the booked trainings, kept to check the weekly limit.
+    #[serde(default)]
+    pub visits: Vec<DateTime<Utc>>,
+    /// Pools of one bundle purchase share this id and the expiry.
+    #[serde(default)]
+    pub bundle_id: Option<ObjectId>,
 }
 
 impl UserSubscription {
@@ -102,10 +284,144 @@ impl UserSubscription {
         matches!(self.status, Status::Active { .. })
     }
 
+    pub fn is_frozen_at(&self, at: DateTime<Utc>) -> bool {
+        self.freeze
+            .as_ref()
+            .is_some_and(|freeze| freeze.freeze_start <= at && at < freeze.freeze_end)
+    }
+
+    /// The smallest of `days` left before the active subscription ends, if it still has
+    /// unused lessons. Frozen and unlimited subscriptions don't burn anything yet.
+    pub fn expiry_reminder(&self, now: DateTime<Utc>, days: &[u32]) -> Option<u32> {
+        let Status::Active { end_date, .. } = self.status else {
+            return None;
+        };
+        if self.unlimited || self.balance == 0 || self.freeze.is_some() || end_date <= now {
+            return None;
+        }
+        days.iter()
+            .copied()
+            .filter(|days| end_date - chrono::Duration::days(i64::from(*days)) <= now)
+            .min()
+    }
+
+    /// Pauses the active subscription for `days` and moves its end date by the same amount.
+    /// The budget is spent as far as it goes, so staff can pause beyond it.
+    pub fn freeze(&mut self, days: u32, now: DateTime<Utc>) -> bool {
+        if self.freeze.is_some() {
+            return false;
+        }
+        let Status::Active { end_date, .. } = &mut self.status else {
+            return false;
+        };
+        let duration = chrono::Duration::days(i64::from(days));
+        *end_date += duration;
+        let charged = days.min(self.freeze_days);
+        self.freeze_days -= charged;
+
+        let freeze = Freeze {
+            freeze_start: now,
+            freeze_end: now + duration,
+            charged_days: Some(charged),
+        };
+        self.freeze_history.push(freeze.clone());
+        self.freeze = Some(freeze);
+        true
+    }
+
+    /// Ends the pause. Whole days that were not used go back to the budget, but never
+    /// more than the pause took from it: the days used are paid from the budget first.
+    pub fn unfreeze(&mut self, now: DateTime<Utc>) -> bool {
+        let Some(freeze) = self.freeze.take() else {
+            return false;
+        };
+        let unused = (freeze.freeze_end - now).num_days().max(0);
+        if unused > 0 {
+            let unused_duration = chrono::Duration::days(unused);
+            if let Status::Active { end_date, .. } = &mut self.status {
+                *end_date -= unused_duration;
+            }
+            let refund = match freeze.charged_days {
+                Some(charged) => {
+                    let used = (freeze.freeze_end - freeze.freeze_start).num_days() - unused;
+                    (i64::from(charged) - used).clamp(0, unused)
+                }
+                None => unused,
+            };
+            self.freeze_days += refund as u32;
+            if let Some(last) = self.freeze_history.last_mut() {
+                last.freeze_end = freeze.freeze_end - unused_duration;
+            }
+        }
+        true
+    }
+
+    /// Splits `items` unlocked lessons off into a new subscription with the same
+    /// lesson price and expiry. Unlimited subscriptions can only be moved whole.
+    pub fn split(&mut self, items: u32) -> Option<UserSubscription> {
+        if self.unlimited || items == 0 || self.balance < items {
+            return None;
+        }
+        let item_price = self.item_price();
+        self.balance -= items;
+        Some(UserSubscription {
+            id: ObjectId::new(),
+            subscription_id: self.subscription_id,
+            name: self.name.clone(),
+            items,
+            days: self.days,
+            status: self.status.clone(),
+            price: item_price * Decimal::from(items),
+            tp: self.tp.clone(),
+            balance: items,
+            locked_balance: 0,
+            unlimited: false,
+            discount: None,
+            item_price: Some(item_price),
+            late_cancel_penalty: Decimal::zero(),
+            trial: self.trial,
+            freeze_days: 0,
+            freeze: None,
+            freeze_history: vec![],
+            restriction: self.restriction.clone(),
+            visits: vec![],
+            bundle_id: None,
+        })
+    }
+
     pub fn activate(&mut self, training: &Training) {
         self.status.activate(training, self.days);
     }
 
+    /// Checks the time restriction for a new booking. Trainings that are already booked
+    /// are counted in the weekly limit and are not checked again.
+    pub fn check_restriction(&self, slot: &Slot) -> Result<(), Uncovered> {
+        let Some(restriction) = &self.restriction else {
+            return Ok(());
+        };
+        restriction.check_slot(slot)?;
+        restriction.check_limit(slot, &self.visits)
+    }
+
+    pub fn add_visit(&mut self, slot: &Slot) {
+        if self
+            .restriction
+            .as_ref()
+            .is_some_and(|restriction| restriction.weekly_limit.is_some())
+        {
+            restriction::add_visit(&mut self.visits, slot.start_at_utc(), Utc::now());
+        }
+    }
+
+    pub fn remove_visit(&mut self, slot: &Slot) {
+        restriction::remove_visit(&mut self.visits, slot.start_at_utc());
+    }
+
+    /// Follows a booked training that was moved to another time.
+    pub fn move_visit(&mut self, from: &Slot, to: &Slot) -> bool {
+        restriction::move_visit(&mut self.visits, from.start_at_utc(), to.start_at_utc())
+    }
+
     pub fn is_empty(&self) -> bool {
         !self.unlimited && self.balance == 0 && self.locked_balance == 0
     }
@@ -127,6 +443,11 @@ impl UserSubscription {
         }
     }
 
+    /// Unused lessons valued at `item_price()`.
+    pub fn remaining_value(&self) -> Decimal {
+        self.item_price() * Decimal::from(self.balance)
+    }
+
     pub fn subscription_price(&self) -> Decimal {
         if let Some(discount) = self.discount {
             self.price * (Decimal::int(1) - discount)
@@ -186,6 +507,23 @@ impl UserSubscription {
         self.locked_balance -= 1;
         true
     }
+
+    /// Writes off `share` of the locked lesson. Shares add up across cancellations: once they
+    /// reach a whole lesson it is burned, until then the lesson goes back to the balance.
+    /// Returns `None` if there is no locked lesson, otherwise whether the lesson was burned.
+    pub fn burn_locked_balance(&mut self, training: &Training, share: Decimal) -> Option<bool> {
+        if self.unlimited {
+            return self.change_locked_balance(training).then_some(false);
+        }
+
+        self.late_cancel_penalty += share;
+        if self.late_cancel_penalty >= Decimal::int(1) {
+            self.late_cancel_penalty -= Decimal::int(1);
+            self.change_locked_balance(training).then_some(true)
+        } else {
+            self.unlock_balance().then_some(false)
+        }
+    }
 }
 
 impl From<Subscription> for UserSubscription {
@@ -204,6 +542,14 @@ impl From<Subscription> for UserSubscription {
             unlimited: value.unlimited,
             discount: None,
             item_price: None,
+            late_cancel_penalty: Decimal::zero(),
+            trial: value.trial,
+            freeze_days: value.freeze_days,
+            freeze: None,
+            freeze_history: vec![],
+            restriction: value.restriction,
+            visits: vec![],
+            bundle_id: None,
         }
     }
 }
@@ -276,3 +622,213 @@ impl Default for SubscriptionType {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr as _;
+
+    #[test]
+    fn test_price_versions() {
+        let now: DateTime<Utc> = "2030-01-01T10:00:00Z".parse().unwrap();
+        let mut sub = Subscription {
+            price: Decimal::int(1000),
+            ..Default::default()
+        };
+        assert!(sub.price_version_at(now).is_none());
+        assert_eq!(sub.apply_price_at(now), None);
+
+        let next_month = now + chrono::Duration::days(31);
+        let version = sub.schedule_price(Decimal::int(1200), next_month, now);
+        assert_eq!(version, 1);
+        assert_eq!(sub.price, Decimal::int(1000));
+        assert_eq!(sub.version, 0);
+        assert_eq!(sub.scheduled_prices(now).len(), 1);
+
+        assert_eq!(sub.apply_price_at(next_month), Some(Decimal::int(1000)));
+        assert_eq!(sub.price, Decimal::int(1200));
+        assert_eq!(sub.version, 1);
+        assert_eq!(sub.apply_price_at(next_month), None);
+
+        let version = sub.schedule_price(Decimal::int(1100), next_month, next_month);
+        assert_eq!(version, 2);
+        assert_eq!(sub.price, Decimal::int(1100));
+        assert_eq!(sub.version, 2);
+        assert_eq!(
+            sub.price_version_at(now).map(|price| price.price),
+            Some(Decimal::int(1000))
+        );
+
+        let version = sub.schedule_price(
+            Decimal::int(1500),
+            next_month + chrono::Duration::days(1),
+            next_month,
+        );
+        assert!(!sub.cancel_scheduled_price(1, next_month));
+        assert!(sub.cancel_scheduled_price(version, next_month));
+        assert!(sub.scheduled_prices(next_month).is_empty());
+    }
+
+    fn active_sub(freeze_days: u32) -> UserSubscription {
+        let mut sub = UserSubscription::from(Subscription {
+            freeze_days,
+            expiration_days: 30,
+            ..Default::default()
+        });
+        let start_date: DateTime<Utc> = "2030-01-01T10:00:00Z".parse().unwrap();
+        sub.status = Status::Active {
+            start_date,
+            end_date: start_date + chrono::Duration::days(30),
+        };
+        sub
+    }
+
+    fn end_date(sub: &UserSubscription) -> DateTime<Utc> {
+        match sub.status {
+            Status::Active { end_date, .. } => end_date,
+            Status::NotActive => panic!("not active"),
+        }
+    }
+
+    #[test]
+    fn test_freeze() {
+        let now: DateTime<Utc> = "2030-01-05T10:00:00Z".parse().unwrap();
+        let mut sub = active_sub(14);
+        let end = end_date(&sub);
+
+        assert!(sub.freeze(10, now));
+        assert!(!sub.freeze(1, now));
+        assert_eq!(end_date(&sub), end + chrono::Duration::days(10));
+        assert_eq!(sub.freeze_days, 4);
+        assert!(sub.is_frozen_at(now + chrono::Duration::days(3)));
+        assert!(!sub.is_frozen_at(now + chrono::Duration::days(10)));
+        assert_eq!(sub.freeze_history.len(), 1);
+
+        let mut inactive = UserSubscription::from(Subscription::default());
+        assert!(!inactive.freeze(1, now));
+    }
+
+    #[test]
+    fn test_unfreeze_over_budget() {
+        let now: DateTime<Utc> = "2030-01-05T10:00:00Z".parse().unwrap();
+        let mut sub = active_sub(7);
+        let end = end_date(&sub);
+
+        assert!(sub.freeze(30, now));
+        assert_eq!(sub.freeze_days, 0);
+        assert!(sub.unfreeze(now + chrono::Duration::days(5)));
+        assert_eq!(end_date(&sub), end + chrono::Duration::days(5));
+        assert_eq!(sub.freeze_days, 2);
+
+        assert!(sub.freeze(2, now + chrono::Duration::days(10)));
+        assert!(sub.unfreeze(now + chrono::Duration::days(10)));
+        assert_eq!(sub.freeze_days, 2);
+    }
+
+    #[test]
+    fn test_expiry_reminder() {
+        let mut sub = active_sub(14);
+        let end = end_date(&sub);
+        let days = [1, 3, 7];
+        assert_eq!(sub.expiry_reminder(end - chrono::Duration::days(2), &days), None);
+
+        sub.balance = 3;
+        assert_eq!(sub.expiry_reminder(end - chrono::Duration::days(10), &days), None);
+        assert_eq!(sub.expiry_reminder(end - chrono::Duration::days(5), &days), Some(7));
+        assert_eq!(sub.expiry_reminder(end - chrono::Duration::days(2), &days), Some(3));
+        assert_eq!(sub.expiry_reminder(end - chrono::Duration::hours(1), &days), Some(1));
+        assert_eq!(sub.expiry_reminder(end + chrono::Duration::hours(1), &days), None);
+        assert_eq!(sub.expiry_reminder(end - chrono::Duration::days(2), &[]), None);
+
+        sub.freeze(3, end - chrono::Duration::days(5));
+        assert_eq!(sub.expiry_reminder(end - chrono::Duration::days(2), &days), None);
+
+        let mut unlimited = active_sub(0);
+        unlimited.balance = 3;
+        unlimited.unlimited = true;
+        assert_eq!(unlimited.expiry_reminder(end - chrono::Duration::days(2), &days), None);
+    }
+
+    #[test]
+    fn test_bundle() {
+        let sub = Subscription {
+            items: 8,
+            price: Decimal::int(10000),
+            freeze_days: 7,
+            pools: vec![BundlePool {
+                tp: SubscriptionType::Personal {
+                    couch_filter: ObjectId::new(),
+                },
+                items: 2,
+                price: Decimal::int(4000),
+            }],
+            ..Default::default()
+        };
+        assert!(UserSubscription::from(Subscription::default())
+            .bundle_id
+            .is_none());
+
+        let subs = sub.user_subscriptions(Some(Decimal::from_str("0.5").unwrap()));
+        assert_eq!(subs.len(), 2);
+        let (group, personal) = (&subs[0], &subs[1]);
+        assert!(group.bundle_id.is_some());
+        assert_eq!(group.bundle_id, personal.bundle_id);
+        assert!(group.tp.is_group());
+        assert!(personal.tp.is_personal());
+        assert_eq!((group.balance, personal.balance), (8, 2));
+        assert_eq!(group.item_price(), Decimal::int(375));
+        assert_eq!(personal.item_price(), Decimal::int(1000));
+        assert_eq!(
+            group.subscription_price() + personal.subscription_price(),
+            Decimal::int(5000)
+        );
+        assert_eq!((group.freeze_days, personal.freeze_days), (7, 0));
+
+        let mut discounted = sub.clone();
+        discounted.take_off(Decimal::int(1500));
+        assert_eq!(discounted.price, Decimal::int(8500));
+        assert_eq!(discounted.main_price(), Decimal::int(4500));
+        discounted.take_off(Decimal::int(5000));
+        assert_eq!(discounted.main_price(), Decimal::zero());
+        assert_eq!(discounted.pools[0].price, Decimal::int(3500));
+    }
+
+    #[test]
+    fn test_split() {
+        let mut sub = UserSubscription::from(Subscription {
+            items: 10,
+            price: Decimal::int(10000),
+            ..Default::default()
+        });
+        sub.discount = Some(Decimal::from_str("0.1").unwrap());
+        sub.lock_balance();
+
+        assert!(sub.split(10).is_none());
+        let part = sub.split(4).unwrap();
+        assert_eq!(sub.balance, 5);
+        assert_eq!(sub.locked_balance, 1);
+        assert_eq!(part.balance, 4);
+        assert_eq!(part.locked_balance, 0);
+        assert_eq!(part.item_price(), sub.item_price());
+        assert_eq!(part.subscription_price(), Decimal::int(3600));
+        assert_eq!(part.remaining_value(), Decimal::int(3600));
+    }
+
+    #[test]
+    fn test_unfreeze_early() {
+        let now: DateTime<Utc> = "2030-01-05T10:00:00Z".parse().unwrap();
+        let mut sub = active_sub(14);
+        let end = end_date(&sub);
+
+        assert!(sub.freeze(10, now));
+        assert!(sub.unfreeze(now + chrono::Duration::days(3)));
+        assert!(sub.freeze.is_none());
+        assert_eq!(end_date(&sub), end + chrono::Duration::days(3));
+        assert_eq!(sub.freeze_days, 11);
+        assert_eq!(
+            sub.freeze_history[0].freeze_end,
+            now + chrono::Duration::days(3)
+        );
+        assert!(!sub.unfreeze(now));
+    }
+}
@@ -5,13 +5,11 @@ use serde::{Deserialize, Serialize};
 use crate::{
     decimal::Decimal,
     ids::DayId,
-    program::{Program, TrainingType},
-    rooms::Room,
+    program::{BookingWindows, Program, TrainingType},
+    rooms::default_room_id,
     slot::Slot,
 };
 
-pub const CLOSE_SING_UP: u32 = 3 * 60; // 3 hours
-
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[non_exhaustive]
 pub struct Training {
@@ -27,6 +25,10 @@ pub struct Training {
     pub duration_min: u32,
     pub instructor: ObjectId,
     pub clients: Vec<ObjectId>,
+    #[serde(default)]
+    pub waitlist: Vec<ObjectId>,
+    #[serde(default)]
+    pub attendance: Vec<AttendanceMark>,
     pub capacity: u32,
     pub is_one_time: bool,
     #[serde(default)]
@@ -42,6 +44,17 @@ pub struct Training {
     pub keep_open: bool,
     #[serde(default)]
     pub tp: TrainingType,
+    #[serde(default)]
+    pub windows: BookingWindows,
+    /// Windows were set for this training only and are not synced with the program.
+    #[serde(default)]
+    pub custom_windows: bool,
+    /// Personal series this session was scheduled from.
+    #[serde(default)]
+    pub personal_series: Option<ObjectId>,
+    /// Clients signed out by the cancellation, their calendar feeds keep the event.
+    #[serde(default)]
+    pub canceled_clients: Vec<ObjectId>,
 }
 
 impl Training {
@@ -66,6 +79,8 @@ impl Training {
             duration_min,
             instructor,
             clients: Vec::new(),
+            waitlist: Vec::new(),
+            attendance: Vec::new(),
             capacity,
             is_one_time,
             is_canceled: false,
@@ -75,6 +90,10 @@ impl Training {
             keep_open: false,
             tp,
             room,
+            windows: BookingWindows::default(),
+            custom_windows: false,
+            personal_series: None,
+            canceled_clients: vec![],
         }
     }
 
@@ -95,6 +114,8 @@ impl Training {
             duration_min,
             instructor: ObjectId::from_bytes([0; 12]),
             clients: vec![],
+            waitlist: vec![],
+            attendance: vec![],
             capacity: 0,
             is_one_time: true,
             is_canceled: false,
@@ -107,6 +128,10 @@ impl Training {
                 price,
             },
             room,
+            windows: BookingWindows::default(),
+            custom_windows: false,
+            personal_series: None,
+            canceled_clients: vec![],
         }
     }
 
@@ -127,6 +152,8 @@ impl Training {
             duration_min,
             instructor,
             clients: vec![],
+            waitlist: vec![],
+            attendance: vec![],
             capacity: 1,
             is_one_time: true,
             is_canceled: false,
@@ -136,6 +163,10 @@ impl Training {
             keep_open: false,
             tp: TrainingType::Personal { is_free: false },
             room,
+            windows: BookingWindows::default(),
+            custom_windows: false,
+            personal_series: None,
+            canceled_clients: vec![],
         }
     }
 
@@ -155,6 +186,8 @@ impl Training {
             duration_min: program.duration_min,
             instructor,
             clients: Vec::new(),
+            waitlist: Vec::new(),
+            attendance: Vec::new(),
             capacity: program.capacity,
             is_one_time,
             is_canceled: false,
@@ -164,6 +197,10 @@ impl Training {
             keep_open: false,
             tp: program.tp,
             room,
+            windows: program.windows,
+            custom_windows: false,
+            personal_series: None,
+            canceled_clients: vec![],
         }
     }
 
@@ -189,6 +226,8 @@ impl Training {
             duration_min: training.duration_min,
             instructor: training.instructor,
             clients: vec![],
+            waitlist: vec![],
+            attendance: vec![],
             capacity: training.capacity,
             is_one_time: training.is_one_time,
             is_canceled: false,
@@ -198,6 +237,10 @@ impl Training {
             keep_open: false,
             tp: training.tp,
             room: training.room,
+            windows: training.windows,
+            custom_windows: training.custom_windows,
+            personal_series: training.personal_series,
+            canceled_clients: vec![],
         }
     }
 
@@ -221,22 +264,66 @@ impl Training {
                 TrainingStatus::Finished
             } else if start_at < now {
                 TrainingStatus::InProgress
-            } else if start_at - chrono::Duration::minutes(CLOSE_SING_UP as i64) < now {
-                if self.clients.is_empty() && !self.keep_open {
-                    TrainingStatus::ClosedToSignup
-                } else {
-                    TrainingStatus::OpenToSignup {
-                        close_sign_out: true,
-                    }
-                }
+            } else if self.sign_up_opens_at().is_some_and(|open_at| now < open_at) {
+                TrainingStatus::NotOpenToSignup
+            } else if self.sign_up_closes_at() < now
+                && self.clients.is_empty()
+                && !self.keep_open
+            {
+                TrainingStatus::ClosedToSignup
             } else {
                 TrainingStatus::OpenToSignup {
-                    close_sign_out: false,
+                    close_sign_out: self.sign_out_deadline() < now,
                 }
             }
         }
     }
 
+    pub fn sign_up_opens_at(&self) -> Option<DateTime<Local>> {
+        self.windows
+            .sign_up_open_min
+            .map(|min| self.get_slot().start_at() - chrono::Duration::minutes(min as i64))
+    }
+
+    pub fn sign_up_closes_at(&self) -> DateTime<Local> {
+        self.get_slot().start_at()
+            - chrono::Duration::minutes(self.windows.sign_up_close_min as i64)
+    }
+
+    /// Last moment a client can cancel the sign up for free.
+    pub fn sign_out_deadline(&self) -> DateTime<Local> {
+        self.get_slot().start_at() - chrono::Duration::minutes(self.windows.cancel_min as i64)
+    }
+
+    /// Attendance can be marked once the training has started and until it is processed.
+    pub fn can_mark_attendance(&self, now: DateTime<Local>) -> bool {
+        matches!(
+            self.status(now),
+            TrainingStatus::InProgress | TrainingStatus::Finished
+        ) && !self.is_processed
+    }
+
+    pub fn attendance(&self, client: ObjectId) -> Option<Attendance> {
+        self.attendance
+            .iter()
+            .find(|mark| mark.client == client)
+            .map(|mark| mark.status)
+    }
+
+    pub fn set_attendance(&mut self, client: ObjectId, status: Attendance) {
+        if let Some(mark) = self.attendance.iter_mut().find(|m| m.client == client) {
+            mark.status = status;
+        } else {
+            self.attendance.push(AttendanceMark { client, status });
+        }
+    }
+
+    pub fn is_attendance_marked(&self) -> bool {
+        self.clients
+            .iter()
+            .all(|client| self.attendance(*client).is_some())
+    }
+
     pub fn is_full(&self) -> bool {
         self.clients.len() as u32 >= self.capacity
     }
@@ -279,6 +366,7 @@ impl Training {
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Copy)]
 pub enum TrainingStatus {
+    NotOpenToSignup,
     OpenToSignup { close_sign_out: bool },
     ClosedToSignup,
     InProgress,
@@ -290,7 +378,9 @@ impl TrainingStatus {
     pub fn can_be_canceled(&self) -> bool {
         matches!(
             self,
-            TrainingStatus::OpenToSignup { .. } | TrainingStatus::ClosedToSignup
+            TrainingStatus::NotOpenToSignup
+                | TrainingStatus::OpenToSignup { .. }
+                | TrainingStatus::ClosedToSignup
         )
     }
 
@@ -299,23 +389,61 @@ impl TrainingStatus {
     }
 
     pub fn can_sign_out(&self) -> bool {
-        if let TrainingStatus::OpenToSignup { close_sign_out } = self {
-            !close_sign_out
-        } else {
-            false
+        match self {
+            TrainingStatus::OpenToSignup { close_sign_out } => !close_sign_out,
+            TrainingStatus::NotOpenToSignup => true,
+            _ => false,
         }
     }
 
+    /// Past the free cancellation deadline, but the training has not started yet.
+    pub fn can_sign_out_late(&self) -> bool {
+        matches!(
+            self,
+            TrainingStatus::OpenToSignup {
+                close_sign_out: true
+            }
+        )
+    }
+
     pub fn can_sign_in(&self) -> bool {
         matches!(self, TrainingStatus::OpenToSignup { .. })
     }
 }
 
+/// Which instances of a recurring training an edit applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SeriesScope {
+    /// Only the selected instance.
+    This,
+    /// The selected instance and every later one.
+    Following,
+    /// Every upcoming instance, including the ones before the selected.
+    All,
+}
+
+impl SeriesScope {
+    pub fn is_series(&self) -> bool {
+        *self != SeriesScope::This
+    }
+}
+
+/// What happens to clients signed up to a training that is being moved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ClientsPolicy {
+    /// Clients stay signed up to the moved training.
+    Move,
+    /// Clients are signed out and their lessons are returned.
+    SignOut,
+}
+
 #[derive(Debug, Clone)]
 pub enum Filter {
     Client(ObjectId),
     Instructor(ObjectId),
     Program(ObjectId),
+    /// Cancelled trainings the client was signed up for.
+    Canceled(ObjectId),
 }
 
 impl Filter {
@@ -324,6 +452,7 @@ impl Filter {
             Filter::Client(client) => training.clients.contains(client),
             Filter::Instructor(instructor) => training.instructor == *instructor,
             Filter::Program(program) => training.proto_id == *program,
+            Filter::Canceled(client) => training.canceled_clients.contains(client),
         }
     }
 }
@@ -332,6 +461,23 @@ impl Filter {
 pub struct Statistics {
     pub earned: Decimal,
     pub couch_rewards: Decimal,
+    #[serde(default)]
+    pub no_show: u32,
+    #[serde(default)]
+    pub excused: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum Attendance {
+    Attended,
+    NoShow,
+    Excused,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct AttendanceMark {
+    pub client: ObjectId,
+    pub status: Attendance,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, PartialOrd, Ord)]
@@ -353,9 +499,6 @@ impl Default for Notified {
     }
 }
 
-fn default_room_id() -> ObjectId {
-    Room::Adult.id()
-}
 
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct TrainingId {
@@ -372,3 +515,72 @@ impl TrainingId {
         self.start_at.with_timezone(&Local)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn training(start_in: Duration, windows: BookingWindows) -> Training {
+        let program = Program {
+            windows,
+            duration_min: 60,
+            capacity: 10,
+            ..Default::default()
+        };
+        Training::new_group(
+            program,
+            Local::now() + start_in,
+            ObjectId::new(),
+            true,
+            default_room_id(),
+        )
+    }
+
+    #[test]
+    fn test_default_windows() {
+        let mut tr = training(Duration::hours(4), BookingWindows::default());
+        assert_eq!(
+            tr.status(Local::now()),
+            TrainingStatus::OpenToSignup {
+                close_sign_out: false
+            }
+        );
+
+        let later = Local::now() + Duration::hours(2);
+        assert_eq!(tr.status(later), TrainingStatus::ClosedToSignup);
+        tr.clients.push(ObjectId::new());
+        assert_eq!(
+            tr.status(later),
+            TrainingStatus::OpenToSignup {
+                close_sign_out: true
+            }
+        );
+    }
+
+    #[test]
+    fn test_custom_windows() {
+        let windows = BookingWindows {
+            sign_up_open_min: Some(24 * 60),
+            sign_up_close_min: 30,
+            cancel_min: 12 * 60,
+        };
+        let tr = training(Duration::hours(30), windows);
+        let now = Local::now();
+        assert_eq!(tr.status(now), TrainingStatus::NotOpenToSignup);
+        assert!(!tr.status(now).can_sign_in());
+
+        let status = tr.status(now + Duration::hours(10));
+        assert!(status.can_sign_in());
+        assert!(status.can_sign_out());
+
+        let status = tr.status(now + Duration::hours(20));
+        assert!(status.can_sign_in());
+        assert!(!status.can_sign_out());
+
+        assert_eq!(
+            tr.status(now + Duration::minutes(30 * 60 - 20)),
+            TrainingStatus::ClosedToSignup
+        );
+    }
+}
@@ -13,6 +13,8 @@ pub struct TreasuryAggregate {
     pub credit: Decimal,
     pub income: AggIncome,
     pub outcome: AggOutcome,
+    #[serde(default)]
+    pub gifts: AggGifts,
 }
 
 #[derive(Serialize, Deserialize, Debug, Default)]
@@ -28,6 +30,18 @@ pub struct AggOutcome {
     pub marketing: HashMap<Source, Agg>,
     pub rent: Agg,
     pub other: Agg,
+    #[serde(default)]
+    pub refunds: Agg,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct AggGifts {
+    /// Money received for certificates, not yet earned.
+    pub sold: Agg,
+    /// Revenue recognized when certificates are redeemed.
+    pub redeemed: Agg,
+    /// Paid for certificates that are neither redeemed nor expired at the end of the period.
+    pub liability: Decimal,
 }
 
 #[derive(Serialize, Deserialize, Debug, Default)]
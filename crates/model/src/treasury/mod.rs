@@ -9,7 +9,10 @@ use chrono::{DateTime, Utc};
 use income::Income;
 use outcome::Outcome;
 use serde::{Deserialize, Serialize};
-use subs::{SellSubscription, UserId};
+use subs::{
+    PayInstallment, RedeemGiftCertificate, RefundSubscription, SellGiftCertificate,
+    SellSubscription, UpgradeSubscription, UserId,
+};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct TreasuryEvent {
@@ -43,4 +46,12 @@ pub enum Event {
     Outcome(Outcome),
     Reward(UserId),
     Marketing(Source),
+    Refund(RefundSubscription),
+    // gift certificates
+    SellGiftCertificate(SellGiftCertificate),
+    RedeemGiftCertificate(RedeemGiftCertificate),
+    // upgrades
+    UpgradeSubscription(UpgradeSubscription),
+    // installments
+    PayInstallment(PayInstallment),
 }
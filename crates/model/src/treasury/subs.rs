@@ -1,4 +1,4 @@
-use crate::{decimal::Decimal, subscription::Subscription};
+use crate::{decimal::Decimal, gift::GiftValue, subscription::Subscription};
 use bson::oid::ObjectId;
 use serde::{Deserialize, Serialize};
 
@@ -32,6 +32,61 @@ pub struct SellSubscription {
     pub info: SubscriptionInfo,
     #[serde(default)]
     pub discount: Option<Decimal>,
+    /// Left to installments, not included in the debit.
+    #[serde(default)]
+    pub debt: Decimal,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RefundSubscription {
+    pub buyer_id: UserId,
+    pub subscription_id: ObjectId,
+    pub name: String,
+    pub items: u32,
+    pub item_price: Decimal,
+    /// Kept by the studio, already subtracted from the credit.
+    pub fee: Decimal,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SellGiftCertificate {
+    pub buyer_id: UserId,
+    pub certificate_id: ObjectId,
+    pub code: String,
+    pub value: GiftValue,
+}
+
+/// Recognizes the prepaid certificate price as revenue, no money moves.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RedeemGiftCertificate {
+    pub user_id: UserId,
+    pub certificate_id: ObjectId,
+    pub code: String,
+    pub info: SubscriptionInfo,
+    /// Paid by the buyer when the certificate was sold.
+    pub amount: Decimal,
+}
+
+/// The remaining lessons of the retired subscription are credited toward the new one,
+/// only the difference is paid.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UpgradeSubscription {
+    pub buyer_id: UserId,
+    pub subscription_id: ObjectId,
+    pub name: String,
+    pub items: u32,
+    pub item_price: Decimal,
+    pub info: SubscriptionInfo,
+    pub credit: Decimal,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PayInstallment {
+    pub buyer_id: UserId,
+    pub debt_id: ObjectId,
+    pub name: String,
+    /// Still owed after this payment.
+    pub remaining: Decimal,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
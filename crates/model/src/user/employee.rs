@@ -114,6 +114,38 @@ impl Employee {
         })
     }
 
+    /// Reward for a lesson written off by a late cancellation.
+    /// The minimal group reward is paid for the training itself, so it does not apply here.
+    pub fn collect_late_cancel_reward(
+        &mut self,
+        training: &Training,
+        user: UserRewardContribution,
+    ) -> Option<Reward> {
+        let percent = self.rates.iter().find_map(|rate| match rate {
+            Rate::GroupTraining { percent, .. } if training.is_group() => Some(*percent),
+            Rate::PersonalTraining { percent } if !training.is_group() => Some(*percent),
+            _ => None,
+        })?;
+        let reward = user.lesson_price * percent;
+        if reward.is_zero() {
+            return None;
+        }
+
+        self.reward += reward;
+        Some(Reward {
+            id: ObjectId::new(),
+            employee: training.instructor,
+            created_at: Utc::now(),
+            reward,
+            source: RewardSource::Training {
+                training_id: training.id(),
+                name: training.name.clone(),
+                user_originals: vec![user],
+                percent,
+            },
+        })
+    }
+
     pub fn collect_fix_rewards(
         &mut self,
         id: ObjectId,
@@ -15,6 +15,8 @@ pub struct UserExtension {
     pub ai_message_prompt: Option<String>,
     #[serde(default)]
     pub comments: Vec<Comment>,
+    #[serde(default)]
+    pub calendar_token: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -9,6 +9,7 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 use crate::{
+    restriction::Uncovered,
     subscription::{self, Status, SubscriptionType, UserSubscription},
     training::Training,
 };
@@ -67,12 +68,32 @@ impl Payer<&mut User> {
         expired
     }
 
+    /// Copies the expiry and the pause of the subscription to the other pools of its bundle.
+    pub fn sync_bundle(&mut self, id: ObjectId) {
+        let Some((bundle_id, status, freeze)) = self
+            .0
+            .subscriptions
+            .iter()
+            .find(|sub| sub.id == id)
+            .and_then(|sub| Some((sub.bundle_id?, sub.status.clone(), sub.freeze.clone())))
+        else {
+            return;
+        };
+        for sub in self.0.subscriptions.iter_mut() {
+            if sub.id != id && sub.bundle_id == Some(bundle_id) {
+                sub.status = status.clone();
+                sub.freeze = freeze.clone();
+            }
+        }
+    }
+
     pub fn find_subscription(
         &mut self,
         reason: FindFor,
         training: &Training,
     ) -> Option<&mut UserSubscription> {
-        let start_at = training.get_slot().start_at();
+        let slot = training.get_slot();
+        let start_at = slot.start_at();
         self.0
             .subscriptions
             .sort_by(|a, b| match (&a.status, &b.status) {
@@ -105,6 +126,7 @@ impl Payer<&mut User> {
                     }
                 }
             })
+            .filter(|s| !matches!(reason, FindFor::Lock) || s.check_restriction(&slot).is_ok())
             .find(|s| match reason {
                 FindFor::Lock => {
                     if let Status::Active {
@@ -112,7 +134,9 @@ impl Payer<&mut User> {
                         end_date,
                     } = s.status
                     {
-                        end_date > start_at && (s.unlimited || s.balance > 0)
+                        end_date > start_at
+                            && !s.is_frozen_at(start_at.with_timezone(&Utc))
+                            && (s.unlimited || s.balance > 0)
                     } else {
                         s.unlimited || s.balance > 0
                     }
@@ -198,9 +222,34 @@ impl<'u> Payer<&User> {
                     }
                 }
             })
+            .filter(|s| s.check_restriction(&training.get_slot()).is_ok())
             .map(|s| s.balance)
             .sum()
     }
+
+    /// Subscriptions with lessons left that suit the training but not its day,
+    /// hours or the weekly limit. Used to explain why the training isn't covered.
+    pub fn uncovered(&self, training: &Training) -> Vec<(&UserSubscription, Uncovered)> {
+        let slot = training.get_slot();
+        self.0
+            .subscriptions
+            .iter()
+            .filter(|s| match &s.tp {
+                SubscriptionType::Group { program_filter } => {
+                    !training.tp.is_personal() && program_filter.contains(&training.proto_id)
+                }
+                SubscriptionType::Personal { couch_filter } => {
+                    training.tp.is_personal() && training.instructor == *couch_filter
+                }
+            })
+            .filter(|s| s.unlimited || s.balance > 0)
+            .filter_map(|s| {
+                s.check_restriction(&slot)
+                    .err()
+                    .map(|reason| (s, reason))
+            })
+            .collect()
+    }
 }
 
 impl Deref for Payer<&mut User> {
@@ -250,14 +299,15 @@ pub enum FindFor {
 #[cfg(test)]
 mod tests {
     use bson::oid::ObjectId;
-    use chrono::{DateTime, Utc};
+    use chrono::{DateTime, Utc, Weekday};
 
     use crate::{
         decimal::Decimal,
         program::TrainingType,
+        restriction::{TimeRestriction, Uncovered},
         rights::Rights,
         statistics::source::Source,
-        subscription::{Status, SubscriptionType, UserSubscription},
+        subscription::{BundlePool, Status, Subscription, SubscriptionType, UserSubscription},
         training::Training,
         user::UserName,
     };
@@ -285,6 +335,7 @@ mod tests {
             come_from: Source::default(),
             family: Default::default(),
             employee: Default::default(),
+            debts: vec![],
         }
     }
 
@@ -318,6 +369,14 @@ mod tests {
             unlimited: false,
             discount: None,
             item_price: None,
+            late_cancel_penalty: Decimal::zero(),
+            trial: false,
+            freeze_days: 0,
+            freeze: None,
+            freeze_history: vec![],
+            restriction: None,
+            visits: vec![],
+            bundle_id: None,
         }
     }
 
@@ -412,4 +471,120 @@ mod tests {
             .status
             .is_active());
     }
+
+    #[test]
+    fn test_find_restricted_subscription() {
+        // Wednesday.
+        let tr = training("2030-01-09T12:00:00Z", true);
+        let mut restricted = sub(
+            2,
+            SubscriptionType::Group {
+                program_filter: vec![tr.proto_id],
+            },
+            30,
+            None,
+        );
+        restricted.restriction = Some(TimeRestriction {
+            weekdays: vec![Weekday::Sat, Weekday::Sun],
+            ..Default::default()
+        });
+        let mut alice = user(vec![restricted.clone()]);
+        assert!(alice
+            .payer_mut()
+            .unwrap()
+            .find_subscription(super::FindFor::Lock, &tr)
+            .is_none());
+
+        // Booked before the training was moved out of the allowed time.
+        let mut booked = restricted.clone();
+        booked.locked_balance = 1;
+        let mut bob = user(vec![booked]);
+        let mut payer = bob.payer_mut().unwrap();
+        assert!(payer.find_subscription(super::FindFor::Unlock, &tr).is_some());
+        assert!(payer.find_subscription(super::FindFor::Charge, &tr).is_some());
+        assert_eq!(
+            alice.payer().unwrap().uncovered(&tr)[0].1,
+            Uncovered::Weekday
+        );
+
+        restricted.restriction = Some(TimeRestriction {
+            weekly_limit: Some(1),
+            ..Default::default()
+        });
+        let mut alice = user(vec![restricted]);
+        let mut payer = alice.payer_mut().unwrap();
+        let sub = payer.find_subscription(super::FindFor::Lock, &tr).unwrap();
+        assert!(sub.lock_balance());
+        sub.add_visit(&tr.get_slot());
+        assert!(payer.find_subscription(super::FindFor::Lock, &tr).is_none());
+        assert!(payer
+            .find_subscription(super::FindFor::Unlock, &tr)
+            .is_some());
+    }
+
+    #[test]
+    fn test_bundle() {
+        let group = training("2012-12-12T12:12:12Z", true);
+        let personal = training("2012-12-13T12:12:12Z", false);
+        let bundle = Subscription {
+            items: 4,
+            subscription_type: SubscriptionType::Group {
+                program_filter: vec![group.proto_id],
+            },
+            expiration_days: 30,
+            pools: vec![BundlePool {
+                tp: SubscriptionType::Personal {
+                    couch_filter: personal.instructor,
+                },
+                items: 2,
+                price: Decimal::int(2000),
+            }],
+            ..Default::default()
+        };
+        let mut alice = user(bundle.user_subscriptions(None));
+        assert_eq!(alice.payer().unwrap().group_balance().balance, 4);
+        assert_eq!(alice.payer().unwrap().personal_balance().balance, 2);
+
+        let mut payer = alice.payer_mut().unwrap();
+        let sub = payer
+            .find_subscription(super::FindFor::Lock, &personal)
+            .unwrap();
+        assert!(sub.tp.is_personal());
+        assert!(sub.lock_balance());
+        let sub = payer
+            .find_subscription(super::FindFor::Charge, &personal)
+            .unwrap();
+        assert!(sub.change_locked_balance(&personal));
+        let id = sub.id;
+        payer.sync_bundle(id);
+        let subs = &payer.subscriptions;
+        assert!(subs[0].status.is_active());
+        assert_eq!(subs[0].status, subs[1].status);
+    }
+
+    #[test]
+    fn test_burn_locked_balance() {
+        let tr = training("2012-12-12T12:12:12Z", true);
+        let mut sub = sub(
+            2,
+            SubscriptionType::Group {
+                program_filter: vec![tr.proto_id],
+            },
+            30,
+            None,
+        );
+        let half = Decimal::int(1) / Decimal::int(2);
+
+        assert!(sub.lock_balance());
+        assert_eq!(sub.burn_locked_balance(&tr, half), Some(false));
+        assert_eq!((sub.balance, sub.locked_balance), (2, 0));
+
+        assert!(sub.lock_balance());
+        assert_eq!(sub.burn_locked_balance(&tr, half), Some(true));
+        assert_eq!((sub.balance, sub.locked_balance), (1, 0));
+        assert!(sub.late_cancel_penalty.is_zero());
+        assert!(sub.status.is_active());
+
+        assert_eq!(sub.burn_locked_balance(&tr, Decimal::int(1)), None);
+    }
 }
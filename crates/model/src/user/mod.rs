@@ -4,7 +4,7 @@ use eyre::Result;
 use std::fmt::{Display, Formatter};
 
 use super::rights::Rights;
-use crate::{statistics::source::Source, subscription::UserSubscription};
+use crate::{installment::Debt, statistics::source::Source, subscription::UserSubscription};
 use chrono::{DateTime, TimeZone as _, Utc};
 use family::{Family, Payer};
 use mongodb::bson::doc;
@@ -46,6 +46,9 @@ pub struct User {
     pub come_from: Source,
     #[serde(default)]
     pub family: Family,
+    /// Unpaid installments of subscriptions sold in parts.
+    #[serde(default)]
+    pub debts: Vec<Debt>,
 }
 
 fn default_created_at() -> DateTime<Utc> {
@@ -78,6 +81,7 @@ impl User {
             come_from,
             family: Family::default(),
             employee: Default::default(),
+            debts: vec![],
         }
     }
 
@@ -114,6 +118,7 @@ impl User {
             come_from: Source::default(),
             family: Family::default(),
             employee: Default::default(),
+            debts: vec![],
         }
     }
 
@@ -165,6 +170,19 @@ impl User {
         self.employee.as_ref().is_some_and(|e| e.is_couch())
     }
 
+    pub fn has_overdue_debt(&self, now: DateTime<Utc>) -> bool {
+        self.debts.iter().any(|debt| debt.is_overdue(now))
+    }
+
+    /// Installment debt that pays for the subscription.
+    pub fn debt_for(&self, subscription: ObjectId) -> Option<&Debt> {
+        let sub = self
+            .subscriptions
+            .iter()
+            .find(|sub| sub.id == subscription)?;
+        self.debts.iter().find(|debt| debt.covers(sub))
+    }
+
     pub fn has_family(&self) -> bool {
         self.family.payer_id.is_some() || !self.family.children_ids.is_empty()
     }
@@ -174,12 +192,16 @@ fn default_is_active() -> bool {
     true
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 pub struct Freeze {
     #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
     pub freeze_start: DateTime<Utc>,
     #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
     pub freeze_end: DateTime<Utc>,
+    /// Days taken from the freeze budget, less than the pause when staff go beyond it.
+    /// `None` for pauses made before the budget was tracked.
+    #[serde(default)]
+    pub charged_days: Option<u32>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
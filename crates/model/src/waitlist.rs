@@ -0,0 +1,147 @@
+use chrono::{DateTime, Local, Utc};
+
+use crate::{
+    training::Training,
+    user::{family::FindFor, User},
+};
+
+/// A freed seat goes to the waitlist only while the training is open to sign-ups.
+pub fn has_free_seat(training: &Training, now: DateTime<Local>) -> bool {
+    !training.waitlist.is_empty()
+        && !training.is_full()
+        && !training.is_processed
+        && training.status(now).can_sign_in()
+}
+
+/// Picks the first waiter in line who passes the sign-up checks: active, not blocked
+/// for an overdue debt and with a subscription that pays for the training. The lesson
+/// is locked on the payer of the picked waiter, the others are left untouched.
+/// `waiters` go in the waitlist order with their families resolved.
+pub fn promote(
+    training: &Training,
+    waiters: &mut [User],
+    now: DateTime<Local>,
+    block_overdue: bool,
+) -> Option<usize> {
+    waiters.iter_mut().position(|waiter| {
+        if !waiter.is_active || training.clients.contains(&waiter.id) {
+            return false;
+        }
+        let Ok(mut payer) = waiter.payer_mut() else {
+            return false;
+        };
+        if block_overdue && payer.has_overdue_debt(now.with_timezone(&Utc)) {
+            return false;
+        }
+        if !training.tp.is_not_free() {
+            return true;
+        }
+        let Some(sub) = payer.find_subscription(FindFor::Lock, training) else {
+            return false;
+        };
+        if !sub.lock_balance() {
+            return false;
+        }
+        sub.add_visit(&training.get_slot());
+        true
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        decimal::Decimal,
+        installment::Debt,
+        program::TrainingType,
+        subscription::{Subscription, SubscriptionType, UserSubscription},
+    };
+    use bson::oid::ObjectId;
+    use chrono::{Duration, TimeZone as _};
+
+    fn now() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2030, 1, 7, 10, 0, 0).unwrap()
+    }
+
+    fn training(capacity: u32) -> Training {
+        Training::new(
+            ObjectId::new(),
+            "yoga".to_string(),
+            "".to_string(),
+            (now() + Duration::days(1)).with_timezone(&Utc),
+            60,
+            ObjectId::new(),
+            capacity,
+            false,
+            TrainingType::Group { is_free: false },
+            ObjectId::new(),
+        )
+    }
+
+    fn waiter(training: &Training, items: u32) -> User {
+        let mut user = User::with_tg_id(0);
+        user.payer_mut()
+            .unwrap()
+            .subscriptions_mut()
+            .push(UserSubscription::from(Subscription {
+                items,
+                subscription_type: SubscriptionType::Group {
+                    program_filter: vec![training.proto_id],
+                },
+                ..Default::default()
+            }));
+        user
+    }
+
+    #[test]
+    fn test_promotion_order() {
+        let mut training = training(1);
+        let mut waiters = vec![
+            waiter(&training, 0),
+            waiter(&training, 2),
+            waiter(&training, 2),
+        ];
+        training.waitlist = waiters.iter().map(|waiter| waiter.id).collect();
+        assert!(has_free_seat(&training, now()));
+
+        assert_eq!(promote(&training, &mut waiters, now(), true), Some(1));
+        assert_eq!(waiters[1].subscriptions()[0].balance, 1);
+        assert_eq!(waiters[1].subscriptions()[0].locked_balance, 1);
+        assert_eq!(waiters[2].subscriptions()[0].locked_balance, 0);
+    }
+
+    #[test]
+    fn test_capacity() {
+        let mut training = training(1);
+        let waiter = waiter(&training, 2);
+        training.waitlist = vec![waiter.id];
+        assert!(has_free_seat(&training, now()));
+
+        training.clients.push(ObjectId::new());
+        assert!(!has_free_seat(&training, now()));
+        training.clients.clear();
+        assert!(!has_free_seat(&training, now() + Duration::days(2)));
+        training.waitlist.clear();
+        assert!(!has_free_seat(&training, now()));
+    }
+
+    #[test]
+    fn test_blocked_waiters() {
+        let training = training(1);
+        let mut blocked = waiter(&training, 2);
+        blocked.is_active = false;
+        let mut debtor = waiter(&training, 2);
+        debtor.debts.push(Debt::new(
+            ObjectId::new(),
+            "test".to_string(),
+            Decimal::int(1000),
+            1,
+            (now() - Duration::days(60)).with_timezone(&Utc),
+        ));
+        let mut waiters = vec![blocked, debtor];
+
+        assert_eq!(promote(&training, &mut waiters, now(), true), None);
+        assert_eq!(waiters[1].subscriptions()[0].locked_balance, 0);
+        assert_eq!(promote(&training, &mut waiters, now(), false), Some(1));
+    }
+}
@@ -0,0 +1,134 @@
+use bson::doc;
+use chrono::{DateTime, Local, Utc};
+use eyre::Error;
+use futures_util::TryStreamExt as _;
+use model::{
+    availability::{Absence, AvailabilityWindow},
+    session::Session,
+};
+use mongodb::{bson::oid::ObjectId, Collection, IndexModel};
+
+const COLLECTION: &str = "instructor_absences";
+const WINDOWS_COLLECTION: &str = "instructor_windows";
+
+pub struct AvailabilityStore {
+    pub(crate) store: Collection<Absence>,
+    pub(crate) windows: Collection<AvailabilityWindow>,
+}
+
+impl AvailabilityStore {
+    pub(crate) async fn new(db: &mongodb::Database) -> Result<Self, Error> {
+        let store = db.collection(COLLECTION);
+        store
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "instructor": 1, "to": 1 })
+                    .build(),
+            )
+            .await?;
+        let windows = db.collection(WINDOWS_COLLECTION);
+        windows
+            .create_index(IndexModel::builder().keys(doc! { "instructor": 1 }).build())
+            .await?;
+        Ok(AvailabilityStore { store, windows })
+    }
+
+    pub async fn insert(&self, session: &mut Session, absence: &Absence) -> Result<(), Error> {
+        self.store
+            .insert_one(absence)
+            .session(&mut *session)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn delete(&self, session: &mut Session, id: ObjectId) -> Result<(), Error> {
+        self.store
+            .delete_one(doc! { "_id": id })
+            .session(&mut *session)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_by_id(
+        &self,
+        session: &mut Session,
+        id: ObjectId,
+    ) -> Result<Option<Absence>, Error> {
+        Ok(self
+            .store
+            .find_one(doc! { "_id": id })
+            .session(&mut *session)
+            .await?)
+    }
+
+    /// Current and upcoming absences of the instructor.
+    pub async fn find_by_instructor(
+        &self,
+        session: &mut Session,
+        instructor: ObjectId,
+    ) -> Result<Vec<Absence>, Error> {
+        let mut cursor = self
+            .store
+            .find(doc! { "instructor": instructor, "to": { "$gt": Utc::now() } })
+            .sort(doc! { "from": 1 })
+            .session(&mut *session)
+            .await?;
+        Ok(cursor.stream(&mut *session).try_collect().await?)
+    }
+
+    pub async fn find_overlapping(
+        &self,
+        session: &mut Session,
+        instructor: ObjectId,
+        from: DateTime<Local>,
+        to: DateTime<Local>,
+    ) -> Result<Vec<Absence>, Error> {
+        let mut cursor = self
+            .store
+            .find(doc! {
+                "instructor": instructor,
+                "from": { "$lt": to.with_timezone(&Utc) },
+                "to": { "$gt": from.with_timezone(&Utc) },
+            })
+            .sort(doc! { "from": 1 })
+            .session(&mut *session)
+            .await?;
+        Ok(cursor.stream(&mut *session).try_collect().await?)
+    }
+
+    pub async fn insert_window(
+        &self,
+        session: &mut Session,
+        window: &AvailabilityWindow,
+    ) -> Result<(), Error> {
+        self.windows
+            .insert_one(window)
+            .session(&mut *session)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn delete_window(&self, session: &mut Session, id: ObjectId) -> Result<(), Error> {
+        self.windows
+            .delete_one(doc! { "_id": id })
+            .session(&mut *session)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn find_windows(
+        &self,
+        session: &mut Session,
+        instructor: ObjectId,
+    ) -> Result<Vec<AvailabilityWindow>, Error> {
+        let mut cursor = self
+            .windows
+            .find(doc! { "instructor": instructor })
+            .session(&mut *session)
+            .await?;
+        let mut windows: Vec<AvailabilityWindow> =
+            cursor.stream(&mut *session).try_collect().await?;
+        windows.sort_by_key(|w| (w.weekday.num_days_from_monday(), w.from_min));
+        Ok(windows)
+    }
+}
@@ -1,17 +1,17 @@
-use bson::to_document;
+use bson::{to_bson, to_document};
 use chrono::{DateTime, Duration, Local, Utc, Weekday};
 use eyre::Result;
 use log::info;
 use model::{
     day::Day,
     ids::DayId,
-    program::TrainingType,
+    program::{BookingWindows, TrainingType},
     session::Session,
-    training::{Filter, Notified, Statistics, Training, TrainingId},
+    training::{AttendanceMark, Filter, Notified, Statistics, Training, TrainingId},
 };
 use mongodb::{
     bson::{doc, oid::ObjectId},
-    options::{FindOneOptions, FindOptions, IndexOptions, UpdateOptions},
+    options::{FindOptions, IndexOptions, UpdateOptions},
     Collection, Database, IndexModel, SessionCursor,
 };
 
@@ -117,6 +117,21 @@ impl CalendarStore {
         Ok(())
     }
 
+    pub async fn set_canceled_clients(
+        &self,
+        session: &mut Session,
+        id: TrainingId,
+        clients: &[ObjectId],
+    ) -> Result<(), eyre::Error> {
+        info!("Set canceled clients: {:?} {:?}", id, clients);
+        let update = doc! { "$set": { "training.$.canceled_clients": clients }, "$inc": { "version": 1 } };
+        self.store
+            .update_one(training_filter(id), update)
+            .session(&mut *session)
+            .await?;
+        Ok(())
+    }
+
     pub async fn find_trainings(
         &self,
         session: &mut Session,
@@ -158,6 +173,16 @@ impl CalendarStore {
                   ]
                 }
             }
+            Filter::Canceled(id) => {
+                doc! {
+                  "$and": [
+                        {
+                            "training.canceled_clients": { "$elemMatch": { "$eq": id } }
+                        },
+                    { "date_time": { "$gte":  day_id.id()} }
+                  ]
+                }
+            }
         };
         let mut cursor = self.store.find(find).session(&mut *session).await?;
 
@@ -186,50 +211,28 @@ impl CalendarStore {
         Ok(trainings)
     }
 
-    pub async fn get_day(&self, session: &mut Session, id: DayId) -> Result<Day, eyre::Error> {
-        let day = self
+    pub async fn find_day(
+        &self,
+        session: &mut Session,
+        id: DayId,
+    ) -> Result<Option<Day>, eyre::Error> {
+        Ok(self
             .store
             .find_one(doc! { "date_time": id.id() })
             .session(&mut *session)
-            .await?;
-        match day {
-            Some(day) => Ok(day),
-            None => {
-                let now = Utc::now();
-                if now + chrono::Duration::days(365 * 2) < id.id() {
-                    return Err(eyre::eyre!("Day is too far in the future:{:?}", id));
-                }
+            .await?)
+    }
 
-                let filter = doc! {
-                    "weekday": id.week_day().to_string(),
-                    "date_time": { "$lt": id.id() },
-                };
-
-                let find_options = FindOneOptions::builder()
-                    .sort(doc! { "date_time": -1 })
-                    .build();
-
-                let prev_day = self
-                    .store
-                    .find_one(filter)
-                    .with_options(find_options)
-                    .session(&mut *session)
-                    .await?
-                    .unwrap_or(Day::new(id));
-
-                let day = Day::copy_day(id, prev_day);
-
-                self.store
-                    .update_one(
-                        doc! { "date_time": day.day_date() },
-                        doc! { "$setOnInsert": to_document(&day)? },
-                    )
-                    .session(&mut *session)
-                    .with_options(UpdateOptions::builder().upsert(true).build())
-                    .await?;
-                Ok(day)
-            }
-        }
+    pub async fn insert_day(&self, session: &mut Session, day: &Day) -> Result<(), eyre::Error> {
+        self.store
+            .update_one(
+                doc! { "date_time": day.day_date() },
+                doc! { "$setOnInsert": to_document(day)? },
+            )
+            .session(&mut *session)
+            .with_options(UpdateOptions::builder().upsert(true).build())
+            .await?;
+        Ok(())
     }
 
     pub async fn delete_training(
@@ -269,14 +272,17 @@ impl CalendarStore {
         training: &Training,
     ) -> Result<(), eyre::Error> {
         info!("Add training: {:?}", training);
-        let filter = doc! { "date_time": training.day_id().id() };
+        let day_id = training.day_id();
+        let filter = doc! { "date_time": day_id.id() };
         let update = doc! {
             "$push": { "training": to_document(training)? },
-            "$inc": { "version": 1 }
+            "$inc": { "version": 1 },
+            "$setOnInsert": { "weekday": day_id.week_day().to_string() }
         };
         self.store
             .update_one(filter, update)
             .session(&mut *session)
+            .with_options(UpdateOptions::builder().upsert(true).build())
             .await?;
         Ok(())
     }
@@ -327,6 +333,91 @@ impl CalendarStore {
         Ok(())
     }
 
+    pub async fn add_to_waitlist(
+        &self,
+        session: &mut Session,
+        id: TrainingId,
+        user_id: ObjectId,
+    ) -> Result<(), eyre::Error> {
+        info!("Add to waitlist: {:?} {}", id, user_id);
+        let update = doc! {
+            "$addToSet": { "training.$.waitlist": user_id },
+            "$inc": { "version": 1 }
+        };
+        let result = self
+            .store
+            .update_one(training_filter(id), update)
+            .session(&mut *session)
+            .await?;
+
+        if result.modified_count != 1 {
+            return Err(eyre::eyre!("Training not found"));
+        }
+        Ok(())
+    }
+
+    pub async fn remove_from_waitlist(
+        &self,
+        session: &mut Session,
+        id: TrainingId,
+        user_id: ObjectId,
+    ) -> Result<(), eyre::Error> {
+        info!("Remove from waitlist: {:?} {}", id, user_id);
+        let update = doc! {
+            "$pull": { "training.$.waitlist": user_id },
+            "$inc": { "version": 1 }
+        };
+        let result = self
+            .store
+            .update_one(training_filter(id), update)
+            .session(&mut *session)
+            .await?;
+
+        if result.modified_count != 1 {
+            return Err(eyre::eyre!("Training not found"));
+        }
+        Ok(())
+    }
+
+    pub async fn clear_waitlist(
+        &self,
+        session: &mut Session,
+        id: TrainingId,
+    ) -> Result<(), eyre::Error> {
+        info!("Clear waitlist: {:?}", id);
+        let update = doc! {
+            "$set": { "training.$.waitlist": [] },
+            "$inc": { "version": 1 }
+        };
+        self.store
+            .update_one(training_filter(id), update)
+            .session(&mut *session)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn set_attendance(
+        &self,
+        session: &mut Session,
+        id: TrainingId,
+        attendance: &[AttendanceMark],
+    ) -> Result<(), eyre::Error> {
+        info!("Set attendance: {:?} {:?}", id, attendance);
+        let update = doc! {
+            "$set": { "training.$.attendance": to_bson(attendance)? },
+            "$inc": { "version": 1 }
+        };
+        let result = self
+            .store
+            .update_one(training_filter(id), update)
+            .session(&mut *session)
+            .await?;
+        if result.matched_count != 1 {
+            return Err(eyre::eyre!("Training not found"));
+        }
+        Ok(())
+    }
+
     pub async fn days_to_process(
         &self,
         session: &mut Session,
@@ -387,6 +478,27 @@ impl CalendarStore {
         Ok(())
     }
 
+    /// Applies program windows to its trainings, except the ones with custom windows.
+    pub async fn edit_windows(
+        &self,
+        session: &mut Session,
+        program_id: ObjectId,
+        windows: BookingWindows,
+    ) -> Result<(), eyre::Error> {
+        info!("Edit windows: {:?} {:?}", program_id, windows);
+        let filter = doc! { "training.proto_id": program_id };
+        let update = doc! {
+            "$set": { "training.$[elem].windows": to_bson(&windows)? },
+            "$inc": { "version": 1 }
+        };
+        self.store
+            .update_many(filter, update)
+            .array_filters([doc! { "elem.proto_id": program_id, "elem.custom_windows": { "$ne": true } }])
+            .session(&mut *session)
+            .await?;
+        Ok(())
+    }
+
     pub async fn edit_program_name(
         &self,
         session: &mut Session,
@@ -436,6 +548,35 @@ impl CalendarStore {
         Ok(self.store.find(filter).session(&mut *session).await?)
     }
 
+    /// Upcoming sessions of the personal series.
+    pub async fn find_series_trainings(
+        &self,
+        session: &mut Session,
+        series: ObjectId,
+    ) -> Result<Vec<Training>, eyre::Error> {
+        let now = Utc::now();
+        let filter = doc! {
+            "training.personal_series": series,
+            "date_time": { "$gte": DayId::from(now).id() },
+        };
+        let mut cursor = self
+            .store
+            .find(filter)
+            .sort(doc! { "date_time": 1 })
+            .session(&mut *session)
+            .await?;
+        let mut trainings = vec![];
+        while let Some(day) = cursor.next(&mut *session).await {
+            for training in day?.training {
+                if training.personal_series == Some(series) && training.start_at_utc() > now {
+                    trainings.push(training);
+                }
+            }
+        }
+        trainings.sort_by_key(|t| t.start_at_utc());
+        Ok(trainings)
+    }
+
     pub async fn update_duration_in_day(
         &self,
         session: &mut Session,
@@ -550,6 +691,34 @@ impl CalendarStore {
         Ok(())
     }
 
+    pub async fn set_windows(
+        &self,
+        session: &mut Session,
+        id: TrainingId,
+        windows: BookingWindows,
+        custom: bool,
+    ) -> Result<(), eyre::Error> {
+        info!("Set windows: {:?} {:?} custom:{}", id, windows, custom);
+        let update = doc! {
+            "$set": {
+                "training.$.windows": to_bson(&windows)?,
+                "training.$.custom_windows": custom,
+            },
+            "$inc": { "version": 1 }
+        };
+        let result = self
+            .store
+            .update_one(training_filter(id), update)
+            .session(&mut *session)
+            .await?;
+
+        if result.matched_count == 0 {
+            return Err(eyre::eyre!("Training not found"));
+        }
+
+        Ok(())
+    }
+
     pub async fn notify(
         &self,
         session: &mut Session,
@@ -0,0 +1,110 @@
+use bson::doc;
+use chrono::{DateTime, Utc};
+use eyre::Error;
+use futures_util::TryStreamExt as _;
+use model::{gift::GiftCertificate, session::Session};
+use mongodb::{bson::oid::ObjectId, options::IndexOptions, Collection, IndexModel};
+
+const COLLECTION: &str = "gift_certificates";
+
+pub struct GiftStore {
+    pub(crate) store: Collection<GiftCertificate>,
+}
+
+impl GiftStore {
+    pub(crate) async fn new(db: &mongodb::Database) -> Result<Self, Error> {
+        let store = db.collection(COLLECTION);
+        store
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "code": 1 })
+                    .options(IndexOptions::builder().unique(true).build())
+                    .build(),
+            )
+            .await?;
+        store
+            .create_index(IndexModel::builder().keys(doc! { "buyer": 1 }).build())
+            .await?;
+        Ok(GiftStore { store })
+    }
+
+    pub async fn insert(
+        &self,
+        session: &mut Session,
+        certificate: &GiftCertificate,
+    ) -> Result<(), Error> {
+        self.store
+            .insert_one(certificate)
+            .session(&mut *session)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn update(
+        &self,
+        session: &mut Session,
+        certificate: &GiftCertificate,
+    ) -> Result<(), Error> {
+        self.store
+            .replace_one(doc! { "_id": certificate.id }, certificate)
+            .session(&mut *session)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get(
+        &self,
+        session: &mut Session,
+        id: ObjectId,
+    ) -> Result<Option<GiftCertificate>, Error> {
+        Ok(self
+            .store
+            .find_one(doc! { "_id": id })
+            .session(&mut *session)
+            .await?)
+    }
+
+    pub async fn get_by_code(
+        &self,
+        session: &mut Session,
+        code: &str,
+    ) -> Result<Option<GiftCertificate>, Error> {
+        Ok(self
+            .store
+            .find_one(doc! { "code": GiftCertificate::normalize(code) })
+            .session(&mut *session)
+            .await?)
+    }
+
+    pub async fn find_by_buyer(
+        &self,
+        session: &mut Session,
+        buyer: ObjectId,
+    ) -> Result<Vec<GiftCertificate>, Error> {
+        let mut cursor = self
+            .store
+            .find(doc! { "buyer": buyer })
+            .sort(doc! { "sold_at": -1 })
+            .session(&mut *session)
+            .await?;
+        Ok(cursor.stream(&mut *session).try_collect().await?)
+    }
+
+    /// Certificates sold before `at` that are neither redeemed nor expired at that moment.
+    pub async fn outstanding(
+        &self,
+        session: &mut Session,
+        at: DateTime<Utc>,
+    ) -> Result<Vec<GiftCertificate>, Error> {
+        let filter = doc! {
+            "sold_at": { "$lte": at },
+            "expires_at": { "$gt": at },
+            "$or": [
+                { "redeemed": null },
+                { "redeemed.at": { "$gt": at } },
+            ],
+        };
+        let mut cursor = self.store.find(filter).session(&mut *session).await?;
+        Ok(cursor.stream(&mut *session).try_collect().await?)
+    }
+}
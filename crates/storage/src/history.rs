@@ -62,6 +62,19 @@ impl HistoryStore {
         Ok(())
     }
 
+    /// Whether a trial subscription was ever sold to the user.
+    pub async fn has_trial(&self, session: &mut Session, user_id: ObjectId) -> Result<bool, Error> {
+        let count = self
+            .store
+            .count_documents(doc! {
+                "sub_actors": user_id,
+                "action.SellSub.subscription.trial": true,
+            })
+            .session(&mut *session)
+            .await?;
+        Ok(count > 0)
+    }
+
     pub async fn get_actor_logs(
         &self,
         session: &mut Session,
@@ -1,15 +1,24 @@
+pub mod availability;
 pub mod calendar;
+pub mod gift;
 pub mod history;
+pub mod loyalty;
 pub mod payment;
+pub mod personal_series;
 pub mod program;
+pub mod promo;
 pub mod requests;
 pub mod rewards;
+pub mod rooms;
+pub mod schedule;
 pub mod session;
+pub mod settings;
 pub mod subscription;
 pub mod treasury;
 pub mod user;
 pub mod notification;
 
+use availability::AvailabilityStore;
 use bson::{doc, Bson};
 use eyre::Result;
 use futures_util::{StreamExt as _, TryStreamExt as _};
@@ -19,8 +28,11 @@ use mongodb::Collection;
 use notification::NotificationStore;
 use requests::RequestStore;
 use rewards::RewardsStore;
+use rooms::RoomStore;
+use schedule::ScheduleTemplateStore;
 use serde::{Deserialize, Serialize};
 use session::Db;
+use settings::SettingsStore;
 use std::{collections::HashMap, sync::Arc};
 use user::UserStore;
 
@@ -38,6 +50,14 @@ pub struct Storage {
     pub rewards: Arc<RewardsStore>,
     pub requests: Arc<RequestStore>,
     pub notification: Arc<NotificationStore>,
+    pub schedule: Arc<ScheduleTemplateStore>,
+    pub rooms: Arc<RoomStore>,
+    pub settings: Arc<SettingsStore>,
+    pub availability: Arc<AvailabilityStore>,
+    pub personal_series: Arc<personal_series::PersonalSeriesStore>,
+    pub promo: Arc<promo::PromoStore>,
+    pub gift: Arc<gift::GiftStore>,
+    pub loyalty: Arc<loyalty::LoyaltyStore>,
 }
 
 impl Storage {
@@ -52,6 +72,14 @@ impl Storage {
         let rewards = RewardsStore::new(&db).await?;
         let requests = RequestStore::new(&db).await?;
         let notification = NotificationStore::new(&db).await?;
+        let schedule = ScheduleTemplateStore::new(&db).await?;
+        let rooms = RoomStore::new(&db).await?;
+        let settings = SettingsStore::new(&db);
+        let availability = AvailabilityStore::new(&db).await?;
+        let personal_series = personal_series::PersonalSeriesStore::new(&db).await?;
+        let promo = promo::PromoStore::new(&db).await?;
+        let gift = gift::GiftStore::new(&db).await?;
+        let loyalty = loyalty::LoyaltyStore::new(&db).await?;
 
         Ok(Storage {
             db: Arc::new(db),
@@ -64,6 +92,14 @@ impl Storage {
             rewards: Arc::new(rewards),
             requests: Arc::new(requests),
             notification: Arc::new(notification),
+            schedule: Arc::new(schedule),
+            rooms: Arc::new(rooms),
+            settings: Arc::new(settings),
+            availability: Arc::new(availability),
+            personal_series: Arc::new(personal_series),
+            promo: Arc::new(promo),
+            gift: Arc::new(gift),
+            loyalty: Arc::new(loyalty),
         })
     }
 
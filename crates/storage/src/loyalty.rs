@@ -0,0 +1,135 @@
+use bson::doc;
+use chrono::{DateTime, Utc};
+use eyre::Error;
+use futures_util::TryStreamExt as _;
+use model::{loyalty::PointsEntry, session::Session};
+use mongodb::{bson::oid::ObjectId, Collection, IndexModel};
+
+const COLLECTION: &str = "loyalty_points";
+
+pub struct LoyaltyStore {
+    pub(crate) store: Collection<PointsEntry>,
+}
+
+impl LoyaltyStore {
+    pub(crate) async fn new(db: &mongodb::Database) -> Result<Self, Error> {
+        let store = db.collection(COLLECTION);
+        store
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "user_id": 1, "created_at": -1 })
+                    .build(),
+            )
+            .await?;
+        Ok(LoyaltyStore { store })
+    }
+
+    pub async fn insert(&self, session: &mut Session, entry: &PointsEntry) -> Result<(), Error> {
+        self.store.insert_one(entry).session(&mut *session).await?;
+        Ok(())
+    }
+
+    pub async fn set_remaining(
+        &self,
+        session: &mut Session,
+        id: ObjectId,
+        remaining: u32,
+    ) -> Result<(), Error> {
+        self.store
+            .update_one(doc! { "_id": id }, doc! { "$set": { "remaining": remaining } })
+            .session(&mut *session)
+            .await?;
+        Ok(())
+    }
+
+    /// Earned points the user can still spend at `now`, oldest first.
+    pub async fn spendable(
+        &self,
+        session: &mut Session,
+        user_id: ObjectId,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<PointsEntry>, Error> {
+        let mut cursor = self
+            .store
+            .find(doc! { "user_id": user_id, "remaining": { "$gt": 0 } })
+            .sort(doc! { "created_at": 1 })
+            .session(&mut *session)
+            .await?;
+        let entries: Vec<PointsEntry> = cursor.stream(&mut *session).try_collect().await?;
+        Ok(entries
+            .into_iter()
+            .filter(|entry| !entry.is_expired(now))
+            .collect())
+    }
+
+    pub async fn balance(
+        &self,
+        session: &mut Session,
+        user_id: ObjectId,
+        now: DateTime<Utc>,
+    ) -> Result<u32, Error> {
+        Ok(self
+            .spendable(session, user_id, now)
+            .await?
+            .iter()
+            .map(|entry| entry.remaining)
+            .sum())
+    }
+
+    /// Earned points past their expiry date that still have something left.
+    pub async fn expired(
+        &self,
+        session: &mut Session,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<PointsEntry>, Error> {
+        let filter = doc! {
+            "remaining": { "$gt": 0 },
+            "expires_at": { "$ne": null },
+        };
+        let mut cursor = self.store.find(filter).session(&mut *session).await?;
+        let entries: Vec<PointsEntry> = cursor.stream(&mut *session).try_collect().await?;
+        Ok(entries
+            .into_iter()
+            .filter(|entry| entry.is_expired(now))
+            .collect())
+    }
+
+    pub async fn find_by_user(
+        &self,
+        session: &mut Session,
+        user_id: ObjectId,
+        limit: i64,
+        offset: u64,
+    ) -> Result<Vec<PointsEntry>, Error> {
+        let mut cursor = self
+            .store
+            .find(doc! { "user_id": user_id })
+            .sort(doc! { "created_at": -1 })
+            .skip(offset)
+            .limit(limit)
+            .session(&mut *session)
+            .await?;
+        Ok(cursor.stream(&mut *session).try_collect().await?)
+    }
+
+    /// Entries made before `client_id` was stored belong to the client who had the birthday.
+    pub async fn has_birthday_points(
+        &self,
+        session: &mut Session,
+        client_id: ObjectId,
+        year: i32,
+    ) -> Result<bool, Error> {
+        let count = self
+            .store
+            .count_documents(doc! {
+                "operation.Birthday.year": year,
+                "$or": [
+                    { "operation.Birthday.client_id": client_id },
+                    { "user_id": client_id, "operation.Birthday.client_id": null },
+                ],
+            })
+            .session(&mut *session)
+            .await?;
+        Ok(count > 0)
+    }
+}
@@ -0,0 +1,69 @@
+use bson::doc;
+use eyre::Error;
+use futures_util::TryStreamExt as _;
+use model::{personal_series::PersonalSeries, session::Session};
+use mongodb::{bson::oid::ObjectId, Collection, IndexModel};
+
+const COLLECTION: &str = "personal_series";
+
+pub struct PersonalSeriesStore {
+    pub(crate) store: Collection<PersonalSeries>,
+}
+
+impl PersonalSeriesStore {
+    pub(crate) async fn new(db: &mongodb::Database) -> Result<Self, Error> {
+        let store = db.collection(COLLECTION);
+        store
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "client": 1, "is_canceled": 1 })
+                    .build(),
+            )
+            .await?;
+        Ok(PersonalSeriesStore { store })
+    }
+
+    pub async fn insert(
+        &self,
+        session: &mut Session,
+        series: &PersonalSeries,
+    ) -> Result<(), Error> {
+        self.store.insert_one(series).session(&mut *session).await?;
+        Ok(())
+    }
+
+    pub async fn update(
+        &self,
+        session: &mut Session,
+        series: &PersonalSeries,
+    ) -> Result<(), Error> {
+        self.store
+            .replace_one(doc! { "_id": series.id }, series)
+            .session(&mut *session)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get(
+        &self,
+        session: &mut Session,
+        id: ObjectId,
+    ) -> Result<Option<PersonalSeries>, Error> {
+        Ok(self
+            .store
+            .find_one(doc! { "_id": id })
+            .session(&mut *session)
+            .await?)
+    }
+
+    /// Series that may still have sessions to schedule.
+    pub async fn find_active(&self, session: &mut Session) -> Result<Vec<PersonalSeries>, Error> {
+        let mut cursor = self
+            .store
+            .find(doc! { "is_canceled": false })
+            .session(&mut *session)
+            .await?;
+        let series: Vec<PersonalSeries> = cursor.stream(&mut *session).try_collect().await?;
+        Ok(series.into_iter().filter(|s| !s.is_finished()).collect())
+    }
+}
@@ -1,7 +1,10 @@
-use bson::to_document;
+use bson::{to_bson, to_document};
 use eyre::Error;
 use futures_util::TryStreamExt as _;
-use model::{program::Program, session::Session};
+use model::{
+    program::{BookingWindows, Program},
+    session::Session,
+};
 use mongodb::{
     bson::{doc, oid::ObjectId},
     options::UpdateOptions,
@@ -213,6 +216,22 @@ impl ProgramStore {
         Ok(())
     }
 
+    pub async fn edit_windows(
+        &self,
+        session: &mut Session,
+        id: ObjectId,
+        windows: BookingWindows,
+    ) -> Result<(), Error> {
+        self.store
+            .update_one(
+                doc! { "_id": id },
+                doc! { "$set": { "windows": to_bson(&windows)? }, "$inc" : { "version": 1 } },
+            )
+            .session(&mut *session)
+            .await?;
+        Ok(())
+    }
+
     pub async fn edit_name(
         &self,
         session: &mut Session,
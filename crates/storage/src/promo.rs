@@ -0,0 +1,161 @@
+use bson::doc;
+use chrono::{DateTime, Local, Utc};
+use eyre::Error;
+use futures_util::TryStreamExt as _;
+use model::{
+    promo::{PromoCode, PromoRedemption},
+    session::Session,
+};
+use mongodb::{bson::oid::ObjectId, options::IndexOptions, Collection, IndexModel, SessionCursor};
+
+const COLLECTION: &str = "promo_codes";
+const REDEMPTIONS: &str = "promo_redemptions";
+
+pub struct PromoStore {
+    pub(crate) store: Collection<PromoCode>,
+    pub(crate) redemptions: Collection<PromoRedemption>,
+}
+
+impl PromoStore {
+    pub(crate) async fn new(db: &mongodb::Database) -> Result<Self, Error> {
+        let store = db.collection(COLLECTION);
+        store
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "code": 1 })
+                    .options(IndexOptions::builder().unique(true).build())
+                    .build(),
+            )
+            .await?;
+        let redemptions = db.collection(REDEMPTIONS);
+        redemptions
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "promo_id": 1, "user_id": 1 })
+                    .build(),
+            )
+            .await?;
+        redemptions
+            .create_index(IndexModel::builder().keys(doc! { "date_time": -1 }).build())
+            .await?;
+        Ok(PromoStore { store, redemptions })
+    }
+
+    pub async fn insert(&self, session: &mut Session, promo: &PromoCode) -> Result<(), Error> {
+        self.store.insert_one(promo).session(&mut *session).await?;
+        Ok(())
+    }
+
+    pub async fn update(&self, session: &mut Session, promo: &PromoCode) -> Result<(), Error> {
+        self.store
+            .replace_one(doc! { "_id": promo.id }, promo)
+            .session(&mut *session)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get(
+        &self,
+        session: &mut Session,
+        id: ObjectId,
+    ) -> Result<Option<PromoCode>, Error> {
+        Ok(self
+            .store
+            .find_one(doc! { "_id": id })
+            .session(&mut *session)
+            .await?)
+    }
+
+    pub async fn get_by_code(
+        &self,
+        session: &mut Session,
+        code: &str,
+    ) -> Result<Option<PromoCode>, Error> {
+        Ok(self
+            .store
+            .find_one(doc! { "code": PromoCode::normalize(code) })
+            .session(&mut *session)
+            .await?)
+    }
+
+    pub async fn list(
+        &self,
+        session: &mut Session,
+        archived: bool,
+    ) -> Result<Vec<PromoCode>, Error> {
+        let mut cursor = self
+            .store
+            .find(doc! { "is_archived": archived })
+            .sort(doc! { "code": 1 })
+            .session(&mut *session)
+            .await?;
+        Ok(cursor.stream(&mut *session).try_collect().await?)
+    }
+
+    pub async fn add_redemption(
+        &self,
+        session: &mut Session,
+        redemption: &PromoRedemption,
+    ) -> Result<(), Error> {
+        self.redemptions
+            .insert_one(redemption)
+            .session(&mut *session)
+            .await?;
+        self.store
+            .update_one(
+                doc! { "_id": redemption.promo_id },
+                doc! { "$inc": { "uses": 1, "version": 1 } },
+            )
+            .session(&mut *session)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn client_redemptions(
+        &self,
+        session: &mut Session,
+        promo_id: ObjectId,
+        user_id: ObjectId,
+    ) -> Result<u64, Error> {
+        Ok(self
+            .redemptions
+            .count_documents(doc! { "promo_id": promo_id, "user_id": user_id })
+            .session(&mut *session)
+            .await?)
+    }
+
+    pub async fn redemptions(
+        &self,
+        session: &mut Session,
+        promo_id: ObjectId,
+    ) -> Result<Vec<PromoRedemption>, Error> {
+        let mut cursor = self
+            .redemptions
+            .find(doc! { "promo_id": promo_id })
+            .sort(doc! { "date_time": -1 })
+            .session(&mut *session)
+            .await?;
+        Ok(cursor.stream(&mut *session).try_collect().await?)
+    }
+
+    pub async fn find_redemptions_range(
+        &self,
+        session: &mut Session,
+        from: Option<DateTime<Local>>,
+        to: Option<DateTime<Local>>,
+    ) -> Result<SessionCursor<PromoRedemption>, Error> {
+        let mut date_time = doc! {};
+        if let Some(from) = from {
+            date_time.insert("$gte", from.with_timezone(&Utc));
+        }
+        if let Some(to) = to {
+            date_time.insert("$lt", to.with_timezone(&Utc));
+        }
+        let filter = if date_time.is_empty() {
+            doc! {}
+        } else {
+            doc! { "date_time": date_time }
+        };
+        Ok(self.redemptions.find(filter).session(&mut *session).await?)
+    }
+}
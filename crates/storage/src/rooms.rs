@@ -0,0 +1,145 @@
+use bson::{doc, Document};
+use eyre::Error;
+use futures_util::TryStreamExt as _;
+use model::{
+    rooms::{Room, ADULT_ROOM_ID, CHILD_ROOM_ID},
+    session::Session,
+};
+use mongodb::{bson::oid::ObjectId, Collection, IndexModel};
+
+const COLLECTION: &str = "rooms";
+const HISTORY_COLLECTION: &str = "history";
+
+pub struct RoomStore {
+    pub(crate) store: Collection<Room>,
+}
+
+impl RoomStore {
+    pub(crate) async fn new(db: &mongodb::Database) -> Result<Self, Error> {
+        let store = db.collection(COLLECTION);
+        store
+            .create_index(IndexModel::builder().keys(doc! { "ordering": 1 }).build())
+            .await?;
+        let rooms = RoomStore { store };
+        rooms.migrate(db).await?;
+        Ok(rooms)
+    }
+
+    /// Rooms used to be a hardcoded enum. Seeds the collection with the old rooms
+    /// and rewrites history rows that stored the enum variant instead of the room id.
+    async fn migrate(&self, db: &mongodb::Database) -> Result<(), Error> {
+        if self.store.count_documents(doc! {}).await? == 0 {
+            self.store.insert_many(Room::legacy()).await?;
+        }
+
+        let history: Collection<Document> = db.collection(HISTORY_COLLECTION);
+        let legacy = [
+            ("Adult", ObjectId::from_bytes(ADULT_ROOM_ID)),
+            ("Child", ObjectId::from_bytes(CHILD_ROOM_ID)),
+        ];
+        for action in [
+            "SignUp",
+            "SignOut",
+            "FinalizedCanceledTraining",
+            "FinalizedTraining",
+        ] {
+            let field = format!("action.{}.room_id", action);
+            for (name, id) in &legacy {
+                history
+                    .update_many(doc! { &field: name }, doc! { "$set": { &field: id } })
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn get_all(&self, session: &mut Session) -> Result<Vec<Room>, Error> {
+        let mut cursor = self
+            .store
+            .find(doc! {})
+            .sort(doc! { "ordering": 1 })
+            .session(&mut *session)
+            .await?;
+        Ok(cursor.stream(&mut *session).try_collect().await?)
+    }
+
+    pub async fn get_active(&self, session: &mut Session) -> Result<Vec<Room>, Error> {
+        let mut cursor = self
+            .store
+            .find(doc! { "is_active": true })
+            .sort(doc! { "ordering": 1 })
+            .session(&mut *session)
+            .await?;
+        Ok(cursor.stream(&mut *session).try_collect().await?)
+    }
+
+    pub async fn get_by_id(
+        &self,
+        session: &mut Session,
+        id: ObjectId,
+    ) -> Result<Option<Room>, Error> {
+        Ok(self
+            .store
+            .find_one(doc! { "_id": id })
+            .session(&mut *session)
+            .await?)
+    }
+
+    pub async fn insert(&self, session: &mut Session, room: &Room) -> Result<(), Error> {
+        self.store.insert_one(room).session(&mut *session).await?;
+        Ok(())
+    }
+
+    pub async fn set_active(
+        &self,
+        session: &mut Session,
+        id: ObjectId,
+        is_active: bool,
+    ) -> Result<(), Error> {
+        self.store
+            .update_one(
+                doc! { "_id": id },
+                doc! { "$set": { "is_active": is_active }, "$inc": { "version": 1 } },
+            )
+            .session(&mut *session)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn set_ordering(
+        &self,
+        session: &mut Session,
+        id: ObjectId,
+        ordering: u32,
+    ) -> Result<(), Error> {
+        self.store
+            .update_one(
+                doc! { "_id": id },
+                doc! { "$set": { "ordering": ordering }, "$inc": { "version": 1 } },
+            )
+            .session(&mut *session)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn update_info(
+        &self,
+        session: &mut Session,
+        id: ObjectId,
+        name: &str,
+        emoji: &str,
+        capacity: u32,
+    ) -> Result<(), Error> {
+        self.store
+            .update_one(
+                doc! { "_id": id },
+                doc! {
+                    "$set": { "name": name, "emoji": emoji, "capacity": capacity },
+                    "$inc": { "version": 1 }
+                },
+            )
+            .session(&mut *session)
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,189 @@
+use bson::{to_bson, to_document};
+use chrono::{DateTime, Utc, Weekday};
+use eyre::Error;
+use futures_util::TryStreamExt as _;
+use model::{
+    day::Day,
+    ids::DayId,
+    schedule::{ScheduleTemplate, TemplateTraining},
+    session::Session,
+};
+use mongodb::{
+    bson::{doc, oid::ObjectId},
+    options::FindOptions,
+    Collection, IndexModel,
+};
+
+const COLLECTION: &str = "schedule_templates";
+const DAYS_COLLECTION: &str = "days";
+
+pub struct ScheduleTemplateStore {
+    pub(crate) store: Collection<ScheduleTemplate>,
+}
+
+impl ScheduleTemplateStore {
+    pub(crate) async fn new(db: &mongodb::Database) -> Result<Self, Error> {
+        let store = db.collection(COLLECTION);
+        store
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "effective_from": -1 })
+                    .build(),
+            )
+            .await?;
+        let templates = ScheduleTemplateStore { store };
+        templates.migrate(db).await?;
+        Ok(templates)
+    }
+
+    /// Days used to be copied from the same weekday of the previous week. Seeds the first
+    /// template with the recurring trainings of the latest stored day of every weekday.
+    async fn migrate(&self, db: &mongodb::Database) -> Result<(), Error> {
+        if self.store.count_documents(doc! {}).await? > 0 {
+            return Ok(());
+        }
+
+        let days: Collection<Day> = db.collection(DAYS_COLLECTION);
+        let mut trainings = vec![];
+        let mut weekday = Weekday::Mon;
+        for _ in 0..7 {
+            let day = days
+                .find_one(doc! { "weekday": weekday.to_string() })
+                .sort(doc! { "date_time": -1 })
+                .await?;
+            weekday = weekday.succ();
+            if let Some(day) = day {
+                trainings.extend(
+                    day.training
+                        .iter()
+                        .filter(|t| t.is_group() && !t.is_one_time && !t.is_canceled)
+                        .map(TemplateTraining::from_training),
+                );
+            }
+        }
+        if trainings.is_empty() {
+            return Ok(());
+        }
+
+        let template =
+            ScheduleTemplate::new("Основное расписание".to_string(), DayId::default(), trainings);
+        self.store.insert_one(template).await?;
+        Ok(())
+    }
+
+    pub async fn get_all(&self, session: &mut Session) -> Result<Vec<ScheduleTemplate>, Error> {
+        let mut cursor = self
+            .store
+            .find(doc! {})
+            .with_options(
+                FindOptions::builder()
+                    .sort(doc! { "effective_from": -1 })
+                    .build(),
+            )
+            .session(&mut *session)
+            .await?;
+        Ok(cursor.stream(&mut *session).try_collect().await?)
+    }
+
+    pub async fn find_effective(
+        &self,
+        session: &mut Session,
+        day: DayId,
+    ) -> Result<Option<ScheduleTemplate>, Error> {
+        Ok(self
+            .get_all(session)
+            .await?
+            .into_iter()
+            .find(|t| t.is_effective(day)))
+    }
+
+    pub async fn get_by_id(
+        &self,
+        session: &mut Session,
+        id: ObjectId,
+    ) -> Result<Option<ScheduleTemplate>, Error> {
+        Ok(self
+            .store
+            .find_one(doc! { "_id": id })
+            .session(&mut *session)
+            .await?)
+    }
+
+    pub async fn insert(
+        &self,
+        session: &mut Session,
+        template: &ScheduleTemplate,
+    ) -> Result<(), Error> {
+        self.store
+            .insert_one(template)
+            .session(&mut *session)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn delete(&self, session: &mut Session, id: ObjectId) -> Result<(), Error> {
+        self.store
+            .delete_one(doc! { "_id": id })
+            .session(&mut *session)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn set_effective_to(
+        &self,
+        session: &mut Session,
+        id: ObjectId,
+        effective_to: Option<DateTime<Utc>>,
+    ) -> Result<(), Error> {
+        self.store
+            .update_one(
+                doc! { "_id": id },
+                doc! { "$set": { "effective_to": to_bson(&effective_to)? }, "$inc": { "version": 1 } },
+            )
+            .session(&mut *session)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn add_training(
+        &self,
+        session: &mut Session,
+        id: ObjectId,
+        training: &TemplateTraining,
+    ) -> Result<(), Error> {
+        self.store
+            .update_one(
+                doc! { "_id": id },
+                doc! { "$push": { "trainings": to_document(training)? }, "$inc": { "version": 1 } },
+            )
+            .session(&mut *session)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn update_training(
+        &self,
+        session: &mut Session,
+        training: &TemplateTraining,
+    ) -> Result<(), Error> {
+        self.store
+            .update_many(
+                doc! { "trainings.id": training.id },
+                doc! { "$set": { "trainings.$": to_document(training)? }, "$inc": { "version": 1 } },
+            )
+            .session(&mut *session)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn remove_training(&self, session: &mut Session, id: ObjectId) -> Result<(), Error> {
+        self.store
+            .update_many(
+                doc! { "trainings.id": id },
+                doc! { "$pull": { "trainings": { "id": id } }, "$inc": { "version": 1 } },
+            )
+            .session(&mut *session)
+            .await?;
+        Ok(())
+    }
+}
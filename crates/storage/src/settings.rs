@@ -0,0 +1,155 @@
+use bson::{doc, to_bson};
+use eyre::Error;
+use model::{
+    program::BookingWindows,
+    session::Session,
+    settings::{LateCancelPolicy, LoyaltyPolicy, NoShowPolicy, Settings},
+};
+use mongodb::{options::UpdateOptions, Collection};
+
+const COLLECTION: &str = "settings";
+
+pub struct SettingsStore {
+    pub(crate) store: Collection<Settings>,
+}
+
+impl SettingsStore {
+    pub(crate) fn new(db: &mongodb::Database) -> Self {
+        SettingsStore {
+            store: db.collection(COLLECTION),
+        }
+    }
+
+    pub async fn get(&self, session: &mut Session) -> Result<Settings, Error> {
+        Ok(self
+            .store
+            .find_one(doc! { "_id": Settings::id() })
+            .session(&mut *session)
+            .await?
+            .unwrap_or_default())
+    }
+
+    pub async fn set_no_show_policy(
+        &self,
+        session: &mut Session,
+        policy: NoShowPolicy,
+    ) -> Result<(), Error> {
+        self.store
+            .update_one(
+                doc! { "_id": Settings::id() },
+                doc! { "$set": { "no_show": to_bson(&policy)? }, "$inc": { "version": 1 } },
+            )
+            .with_options(UpdateOptions::builder().upsert(true).build())
+            .session(&mut *session)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn set_attendance_window(
+        &self,
+        session: &mut Session,
+        minutes: u32,
+    ) -> Result<(), Error> {
+        self.store
+            .update_one(
+                doc! { "_id": Settings::id() },
+                doc! { "$set": { "attendance_window_min": minutes }, "$inc": { "version": 1 } },
+            )
+            .with_options(UpdateOptions::builder().upsert(true).build())
+            .session(&mut *session)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn set_personal_windows(
+        &self,
+        session: &mut Session,
+        windows: BookingWindows,
+    ) -> Result<(), Error> {
+        self.store
+            .update_one(
+                doc! { "_id": Settings::id() },
+                doc! { "$set": { "personal_windows": to_bson(&windows)? }, "$inc": { "version": 1 } },
+            )
+            .with_options(UpdateOptions::builder().upsert(true).build())
+            .session(&mut *session)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn set_late_cancel_policy(
+        &self,
+        session: &mut Session,
+        policy: LateCancelPolicy,
+    ) -> Result<(), Error> {
+        self.store
+            .update_one(
+                doc! { "_id": Settings::id() },
+                doc! { "$set": { "late_cancel": to_bson(&policy)? }, "$inc": { "version": 1 } },
+            )
+            .with_options(UpdateOptions::builder().upsert(true).build())
+            .session(&mut *session)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn set_refund_fee(&self, session: &mut Session, percent: u32) -> Result<(), Error> {
+        self.store
+            .update_one(
+                doc! { "_id": Settings::id() },
+                doc! { "$set": { "refund_fee_percent": percent }, "$inc": { "version": 1 } },
+            )
+            .with_options(UpdateOptions::builder().upsert(true).build())
+            .session(&mut *session)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn set_block_overdue_sign_up(
+        &self,
+        session: &mut Session,
+        block: bool,
+    ) -> Result<(), Error> {
+        self.store
+            .update_one(
+                doc! { "_id": Settings::id() },
+                doc! { "$set": { "block_overdue_sign_up": block }, "$inc": { "version": 1 } },
+            )
+            .with_options(UpdateOptions::builder().upsert(true).build())
+            .session(&mut *session)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn set_expiry_reminder_days(
+        &self,
+        session: &mut Session,
+        days: Vec<u32>,
+    ) -> Result<(), Error> {
+        self.store
+            .update_one(
+                doc! { "_id": Settings::id() },
+                doc! { "$set": { "expiry_reminder_days": days }, "$inc": { "version": 1 } },
+            )
+            .with_options(UpdateOptions::builder().upsert(true).build())
+            .session(&mut *session)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn set_loyalty_policy(
+        &self,
+        session: &mut Session,
+        policy: LoyaltyPolicy,
+    ) -> Result<(), Error> {
+        self.store
+            .update_one(
+                doc! { "_id": Settings::id() },
+                doc! { "$set": { "loyalty": to_bson(&policy)? }, "$inc": { "version": 1 } },
+            )
+            .with_options(UpdateOptions::builder().upsert(true).build())
+            .session(&mut *session)
+            .await?;
+        Ok(())
+    }
+}
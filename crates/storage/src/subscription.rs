@@ -1,8 +1,10 @@
 use crate::session::Db;
-use bson::{doc, oid::ObjectId};
+use bson::{doc, oid::ObjectId, to_bson};
 use eyre::Error;
 use log::info;
-use model::{decimal::Decimal, session::Session, subscription::Subscription};
+use model::{
+    decimal::Decimal, restriction::TimeRestriction, session::Session, subscription::Subscription,
+};
 use mongodb::Collection;
 
 const TABLE_NAME: &str = "subscriptions";
@@ -172,6 +174,43 @@ impl SubscriptionsStore {
         Ok(())
     }
 
+    pub async fn edit_restriction(
+        &self,
+        session: &mut Session,
+        id: ObjectId,
+        restriction: Option<&TimeRestriction>,
+    ) -> Result<(), Error> {
+        info!("Edit restriction: {:?} {:?}", id, restriction);
+        self.collection
+            .update_one(
+                doc! { "_id": id },
+                doc! {
+                    "$set": {"restriction": to_bson(&restriction)?}
+                },
+            )
+            .session(session)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn edit_trial(
+        &self,
+        session: &mut Session,
+        id: ObjectId,
+        trial: bool,
+    ) -> Result<(), Error> {
+        self.collection
+            .update_one(
+                doc! { "_id": id },
+                doc! {
+                    "$set": {"trial": trial}
+                },
+            )
+            .session(session)
+            .await?;
+        Ok(())
+    }
+
     pub async fn edit_name(
         &self,
         session: &mut Session,
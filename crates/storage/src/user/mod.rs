@@ -1,15 +1,16 @@
 mod employee;
 
-use bson::to_document;
+use bson::{to_document, Document};
 use chrono::{DateTime, Local, Utc};
 use eyre::{bail, eyre, Error, Result};
 use futures_util::stream::TryStreamExt;
 use log::info;
 use model::decimal::Decimal;
+use model::installment::Debt;
 use model::rights::{self, Rule};
 use model::session::Session;
 use model::statistics::source::Source;
-use model::subscription::{Status, Subscription, UserSubscription};
+use model::subscription::{Status, Subscription};
 use model::user::extension::UserExtension;
 use model::user::{Freeze, User, UserName};
 use mongodb::options::UpdateOptions;
@@ -20,6 +21,7 @@ use mongodb::{
 use mongodb::{IndexModel, SessionCursor};
 
 const COLLECTION: &str = "users";
+const SUBSCRIPTIONS_COLLECTION: &str = "subscriptions";
 
 pub struct UserStore {
     pub(crate) users: Collection<User>,
@@ -35,10 +37,57 @@ impl UserStore {
         users
             .create_index(IndexModel::builder().keys(doc! { "phone": 1 }).build())
             .await?;
-        Ok(UserStore {
-            users,
-            extensions: db.collection("users_extension"),
-        })
+        let extensions: Collection<UserExtension> = db.collection("users_extension");
+        extensions
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "calendar_token": 1 })
+                    .build(),
+            )
+            .await?;
+        let store = UserStore { users, extensions };
+        store.migrate(db).await?;
+        Ok(store)
+    }
+
+    /// Subscriptions used to share the account freeze budget. Gives every subscription sold
+    /// before the split its catalog freeze days, capped by what is left of the account budget.
+    async fn migrate(&self, db: &Database) -> Result<()> {
+        let raw: Collection<Document> = db.collection(COLLECTION);
+        let catalog: Collection<Subscription> = db.collection(SUBSCRIPTIONS_COLLECTION);
+        let filter = doc! {
+            "subscriptions": { "$elemMatch": { "freeze_days": { "$exists": false } } }
+        };
+        let mut cursor = raw.find(filter).await?;
+        while let Some(doc) = cursor.try_next().await? {
+            let user: User = bson::from_document(doc.clone())?;
+            let mut budget = user.freeze_days;
+            let mut update = Document::new();
+            for (idx, sub) in doc.get_array("subscriptions")?.iter().enumerate() {
+                let Some(sub) = sub.as_document() else {
+                    continue;
+                };
+                if sub.contains_key("freeze_days") {
+                    continue;
+                }
+                let catalog_days = match sub.get_object_id("subscription_id") {
+                    Ok(id) => catalog
+                        .find_one(doc! { "_id": id })
+                        .await?
+                        .map(|sub| sub.freeze_days)
+                        .unwrap_or_default(),
+                    Err(_) => 0,
+                };
+                let days = catalog_days.min(budget);
+                budget -= days;
+                update.insert(format!("subscriptions.{}.freeze_days", idx), days);
+            }
+            info!("Migrate subscription freeze days of user {}", user.id);
+            self.users
+                .update_one(doc! { "_id": user.id }, doc! { "$set": update })
+                .await?;
+        }
+        Ok(())
     }
 
     pub async fn find_users_for_personal_training(
@@ -246,13 +295,12 @@ impl UserStore {
         id: ObjectId,
         sub: Subscription,
         discount: Option<Decimal>,
-    ) -> Result<()> {
+    ) -> Result<Vec<ObjectId>> {
         info!("Add subscription for user {}: {:?}", id, sub);
-        let freeze_days = sub.freeze_days as i32;
-        let amount = sub.items as i32;
-
-        let mut sub = UserSubscription::from(sub);
-        sub.discount = discount;
+        let subs = sub.user_subscriptions(discount);
+        let amount = subs.iter().map(|sub| sub.items as i32).sum::<i32>();
+        let ids = subs.iter().map(|sub| sub.id).collect();
+        let subs = subs.iter().map(to_document).collect::<Result<Vec<_>, _>>()?;
 
         let result = self
             .users
@@ -261,11 +309,10 @@ impl UserStore {
                 doc! {
                 "$inc": {
                     "balance": amount,
-                    "freeze_days": freeze_days,
                      "version": 1
                     },
                     "$push": {
-                        "subscriptions": to_document(&sub)?
+                        "subscriptions": { "$each": subs }
                     }
                 },
             )
@@ -275,9 +322,48 @@ impl UserStore {
         if result.modified_count != 1 {
             return Err(eyre!("Failed to modify balance"));
         }
+        Ok(ids)
+    }
+
+    pub async fn add_debt(&self, session: &mut Session, id: ObjectId, debt: &Debt) -> Result<()> {
+        let result = self
+            .users
+            .update_one(
+                doc! { "_id": id },
+                doc! {
+                    "$push": { "debts": to_document(debt)? },
+                    "$inc": { "version": 1 }
+                },
+            )
+            .session(&mut *session)
+            .await?;
+        if result.modified_count != 1 {
+            return Err(eyre!("Failed to add debt"));
+        }
+        Ok(())
+    }
+
+    pub async fn set_debts(&self, session: &mut Session, id: ObjectId, debts: &[Debt]) -> Result<()> {
+        let debts = debts.iter().map(to_document).collect::<Result<Vec<_>, _>>()?;
+        self.users
+            .update_one(
+                doc! { "_id": id },
+                doc! {
+                    "$set": { "debts": debts },
+                    "$inc": { "version": 1 }
+                },
+            )
+            .session(&mut *session)
+            .await?;
         Ok(())
     }
 
+    pub async fn find_users_with_debts(&self, session: &mut Session) -> Result<Vec<User>, Error> {
+        let filter = doc! { "debts.0": { "$exists": true } };
+        let mut cursor = self.users.find(filter).session(&mut *session).await?;
+        Ok(cursor.stream(&mut *session).try_collect().await?)
+    }
+
     pub async fn find_users_to_unfreeze(&self, session: &mut Session) -> Result<Vec<User>, Error> {
         let filter = doc! {
             "freeze.freeze_end": { "$lte": Local::now().with_timezone(&Utc) }
@@ -286,6 +372,17 @@ impl UserStore {
         Ok(cursor.stream(&mut *session).try_collect().await?)
     }
 
+    pub async fn find_users_with_expired_subscription_freeze(
+        &self,
+        session: &mut Session,
+    ) -> Result<Vec<User>, Error> {
+        let filter = doc! {
+            "subscriptions.freeze.freeze_end": { "$lte": Local::now().with_timezone(&Utc) }
+        };
+        let mut cursor = self.users.find(filter).session(&mut *session).await?;
+        Ok(cursor.stream(&mut *session).try_collect().await?)
+    }
+
     pub async fn unfreeze(&self, session: &mut Session, id: ObjectId) -> Result<()> {
         info!("Unfreeze account:{}", id);
         let result = self
@@ -347,6 +444,7 @@ impl UserStore {
         user.freeze = Some(Freeze {
             freeze_start: Local::now().with_timezone(&Utc),
             freeze_end: Local::now().with_timezone(&Utc) + chrono::Duration::days(days as i64),
+            charged_days: Some(days),
         });
 
         self.users
@@ -600,9 +698,26 @@ impl UserStore {
                 notification_mask: Default::default(),
                 ai_message_prompt: None,
                 comments: Default::default(),
+                calendar_token: None,
             }))
     }
 
+    pub async fn find_by_calendar_token(
+        &self,
+        session: &mut Session,
+        token: &str,
+    ) -> Result<Option<User>> {
+        let extension = self
+            .extensions
+            .find_one(doc! { "calendar_token": token })
+            .session(&mut *session)
+            .await?;
+        match extension {
+            Some(extension) => self.get(session, extension.id).await,
+            None => Ok(None),
+        }
+    }
+
     pub async fn update_extension(
         &self,
         session: &mut Session,